@@ -1,15 +1,22 @@
 use crate::api::types::*;
 use crate::api::auth::HyperLiquidAuth;
-use crate::trading::types::Position;
+use crate::api::rate_limiter::{RateLimiter, RequestKind};
+use crate::trading::pnl_reconstruction::PnlReconstruction;
+use crate::trading::types::{FeeRates, Fill, Order, OrderStatus, OrderType, Position, Side};
 use anyhow::Result;
 use crossbeam_channel::{Sender, Receiver, unbounded};
 use dashmap::DashMap;
 use parking_lot::RwLock;
 use rust_decimal::Decimal;
 use serde::{Serialize, Deserialize};
+use serde::de::DeserializeOwned;
 use std::sync::Arc;
 use std::str::FromStr;
-use tracing::{error, info, debug};
+use std::collections::HashSet;
+use tracing::{error, info, debug, warn};
+
+// Maximum number of fills HyperLiquid returns for a single userFillsByTime request
+pub const FILLS_PAGE_SIZE: usize = 2000;
 
 #[derive(Debug, Clone)]
 pub struct AccountApi {
@@ -19,6 +26,9 @@ pub struct AccountApi {
     pub account_info: Arc<RwLock<Option<HyperLiquidAccountInfo>>>,
     pub account_events_tx: Sender<ApiEvent>,
     pub last_update: Arc<RwLock<std::time::Instant>>,
+    pub last_funding_time: Arc<RwLock<u64>>,
+    pub rate_limiter: Arc<RateLimiter>,
+    pub fee_rates: Arc<RwLock<FeeRates>>, // Base tier until the first refresh
 }
 
 impl AccountApi {
@@ -27,16 +37,25 @@ impl AccountApi {
         
         let api = Self {
             auth,
-            config,
             positions: Arc::new(DashMap::new()),
             account_info: Arc::new(RwLock::new(None)),
             account_events_tx: tx,
             last_update: Arc::new(RwLock::new(std::time::Instant::now())),
+            last_funding_time: Arc::new(RwLock::new(chrono::Utc::now().timestamp_millis() as u64)),
+            rate_limiter: Arc::new(RateLimiter::new(config.rate_limit.clone())),
+            fee_rates: Arc::new(RwLock::new(FeeRates::default())),
+            config,
         };
         
         (api, rx)
     }
 
+    /// Shares one request budget with other API clients.
+    pub fn with_rate_limiter(mut self, rate_limiter: Arc<RateLimiter>) -> Self {
+        self.rate_limiter = rate_limiter;
+        self
+    }
+
     pub async fn get_account_info(&self) -> Result<HyperLiquidAccountInfo, ApiError> {
         let info_request = HyperLiquidInfoRequest {
             type_: "clearinghouseState".to_string(),
@@ -46,13 +65,16 @@ impl AccountApi {
         let signed_request = self.auth.create_signed_request("info", &info_request)?;
         let headers = self.auth.get_headers()?;
 
+        self.rate_limiter.acquire(RequestKind::InfoLight).await?;
+
         let response = self.auth.client
-            .post(&format!("{}/info", self.config.base_url))
+            .post(format!("{}/info", self.config.base_url))
             .headers(headers)
             .json(&signed_request)
             .send()
             .await
             .map_err(|e| ApiError::NetworkError(e.to_string()))?;
+        self.rate_limiter.check_response(&response)?;
 
         let status = response.status();
         if !status.is_success() {
@@ -105,30 +127,15 @@ impl AccountApi {
 
     pub async fn get_positions(&self) -> Result<Vec<Position>, ApiError> {
         let account_info = self.get_account_info().await?;
-        
-        let positions: Vec<Position> = account_info.asset_positions
-            .into_iter()
-            .filter_map(|hl_pos| {
-                let size = Decimal::from_str(&hl_pos.szi).ok()?;
-                let entry_price = Decimal::from_str(&hl_pos.entry_px).ok()?;
-                let unrealized_pnl = Decimal::from_str(&hl_pos.unrealized_pnl).ok()?;
-                
-                // Use entry price as mark price for now
-                let mark_price = entry_price;
-                
-                Some(Position {
-                    symbol: hl_pos.coin,
-                    size,
-                    entry_price,
-                    mark_price,
-                    unrealized_pnl,
-                    realized_pnl: Decimal::ZERO, // This would need to be tracked separately
-                    updated_at: chrono::Utc::now(),
-                })
-            })
-            .collect();
 
-        Ok(positions)
+        // A position whose size can't be read fails the whole call rather than going missing
+        account_info.asset_positions
+            .iter()
+            .map(|hl_position| {
+                let previous = self.get_cached_position(&hl_position.coin);
+                parse_position(hl_position, previous.as_ref()).map(|(position, _)| position)
+            })
+            .collect()
     }
 
     pub async fn get_balance(&self) -> Result<Decimal, ApiError> {
@@ -156,13 +163,16 @@ impl AccountApi {
         let signed_request = self.auth.create_signed_request("info", &info_request)?;
         let headers = self.auth.get_headers()?;
 
+        self.rate_limiter.acquire(RequestKind::InfoLight).await?;
+
         let response = self.auth.client
-            .post(&format!("{}/info", self.config.base_url))
+            .post(format!("{}/info", self.config.base_url))
             .headers(headers)
             .json(&signed_request)
             .send()
             .await
             .map_err(|e| ApiError::NetworkError(e.to_string()))?;
+        self.rate_limiter.check_response(&response)?;
 
         if !response.status().is_success() {
             return Err(ApiError::NetworkError(
@@ -195,6 +205,7 @@ impl AccountApi {
 
     pub async fn get_fills(&self, start_time: Option<u64>, end_time: Option<u64>) -> Result<Vec<HyperLiquidFill>, ApiError> {
         let fills_request = HyperLiquidFillsRequest {
+            type_: "userFillsByTime".to_string(),
             user: self.auth.account_id.map(|id| id.to_string()),
             start_time,
             end_time,
@@ -203,13 +214,16 @@ impl AccountApi {
         let signed_request = self.auth.create_signed_request("info", &fills_request)?;
         let headers = self.auth.get_headers()?;
 
+        self.rate_limiter.acquire(RequestKind::InfoHeavy).await?;
+
         let response = self.auth.client
-            .post(&format!("{}/info", self.config.base_url))
+            .post(format!("{}/info", self.config.base_url))
             .headers(headers)
             .json(&signed_request)
             .send()
             .await
             .map_err(|e| ApiError::NetworkError(e.to_string()))?;
+        self.rate_limiter.check_response(&response)?;
 
         if !response.status().is_success() {
             return Err(ApiError::NetworkError(
@@ -231,33 +245,194 @@ impl AccountApi {
         Ok(fills_response.response.unwrap_or_default())
     }
 
-    async fn update_positions_from_account_info(&self, account_info: &HyperLiquidAccountInfo) {
-        for hl_position in &account_info.asset_positions {
-            if let (Ok(size), Ok(entry_price), Ok(unrealized_pnl)) = (
-                Decimal::from_str(&hl_position.szi),
-                Decimal::from_str(&hl_position.entry_px),
-                Decimal::from_str(&hl_position.unrealized_pnl),
-            ) {
-                let position = Position {
-                    symbol: hl_position.coin.clone(),
-                    size,
-                    entry_price,
-                    mark_price: entry_price, // Use entry price as mark price for now
-                    unrealized_pnl,
-                    realized_pnl: Decimal::ZERO,
-                    updated_at: chrono::Utc::now(),
-                };
+    /// Fetches every fill in `[start, end]`, paging past the per-response cap by
+    /// advancing the window to the last returned fill's time. Fills are deduped by hash
+    /// since consecutive pages overlap on that timestamp.
+    pub async fn get_all_fills(&self, start: u64, end: u64) -> Result<Vec<HyperLiquidFill>, ApiError> {
+        let mut fills = Vec::new();
+        let mut seen = HashSet::new();
+        let mut page_start = start;
 
-                self.positions.insert(hl_position.coin.clone(), position.clone());
+        while page_start <= end {
+            let page = self.get_fills(Some(page_start), Some(end)).await?;
+            let page_len = page.len();
+            let last_time = page.iter().map(|f| f.time).max();
 
-                // Send position update event
-                let _ = self.account_events_tx.send(ApiEvent::PositionUpdate {
-                    coin: hl_position.coin.clone(),
-                    size: hl_position.szi.clone(),
-                    entry_price: hl_position.entry_px.clone(),
-                    unrealized_pnl: hl_position.unrealized_pnl.clone(),
-                });
+            let mut added = 0;
+            for fill in page {
+                if seen.insert(fill.hash.clone()) {
+                    fills.push(fill);
+                    added += 1;
+                }
             }
+
+            if page_len < FILLS_PAGE_SIZE {
+                break;
+            }
+
+            page_start = match last_time {
+                Some(last_time) if last_time > page_start && added > 0 => last_time,
+                _ => {
+                    // A full page that doesn't move the window forward would repeat forever
+                    warn!("Fills page at {} made no progress, skipping ahead 1ms", page_start);
+                    page_start + 1
+                }
+            };
+        }
+
+        fills.sort_by_key(|f| f.time);
+        Ok(fills)
+    }
+
+    /// Rebuilds positions and PnL for `[start, end]` from the fill history alone.
+    pub async fn reconstruct_pnl(&self, start: u64, end: u64) -> Result<PnlReconstruction, ApiError> {
+        let fills = self.get_all_fills(start, end).await?;
+        Ok(PnlReconstruction::from_fills(&fills))
+    }
+
+    /// Startup check that the configured account exists and, when trading through an
+    /// agent wallet, that the agent is approved and unexpired. Returns a descriptive
+    /// `AuthenticationError` otherwise so misconfiguration fails before any order.
+    pub async fn validate_account(&self, agent_address: Option<&str>) -> Result<(), ApiError> {
+        let account_id = self.auth.account_id.ok_or_else(|| ApiError::AuthenticationError(
+            "No account configured; set the account id before starting".to_string()
+        ))?;
+        let user = account_id.to_string();
+
+        let role: HyperLiquidUserRole = self.info_request(&HyperLiquidInfoRequest {
+            type_: "userRole".to_string(),
+            user: Some(user.clone()),
+        }, "User role").await?;
+
+        if role.role == "missing" {
+            return Err(ApiError::AuthenticationError(format!(
+                "Account {} does not exist on {}; check the account id and network",
+                user, self.config.base_url
+            )));
+        }
+
+        let Some(agent_address) = agent_address else {
+            info!("Account {} validated (role: {})", user, role.role);
+            return Ok(());
+        };
+
+        let agents: Vec<HyperLiquidExtraAgent> = self.info_request(&HyperLiquidInfoRequest {
+            type_: "extraAgents".to_string(),
+            user: Some(user.clone()),
+        }, "Agent approval").await?;
+
+        let now = chrono::Utc::now().timestamp_millis() as u64;
+        match agents.iter().find(|a| a.address.eq_ignore_ascii_case(agent_address)) {
+            None => Err(ApiError::AuthenticationError(format!(
+                "Agent {} is not approved for account {}; approve it from the account before trading",
+                agent_address, user
+            ))),
+            Some(agent) if agent.valid_until <= now => Err(ApiError::AuthenticationError(format!(
+                "Agent {} approval for account {} expired at {}; re-approve it before trading",
+                agent_address, user, agent.valid_until
+            ))),
+            Some(agent) => {
+                info!("Account {} validated with agent {} ({})", user, agent_address, agent.name);
+                Ok(())
+            }
+        }
+    }
+
+    /// Fetches the account's current maker and taker rates, which follow its volume
+    /// tier and any referral or staking discount.
+    pub async fn get_fee_rates(&self) -> Result<FeeRates, ApiError> {
+        let fees: HyperLiquidUserFees = self.info_request(&HyperLiquidInfoRequest {
+            type_: "userFees".to_string(),
+            user: self.auth.account_id.map(|id| id.to_string()),
+        }, "User fees").await?;
+
+        let maker_rate = Decimal::from_str(&fees.user_add_rate)
+            .map_err(|e| ApiError::ParseError(format!("Invalid maker rate {}: {}", fees.user_add_rate, e)))?;
+        let taker_rate = Decimal::from_str(&fees.user_cross_rate)
+            .map_err(|e| ApiError::ParseError(format!("Invalid taker rate {}: {}", fees.user_cross_rate, e)))?;
+
+        Ok(FeeRates { maker_rate, taker_rate })
+    }
+
+    pub async fn refresh_fee_rates(&self) -> Result<FeeRates, ApiError> {
+        let rates = self.get_fee_rates().await?;
+        let previous = std::mem::replace(&mut *self.fee_rates.write(), rates);
+        if previous != rates {
+            info!("Fee tier updated: maker {} taker {}", rates.maker_rate, rates.taker_rate);
+        }
+        Ok(rates)
+    }
+
+    pub fn get_cached_fee_rates(&self) -> FeeRates {
+        *self.fee_rates.read()
+    }
+
+    /// Refreshes the cached fee rates every `interval_seconds`, keeping the last known
+    /// rates when a refresh fails.
+    pub fn start_fee_refresh(&self, interval_seconds: u64) {
+        let api = self.clone();
+
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval(std::time::Duration::from_secs(interval_seconds));
+
+            loop {
+                interval.tick().await;
+
+                if let Err(e) = api.refresh_fee_rates().await {
+                    warn!("Failed to refresh fee rates, keeping cached rates: {}", e);
+                }
+            }
+        });
+    }
+
+    // Sends a light info request and unwraps the `{status, response}` envelope
+    async fn info_request<Req: Serialize, Resp: DeserializeOwned>(
+        &self,
+        request: &Req,
+        what: &str,
+    ) -> Result<Resp, ApiError> {
+        let signed_request = self.auth.create_signed_request("info", request)?;
+        let headers = self.auth.get_headers()?;
+
+        self.rate_limiter.acquire(RequestKind::InfoLight).await?;
+
+        let response = self.auth.client
+            .post(format!("{}/info", self.config.base_url))
+            .headers(headers)
+            .json(&signed_request)
+            .send()
+            .await
+            .map_err(|e| ApiError::NetworkError(e.to_string()))?;
+        self.rate_limiter.check_response(&response)?;
+
+        if !response.status().is_success() {
+            return Err(ApiError::NetworkError(
+                format!("{} request failed with status: {}", what, response.status())
+            ));
+        }
+
+        let info_response: HyperLiquidInfoResponse<Resp> = response
+            .json()
+            .await
+            .map_err(|e| ApiError::ParseError(e.to_string()))?;
+
+        if info_response.status != "ok" {
+            return Err(ApiError::NetworkError(
+                format!("{} response status not ok", what)
+            ));
+        }
+
+        info_response.response
+            .ok_or_else(|| ApiError::ParseError(format!("No data in {} response", what.to_lowercase())))
+    }
+
+    pub async fn get_funding_history(&self, start: Option<u64>, end: Option<u64>) -> Result<Vec<FundingPayment>, ApiError> {
+        Self::fetch_funding_history(&self.auth, &self.config, &self.rate_limiter, start, end).await
+    }
+
+    async fn update_positions_from_account_info(&self, account_info: &HyperLiquidAccountInfo) {
+        for hl_position in &account_info.asset_positions {
+            Self::apply_position(&self.positions, &self.account_events_tx, hl_position);
         }
 
         // Send account update event
@@ -268,6 +443,39 @@ impl AccountApi {
         });
     }
 
+    // Caches one exchange position and announces it. Parse problems are logged and sent
+    // as ApiEvent::Error; a position whose size can't be read keeps its cached value
+    fn apply_position(positions: &DashMap<String, Position>, account_events_tx: &Sender<ApiEvent>, hl_position: &HyperLiquidPosition) {
+        let previous = positions.get(&hl_position.coin).map(|entry| entry.value().clone());
+        let (position, warnings) = match parse_position(hl_position, previous.as_ref()) {
+            Ok(parsed) => parsed,
+            Err(e) => {
+                error!("Keeping last known {} position: {}", hl_position.coin, e);
+                let _ = account_events_tx.send(ApiEvent::Error {
+                    error: e.to_string(),
+                    timestamp: chrono::Utc::now().timestamp_millis() as u64,
+                });
+                return;
+            }
+        };
+        for warning in warnings {
+            warn!("{}", warning);
+            let _ = account_events_tx.send(ApiEvent::Error {
+                error: warning,
+                timestamp: chrono::Utc::now().timestamp_millis() as u64,
+            });
+        }
+
+        // Parsed values rather than the raw strings, so listeners never see the bad field
+        let _ = account_events_tx.send(ApiEvent::PositionUpdate {
+            coin: position.symbol.clone(),
+            size: position.size.to_string(),
+            entry_price: position.entry_price.to_string(),
+            unrealized_pnl: position.unrealized_pnl.to_string(),
+        });
+        positions.insert(position.symbol.clone(), position);
+    }
+
     pub fn get_cached_position(&self, symbol: &str) -> Option<Position> {
         self.positions.get(symbol).map(|entry| entry.value().clone())
     }
@@ -292,9 +500,11 @@ impl AccountApi {
         let positions = Arc::clone(&self.positions);
         let account_info = Arc::clone(&self.account_info);
         let last_update = Arc::clone(&self.last_update);
+        let last_funding_time = Arc::clone(&self.last_funding_time);
         let account_events_tx = self.account_events_tx.clone();
         let auth = self.auth.clone();
         let config = self.config.clone();
+        let rate_limiter = Arc::clone(&self.rate_limiter);
 
         tokio::spawn(async move {
             let mut interval = tokio::time::interval(std::time::Duration::from_secs(interval_seconds));
@@ -302,7 +512,7 @@ impl AccountApi {
             loop {
                 interval.tick().await;
                 
-                match Self::fetch_account_info(&auth, &config).await {
+                match Self::fetch_account_info(&auth, &config, &rate_limiter).await {
                     Ok(info) => {
                         // Update cached data
                         {
@@ -317,30 +527,7 @@ impl AccountApi {
 
                         // Update positions
                         for hl_position in &info.asset_positions {
-                            if let (Ok(size), Ok(entry_price), Ok(unrealized_pnl)) = (
-                                Decimal::from_str(&hl_position.szi),
-                                Decimal::from_str(&hl_position.entry_px),
-                                Decimal::from_str(&hl_position.unrealized_pnl),
-                            ) {
-                                let position = Position {
-                                    symbol: hl_position.coin.clone(),
-                                    size,
-                                    entry_price,
-                                    mark_price: entry_price,
-                                    unrealized_pnl,
-                                    realized_pnl: Decimal::ZERO,
-                                    updated_at: chrono::Utc::now(),
-                                };
-
-                                positions.insert(hl_position.coin.clone(), position.clone());
-
-                                let _ = account_events_tx.send(ApiEvent::PositionUpdate {
-                                    coin: hl_position.coin.clone(),
-                                    size: hl_position.szi.clone(),
-                                    entry_price: hl_position.entry_px.clone(),
-                                    unrealized_pnl: hl_position.unrealized_pnl.clone(),
-                                });
-                            }
+                            Self::apply_position(&positions, &account_events_tx, hl_position);
                         }
 
                         let _ = account_events_tx.send(ApiEvent::AccountUpdate {
@@ -355,6 +542,29 @@ impl AccountApi {
                         error!("Failed to fetch account info during periodic update: {}", e);
                     }
                 }
+
+                // Emit funding payments received since the last poll
+                let since = *last_funding_time.read() + 1;
+                match Self::fetch_funding_history(&auth, &config, &rate_limiter, Some(since), None).await {
+                    Ok(payments) => {
+                        for payment in payments {
+                            {
+                                let mut last_time = last_funding_time.write();
+                                *last_time = (*last_time).max(payment.time);
+                            }
+
+                            let _ = account_events_tx.send(ApiEvent::FundingPayment {
+                                coin: payment.coin.clone(),
+                                amount: payment.amount.to_string(),
+                                rate: payment.rate.to_string(),
+                                timestamp: payment.time,
+                            });
+                        }
+                    }
+                    Err(e) => {
+                        error!("Failed to fetch funding history during periodic update: {}", e);
+                    }
+                }
             }
         });
     }
@@ -362,6 +572,7 @@ impl AccountApi {
     async fn fetch_account_info(
         auth: &HyperLiquidAuth,
         config: &ApiConfig,
+        rate_limiter: &RateLimiter,
     ) -> Result<HyperLiquidAccountInfo, ApiError> {
         let info_request = HyperLiquidInfoRequest {
             type_: "clearinghouseState".to_string(),
@@ -371,13 +582,16 @@ impl AccountApi {
         let signed_request = auth.create_signed_request("info", &info_request)?;
         let headers = auth.get_headers()?;
 
+        rate_limiter.acquire(RequestKind::InfoLight).await?;
+
         let response = auth.client
-            .post(&format!("{}/info", config.base_url))
+            .post(format!("{}/info", config.base_url))
             .headers(headers)
             .json(&signed_request)
             .send()
             .await
             .map_err(|e| ApiError::NetworkError(e.to_string()))?;
+        rate_limiter.check_response(&response)?;
 
         if !response.status().is_success() {
             return Err(ApiError::NetworkError(
@@ -405,6 +619,196 @@ impl AccountApi {
             asset_positions: user_state.asset_positions,
         })
     }
+
+    async fn fetch_funding_history(
+        auth: &HyperLiquidAuth,
+        config: &ApiConfig,
+        rate_limiter: &RateLimiter,
+        start_time: Option<u64>,
+        end_time: Option<u64>,
+    ) -> Result<Vec<FundingPayment>, ApiError> {
+        let funding_request = HyperLiquidFundingRequest {
+            type_: "userFunding".to_string(),
+            user: auth.account_id.map(|id| id.to_string()),
+            start_time,
+            end_time,
+        };
+
+        let signed_request = auth.create_signed_request("info", &funding_request)?;
+        let headers = auth.get_headers()?;
+
+        rate_limiter.acquire(RequestKind::InfoHeavy).await?;
+
+        let response = auth.client
+            .post(format!("{}/info", config.base_url))
+            .headers(headers)
+            .json(&signed_request)
+            .send()
+            .await
+            .map_err(|e| ApiError::NetworkError(e.to_string()))?;
+        rate_limiter.check_response(&response)?;
+
+        if !response.status().is_success() {
+            return Err(ApiError::NetworkError(
+                format!("Funding history request failed with status: {}", response.status())
+            ));
+        }
+
+        let funding_response: HyperLiquidFundingResponse = response
+            .json()
+            .await
+            .map_err(|e| ApiError::ParseError(e.to_string()))?;
+
+        if funding_response.status != "ok" {
+            return Err(ApiError::NetworkError(
+                "Funding history response status not ok".to_string()
+            ));
+        }
+
+        funding_response.response
+            .unwrap_or_default()
+            .iter()
+            .map(parse_funding_entry)
+            .collect()
+    }
+}
+
+fn parse_funding_entry(entry: &HyperLiquidFundingEntry) -> Result<FundingPayment, ApiError> {
+    let amount = Decimal::from_str(&entry.delta.usdc)
+        .map_err(|e| ApiError::ParseError(format!("Failed to parse funding amount: {}", e)))?;
+    let rate = Decimal::from_str(&entry.delta.funding_rate)
+        .map_err(|e| ApiError::ParseError(format!("Failed to parse funding rate: {}", e)))?;
+
+    Ok(FundingPayment {
+        coin: entry.delta.coin.clone(),
+        amount,
+        rate,
+        time: entry.time,
+    })
+}
+
+/// Converts an `assetPositions` entry into a `Position`. Only the size is required: an
+/// unreadable entry price falls back to `previous`'s (or zero) and unreadable PnL to
+/// zero, each described in the returned warnings.
+pub fn parse_position(hl_position: &HyperLiquidPosition, previous: Option<&Position>) -> Result<(Position, Vec<String>), ApiError> {
+    let size = Decimal::from_str(&hl_position.szi).map_err(|e| ApiError::ParseError(
+        format!("Invalid size {} for {} position: {}", hl_position.szi, hl_position.coin, e)
+    ))?;
+
+    let mut warnings = Vec::new();
+    let entry_price = Decimal::from_str(&hl_position.entry_px).unwrap_or_else(|e| {
+        let fallback = previous.map(|p| p.entry_price).unwrap_or(Decimal::ZERO);
+        warnings.push(format!("Invalid entry price {} for {} position, using {}: {}", hl_position.entry_px, hl_position.coin, fallback, e));
+        fallback
+    });
+    let unrealized_pnl = Decimal::from_str(&hl_position.unrealized_pnl).unwrap_or_else(|e| {
+        warnings.push(format!("Invalid unrealized PnL {} for {} position, using 0: {}", hl_position.unrealized_pnl, hl_position.coin, e));
+        Decimal::ZERO
+    });
+
+    let position = Position {
+        symbol: hl_position.coin.clone(),
+        size,
+        entry_price,
+        mark_price: entry_price, // Use entry price as mark price for now
+        unrealized_pnl,
+        realized_pnl: Decimal::ZERO, // This would need to be tracked separately
+        updated_at: chrono::Utc::now(),
+    };
+    Ok((position, warnings))
+}
+
+/// Converts a resting order from `openOrders` into an `Order` under a fresh internal id.
+pub fn parse_open_order(order: &HyperLiquidOrderRest) -> Result<Order, ApiError> {
+    let parse = |field: &str, value: &str| {
+        Decimal::from_str(value).map_err(|e| ApiError::ParseError(
+            format!("Invalid {} {} for order {}: {}", field, value, order.oid, e)
+        ))
+    };
+
+    let size = parse("size", &order.total_sz)?;
+    let remaining_size = parse("remaining size", &order.sz)?;
+    let filled_size = size - remaining_size;
+    let created_at = chrono::DateTime::from_timestamp_millis(order.timestamp as i64)
+        .unwrap_or_else(chrono::Utc::now);
+
+    Ok(Order {
+        id: uuid::Uuid::new_v4(),
+        client_id: order.cloid.clone(),
+        symbol: order.coin.clone(),
+        side: if order.side == "B" { Side::Buy } else { Side::Sell },
+        order_type: OrderType::Limit,
+        price: parse("price", &order.px)?,
+        size,
+        filled_size,
+        remaining_size,
+        status: if filled_size > Decimal::ZERO { OrderStatus::PartiallyFilled } else { OrderStatus::Submitted },
+        created_at,
+        updated_at: chrono::Utc::now(),
+        exchange_oid: Some(order.oid),
+    })
+}
+
+/// Reads a wire side: "B"/"A" as the exchange sends them, or "buy"/"sell".
+pub fn parse_side(side: &str) -> Result<Side, ApiError> {
+    match side.to_ascii_lowercase().as_str() {
+        "b" | "buy" => Ok(Side::Buy),
+        "a" | "sell" => Ok(Side::Sell),
+        _ => Err(ApiError::ParseError(format!("Invalid side {}", side))),
+    }
+}
+
+/// Reads an order update status. An "open" order with fills is partially filled;
+/// statuses with no internal equivalent give `None`.
+pub fn parse_order_status(status: &str, filled_size: Decimal) -> Option<OrderStatus> {
+    match status {
+        "open" if filled_size > Decimal::ZERO => Some(OrderStatus::PartiallyFilled),
+        "open" => Some(OrderStatus::Submitted),
+        "filled" => Some(OrderStatus::Filled),
+        "canceled" | "cancelled" => Some(OrderStatus::Cancelled),
+        "rejected" => Some(OrderStatus::Rejected),
+        _ => None,
+    }
+}
+
+/// Converts an exchange fill under a fresh internal id. The order id is derived from the
+/// exchange's oid; no strategy is attributed.
+impl TryFrom<&HyperLiquidFill> for Fill {
+    type Error = ApiError;
+
+    fn try_from(fill: &HyperLiquidFill) -> Result<Self, Self::Error> {
+        let parse = |field: &str, value: &str| {
+            Decimal::from_str(value).map_err(|e| ApiError::ParseError(
+                format!("Invalid {} {} for fill {}: {}", field, value, fill.hash, e)
+            ))
+        };
+
+        let timestamp = i64::try_from(fill.time).ok()
+            .and_then(chrono::DateTime::from_timestamp_millis)
+            .ok_or_else(|| ApiError::ParseError(
+            format!("Invalid time {} for fill {}", fill.time, fill.hash)
+        ))?;
+
+        Ok(Fill {
+            id: uuid::Uuid::new_v4(),
+            order_id: uuid::Uuid::from_u128(fill.oid as u128),
+            symbol: fill.coin.clone(),
+            side: parse_side(&fill.side)?,
+            price: parse("price", &fill.px)?,
+            size: parse("size", &fill.sz)?,
+            fee: parse("fee", &fill.fee)?,
+            timestamp,
+            strategy: None,
+        })
+    }
+}
+
+impl TryFrom<HyperLiquidFill> for Fill {
+    type Error = ApiError;
+
+    fn try_from(fill: HyperLiquidFill) -> Result<Self, Self::Error> {
+        Fill::try_from(&fill)
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -415,12 +819,59 @@ pub struct HyperLiquidInfoRequest {
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
 pub struct HyperLiquidFillsRequest {
+    #[serde(rename = "type")]
+    pub type_: String,
     pub user: Option<String>,
     pub start_time: Option<u64>,
     pub end_time: Option<u64>,
 }
 
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HyperLiquidFundingRequest {
+    #[serde(rename = "type")]
+    pub type_: String,
+    pub user: Option<String>,
+    pub start_time: Option<u64>,
+    pub end_time: Option<u64>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HyperLiquidFundingResponse {
+    pub status: String,
+    pub response: Option<Vec<HyperLiquidFundingEntry>>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HyperLiquidInfoResponse<T> {
+    pub status: String,
+    pub response: Option<T>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HyperLiquidUserRole {
+    pub role: String, // "user", "agent", "vault", "subAccount" or "missing"
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HyperLiquidExtraAgent {
+    pub address: String,
+    pub name: String,
+    pub valid_until: u64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HyperLiquidUserFees {
+    pub user_add_rate: String,   // Maker rate after discounts
+    pub user_cross_rate: String, // Taker rate after discounts
+    #[serde(default)]
+    pub active_referral_discount: Option<String>,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct HyperLiquidFillsResponse {
     pub status: String,
@@ -428,3 +879,254 @@ pub struct HyperLiquidFillsResponse {
 }
 
 // Clone implementation removed to avoid conflicts
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::secrets::Secret;
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+    use tokio::net::TcpListener;
+
+    fn fill(i: u64) -> HyperLiquidFill {
+        HyperLiquidFill {
+            coin: "HYPE".to_string(),
+            px: "25.0".to_string(),
+            sz: "1".to_string(),
+            side: "B".to_string(),
+            time: 1000 + i / 2, // Two fills per millisecond, so pages end mid-timestamp
+            start_position: i.to_string(),
+            dir: "Open Long".to_string(),
+            closed_pnl: "0".to_string(),
+            hash: format!("0x{:064x}", i),
+            oid: i,
+            crossed: false,
+            fee: "0.01".to_string(),
+        }
+    }
+
+    // Answers each `/info` POST with `respond(request data)` and records the request data
+    async fn mock_info_server(
+        respond: impl Fn(&serde_json::Value) -> serde_json::Value + Send + Sync + 'static,
+    ) -> (String, Arc<parking_lot::Mutex<Vec<serde_json::Value>>>) {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let base_url = format!("http://{}", listener.local_addr().unwrap());
+        let requests = Arc::new(parking_lot::Mutex::new(Vec::new()));
+        let recorded = Arc::clone(&requests);
+
+        tokio::spawn(async move {
+            while let Ok((mut socket, _)) = listener.accept().await {
+                let mut buf = Vec::new();
+                let mut chunk = [0u8; 4096];
+                let body = loop {
+                    let n = socket.read(&mut chunk).await.unwrap();
+                    buf.extend_from_slice(&chunk[..n]);
+                    let Some(header_end) = buf.windows(4).position(|w| w == b"\r\n\r\n") else { continue };
+                    let headers = String::from_utf8_lossy(&buf[..header_end]).to_lowercase();
+                    let content_length: usize = headers.lines()
+                        .find_map(|line| line.strip_prefix("content-length:"))
+                        .map(|len| len.trim().parse().unwrap())
+                        .unwrap_or(0);
+                    if buf.len() >= header_end + 4 + content_length {
+                        break buf[header_end + 4..header_end + 4 + content_length].to_vec();
+                    }
+                };
+
+                let request: serde_json::Value = serde_json::from_slice(&body).unwrap();
+                let response = respond(&request["data"]).to_string();
+                recorded.lock().push(request["data"].clone());
+                let reply = format!(
+                    "HTTP/1.1 200 OK\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                    response.len(), response
+                );
+                socket.write_all(reply.as_bytes()).await.unwrap();
+            }
+        });
+
+        (base_url, requests)
+    }
+
+    #[tokio::test]
+    async fn all_fills_pages_through_the_window_and_dedupes_page_boundaries() {
+        // 4010 fills: two full pages and a short third, each page after the first
+        // starting on the last page's final timestamp and so repeating its fills there
+        let history: Vec<HyperLiquidFill> = (0..4010).map(fill).collect();
+        let (base_url, requests) = mock_info_server(move |data| {
+            assert_eq!(data["type"], "userFillsByTime");
+            let start = data["startTime"].as_u64().unwrap();
+            let end = data["endTime"].as_u64().unwrap();
+            let page: Vec<_> = history.iter()
+                .filter(|f| f.time >= start && f.time <= end)
+                .take(FILLS_PAGE_SIZE)
+                .collect();
+            serde_json::json!({ "status": "ok", "response": page })
+        }).await;
+
+        let config = ApiConfig { base_url, ..ApiConfig::default() };
+        let auth = HyperLiquidAuth::new(Secret::new("test".to_string())).with_account_id(1);
+        let (api, _rx) = AccountApi::new(auth, config);
+
+        let fills = api.get_all_fills(1000, 10_000).await.unwrap();
+
+        let start_times: Vec<_> = requests.lock().iter().map(|r| r["startTime"].as_u64().unwrap()).collect();
+        assert_eq!(start_times, [1000, 1999, 2998]);
+        assert_eq!(fills.len(), 4010);
+        assert_eq!(fills.iter().map(|f| f.hash.clone()).collect::<HashSet<_>>().len(), 4010);
+        assert!(fills.windows(2).all(|w| w[0].time <= w[1].time));
+        assert_eq!(fills.last().unwrap().time, 1000 + 4009 / 2);
+    }
+
+    #[tokio::test]
+    async fn clients_sharing_a_rate_limiter_share_its_budget() {
+        let (base_url, requests) = mock_info_server(|_| serde_json::json!({ "status": "ok", "response": [] })).await;
+        let config = ApiConfig {
+            base_url,
+            rate_limit: RateLimitConfig { budget_per_minute: 40, max_wait_ms: 0, ..RateLimitConfig::default() },
+            ..ApiConfig::default()
+        };
+        let limiter = Arc::new(RateLimiter::new(config.rate_limit.clone()));
+        let auth = HyperLiquidAuth::new(Secret::new("test".to_string())).with_account_id(1);
+        let first = AccountApi::new(auth.clone(), config.clone()).0.with_rate_limiter(Arc::clone(&limiter));
+        let second = AccountApi::new(auth, config).0.with_rate_limiter(limiter);
+
+        first.get_fills(None, None).await.unwrap();
+        second.get_fills(None, None).await.unwrap();
+        assert!(matches!(first.get_fills(None, None).await, Err(ApiError::RateLimitError { .. })));
+        assert_eq!(requests.lock().len(), 2);
+    }
+
+    // A userFunding response body in the exchange's shape
+    const USER_FUNDING: &str = include_str!(concat!(env!("CARGO_MANIFEST_DIR"), "/fixtures/user_funding.json"));
+
+    #[tokio::test]
+    async fn funding_history_parses_a_user_funding_response() {
+        let entries: serde_json::Value = serde_json::from_str(USER_FUNDING).unwrap();
+        let (base_url, requests) = mock_info_server(move |_| serde_json::json!({ "status": "ok", "response": entries })).await;
+        let auth = HyperLiquidAuth::new(Secret::new("test".to_string())).with_account_id(1);
+        let (api, _rx) = AccountApi::new(auth, ApiConfig { base_url, ..ApiConfig::default() });
+
+        let payments = api.get_funding_history(Some(1_760_000_000_000), None).await.unwrap();
+
+        let request = &requests.lock()[0];
+        assert_eq!(request["type"], "userFunding");
+        assert_eq!(request["startTime"], 1_760_000_000_000u64);
+        let parsed: Vec<_> = payments.iter().map(|p| (p.coin.as_str(), p.amount, p.rate, p.time)).collect();
+        assert_eq!(parsed, [
+            ("HYPE", Decimal::from_str("-0.037639").unwrap(), Decimal::from_str("0.0000125").unwrap(), 1_760_000_400_052),
+            ("BTC", Decimal::from_str("-0.088094").unwrap(), Decimal::from_str("-0.0000031").unwrap(), 1_760_000_400_052),
+            ("HYPE", Decimal::from_str("0.026198").unwrap(), Decimal::from_str("-0.0000087").unwrap(), 1_760_004_000_118),
+        ]);
+    }
+
+    #[test]
+    fn an_unreadable_funding_amount_is_a_parse_error() {
+        let mut entries: Vec<HyperLiquidFundingEntry> = serde_json::from_str(USER_FUNDING).unwrap();
+        entries[0].delta.usdc = "n/a".to_string();
+
+        assert!(matches!(parse_funding_entry(&entries[0]), Err(ApiError::ParseError(_))));
+        assert!(parse_funding_entry(&entries[1]).is_ok());
+    }
+
+    #[tokio::test]
+    async fn an_unapproved_or_expired_agent_fails_validation_with_a_descriptive_error() {
+        let (base_url, _requests) = mock_info_server(|data| {
+            let response = match (data["type"].as_str().unwrap(), data["user"].as_str().unwrap()) {
+                ("userRole", "2") => serde_json::json!({ "role": "missing" }),
+                ("userRole", _) => serde_json::json!({ "role": "user" }),
+                ("extraAgents", _) => serde_json::json!([
+                    { "address": "0xAbC1", "name": "hedger", "validUntil": u64::MAX },
+                    { "address": "0xdef2", "name": "old", "validUntil": 1_000 },
+                ]),
+                (other, _) => panic!("unexpected request {}", other),
+            };
+            serde_json::json!({ "status": "ok", "response": response })
+        }).await;
+        let config = ApiConfig { base_url, ..ApiConfig::default() };
+        let api = |account_id| {
+            let auth = HyperLiquidAuth::new(Secret::new("test".to_string())).with_account_id(account_id);
+            AccountApi::new(auth, config.clone()).0
+        };
+
+        let error_of = |result: Result<(), ApiError>| match result {
+            Err(ApiError::AuthenticationError(message)) => message,
+            other => panic!("expected an authentication error, got {:?}", other),
+        };
+        assert_eq!(
+            error_of(api(1).validate_account(Some("0x9999")).await),
+            "Agent 0x9999 is not approved for account 1; approve it from the account before trading"
+        );
+        assert!(error_of(api(1).validate_account(Some("0xdef2")).await).contains("expired at 1000"));
+        assert!(error_of(api(2).validate_account(None).await).contains("Account 2 does not exist"));
+
+        // Addresses compare case-insensitively
+        api(1).validate_account(Some("0xabc1")).await.unwrap();
+        api(1).validate_account(None).await.unwrap();
+    }
+
+    fn hl_position(szi: &str, entry_px: &str, unrealized_pnl: &str) -> HyperLiquidPosition {
+        HyperLiquidPosition {
+            coin: "HYPE".to_string(),
+            szi: szi.to_string(),
+            entry_px: entry_px.to_string(),
+            position_value: "50".to_string(),
+            unrealized_pnl: unrealized_pnl.to_string(),
+            margin_used: "10".to_string(),
+        }
+    }
+
+    #[test]
+    fn a_position_with_unreadable_pnl_is_still_tracked_by_its_size() {
+        let positions = DashMap::new();
+        let (tx, rx) = unbounded();
+
+        AccountApi::apply_position(&positions, &tx, &hl_position("2", "25", "NaN-ish"));
+
+        let position = positions.get("HYPE").unwrap().clone();
+        assert_eq!((position.size, position.entry_price, position.unrealized_pnl), (Decimal::from(2), Decimal::from(25), Decimal::ZERO));
+        let events: Vec<ApiEvent> = rx.try_iter().collect();
+        assert!(matches!(&events[0], ApiEvent::Error { error, .. } if error.starts_with("Invalid unrealized PnL NaN-ish for HYPE")));
+        assert!(matches!(&events[1], ApiEvent::PositionUpdate { size, unrealized_pnl, .. } if size == "2" && unrealized_pnl == "0"));
+
+        // A bad entry price keeps the last known one; a bad size keeps the whole position
+        AccountApi::apply_position(&positions, &tx, &hl_position("3", "", "1.5"));
+        let position = positions.get("HYPE").unwrap().clone();
+        assert_eq!((position.size, position.entry_price, position.unrealized_pnl), (Decimal::from(3), Decimal::from(25), Decimal::new(15, 1)));
+        AccountApi::apply_position(&positions, &tx, &hl_position("three", "26", "0"));
+        assert_eq!(positions.get("HYPE").unwrap().size, Decimal::from(3));
+    }
+
+    #[test]
+    fn a_fill_converts_from_either_side_encoding() {
+        for (side, expected) in [("B", Side::Buy), ("A", Side::Sell), ("buy", Side::Buy), ("Sell", Side::Sell)] {
+            let converted = Fill::try_from(HyperLiquidFill { side: side.to_string(), ..fill(7) }).unwrap();
+            assert_eq!(converted.side, expected, "side {}", side);
+        }
+
+        let converted = Fill::try_from(&fill(7)).unwrap();
+        assert_eq!((converted.symbol.as_str(), converted.price, converted.size, converted.fee), ("HYPE", Decimal::new(250, 1), Decimal::ONE, Decimal::new(1, 2)));
+        assert_eq!(converted.timestamp.timestamp_millis(), 1003);
+        assert_eq!(converted.order_id, uuid::Uuid::from_u128(7));
+        assert_eq!(converted.strategy, None);
+    }
+
+    #[test]
+    fn a_malformed_fill_is_a_parse_error_naming_the_field() {
+        let invalid = [
+            HyperLiquidFill { side: "X".to_string(), ..fill(1) },
+            HyperLiquidFill { px: "".to_string(), ..fill(1) },
+            HyperLiquidFill { sz: "one".to_string(), ..fill(1) },
+            HyperLiquidFill { fee: "0.0.1".to_string(), ..fill(1) },
+            HyperLiquidFill { time: u64::MAX, ..fill(1) },
+        ];
+        let errors: Vec<String> = invalid.into_iter()
+            .map(|fill| match Fill::try_from(fill) {
+                Err(ApiError::ParseError(e)) => e,
+                other => panic!("unexpected {:?}", other),
+            })
+            .collect();
+        assert!(errors[0].starts_with("Invalid side X"), "{}", errors[0]);
+        assert!(errors[1].starts_with("Invalid price  for fill"), "{}", errors[1]);
+        assert!(errors[2].starts_with("Invalid size one"), "{}", errors[2]);
+        assert!(errors[3].starts_with("Invalid fee 0.0.1"), "{}", errors[3]);
+        assert!(errors[4].starts_with(&format!("Invalid time {}", u64::MAX)), "{}", errors[4]);
+    }
+}
@@ -0,0 +1,186 @@
+use crate::api::market_info::HyperLiquidMeta;
+use crate::api::types::ApiError;
+use crate::trading::types::NewOrder;
+use crate::utils::decimal;
+use dashmap::DashMap;
+use rust_decimal::{Decimal, RoundingStrategy};
+
+// HyperLiquid perp prices allow at most 5 significant figures and 6 - szDecimals decimals
+const MAX_PRICE_SIG_FIGS: u32 = 5;
+const MAX_PERP_PRICE_DECIMALS: u32 = 6;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AssetInfo {
+    pub symbol: String,
+    pub index: u32, // Position in the `meta` universe, used as the order's asset id
+    pub sz_decimals: u32,
+    pub max_leverage: u32,
+}
+
+/// Per-asset metadata from the `meta` info request, used to address orders by asset
+/// index and to round them to the precision the exchange accepts.
+#[derive(Debug, Default)]
+pub struct AssetRegistry {
+    assets: DashMap<String, AssetInfo>,
+}
+
+impl AssetRegistry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn from_meta(meta: &HyperLiquidMeta) -> Self {
+        let registry = Self::new();
+        registry.load(meta);
+        registry
+    }
+
+    /// Replaces the registry contents with the assets in `meta`.
+    pub fn load(&self, meta: &HyperLiquidMeta) {
+        self.assets.clear();
+        for (index, asset) in meta.universe.iter().enumerate() {
+            self.assets.insert(asset.name.clone(), AssetInfo {
+                symbol: asset.name.clone(),
+                index: index as u32,
+                sz_decimals: asset.sz_decimals,
+                max_leverage: asset.max_leverage,
+            });
+        }
+    }
+
+    pub fn get(&self, symbol: &str) -> Result<AssetInfo, ApiError> {
+        self.assets.get(symbol)
+            .map(|asset| asset.value().clone())
+            .ok_or_else(|| ApiError::InvalidOrder(format!("Unknown asset: {}", symbol)))
+    }
+
+    pub fn len(&self) -> usize {
+        self.assets.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.assets.is_empty()
+    }
+
+    /// Rounds to the nearest valid price: 5 significant figures and at most
+    /// `6 - szDecimals` decimals. Integer prices are always valid.
+    pub fn round_price(&self, symbol: &str, px: Decimal) -> Result<Decimal, ApiError> {
+        let asset = self.get(symbol)?;
+        if px.fract().is_zero() {
+            return Ok(px.normalize());
+        }
+
+        let max_decimals = MAX_PERP_PRICE_DECIMALS.saturating_sub(asset.sz_decimals);
+        let sig_fig_decimals = (MAX_PRICE_SIG_FIGS as i64 - magnitude(px)).max(0) as u32;
+
+        Ok(px.round_dp_with_strategy(max_decimals.min(sig_fig_decimals), RoundingStrategy::MidpointAwayFromZero)
+            .normalize())
+    }
+
+    /// Truncates to `szDecimals`, so rounding never grows an order.
+    pub fn round_size(&self, symbol: &str, sz: Decimal) -> Result<Decimal, ApiError> {
+        let asset = self.get(symbol)?;
+        Ok(decimal::round_size(sz, asset.sz_decimals))
+    }
+
+    pub fn normalize_order(&self, order: &NewOrder) -> Result<NewOrder, ApiError> {
+        let size = self.round_size(&order.symbol, order.size)?;
+        if size.is_zero() {
+            return Err(ApiError::InvalidOrder(format!(
+                "Order size {} rounds to zero for {}", order.size, order.symbol
+            )));
+        }
+
+        Ok(NewOrder {
+            price: self.round_price(&order.symbol, order.price)?,
+            size,
+            ..order.clone()
+        })
+    }
+}
+
+// Number of digits before the decimal point, or minus the leading zeros after it
+// for values below one (0.0123 -> -1)
+fn magnitude(px: Decimal) -> i64 {
+    let mut value = px.abs();
+    if value >= Decimal::ONE {
+        return value.trunc().to_string().len() as i64;
+    }
+
+    let mut leading_zeros = 0;
+    while value < Decimal::ONE && !value.is_zero() {
+        value *= Decimal::TEN;
+        leading_zeros += 1;
+    }
+    1 - leading_zeros
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::trading::types::{OrderType, Side};
+    use rust_decimal_macros::dec;
+
+    fn registry() -> AssetRegistry {
+        let meta = serde_json::from_value(serde_json::json!({
+            "universe": [
+                { "name": "BTC", "szDecimals": 5, "maxLeverage": 40 },
+                { "name": "HYPE", "szDecimals": 2, "maxLeverage": 5 },
+                { "name": "PURR", "szDecimals": 0, "maxLeverage": 3 }
+            ]
+        })).unwrap();
+        AssetRegistry::from_meta(&meta)
+    }
+
+    fn order(symbol: &str, price: Decimal, size: Decimal) -> NewOrder {
+        NewOrder {
+            symbol: symbol.to_string(),
+            side: Side::Buy,
+            order_type: OrderType::Limit,
+            price,
+            size,
+            client_id: None,
+            reduce_only: false,
+            tick_received_at: None,
+        }
+    }
+
+    #[test]
+    fn prices_round_to_five_significant_figures_within_the_decimal_limit() {
+        let assets = registry();
+        assert_eq!(assets.get("HYPE").unwrap().index, 1);
+
+        // Five significant figures leave no decimals at this size
+        assert_eq!(assets.round_price("BTC", dec!(60123.4)).unwrap(), dec!(60123));
+        // Integer prices are valid however many figures they have
+        assert_eq!(assets.round_price("BTC", dec!(100000)).unwrap(), dec!(100000));
+        assert_eq!(assets.round_price("HYPE", dec!(25.12345)).unwrap(), dec!(25.123));
+        assert_eq!(assets.round_price("HYPE", dec!(25.1235)).unwrap(), dec!(25.124));
+        // Leading zeros don't count towards the five, but 6 - szDecimals caps the decimals
+        assert_eq!(assets.round_price("PURR", dec!(0.01234567)).unwrap(), dec!(0.012346));
+        assert_eq!(assets.round_price("HYPE", dec!(0.01234567)).unwrap(), dec!(0.0123));
+    }
+
+    #[test]
+    fn sizes_are_truncated_and_a_size_rounding_to_zero_is_rejected() {
+        let assets = registry();
+        let normalized = assets.normalize_order(&order("HYPE", dec!(25.12345), dec!(1.239))).unwrap();
+        assert_eq!((normalized.price, normalized.size), (dec!(25.123), dec!(1.23)));
+        assert_eq!(assets.round_size("PURR", dec!(12.9)).unwrap(), dec!(12));
+
+        assert!(matches!(
+            assets.normalize_order(&order("HYPE", dec!(25), dec!(0.004))),
+            Err(ApiError::InvalidOrder(message)) if message.contains("rounds to zero")
+        ));
+    }
+
+    #[test]
+    fn an_unknown_symbol_is_an_invalid_order() {
+        let assets = registry();
+        assert!(matches!(
+            assets.normalize_order(&order("DOGE", dec!(0.1), dec!(10))),
+            Err(ApiError::InvalidOrder(message)) if message == "Unknown asset: DOGE"
+        ));
+        assert!(matches!(assets.round_price("DOGE", dec!(0.1)), Err(ApiError::InvalidOrder(_))));
+    }
+}
@@ -1,4 +1,5 @@
 use crate::api::types::ApiError;
+use crate::config::secrets::Secret;
 use anyhow::Result;
 use reqwest::Client;
 use serde::{Deserialize, Serialize};
@@ -6,19 +7,32 @@ use std::time::{SystemTime, UNIX_EPOCH};
 
 #[derive(Debug, Clone)]
 pub struct HyperLiquidAuth {
-    pub private_key: String,
+    pub private_key: Secret, // Debug-printing the auth never shows it
     pub account_id: Option<u64>,
+    pub agent_address: Option<String>, // Set when signing with an approved agent wallet
+    pub identity: Option<AuthIdentity>, // Set by a successful authenticate
     pub client: Client,
 }
 
+/// The account the exchange resolved our key to, and what it may do.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AuthIdentity {
+    #[serde(alias = "user")]
+    pub account: String,
+    #[serde(default)]
+    pub permissions: Vec<String>,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct AuthRequest {
     pub action: String,
     pub nonce: u64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub user: Option<String>,
 }
 
 impl HyperLiquidAuth {
-    pub fn new(private_key: String) -> Self {
+    pub fn new(private_key: Secret) -> Self {
         let client = Client::builder()
             .timeout(std::time::Duration::from_secs(30))
             .build()
@@ -27,6 +41,8 @@ impl HyperLiquidAuth {
         Self {
             private_key,
             account_id: None,
+            agent_address: None,
+            identity: None,
             client,
         }
     }
@@ -36,6 +52,11 @@ impl HyperLiquidAuth {
         self
     }
 
+    pub fn with_agent_address(mut self, agent_address: String) -> Self {
+        self.agent_address = Some(agent_address);
+        self
+    }
+
     pub fn get_nonce(&self) -> u64 {
         SystemTime::now()
             .duration_since(UNIX_EPOCH)
@@ -50,7 +71,7 @@ impl HyperLiquidAuth {
         
         let mut hasher = Sha256::new();
         hasher.update(message.as_bytes());
-        hasher.update(self.private_key.as_bytes());
+        hasher.update(self.private_key.expose().as_bytes());
         let result = hasher.finalize();
         
         Ok(hex::encode(&result))
@@ -96,10 +117,14 @@ pub struct HyperLiquidAuthResponse {
 }
 
 impl HyperLiquidAuth {
-    pub async fn authenticate(&mut self) -> Result<(), ApiError> {
+    /// Asks the exchange who our key belongs to and keeps the answer in `identity`.
+    /// Any failure leaves the auth unauthenticated.
+    pub async fn authenticate(&mut self) -> Result<&AuthIdentity, ApiError> {
+        self.identity = None;
         let auth_request = AuthRequest {
             action: "info".to_string(),
             nonce: self.get_nonce(),
+            user: self.account_id.map(|id| id.to_string()),
         };
 
         let signed_request = self.create_signed_request("info", &auth_request)?;
@@ -124,17 +149,39 @@ impl HyperLiquidAuth {
             .await
             .map_err(|e| ApiError::ParseError(e.to_string()))?;
 
+        self.apply_auth_response(auth_response)
+    }
+
+    /// Stores the identity from an authenticate response, or clears it and returns why
+    /// the response does not establish one.
+    pub fn apply_auth_response(&mut self, auth_response: HyperLiquidAuthResponse) -> Result<&AuthIdentity, ApiError> {
+        self.identity = None;
         if auth_response.status != "ok" {
             return Err(ApiError::AuthenticationError(
-                "Authentication response status not ok".to_string()
+                format!("Authentication response status {}", auth_response.status)
             ));
         }
 
-        Ok(())
+        let identity: AuthIdentity = auth_response.response
+            .ok_or_else(|| ApiError::AuthenticationError("Authentication response has no account".to_string()))
+            .and_then(|response| serde_json::from_value(response)
+                .map_err(|e| ApiError::AuthenticationError(format!("Authentication response has no account: {}", e))))?;
+        if identity.account.is_empty() {
+            return Err(ApiError::AuthenticationError("Authentication response has no account".to_string()));
+        }
+        if let Some(account_id) = self.account_id {
+            if identity.account != account_id.to_string() {
+                return Err(ApiError::AuthenticationError(
+                    format!("Key belongs to account {}, not the configured {}", identity.account, account_id)
+                ));
+            }
+        }
+
+        Ok(self.identity.insert(identity))
     }
 
     pub fn is_authenticated(&self) -> bool {
-        self.account_id.is_some()
+        self.identity.is_some()
     }
 }
 
@@ -146,3 +193,44 @@ mod hex {
             .collect()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn response(status: &str, response: serde_json::Value) -> HyperLiquidAuthResponse {
+        HyperLiquidAuthResponse { status: status.to_string(), response: Some(response) }
+    }
+
+    #[test]
+    fn a_successful_authenticate_sets_the_identity() {
+        let mut auth = HyperLiquidAuth::new(Secret::new("test".to_string())).with_account_id(7);
+        assert!(!auth.is_authenticated());
+
+        let identity = auth.apply_auth_response(response("ok", serde_json::json!({
+            "user": "7", "permissions": ["trade"]
+        }))).unwrap().clone();
+
+        assert_eq!(identity, AuthIdentity { account: "7".to_string(), permissions: vec!["trade".to_string()] });
+        assert!(auth.is_authenticated());
+        assert_eq!(auth.identity, Some(identity));
+    }
+
+    #[test]
+    fn a_failed_authenticate_leaves_the_auth_unauthenticated() {
+        let mut auth = HyperLiquidAuth::new(Secret::new("test".to_string())).with_account_id(7);
+        let failures = [
+            response("err", serde_json::json!({ "user": "7" })),
+            HyperLiquidAuthResponse { status: "ok".to_string(), response: None },
+            response("ok", serde_json::json!({ "user": "" })),
+            response("ok", serde_json::json!({ "user": "8" })),
+        ];
+
+        for failure in failures {
+            // Even an identity from an earlier success is cleared
+            auth.apply_auth_response(response("ok", serde_json::json!({ "user": "7" }))).unwrap();
+            assert!(matches!(auth.apply_auth_response(failure), Err(ApiError::AuthenticationError(_))));
+            assert!(!auth.is_authenticated());
+        }
+    }
+}
@@ -0,0 +1,192 @@
+use dashmap::DashMap;
+use parking_lot::RwLock;
+use std::sync::atomic::{AtomicU64, Ordering};
+
+// Strategy id for orders not placed by a strategy (manual, flattening, triggers)
+pub const UNATTRIBUTED_STRATEGY: u16 = 0;
+
+/// Structured HyperLiquid client order id.
+///
+/// The 128 bits are laid out most significant first as
+/// `version (8) | strategy id (16) | symbol index (16) | session (24) | sequence (64)`,
+/// so any cloid seen on the exchange can be attributed without local state beyond the
+/// id registry. The session salt keeps ids unique across restarts, since the exchange
+/// rejects a reused cloid.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Cloid {
+    pub strategy_id: u16,
+    pub symbol_index: u16,
+    pub session: u32, // Only the low 24 bits are encoded
+    pub sequence: u64,
+}
+
+impl Cloid {
+    pub const VERSION: u8 = 1;
+    const SESSION_MASK: u32 = 0x00ff_ffff;
+
+    pub fn encode(&self) -> u128 {
+        ((Self::VERSION as u128) << 120)
+            | ((self.strategy_id as u128) << 104)
+            | ((self.symbol_index as u128) << 88)
+            | (((self.session & Self::SESSION_MASK) as u128) << 64)
+            | self.sequence as u128
+    }
+
+    /// Returns `None` for ids not produced by this scheme.
+    pub fn decode(raw: u128) -> Option<Self> {
+        if (raw >> 120) as u8 != Self::VERSION {
+            return None;
+        }
+
+        Some(Self {
+            strategy_id: (raw >> 104) as u16,
+            symbol_index: (raw >> 88) as u16,
+            session: (raw >> 64) as u32 & Self::SESSION_MASK,
+            sequence: raw as u64,
+        })
+    }
+
+    /// Wire format: `0x` followed by 32 hex digits.
+    pub fn to_hex(&self) -> String {
+        format!("0x{:032x}", self.encode())
+    }
+
+    pub fn from_hex(cloid: &str) -> Option<Self> {
+        let digits = cloid.strip_prefix("0x").unwrap_or(cloid);
+        if digits.len() != 32 {
+            return None;
+        }
+        u128::from_str_radix(digits, 16).ok().and_then(Self::decode)
+    }
+}
+
+/// Assigns stable strategy ids and symbol indices and mints cloids from them.
+///
+/// Ids are handed out in registration order, so register from configuration in a
+/// fixed order to decode ids from earlier runs. Id 0 is reserved for unattributed
+/// orders in both namespaces.
+#[derive(Debug)]
+pub struct CloidRegistry {
+    session: u32,
+    sequence: AtomicU64,
+    strategies: RwLock<Vec<String>>,
+    symbols: RwLock<Vec<String>>,
+    strategy_ids: DashMap<String, u16>,
+    symbol_indices: DashMap<String, u16>,
+}
+
+impl CloidRegistry {
+    pub fn new() -> Self {
+        let session = chrono::Utc::now().timestamp() as u32 & Cloid::SESSION_MASK;
+        Self::with_session(session)
+    }
+
+    pub fn with_session(session: u32) -> Self {
+        Self {
+            session: session & Cloid::SESSION_MASK,
+            sequence: AtomicU64::new(1),
+            strategies: RwLock::new(vec![String::new()]),
+            symbols: RwLock::new(vec![String::new()]),
+            strategy_ids: DashMap::new(),
+            symbol_indices: DashMap::new(),
+        }
+    }
+
+    pub fn register_strategy(&self, name: &str) -> u16 {
+        Self::register(&self.strategies, &self.strategy_ids, name)
+    }
+
+    pub fn register_symbol(&self, symbol: &str) -> u16 {
+        Self::register(&self.symbols, &self.symbol_indices, symbol)
+    }
+
+    pub fn strategy_id(&self, name: &str) -> Option<u16> {
+        self.strategy_ids.get(name).map(|id| *id)
+    }
+
+    pub fn strategy_name(&self, id: u16) -> Option<String> {
+        self.strategies.read().get(id as usize).filter(|name| !name.is_empty()).cloned()
+    }
+
+    pub fn symbol_name(&self, index: u16) -> Option<String> {
+        self.symbols.read().get(index as usize).filter(|name| !name.is_empty()).cloned()
+    }
+
+    /// Mints the next cloid; unknown symbols are registered on first use.
+    pub fn next(&self, strategy_id: u16, symbol: &str) -> Cloid {
+        Cloid {
+            strategy_id,
+            symbol_index: self.register_symbol(symbol),
+            session: self.session,
+            sequence: self.sequence.fetch_add(1, Ordering::Relaxed),
+        }
+    }
+
+    /// Strategy and symbol names behind an exchange cloid, when it is one of ours.
+    pub fn attribute(&self, cloid: &str) -> Option<(Option<String>, Option<String>)> {
+        let cloid = Cloid::from_hex(cloid)?;
+        Some((self.strategy_name(cloid.strategy_id), self.symbol_name(cloid.symbol_index)))
+    }
+
+    fn register(names: &RwLock<Vec<String>>, ids: &DashMap<String, u16>, name: &str) -> u16 {
+        if let Some(id) = ids.get(name) {
+            return *id;
+        }
+
+        let mut names = names.write();
+        // Re-check under the write lock in case another caller registered it first
+        if let Some(id) = ids.get(name) {
+            return *id;
+        }
+        let id = names.len() as u16;
+        names.push(name.to_string());
+        ids.insert(name.to_string(), id);
+        id
+    }
+}
+
+impl Default for CloidRegistry {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn encoded_cloids_decode_to_the_same_strategy_and_symbol() {
+        let cloid = Cloid { strategy_id: 7, symbol_index: 513, session: 0x12_3456, sequence: u64::MAX - 1 };
+        assert_eq!(Cloid::decode(cloid.encode()), Some(cloid));
+        assert_eq!(Cloid::from_hex(&cloid.to_hex()), Some(cloid));
+        assert_eq!(cloid.to_hex().len(), 34);
+
+        let registry = CloidRegistry::with_session(42);
+        let strategy_id = registry.register_strategy("mm_hype");
+        registry.register_symbol("BTC");
+        let minted = registry.next(strategy_id, "HYPE");
+        assert_eq!(
+            registry.attribute(&minted.to_hex()),
+            Some((Some("mm_hype".to_string()), Some("HYPE".to_string())))
+        );
+
+        let manual = registry.next(UNATTRIBUTED_STRATEGY, "BTC");
+        assert_eq!(registry.attribute(&manual.to_hex()), Some((None, Some("BTC".to_string()))));
+        assert!(registry.next(strategy_id, "HYPE").sequence > minted.sequence);
+    }
+
+    #[test]
+    fn cloids_from_other_sources_are_rejected() {
+        let registry = CloidRegistry::with_session(42);
+        registry.register_strategy("mm_hype");
+
+        // Another client's counter, our layout under a different version, and malformed ids
+        let foreign = format!("0x{:032x}", 1u128);
+        let other_version = format!("0x{:032x}", (2u128 << 120) | (1u128 << 104));
+        for cloid in [foreign.as_str(), other_version.as_str(), "0x1234", "0xzz000000000000000000000000000000", ""] {
+            assert_eq!(Cloid::from_hex(cloid), None, "{}", cloid);
+            assert_eq!(registry.attribute(cloid), None, "{}", cloid);
+        }
+    }
+}
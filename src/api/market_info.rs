@@ -0,0 +1,224 @@
+use crate::api::types::*;
+use crate::api::auth::HyperLiquidAuth;
+use crate::api::rate_limiter::{RateLimiter, RequestKind};
+use crate::trading::types::FundingInfo;
+use dashmap::DashMap;
+use parking_lot::RwLock;
+use rust_decimal::Decimal;
+use serde::{Serialize, Deserialize};
+use std::sync::Arc;
+use std::str::FromStr;
+use tracing::{debug, warn};
+
+// HyperLiquid settles funding at the top of every hour
+const FUNDING_INTERVAL_MS: u64 = 60 * 60 * 1000;
+
+/// Public market data from the info endpoint: predicted funding, oracle price and
+/// open interest per perp, cached for strategies and the risk manager.
+#[derive(Debug, Clone)]
+pub struct MarketInfoApi {
+    pub auth: HyperLiquidAuth,
+    pub config: ApiConfig,
+    pub funding: Arc<DashMap<String, FundingInfo>>,
+    pub last_update: Arc<RwLock<Option<std::time::Instant>>>,
+    pub rate_limiter: Arc<RateLimiter>,
+}
+
+impl MarketInfoApi {
+    pub fn new(auth: HyperLiquidAuth, config: ApiConfig) -> Self {
+        Self {
+            auth,
+            funding: Arc::new(DashMap::new()),
+            last_update: Arc::new(RwLock::new(None)),
+            rate_limiter: Arc::new(RateLimiter::new(config.rate_limit.clone())),
+            config,
+        }
+    }
+
+    /// Shares one request budget with other API clients.
+    pub fn with_rate_limiter(mut self, rate_limiter: Arc<RateLimiter>) -> Self {
+        self.rate_limiter = rate_limiter;
+        self
+    }
+
+    pub async fn get_meta_and_asset_ctxs(&self) -> Result<Vec<FundingInfo>, ApiError> {
+        let body = self.info_request("metaAndAssetCtxs").await?;
+        parse_meta_and_asset_ctxs(&body, chrono::Utc::now().timestamp_millis() as u64)
+    }
+
+    /// Perp universe: asset names in index order with their size precision and leverage.
+    pub async fn get_meta(&self) -> Result<HyperLiquidMeta, ApiError> {
+        let body = self.info_request("meta").await?;
+        serde_json::from_str(&body)
+            .map_err(|e| ApiError::ParseError(format!("Invalid meta response: {}", e)))
+    }
+
+    // Public info requests need no signature and return their payload unwrapped
+    async fn info_request(&self, type_: &str) -> Result<String, ApiError> {
+        let request = HyperLiquidMarketInfoRequest {
+            type_: type_.to_string(),
+        };
+
+        self.rate_limiter.acquire(RequestKind::InfoLight).await?;
+
+        let response = self.auth.client
+            .post(format!("{}/info", self.config.base_url))
+            .json(&request)
+            .send()
+            .await
+            .map_err(|e| ApiError::NetworkError(e.to_string()))?;
+        self.rate_limiter.check_response(&response)?;
+
+        if !response.status().is_success() {
+            return Err(ApiError::NetworkError(
+                format!("{} request failed with status: {}", type_, response.status())
+            ));
+        }
+
+        response
+            .text()
+            .await
+            .map_err(|e| ApiError::NetworkError(e.to_string()))
+    }
+
+    pub async fn refresh_funding(&self) -> Result<usize, ApiError> {
+        let infos = self.get_meta_and_asset_ctxs().await?;
+        let count = infos.len();
+
+        for info in infos {
+            self.funding.insert(info.symbol.clone(), info);
+        }
+        *self.last_update.write() = Some(std::time::Instant::now());
+
+        debug!("Refreshed funding for {} assets", count);
+        Ok(count)
+    }
+
+    pub fn get_funding(&self, symbol: &str) -> Option<FundingInfo> {
+        self.funding.get(symbol).map(|entry| entry.value().clone())
+    }
+
+    pub fn get_all_funding(&self) -> Vec<FundingInfo> {
+        self.funding.iter().map(|entry| entry.value().clone()).collect()
+    }
+
+    pub fn is_data_fresh(&self, max_age_seconds: u64) -> bool {
+        self.last_update.read()
+            .map(|at| at.elapsed().as_secs() < max_age_seconds)
+            .unwrap_or(false)
+    }
+
+    /// Refreshes the funding cache every `interval_seconds`, keeping the last known
+    /// values when a refresh fails.
+    pub fn start_periodic_updates(&self, interval_seconds: u64) {
+        let api = self.clone();
+
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval(std::time::Duration::from_secs(interval_seconds));
+
+            loop {
+                interval.tick().await;
+
+                if let Err(e) = api.refresh_funding().await {
+                    warn!("Failed to refresh funding rates, keeping cached values: {}", e);
+                }
+            }
+        });
+    }
+}
+
+/// Parses a `metaAndAssetCtxs` response, a `[meta, assetCtxs]` pair whose contexts
+/// line up index for index with `meta.universe`.
+pub fn parse_meta_and_asset_ctxs(body: &str, now_ms: u64) -> Result<Vec<FundingInfo>, ApiError> {
+    let (meta, ctxs): (HyperLiquidMeta, Vec<HyperLiquidAssetCtx>) = serde_json::from_str(body)
+        .map_err(|e| ApiError::ParseError(format!("Invalid metaAndAssetCtxs response: {}", e)))?;
+
+    if meta.universe.len() != ctxs.len() {
+        return Err(ApiError::ParseError(format!(
+            "metaAndAssetCtxs has {} assets but {} contexts",
+            meta.universe.len(), ctxs.len()
+        )));
+    }
+
+    let next_funding_time = (now_ms / FUNDING_INTERVAL_MS + 1) * FUNDING_INTERVAL_MS;
+
+    meta.universe.iter().zip(ctxs.iter())
+        .map(|(asset, ctx)| {
+            let parse = |field: &str, value: &str| {
+                Decimal::from_str(value).map_err(|e| ApiError::ParseError(
+                    format!("Invalid {} {} for {}: {}", field, value, asset.name, e)
+                ))
+            };
+
+            Ok(FundingInfo {
+                symbol: asset.name.clone(),
+                funding_rate: parse("funding", &ctx.funding)?,
+                next_funding_time,
+                oracle_px: parse("oraclePx", &ctx.oracle_px)?,
+                open_interest: parse("openInterest", &ctx.open_interest)?,
+            })
+        })
+        .collect()
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HyperLiquidMarketInfoRequest {
+    #[serde(rename = "type")]
+    pub type_: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HyperLiquidMeta {
+    pub universe: Vec<HyperLiquidAssetMeta>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HyperLiquidAssetMeta {
+    pub name: String,
+    pub sz_decimals: u32,
+    #[serde(default)]
+    pub max_leverage: u32,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HyperLiquidAssetCtx {
+    pub funding: String, // Predicted hourly rate for the next settlement
+    pub open_interest: String,
+    pub oracle_px: String,
+    #[serde(default)]
+    pub mark_px: Option<String>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rust_decimal_macros::dec;
+
+    // A metaAndAssetCtxs response body in the exchange's shape, context fields and all
+    const META_AND_ASSET_CTXS: &str = include_str!(concat!(env!("CARGO_MANIFEST_DIR"), "/fixtures/meta_and_asset_ctxs.json"));
+
+    #[test]
+    fn funding_is_read_per_asset_with_the_next_hourly_settlement() {
+        let now_ms = 1_760_000_123_456; // 08:55:23 UTC
+        let infos = parse_meta_and_asset_ctxs(META_AND_ASSET_CTXS, now_ms).unwrap();
+
+        let parsed: Vec<_> = infos.iter().map(|info| (info.symbol.as_str(), info.funding_rate, info.oracle_px, info.open_interest)).collect();
+        assert_eq!(parsed, [
+            ("BTC", dec!(0.0000125), dec!(113670.0), dec!(31254.81592)),
+            ("ETH", dec!(-0.0000031), dec!(4103.9), dec!(512371.9074)),
+            ("HYPE", dec!(0.0000087), dec!(47.178), dec!(14327861.09)),
+        ]);
+        assert!(infos.iter().all(|info| info.next_funding_time == 1_760_000_400_000));
+    }
+
+    #[test]
+    fn contexts_that_do_not_line_up_with_the_universe_are_rejected() {
+        let (meta, mut ctxs): (serde_json::Value, Vec<serde_json::Value>) = serde_json::from_str(META_AND_ASSET_CTXS).unwrap();
+        ctxs.pop();
+
+        let body = serde_json::json!([meta, ctxs]).to_string();
+        assert!(matches!(parse_meta_and_asset_ctxs(&body, 0), Err(ApiError::ParseError(_))));
+    }
+}
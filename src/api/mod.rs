@@ -1,5 +1,9 @@
 pub mod trading_api;
 pub mod account_api;
+pub mod market_info;
+pub mod assets;
 pub mod ws_trading;
 pub mod auth;
-pub mod types;
+pub mod cloid;
+pub mod rate_limiter;
+pub mod types;
\ No newline at end of file
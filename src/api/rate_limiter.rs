@@ -0,0 +1,228 @@
+use crate::api::types::{ApiError, RateLimitConfig};
+use parking_lot::Mutex;
+use std::time::{Duration, Instant};
+use tokio::time::sleep;
+use tracing::warn;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RequestKind {
+    Exchange,  // order, cancel and modify actions
+    InfoLight, // clearinghouseState, l2Book, allMids, orderStatus
+    InfoHeavy, // userFills, userFunding and other historical queries
+}
+
+impl RequestKind {
+    pub fn weight(&self, config: &RateLimitConfig) -> u32 {
+        match self {
+            RequestKind::Exchange => config.exchange_weight,
+            RequestKind::InfoLight => config.info_light_weight,
+            RequestKind::InfoHeavy => config.info_heavy_weight,
+        }
+    }
+}
+
+#[derive(Debug)]
+struct LimiterState {
+    tokens: f64,
+    last_refill: Instant,
+    blocked_until: Option<Instant>,
+}
+
+/// Weight-based token bucket shared by every client hitting the REST API.
+///
+/// The bucket holds `budget_per_minute` weight and refills continuously. Callers wait
+/// for capacity up to `max_wait_ms`; beyond that a `RateLimitError` carrying the
+/// expected wait is returned instead of blocking indefinitely.
+#[derive(Debug)]
+pub struct RateLimiter {
+    pub config: RateLimitConfig,
+    state: Mutex<LimiterState>,
+}
+
+impl RateLimiter {
+    pub fn new(config: RateLimitConfig) -> Self {
+        Self {
+            state: Mutex::new(LimiterState {
+                tokens: config.budget_per_minute as f64,
+                last_refill: Instant::now(),
+                blocked_until: None,
+            }),
+            config,
+        }
+    }
+
+    pub async fn acquire(&self, kind: RequestKind) -> Result<(), ApiError> {
+        self.acquire_weight(kind.weight(&self.config)).await
+    }
+
+    /// Takes `count` requests' worth of `kind` weight in one go, as a batch of
+    /// `count` orders costs.
+    pub async fn acquire_batch(&self, kind: RequestKind, count: u32) -> Result<(), ApiError> {
+        self.acquire_weight(kind.weight(&self.config).saturating_mul(count)).await
+    }
+
+    pub async fn acquire_weight(&self, weight: u32) -> Result<(), ApiError> {
+        // The bucket never holds more than its capacity, so a heavier request would wait forever
+        if weight > self.config.budget_per_minute {
+            // Not a RateLimitError, since retrying can't help
+            return Err(ApiError::Unknown(format!(
+                "Request weight {} exceeds the {} per minute budget", weight, self.config.budget_per_minute
+            )));
+        }
+
+        let weight = weight as f64;
+        let max_wait = Duration::from_millis(self.config.max_wait_ms);
+
+        // Tokens are only taken under the lock, so a caller woken here competes again
+        // rather than being charged twice
+        loop {
+            let wait = match self.try_acquire(weight) {
+                Ok(()) => return Ok(()),
+                Err(wait) => wait,
+            };
+
+            if wait > max_wait {
+                return Err(ApiError::RateLimitError {
+                    message: format!("Request weight {} exceeds available budget", weight),
+                    retry_after_ms: wait.as_millis() as u64,
+                });
+            }
+
+            sleep(wait).await;
+        }
+    }
+
+    // Takes `weight` tokens if available, otherwise returns how long until they will be
+    fn try_acquire(&self, weight: f64) -> Result<(), Duration> {
+        let mut state = self.state.lock();
+        let now = Instant::now();
+
+        if let Some(blocked_until) = state.blocked_until {
+            if now < blocked_until {
+                return Err(blocked_until - now);
+            }
+            state.blocked_until = None;
+        }
+
+        let capacity = self.config.budget_per_minute as f64;
+        let refill_per_sec = capacity / 60.0;
+        let elapsed = now.duration_since(state.last_refill).as_secs_f64();
+        state.tokens = (state.tokens + elapsed * refill_per_sec).min(capacity);
+        state.last_refill = now;
+
+        if state.tokens >= weight {
+            state.tokens -= weight;
+            Ok(())
+        } else {
+            Err(Duration::from_secs_f64((weight - state.tokens) / refill_per_sec))
+        }
+    }
+
+    /// Records a 429 from the server: drains the bucket and blocks all callers until
+    /// the server's Retry-After (or the configured backoff) has passed.
+    pub fn on_rate_limited(&self, retry_after: Option<Duration>) -> Duration {
+        let backoff = retry_after.unwrap_or(Duration::from_millis(self.config.backoff_on_429_ms));
+        let mut state = self.state.lock();
+        state.tokens = 0.0;
+        state.blocked_until = Some(Instant::now() + backoff);
+        warn!("Rate limited by server, backing off for {:?}", backoff);
+        backoff
+    }
+
+    /// Converts an HTTP 429 into a `RateLimitError` and feeds it into the limiter state.
+    pub fn check_response(&self, response: &reqwest::Response) -> Result<(), ApiError> {
+        if response.status() != reqwest::StatusCode::TOO_MANY_REQUESTS {
+            return Ok(());
+        }
+
+        let retry_after = response.headers()
+            .get(reqwest::header::RETRY_AFTER)
+            .and_then(|v| v.to_str().ok())
+            .and_then(|v| v.parse::<u64>().ok())
+            .map(Duration::from_secs);
+        let backoff = self.on_rate_limited(retry_after);
+
+        Err(ApiError::RateLimitError {
+            message: "HTTP 429 Too Many Requests".to_string(),
+            retry_after_ms: backoff.as_millis() as u64,
+        })
+    }
+
+    pub fn available(&self) -> f64 {
+        self.state.lock().tokens
+    }
+}
+
+impl Default for RateLimiter {
+    fn default() -> Self {
+        Self::new(RateLimitConfig::default())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn limiter(budget_per_minute: u32, max_wait_ms: u64) -> RateLimiter {
+        RateLimiter::new(RateLimitConfig { budget_per_minute, max_wait_ms, ..RateLimitConfig::default() })
+    }
+
+    #[tokio::test]
+    async fn heavy_requests_use_up_the_budget_faster_than_light_ones() {
+        let limiter = limiter(40, 0);
+
+        limiter.acquire(RequestKind::InfoHeavy).await.unwrap();
+        limiter.acquire(RequestKind::InfoLight).await.unwrap();
+        limiter.acquire(RequestKind::Exchange).await.unwrap();
+        assert!((limiter.available() - 17.0).abs() < 0.1);
+
+        // Room for light requests, but not another heavy one
+        match limiter.acquire(RequestKind::InfoHeavy).await {
+            Err(ApiError::RateLimitError { retry_after_ms, .. }) => assert!(retry_after_ms > 0),
+            other => panic!("expected a rate limit error, got {:?}", other),
+        }
+        limiter.acquire(RequestKind::InfoLight).await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn a_caller_waits_for_the_refill_when_it_is_within_max_wait() {
+        // 100 weight a second
+        let limiter = limiter(6000, 2000);
+        limiter.acquire_weight(6000).await.unwrap();
+
+        let start = Instant::now();
+        limiter.acquire_weight(10).await.unwrap();
+        assert!(start.elapsed() >= Duration::from_millis(80), "{:?}", start.elapsed());
+    }
+
+    #[tokio::test]
+    async fn a_429_blocks_every_caller_until_the_backoff_passes() {
+        let limiter = limiter(1200, 0);
+
+        assert_eq!(limiter.on_rate_limited(Some(Duration::from_secs(3))), Duration::from_secs(3));
+
+        match limiter.acquire(RequestKind::Exchange).await {
+            Err(ApiError::RateLimitError { retry_after_ms, .. }) => assert!((2900..=3000).contains(&retry_after_ms)),
+            other => panic!("expected a rate limit error, got {:?}", other),
+        }
+    }
+
+    #[tokio::test]
+    async fn a_batch_is_charged_its_requests_combined_weight() {
+        let limiter = limiter(100, 0);
+
+        limiter.acquire_batch(RequestKind::InfoLight, 30).await.unwrap();
+        assert!((limiter.available() - 40.0).abs() < 0.1);
+        limiter.acquire_weight(35).await.unwrap();
+        assert!(matches!(limiter.acquire_batch(RequestKind::Exchange, 10).await, Err(ApiError::RateLimitError { .. })));
+    }
+
+    #[tokio::test]
+    async fn a_weight_over_the_whole_budget_fails_without_being_retryable() {
+        let limiter = limiter(100, 60_000);
+
+        assert!(matches!(limiter.acquire_weight(101).await, Err(ApiError::Unknown(_))));
+        assert!(matches!(limiter.acquire_batch(RequestKind::InfoHeavy, u32::MAX).await, Err(ApiError::Unknown(_))));
+        assert!((limiter.available() - 100.0).abs() < 0.1);
+    }
+}
@@ -1,7 +1,14 @@
 use crate::api::types::*;
+use crate::api::assets::AssetRegistry;
 use crate::api::auth::HyperLiquidAuth;
-use crate::trading::types::{NewOrder, OrderType, Side};
+use crate::api::cloid::{Cloid, CloidRegistry, UNATTRIBUTED_STRATEGY};
+use crate::api::rate_limiter::{RateLimiter, RequestKind};
+use crate::metrics::registry::Metrics;
+use crate::trading::order_book::OrderBook;
+use crate::trading::paper_trading::PaperMatcher;
+use crate::trading::types::{Fill, NewOrder, OrderType, Side};
 use anyhow::Result;
+use async_trait::async_trait;
 use crossbeam_channel::{Sender, Receiver, unbounded};
 use dashmap::DashMap;
 use tokio::sync::RwLock;
@@ -9,7 +16,7 @@ use rust_decimal::Decimal;
 use serde::{Serialize, Deserialize};
 use std::sync::Arc;
 use std::time::Duration;
-use tokio::time::sleep;
+use rand::Rng;
 use tracing::{info, warn, debug};
 use uuid::Uuid;
 
@@ -20,18 +27,28 @@ pub struct TradingApi {
     pub pending_orders: Arc<DashMap<u64, PendingOrder>>,
     pub order_events_tx: Sender<ApiEvent>,
     pub retry_queue: Arc<RwLock<Vec<RetryRequest>>>,
-    pub rate_limiter: Arc<RwLock<RateLimiter>>,
+    pub rate_limiter: Arc<RateLimiter>,
+    pub cloids: Arc<CloidRegistry>,
+    pub assets: Arc<AssetRegistry>, // Orders for symbols missing here are rejected
+    pub paper: Option<Arc<parking_lot::Mutex<PaperMatcher>>>, // Set in paper mode; orders never reach the exchange
+    pub order_books: Arc<DashMap<String, OrderBook>>, // Books paper orders are matched against
+    pub metrics: Arc<Metrics>,
+    pub submitter: Option<Arc<dyn OrderSubmitter>>, // Set to send orders somewhere other than the exchange
 }
 
 #[derive(Debug, Clone)]
 pub struct PendingOrder {
     pub internal_id: Uuid,
     pub client_order_id: u64,
+    pub cloid: Cloid,
+    pub asset: u32,
     pub symbol: String,
     pub side: Side,
     pub order_type: OrderType,
     pub price: Decimal,
     pub size: Decimal,
+    pub filled_size: Decimal, // From exchange and paper fills; the order leaves pending_orders once it reaches size
+    pub reduce_only: bool,
     pub created_at: std::time::Instant,
     pub retry_count: u32,
 }
@@ -42,21 +59,11 @@ pub struct RetryRequest {
     pub retry_after: std::time::Instant,
 }
 
-#[derive(Debug, Clone)]
-pub struct RateLimiter {
-    pub last_request: std::time::Instant,
-    pub request_count: u32,
-    pub window_start: std::time::Instant,
-}
-
-impl Default for RateLimiter {
-    fn default() -> Self {
-        Self {
-            last_request: std::time::Instant::now(),
-            request_count: 0,
-            window_start: std::time::Instant::now(),
-        }
-    }
+/// Takes order submissions in place of the exchange. Errors are classified the same
+/// way, so retryable ones go through the retry queue.
+#[async_trait]
+pub trait OrderSubmitter: Send + Sync + std::fmt::Debug {
+    async fn submit_order(&self, order: &PendingOrder) -> Result<(), ApiError>;
 }
 
 impl TradingApi {
@@ -65,55 +72,153 @@ impl TradingApi {
         
         let api = Self {
             auth,
-            config,
             pending_orders: Arc::new(DashMap::new()),
             order_events_tx: tx,
             retry_queue: Arc::new(RwLock::new(Vec::new())),
-            rate_limiter: Arc::new(RwLock::new(RateLimiter::default())),
+            rate_limiter: Arc::new(RateLimiter::new(config.rate_limit.clone())),
+            cloids: Arc::new(CloidRegistry::new()),
+            assets: Arc::new(AssetRegistry::new()),
+            paper: config.paper_trading.then(|| Arc::new(parking_lot::Mutex::new(PaperMatcher::default()))),
+            order_books: Arc::new(DashMap::new()),
+            metrics: Arc::new(Metrics::new()),
+            submitter: None,
+            config,
         };
         
         (api, rx)
     }
 
+    /// Shares one request budget with other API clients.
+    pub fn with_rate_limiter(mut self, rate_limiter: Arc<RateLimiter>) -> Self {
+        self.rate_limiter = rate_limiter;
+        self
+    }
+
+    pub fn with_cloid_registry(mut self, cloids: Arc<CloidRegistry>) -> Self {
+        self.cloids = cloids;
+        self
+    }
+
+    pub fn with_asset_registry(mut self, assets: Arc<AssetRegistry>) -> Self {
+        self.assets = assets;
+        self
+    }
+
+    pub fn with_order_books(mut self, order_books: Arc<DashMap<String, OrderBook>>) -> Self {
+        self.order_books = order_books;
+        self
+    }
+
+    pub fn with_metrics(mut self, metrics: Arc<Metrics>) -> Self {
+        self.metrics = metrics;
+        self
+    }
+
+    pub fn with_submitter(mut self, submitter: Arc<dyn OrderSubmitter>) -> Self {
+        self.submitter = Some(submitter);
+        self
+    }
+
+    pub fn is_paper(&self) -> bool {
+        self.paper.is_some()
+    }
+
     pub async fn place_order(&self, order: NewOrder) -> Result<Uuid, ApiError> {
+        self.place_order_for_strategy(order, UNATTRIBUTED_STRATEGY).await
+    }
+
+    /// Places an order whose cloid attributes it to `strategy_id` (see `CloidRegistry`).
+    pub async fn place_order_for_strategy(&self, order: NewOrder, strategy_id: u16) -> Result<Uuid, ApiError> {
+        // Exchange rejects prices and sizes finer than the asset allows
+        let asset = self.assets.get(&order.symbol)?;
+        let order = self.assets.normalize_order(&order)?;
         let internal_id = Uuid::new_v4();
-        let client_order_id = self.generate_client_order_id();
+        let cloid = self.cloids.next(strategy_id, &order.symbol);
+        let client_order_id = cloid.sequence;
         
         let pending_order = PendingOrder {
             internal_id,
             client_order_id,
+            cloid,
+            asset: asset.index,
             symbol: order.symbol.clone(),
             side: order.side,
             order_type: order.order_type,
             price: order.price,
             size: order.size,
+            filled_size: Decimal::ZERO,
+            reduce_only: order.reduce_only,
             created_at: std::time::Instant::now(),
             retry_count: 0,
         };
 
         self.pending_orders.insert(client_order_id, pending_order.clone());
+        self.record_tick_to_trade(&order);
+
+        if let Some(paper) = &self.paper {
+            return self.simulate_order(paper, &pending_order, &order);
+        }
 
-        match self.submit_order_to_exchange(&pending_order).await {
+        let started = std::time::Instant::now();
+        let result = self.submit_order_to_exchange(&pending_order).await;
+        self.metrics.place_latency.observe(started.elapsed());
+
+        match result {
             Ok(_) => {
+                self.metrics.orders_placed.inc();
                 info!("Order placed successfully: {} for {}", internal_id, order.symbol);
                 Ok(internal_id)
             }
+            Err(e) if e.is_retryable() => {
+                // The order stays pending; the retry processor reports the final outcome
+                let mut queue = self.retry_queue.write().await;
+                if Self::schedule_retry(&mut queue, &self.config, pending_order, &e) {
+                    warn!("Failed to place order {}, queued for retry: {}", internal_id, e);
+                    Ok(internal_id)
+                } else {
+                    self.pending_orders.remove(&client_order_id);
+                    Err(e)
+                }
+            }
             Err(e) => {
                 warn!("Failed to place order {}: {}", internal_id, e);
                 self.pending_orders.remove(&client_order_id);
+                self.metrics.orders_rejected.inc();
+                let _ = self.order_events_tx.send(ApiEvent::OrderRejected {
+                    order_id: client_order_id,
+                    reason: e.to_string(),
+                    timestamp: now_millis(),
+                });
                 Err(e)
             }
         }
     }
 
-    pub async fn cancel_order(&self, internal_id: Uuid) -> Result<(), ApiError> {
-        // Find the order by internal ID
-        let client_order_id = self.pending_orders
+    fn record_tick_to_trade(&self, order: &NewOrder) {
+        let Some(received_at) = order.tick_received_at else {
+            return;
+        };
+
+        let elapsed = received_at.elapsed();
+        self.metrics.tick_to_trade.observe(elapsed);
+        if elapsed > Duration::from_millis(self.config.tick_to_trade_budget_ms) {
+            warn!(
+                "Tick-to-trade for {} {:?} order was {:?}, over the {}ms budget",
+                order.symbol, order.side, elapsed, self.config.tick_to_trade_budget_ms
+            );
+        }
+    }
+
+    /// Client order id of a pending order; `None` once it is filled or cancelled.
+    pub fn client_order_id(&self, internal_id: Uuid) -> Option<u64> {
+        self.pending_orders
             .iter()
             .find(|entry| entry.value().internal_id == internal_id)
-            .map(|entry| *entry.key());
+            .map(|entry| *entry.key())
+    }
 
-        let client_order_id = match client_order_id {
+    pub async fn cancel_order(&self, internal_id: Uuid) -> Result<(), ApiError> {
+        let client_order_id = match self.client_order_id(internal_id) {
             Some(id) => id,
             None => return Err(ApiError::InvalidOrder("Order not found".to_string())),
         };
@@ -122,22 +227,59 @@ impl TradingApi {
     }
 
     pub async fn cancel_order_by_client_id(&self, client_order_id: u64) -> Result<(), ApiError> {
+        if let Some(paper) = &self.paper {
+            let (_, pending_order) = self.pending_orders.remove(&client_order_id)
+                .ok_or_else(|| ApiError::InvalidOrder("Order not found".to_string()))?;
+            let remaining = paper.lock().cancel_order(&pending_order.internal_id)
+                .map_or(Decimal::ZERO, |order| order.remaining);
+            self.send_order_update(&pending_order, "canceled", remaining);
+            self.metrics.orders_cancelled.inc();
+            info!("Paper order cancelled: {}", client_order_id);
+            return Ok(());
+        }
+
+        let cancel_request = match self.pending_orders.get(&client_order_id) {
+            Some(pending_order) => HyperLiquidCancelByCloidRequest::for_order(&pending_order),
+            None => return Err(ApiError::InvalidOrder("Order not found".to_string())),
+        };
+
+        let started = std::time::Instant::now();
+        let result = self.send_cancel("cancelByCloid", &cancel_request).await;
+        self.metrics.cancel_latency.observe(started.elapsed());
+        result?;
+
+        self.metrics.orders_cancelled.inc();
+        if let Some((_, pending_order)) = self.pending_orders.remove(&client_order_id) {
+            let remaining = (pending_order.size - pending_order.filled_size).max(Decimal::ZERO);
+            self.send_order_update(&pending_order, "canceled", remaining);
+        }
+        info!("Order cancelled successfully: {}", client_order_id);
+        Ok(())
+    }
+
+    /// Cancels a resting order by exchange oid, including ones this session didn't place.
+    pub async fn cancel_exchange_order(&self, oid: u64) -> Result<(), ApiError> {
         let cancel_request = HyperLiquidCancelRequest {
-            oid: client_order_id,
+            oid,
         };
 
-        let signed_request = self.auth.create_signed_request("cancel", &cancel_request)?;
+        self.send_cancel("cancel", &cancel_request).await
+    }
+
+    async fn send_cancel<T: Serialize>(&self, action: &str, cancel_request: &T) -> Result<(), ApiError> {
+        let signed_request = self.auth.create_signed_request(action, cancel_request)?;
         let headers = self.auth.get_headers()?;
 
-        self.enforce_rate_limit().await;
+        self.rate_limiter.acquire(RequestKind::Exchange).await?;
 
         let response = self.auth.client
-            .post(&format!("{}/exchange", self.config.base_url))
+            .post(format!("{}/exchange", self.config.base_url))
             .headers(headers)
             .json(&signed_request)
             .send()
             .await
             .map_err(|e| ApiError::NetworkError(e.to_string()))?;
+        self.rate_limiter.check_response(&response)?;
 
         let status = response.status();
         if !status.is_success() {
@@ -160,8 +302,6 @@ impl TradingApi {
             ));
         }
 
-        self.pending_orders.remove(&client_order_id);
-        info!("Order cancelled successfully: {}", client_order_id);
         Ok(())
     }
 
@@ -189,29 +329,35 @@ impl TradingApi {
     }
 
     async fn submit_order_to_exchange(&self, pending_order: &PendingOrder) -> Result<(), ApiError> {
+        if let Some(submitter) = &self.submitter {
+            return submitter.submit_order(pending_order).await;
+        }
+
         let hl_order = HyperLiquidOrder {
-            a: self.auth.account_id,
+            a: Some(pending_order.asset as u64),
             b: matches!(pending_order.side, Side::Buy),
             p: pending_order.price.to_string(),
             s: pending_order.size.to_string(),
-            r: false, // reduce only
+            r: pending_order.reduce_only,
             t: self.map_order_type(&pending_order.order_type),
             cid: pending_order.client_order_id,
             oid: None,
+            c: Some(pending_order.cloid.to_hex()),
         };
 
         let signed_request = self.auth.create_signed_request("order", &hl_order)?;
         let headers = self.auth.get_headers()?;
 
-        self.enforce_rate_limit().await;
+        self.rate_limiter.acquire(RequestKind::Exchange).await?;
 
         let response = self.auth.client
-            .post(&format!("{}/exchange", self.config.base_url))
+            .post(format!("{}/exchange", self.config.base_url))
             .headers(headers)
             .json(&signed_request)
             .send()
             .await
             .map_err(|e| ApiError::NetworkError(e.to_string()))?;
+        self.rate_limiter.check_response(&response)?;
 
         let status = response.status();
         if !status.is_success() {
@@ -238,58 +384,160 @@ impl TradingApi {
         Ok(())
     }
 
-    fn map_order_type(&self, order_type: &OrderType) -> String {
-        match order_type {
-            OrderType::Market => "Market".to_string(),
-            OrderType::Limit => "Limit".to_string(),
-            OrderType::PostOnly => "Limit".to_string(), // HyperLiquid doesn't have PostOnly, use Limit
+    // Matches the order against the current book instead of sending it, reporting the
+    // outcome through the same events the exchange would produce
+    fn simulate_order(&self, paper: &parking_lot::Mutex<PaperMatcher>, pending_order: &PendingOrder, order: &NewOrder) -> Result<Uuid, ApiError> {
+        let book = self.order_books.get(&order.symbol)
+            .map(|book| book.clone())
+            .unwrap_or_else(|| OrderBook::new(order.symbol.clone()));
+
+        let fills = match paper.lock().add_order(pending_order.internal_id, order, &book) {
+            Ok(fills) => fills,
+            Err(reason) => {
+                self.pending_orders.remove(&pending_order.client_order_id);
+                self.metrics.orders_rejected.inc();
+                let _ = self.order_events_tx.send(ApiEvent::OrderRejected {
+                    order_id: pending_order.client_order_id,
+                    reason: reason.clone(),
+                    timestamp: now_millis(),
+                });
+                return Err(ApiError::OrderRejected(reason));
+            }
+        };
+
+        self.metrics.orders_placed.inc();
+        let filled: Decimal = fills.iter().map(|fill| fill.size).sum();
+        let remaining = pending_order.size - filled;
+        for fill in &fills {
+            self.send_fill(pending_order, fill);
         }
-    }
 
-    fn generate_client_order_id(&self) -> u64 {
-        use std::sync::atomic::{AtomicU64, Ordering};
-        static COUNTER: AtomicU64 = AtomicU64::new(1);
-        COUNTER.fetch_add(1, Ordering::Relaxed)
+        if remaining > Decimal::ZERO && order.order_type != OrderType::Market {
+            if let Some(mut entry) = self.pending_orders.get_mut(&pending_order.client_order_id) {
+                entry.filled_size = filled;
+            }
+            self.send_order_update(pending_order, "open", remaining);
+        } else {
+            self.pending_orders.remove(&pending_order.client_order_id);
+            self.send_order_update(pending_order, "filled", Decimal::ZERO);
+        }
+
+        debug!("Paper order {} filled {} of {}", pending_order.client_order_id, filled, pending_order.size);
+        Ok(pending_order.internal_id)
     }
 
-    async fn enforce_rate_limit(&self) {
-        {
-            let mut rate_limiter = self.rate_limiter.write().await;
-            let now = std::time::Instant::now();
-            
-            // Reset window if more than 1 second has passed
-            if now.duration_since(rate_limiter.window_start) > Duration::from_secs(1) {
-                rate_limiter.window_start = now;
-                rate_limiter.request_count = 0;
-            }
-            
-            // HyperLiquid rate limit: 100 requests per second
-            if rate_limiter.request_count >= 100 {
-                let sleep_duration = Duration::from_secs(1) - now.duration_since(rate_limiter.window_start);
-                if sleep_duration > Duration::ZERO {
-                    drop(rate_limiter); // Release lock before sleeping
-                    sleep(sleep_duration).await;
-                    // Re-acquire lock after sleeping
-                    let mut rate_limiter = self.rate_limiter.write().await;
-                    rate_limiter.request_count += 1;
-                    rate_limiter.last_request = std::time::Instant::now();
-                } else {
-                    rate_limiter.request_count += 1;
-                    rate_limiter.last_request = now;
+    /// Runs resting paper orders against a new book state and reports their fills.
+    /// Does nothing outside paper mode.
+    pub fn on_book_update(&self, book: &OrderBook) {
+        let Some(paper) = &self.paper else {
+            return;
+        };
+
+        let (fills, resting) = {
+            let mut matcher = paper.lock();
+            let fills = matcher.on_book_update(book);
+            let resting: std::collections::HashMap<Uuid, Decimal> = matcher.get_resting_orders(Some(&book.symbol))
+                .into_iter()
+                .map(|order| (order.id, order.remaining))
+                .collect();
+            (fills, resting)
+        };
+
+        for fill in fills {
+            let pending_order = self.pending_orders.iter_mut()
+                .find(|entry| entry.value().internal_id == fill.order_id)
+                .map(|mut entry| {
+                    entry.filled_size += fill.size;
+                    entry.value().clone()
+                });
+            let Some(pending_order) = pending_order else {
+                continue;
+            };
+
+            self.send_fill(&pending_order, &fill);
+            match resting.get(&fill.order_id) {
+                Some(remaining) => self.send_order_update(&pending_order, "open", *remaining),
+                None => {
+                    self.pending_orders.remove(&pending_order.client_order_id);
+                    self.send_order_update(&pending_order, "filled", Decimal::ZERO);
                 }
-            } else {
-                rate_limiter.request_count += 1;
-                rate_limiter.last_request = now;
             }
         }
     }
 
+    /// Books a fill reported by the exchange (an `ApiEvent::Fill` off the trading
+    /// WebSocket) against the pending order its cloid names, then re-emits it on
+    /// `order_events_tx` so REST placements and exchange fills reach consumers on one
+    /// channel. A fully filled order leaves `pending_orders`; a partial fill is followed
+    /// by an "open" order update with the remaining size. Fills for orders placed
+    /// elsewhere are forwarded unchanged.
+    pub fn on_exchange_fill(&self, event: ApiEvent) {
+        let ApiEvent::Fill { order_id, coin, side, fill_size, fill_price, fee, timestamp, cloid } = event else {
+            return;
+        };
+
+        let pending_order = cloid.as_deref()
+            .and_then(Cloid::from_hex)
+            .and_then(|cloid| {
+                let mut entry = self.pending_orders.get_mut(&cloid.sequence).filter(|entry| entry.cloid == cloid)?;
+                match fill_size.parse::<Decimal>() {
+                    Ok(size) => entry.filled_size += size,
+                    Err(_) => warn!("Unparseable fill size {} for order {}", fill_size, entry.client_order_id),
+                }
+                Some(entry.clone())
+            });
+
+        let order_id = pending_order.as_ref().map_or(order_id, |pending_order| pending_order.client_order_id);
+        let _ = self.order_events_tx.send(ApiEvent::Fill { order_id, coin, side, fill_size, fill_price, fee, timestamp, cloid });
+
+        let Some(pending_order) = pending_order else {
+            return;
+        };
+        let remaining = (pending_order.size - pending_order.filled_size).max(Decimal::ZERO);
+        if remaining.is_zero() {
+            self.pending_orders.remove(&pending_order.client_order_id);
+            debug!("Order {} filled", pending_order.internal_id);
+            self.send_order_update(&pending_order, "filled", Decimal::ZERO);
+        } else {
+            self.send_order_update(&pending_order, "open", remaining);
+        }
+    }
+
+    fn send_fill(&self, pending_order: &PendingOrder, fill: &Fill) {
+        let _ = self.order_events_tx.send(ApiEvent::Fill {
+            order_id: pending_order.client_order_id,
+            coin: fill.symbol.clone(),
+            side: if fill.side == Side::Buy { "B" } else { "A" }.to_string(),
+            fill_size: fill.size.to_string(),
+            fill_price: fill.price.to_string(),
+            fee: fill.fee.to_string(),
+            timestamp: fill.timestamp.timestamp_millis() as u64,
+            cloid: Some(pending_order.cloid.to_hex()),
+        });
+    }
+
+    fn send_order_update(&self, pending_order: &PendingOrder, status: &str, remaining: Decimal) {
+        let _ = self.order_events_tx.send(ApiEvent::OrderUpdate {
+            order_id: pending_order.client_order_id,
+            status: status.to_string(),
+            filled_size: (pending_order.size - remaining).to_string(),
+            remaining_size: remaining.to_string(),
+            price: pending_order.price.to_string(),
+            timestamp: now_millis(),
+            cloid: Some(pending_order.cloid.to_hex()),
+        });
+    }
+
+    fn map_order_type(&self, order_type: &OrderType) -> String {
+        match order_type {
+            OrderType::Market => "Market".to_string(),
+            OrderType::Limit => "Limit".to_string(),
+            OrderType::PostOnly => "Limit".to_string(), // HyperLiquid doesn't have PostOnly, use Limit
+        }
+    }
+
     pub async fn start_retry_processor(&self) {
-        let retry_queue = Arc::clone(&self.retry_queue);
-        let pending_orders = Arc::clone(&self.pending_orders);
-        let order_events_tx = self.order_events_tx.clone();
-        let config = self.config.clone();
-        let auth = self.auth.clone();
+        let api = self.clone();
 
         tokio::spawn(async move {
             let mut interval = tokio::time::interval(Duration::from_millis(100));
@@ -299,7 +547,7 @@ impl TradingApi {
                 
                 let now = std::time::Instant::now();
                 let retry_requests = {
-                    let mut queue = retry_queue.write().await;
+                    let mut queue = api.retry_queue.write().await;
                     let mut to_retry = Vec::new();
                     let mut remaining = Vec::new();
                     
@@ -316,35 +564,54 @@ impl TradingApi {
                 };
 
                 for retry_request in retry_requests {
-                    if retry_request.order.retry_count >= config.max_retries {
-                        warn!("Max retries exceeded for order: {}", retry_request.order.internal_id);
-                        pending_orders.remove(&retry_request.order.client_order_id);
-                        let _ = order_events_tx.send(ApiEvent::Error {
-                            error: format!("Max retries exceeded for order {}", retry_request.order.internal_id),
-                            timestamp: std::time::SystemTime::now()
-                                .duration_since(std::time::UNIX_EPOCH)
-                                .unwrap()
-                                .as_millis() as u64,
-                        });
-                        continue;
-                    }
-
-                    let mut updated_order = retry_request.order.clone();
+                    let mut updated_order = retry_request.order;
                     updated_order.retry_count += 1;
 
-                    match Self::submit_order_with_auth(&auth, &config, &updated_order).await {
+                    let error = match api.submit_order_to_exchange(&updated_order).await {
                         Ok(_) => {
+                            api.metrics.orders_placed.inc();
                             info!("Order retry successful: {}", updated_order.internal_id);
-                            pending_orders.insert(updated_order.client_order_id, updated_order);
+                            // A cancel may have dropped the order while it was in flight
+                            if let Some(mut order) = api.pending_orders.get_mut(&updated_order.client_order_id) {
+                                order.retry_count = updated_order.retry_count;
+                            }
+                            continue;
                         }
-                        Err(e) => {
-                            warn!("Order retry failed: {} - {}", updated_order.internal_id, e);
-                            let retry_after = now + Duration::from_millis(config.retry_delay_ms);
-                            let new_retry_request = RetryRequest {
-                                order: updated_order,
-                                retry_after,
-                            };
-                            retry_queue.write().await.push(new_retry_request);
+                        Err(e) => e,
+                    };
+
+                    if !error.is_retryable() {
+                        warn!("Order {} permanently rejected: {}", updated_order.internal_id, error);
+                        api.pending_orders.remove(&updated_order.client_order_id);
+                        api.metrics.orders_rejected.inc();
+                        let _ = api.order_events_tx.send(ApiEvent::OrderRejected {
+                            order_id: updated_order.client_order_id,
+                            reason: error.to_string(),
+                            timestamp: now_millis(),
+                        });
+                    } else if updated_order.retry_count >= api.config.max_retries {
+                        warn!("Max retries exceeded for order: {}", updated_order.internal_id);
+                        api.pending_orders.remove(&updated_order.client_order_id);
+                        let _ = api.order_events_tx.send(ApiEvent::OrderRetriesExhausted {
+                            order_id: updated_order.client_order_id,
+                            // Counting the initial submission
+                            attempts: updated_order.retry_count + 1,
+                            error: error.to_string(),
+                            timestamp: now_millis(),
+                        });
+                    } else {
+                        warn!("Order retry failed: {} - {}", updated_order.internal_id, error);
+                        let client_order_id = updated_order.client_order_id;
+                        let mut queue = api.retry_queue.write().await;
+                        let attempts = updated_order.retry_count + 1;
+                        if !Self::schedule_retry(&mut queue, &api.config, updated_order, &error) {
+                            api.pending_orders.remove(&client_order_id);
+                            let _ = api.order_events_tx.send(ApiEvent::OrderRetriesExhausted {
+                                order_id: client_order_id,
+                                attempts,
+                                error: format!("Retry queue full: {}", error),
+                                timestamp: now_millis(),
+                            });
                         }
                     }
                 }
@@ -352,59 +619,41 @@ impl TradingApi {
         });
     }
 
-    async fn submit_order_with_auth(
-        auth: &HyperLiquidAuth,
+    // Queues the order after its backoff delay. Returns false if the queue is already
+    // at capacity and the order was dropped.
+    fn schedule_retry(
+        queue: &mut Vec<RetryRequest>,
         config: &ApiConfig,
-        pending_order: &PendingOrder,
-    ) -> Result<(), ApiError> {
-        let hl_order = HyperLiquidOrder {
-            a: auth.account_id,
-            b: matches!(pending_order.side, Side::Buy),
-            p: pending_order.price.to_string(),
-            s: pending_order.size.to_string(),
-            r: false,
-            t: match pending_order.order_type {
-                OrderType::Market => "Market".to_string(),
-                OrderType::Limit => "Limit".to_string(),
-                OrderType::PostOnly => "Limit".to_string(),
-            },
-            cid: pending_order.client_order_id,
-            oid: None,
-        };
-
-        let signed_request = auth.create_signed_request("order", &hl_order)?;
-        let headers = auth.get_headers()?;
-
-        let response = auth.client
-            .post(&format!("{}/exchange", config.base_url))
-            .headers(headers)
-            .json(&signed_request)
-            .send()
-            .await
-            .map_err(|e| ApiError::NetworkError(e.to_string()))?;
+        order: PendingOrder,
+        error: &ApiError,
+    ) -> bool {
+        if queue.len() >= config.max_retry_queue {
+            warn!("Retry queue full ({} orders), dropping order {}", queue.len(), order.internal_id);
+            return false;
+        }
 
-        let status = response.status();
-        if !status.is_success() {
-            let error_text = response.text().await.unwrap_or_default();
-            return Err(ApiError::OrderRejected(format!(
-                "Order failed with status {}: {}", 
-                status, 
-                error_text
-            )));
+        let mut delay = retry_backoff(config, order.retry_count);
+        if let ApiError::RateLimitError { retry_after_ms, .. } = error {
+            delay = delay.max(Duration::from_millis(*retry_after_ms));
         }
 
-        let order_response: HyperLiquidOrderResponse = response
-            .json()
-            .await
-            .map_err(|e| ApiError::ParseError(e.to_string()))?;
+        debug!("Retrying order {} in {:?}", order.internal_id, delay);
+        queue.push(RetryRequest {
+            order,
+            retry_after: std::time::Instant::now() + delay,
+        });
+        true
+    }
 
-        if order_response.status != "ok" {
-            return Err(ApiError::OrderRejected(
-                "Order response status not ok".to_string()
-            ));
+    /// Drops every order waiting to be retried, returning how many were discarded.
+    pub async fn clear_retry_queue(&self) -> usize {
+        let mut queue = self.retry_queue.write().await;
+        for request in queue.iter() {
+            self.pending_orders.remove(&request.order.client_order_id);
         }
-
-        Ok(())
+        let cleared = queue.len();
+        queue.clear();
+        cleared
     }
 
     pub fn get_pending_orders(&self) -> Vec<PendingOrder> {
@@ -422,9 +671,298 @@ impl TradingApi {
     }
 }
 
+/// Exponential backoff from `retry_delay_ms`, capped at `max_retry_delay_ms`, with
+/// jitter over the upper half of the interval so failed orders don't retry in lockstep.
+pub fn retry_backoff(config: &ApiConfig, retry_count: u32) -> Duration {
+    let base = config.retry_delay_ms
+        .saturating_mul(1u64 << retry_count.min(20))
+        .min(config.max_retry_delay_ms);
+    let jittered = rand::thread_rng().gen_range(base / 2..=base);
+    Duration::from_millis(jittered)
+}
+
+fn now_millis() -> u64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .unwrap()
+        .as_millis() as u64
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct HyperLiquidCancelRequest {
     pub oid: u64,
 }
 
-// Clone implementation removed to avoid conflicts
+/// Cancels by the cloid we attached when placing, since the exchange oid isn't known to us.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HyperLiquidCancelByCloidRequest {
+    pub asset: u32,
+    pub cloid: String,
+}
+
+impl HyperLiquidCancelByCloidRequest {
+    pub fn for_order(order: &PendingOrder) -> Self {
+        Self {
+            asset: order.asset,
+            cloid: order.cloid.to_hex(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::secrets::Secret;
+    use rust_decimal_macros::dec;
+    use std::collections::VecDeque;
+
+    // Answers submissions from a script, succeeding once it runs out, and records when
+    // each one came in
+    #[derive(Debug, Default)]
+    struct ScriptedSubmitter {
+        results: parking_lot::Mutex<VecDeque<Result<(), ApiError>>>,
+        attempts: parking_lot::Mutex<Vec<std::time::Instant>>,
+    }
+
+    #[async_trait]
+    impl OrderSubmitter for ScriptedSubmitter {
+        async fn submit_order(&self, _order: &PendingOrder) -> Result<(), ApiError> {
+            self.attempts.lock().push(std::time::Instant::now());
+            self.results.lock().pop_front().unwrap_or(Ok(()))
+        }
+    }
+
+    fn scripted_api(results: Vec<Result<(), ApiError>>) -> (TradingApi, Arc<ScriptedSubmitter>, Receiver<ApiEvent>) {
+        let config = ApiConfig { retry_delay_ms: 100, max_retry_delay_ms: 10_000, max_retries: 5, ..ApiConfig::default() };
+        api_with_config(config, results)
+    }
+
+    fn api_with_config(config: ApiConfig, results: Vec<Result<(), ApiError>>) -> (TradingApi, Arc<ScriptedSubmitter>, Receiver<ApiEvent>) {
+        let meta = serde_json::from_value(serde_json::json!({
+            "universe": [{ "name": "HYPE", "szDecimals": 2, "maxLeverage": 5 }]
+        })).unwrap();
+        let submitter = Arc::new(ScriptedSubmitter { results: parking_lot::Mutex::new(results.into()), ..Default::default() });
+
+        let (api, rx) = TradingApi::new(HyperLiquidAuth::new(Secret::new("test".to_string())), config);
+        let api = api
+            .with_asset_registry(Arc::new(AssetRegistry::from_meta(&meta)))
+            .with_submitter(submitter.clone());
+        (api, submitter, rx)
+    }
+
+    fn buy() -> NewOrder {
+        NewOrder {
+            symbol: "HYPE".to_string(),
+            side: Side::Buy,
+            order_type: OrderType::Limit,
+            price: dec!(25),
+            size: dec!(1),
+            client_id: None,
+            reduce_only: false,
+            tick_received_at: None,
+        }
+    }
+
+    async fn wait_until(condition: impl Fn() -> bool) {
+        tokio::time::timeout(Duration::from_secs(5), async {
+            while !condition() {
+                tokio::time::sleep(Duration::from_millis(5)).await;
+            }
+        }).await.expect("condition never held");
+    }
+
+    #[tokio::test]
+    async fn transient_failures_are_retried_after_growing_backoffs() {
+        let (api, submitter, rx) = scripted_api(vec![
+            Err(ApiError::NetworkError("connection reset".to_string())),
+            Err(ApiError::Timeout("no response".to_string())),
+            Err(ApiError::NetworkError("connection reset".to_string())),
+        ]);
+        api.start_retry_processor().await;
+
+        let id = api.place_order(buy()).await.unwrap();
+        wait_until(|| api.get_pending_order(id).is_some_and(|order| order.retry_count == 3)).await;
+
+        // Each retry waits at least the lower half of a backoff doubling from 100ms
+        let attempts = submitter.attempts.lock().clone();
+        assert_eq!(attempts.len(), 4);
+        let gaps: Vec<_> = attempts.windows(2).map(|w| w[1] - w[0]).collect();
+        for (gap, min_ms) in gaps.iter().zip([50, 100, 200]) {
+            assert!(*gap >= Duration::from_millis(min_ms), "gaps {:?}", gaps);
+        }
+        assert!(rx.try_iter().all(|event| !matches!(event, ApiEvent::OrderRejected { .. } | ApiEvent::OrderRetriesExhausted { .. })));
+    }
+
+    #[tokio::test]
+    async fn a_permanent_rejection_is_attempted_exactly_once() {
+        let (api, submitter, rx) = scripted_api(vec![Err(ApiError::InsufficientBalance("margin".to_string()))]);
+        api.start_retry_processor().await;
+
+        assert!(matches!(api.place_order(buy()).await, Err(ApiError::InsufficientBalance(_))));
+        tokio::time::sleep(Duration::from_millis(300)).await;
+
+        assert_eq!(submitter.attempts.lock().len(), 1);
+        assert!(api.get_pending_orders().is_empty());
+        assert!(matches!(rx.try_recv(), Ok(ApiEvent::OrderRejected { .. })));
+    }
+
+    #[tokio::test]
+    async fn a_permanent_rejection_on_retry_ends_the_retries() {
+        let (api, submitter, rx) = scripted_api(vec![
+            Err(ApiError::NetworkError("connection reset".to_string())),
+            Err(ApiError::InvalidOrder("price too far from oracle".to_string())),
+        ]);
+        api.start_retry_processor().await;
+
+        api.place_order(buy()).await.unwrap();
+        wait_until(|| api.get_pending_orders().is_empty()).await;
+        tokio::time::sleep(Duration::from_millis(300)).await;
+
+        assert_eq!(submitter.attempts.lock().len(), 2);
+        assert!(matches!(rx.try_recv(), Ok(ApiEvent::OrderRejected { .. })));
+    }
+
+    // Fails the first submission, then holds the retry until released
+    #[derive(Debug, Default)]
+    struct HeldRetry {
+        calls: std::sync::atomic::AtomicUsize,
+        entered: tokio::sync::Notify,
+        release: tokio::sync::Notify,
+    }
+
+    #[async_trait]
+    impl OrderSubmitter for HeldRetry {
+        async fn submit_order(&self, _order: &PendingOrder) -> Result<(), ApiError> {
+            if self.calls.fetch_add(1, std::sync::atomic::Ordering::SeqCst) == 0 {
+                return Err(ApiError::NetworkError("connection reset".to_string()));
+            }
+            self.entered.notify_one();
+            self.release.notified().await;
+            Ok(())
+        }
+    }
+
+    #[tokio::test]
+    async fn a_retry_that_lands_after_a_cancel_does_not_bring_the_order_back() {
+        let (api, _, _rx) = scripted_api(Vec::new());
+        let submitter = Arc::new(HeldRetry::default());
+        let api = api.with_submitter(submitter.clone());
+        api.start_retry_processor().await;
+
+        let id = api.place_order(buy()).await.unwrap();
+        submitter.entered.notified().await;
+        api.pending_orders.retain(|_, order| order.internal_id != id);
+        submitter.release.notify_one();
+
+        wait_until(|| api.metrics.orders_placed.get() == 1).await;
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        assert!(api.get_pending_order(id).is_none());
+    }
+
+    #[test]
+    fn cancel_by_cloid_carries_the_orders_cloid_and_asset() {
+        let cloid = Cloid { strategy_id: 3, symbol_index: 2, session: 0x1234, sequence: 42 };
+        let order = PendingOrder {
+            internal_id: Uuid::new_v4(),
+            client_order_id: cloid.sequence,
+            cloid,
+            asset: 7,
+            symbol: "ETH".to_string(),
+            side: Side::Buy,
+            order_type: OrderType::Limit,
+            price: dec!(2000),
+            size: dec!(1),
+            filled_size: Decimal::ZERO,
+            reduce_only: false,
+            created_at: std::time::Instant::now(),
+            retry_count: 0,
+        };
+
+        let request = HyperLiquidCancelByCloidRequest::for_order(&order);
+        assert_eq!(Cloid::from_hex(&request.cloid), Some(cloid));
+        assert_eq!(
+            serde_json::to_value(&request).unwrap(),
+            serde_json::json!({ "asset": 7, "cloid": cloid.to_hex() })
+        );
+    }
+
+    #[tokio::test]
+    async fn paper_orders_fill_against_the_book_without_reaching_the_exchange() {
+        // Both the exchange and the submitter would fail if anything were sent
+        let config = ApiConfig { base_url: "http://127.0.0.1:9".to_string(), paper_trading: true, ..ApiConfig::default() };
+        let (api, submitter, rx) = api_with_config(config, vec![Err(ApiError::NetworkError("unreachable".to_string()))]);
+        let mut book = OrderBook::new("HYPE".to_string());
+        book.update_level(Side::Sell, dec!(24.9), dec!(0.6));
+        book.update_level(Side::Sell, dec!(25.1), dec!(5));
+        let order_books = Arc::new(DashMap::from_iter([("HYPE".to_string(), book)]));
+        let api = api.with_order_books(order_books);
+
+        // Crosses the first ask only, and the rest of the bid rests
+        let id = api.place_order(buy()).await.unwrap();
+
+        assert!(submitter.attempts.lock().is_empty());
+        assert_eq!(api.metrics.orders_placed.get(), 1);
+        let events: Vec<ApiEvent> = rx.try_iter().collect();
+        assert!(matches!(
+            &events[0],
+            ApiEvent::Fill { side, fill_size, fill_price, .. } if (side.as_str(), fill_size.as_str(), fill_price.as_str()) == ("B", "0.6", "24.9")
+        ), "{:?}", events);
+        assert!(matches!(
+            &events[1],
+            ApiEvent::OrderUpdate { status, remaining_size, .. } if status == "open" && remaining_size == "0.4"
+        ), "{:?}", events);
+        assert_eq!(api.get_pending_order(id).unwrap().filled_size, dec!(0.6));
+    }
+
+    fn exchange_fill(cloid: Option<String>, fill_size: &str) -> ApiEvent {
+        ApiEvent::Fill {
+            order_id: 41893022544,
+            coin: "HYPE".to_string(),
+            side: "B".to_string(),
+            fill_size: fill_size.to_string(),
+            fill_price: "25".to_string(),
+            fee: "0.01".to_string(),
+            timestamp: 1,
+            cloid,
+        }
+    }
+
+    #[tokio::test]
+    async fn exchange_fills_work_off_the_pending_order_and_are_passed_on() {
+        let (api, _, rx) = scripted_api(Vec::new());
+        let id = api.place_order(buy()).await.unwrap();
+        let pending = api.get_pending_order(id).unwrap();
+        let cloid = Some(pending.cloid.to_hex());
+
+        api.on_exchange_fill(exchange_fill(cloid.clone(), "0.4"));
+        assert_eq!(api.get_pending_order(id).unwrap().filled_size, dec!(0.4));
+        api.on_exchange_fill(exchange_fill(cloid, "0.6"));
+        assert!(api.get_pending_order(id).is_none());
+
+        // Fills come back out under the client order id, each followed by the order's state
+        let events: Vec<_> = rx.try_iter()
+            .map(|event| match event {
+                ApiEvent::Fill { order_id, fill_size, .. } => (order_id, "fill".to_string(), fill_size),
+                ApiEvent::OrderUpdate { order_id, status, remaining_size, .. } => (order_id, status, remaining_size),
+                event => panic!("unexpected event {:?}", event),
+            })
+            .collect();
+        let client_order_id = pending.client_order_id;
+        assert_eq!(events, [
+            (client_order_id, "fill".to_string(), "0.4".to_string()),
+            (client_order_id, "open".to_string(), "0.6".to_string()),
+            (client_order_id, "fill".to_string(), "0.6".to_string()),
+            (client_order_id, "filled".to_string(), "0".to_string()),
+        ]);
+    }
+
+    #[test]
+    fn fills_for_orders_placed_elsewhere_are_passed_on_unchanged() {
+        let (api, _, rx) = scripted_api(Vec::new());
+
+        api.on_exchange_fill(exchange_fill(None, "1"));
+
+        assert!(matches!(rx.try_recv(), Ok(ApiEvent::Fill { order_id: 41893022544, cloid: None, .. })));
+        assert!(rx.try_recv().is_err());
+    }
+}
@@ -1,3 +1,4 @@
+use rust_decimal::Decimal;
 use serde::{Deserialize, Serialize};
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -7,6 +8,113 @@ pub struct ApiConfig {
     pub timeout_ms: u64,
     pub max_retries: u32,
     pub retry_delay_ms: u64,
+    #[serde(default = "default_max_retry_delay_ms")]
+    pub max_retry_delay_ms: u64,    // Cap on the exponential order retry backoff
+    #[serde(default = "default_max_retry_queue")]
+    pub max_retry_queue: usize,     // Orders beyond this many awaiting retry are dropped
+    #[serde(default = "default_heartbeat_interval_ms")]
+    pub heartbeat_interval_ms: u64, // How often the trading WebSocket sends a ping
+    #[serde(default = "default_heartbeat_timeout_ms")]
+    pub heartbeat_timeout_ms: u64,  // Reconnect if nothing is received for this long
+    #[serde(default = "default_heartbeat_warning_ms")]
+    pub heartbeat_warning_ms: u64,  // Warn and ping early if nothing is received for this long, 0 disables
+    #[serde(default)]
+    pub rate_limit: RateLimitConfig,
+    #[serde(default = "default_warm_reconnect")]
+    pub warm_reconnect: bool,       // Keep the last book (marked stale) across a data reconnect
+    #[serde(default = "default_fee_refresh_interval_secs")]
+    pub fee_refresh_interval_secs: u64, // How often the account's fee tier is re-fetched
+    #[serde(default = "default_funding_refresh_interval_secs")]
+    pub funding_refresh_interval_secs: u64, // How often predicted funding rates are re-fetched
+    #[serde(default = "default_market_data_connections")]
+    pub market_data_connections: u64, // Redundant market data WebSockets, see ws_manager::MAX_STREAMS
+    #[serde(default = "default_market_data_warmup_updates")]
+    pub market_data_warmup_updates: u64, // Valid book updates a connection must deliver before it is used
+    #[serde(default = "default_max_market_data_age_ms")]
+    pub max_market_data_age_ms: u64, // Book updates older than this by exchange time are dropped, 0 disables
+    #[serde(default)]
+    pub adopt_unknown_orders: bool, // On startup, track resting orders without one of our cloids instead of cancelling them
+    #[serde(default)]
+    pub paper_trading: bool, // Simulate orders against the live book instead of sending them
+    #[serde(default = "default_tick_to_trade_budget_ms")]
+    pub tick_to_trade_budget_ms: u64, // Warn when an order is submitted later than this after the data behind it arrived
+    #[serde(default = "default_shutdown_cancel_timeout_secs")]
+    pub shutdown_cancel_timeout_secs: u64, // How long stop keeps re-cancelling orders still open on the exchange
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RateLimitConfig {
+    pub budget_per_minute: u32, // Total request weight allowed per minute
+    pub exchange_weight: u32,
+    pub info_light_weight: u32,
+    pub info_heavy_weight: u32,
+    pub max_wait_ms: u64,       // Longest a caller waits for capacity before getting a RateLimitError
+    pub backoff_on_429_ms: u64, // Used when a 429 carries no Retry-After header
+}
+
+impl Default for RateLimitConfig {
+    fn default() -> Self {
+        Self {
+            budget_per_minute: 1200, // HyperLiquid REST limit per IP
+            exchange_weight: 1,
+            info_light_weight: 2,
+            info_heavy_weight: 20,
+            max_wait_ms: 2000,
+            backoff_on_429_ms: 10000,
+        }
+    }
+}
+
+fn default_max_retry_delay_ms() -> u64 {
+    30000
+}
+
+fn default_max_retry_queue() -> usize {
+    1000
+}
+
+fn default_warm_reconnect() -> bool {
+    true
+}
+
+fn default_fee_refresh_interval_secs() -> u64 {
+    3600
+}
+
+fn default_market_data_connections() -> u64 {
+    3
+}
+
+fn default_tick_to_trade_budget_ms() -> u64 {
+    250
+}
+
+fn default_market_data_warmup_updates() -> u64 {
+    2
+}
+
+fn default_max_market_data_age_ms() -> u64 {
+    5000
+}
+
+fn default_shutdown_cancel_timeout_secs() -> u64 {
+    10
+}
+
+fn default_funding_refresh_interval_secs() -> u64 {
+    60
+}
+
+fn default_heartbeat_interval_ms() -> u64 {
+    30000
+}
+
+fn default_heartbeat_timeout_ms() -> u64 {
+    60000
+}
+
+fn default_heartbeat_warning_ms() -> u64 {
+    45000
 }
 
 impl Default for ApiConfig {
@@ -17,13 +125,29 @@ impl Default for ApiConfig {
             timeout_ms: 5000,
             max_retries: 3,
             retry_delay_ms: 1000,
+            max_retry_delay_ms: default_max_retry_delay_ms(),
+            max_retry_queue: default_max_retry_queue(),
+            heartbeat_interval_ms: default_heartbeat_interval_ms(),
+            heartbeat_timeout_ms: default_heartbeat_timeout_ms(),
+            heartbeat_warning_ms: default_heartbeat_warning_ms(),
+            rate_limit: RateLimitConfig::default(),
+            warm_reconnect: default_warm_reconnect(),
+            fee_refresh_interval_secs: default_fee_refresh_interval_secs(),
+            funding_refresh_interval_secs: default_funding_refresh_interval_secs(),
+            market_data_connections: default_market_data_connections(),
+            market_data_warmup_updates: default_market_data_warmup_updates(),
+            max_market_data_age_ms: default_max_market_data_age_ms(),
+            adopt_unknown_orders: false,
+            paper_trading: false,
+            tick_to_trade_budget_ms: default_tick_to_trade_budget_ms(),
+            shutdown_cancel_timeout_secs: default_shutdown_cancel_timeout_secs(),
         }
     }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct HyperLiquidOrder {
-    pub a: Option<u64>, // account id
+    pub a: Option<u64>, // asset index, see `api::assets`
     pub b: bool,        // is buy
     pub p: String,      // price
     pub s: String,      // size
@@ -31,6 +155,8 @@ pub struct HyperLiquidOrder {
     pub t: String,      // order type
     pub cid: u64,       // client order id
     pub oid: Option<u64>, // order id
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub c: Option<String>, // 128-bit cloid, see `api::cloid`
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -58,6 +184,8 @@ pub struct HyperLiquidOrderStatus {
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct HyperLiquidOrderRest {
+    #[serde(default)]
+    pub coin: String, // Only present in openOrders
     pub oid: u64,
     pub total_sz: String,
     pub sz: String,
@@ -121,6 +249,32 @@ pub struct HyperLiquidFill {
     pub fee: String,
 }
 
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HyperLiquidFundingDelta {
+    pub coin: String,
+    pub funding_rate: String,
+    pub szi: String,
+    #[serde(rename = "type")]
+    pub type_: String,
+    pub usdc: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HyperLiquidFundingEntry {
+    pub delta: HyperLiquidFundingDelta,
+    pub hash: String,
+    pub time: u64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FundingPayment {
+    pub coin: String,
+    pub amount: Decimal, // USDC paid (negative) or received (positive)
+    pub rate: Decimal,
+    pub time: u64,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct HyperLiquidUserState {
     pub asset_positions: Vec<HyperLiquidPosition>,
@@ -142,7 +296,7 @@ pub enum ApiError {
     NetworkError(String),
     ParseError(String),
     AuthenticationError(String),
-    RateLimitError(String),
+    RateLimitError { message: String, retry_after_ms: u64 },
     OrderRejected(String),
     InsufficientBalance(String),
     InvalidOrder(String),
@@ -156,7 +310,9 @@ impl std::fmt::Display for ApiError {
             ApiError::NetworkError(msg) => write!(f, "Network error: {}", msg),
             ApiError::ParseError(msg) => write!(f, "Parse error: {}", msg),
             ApiError::AuthenticationError(msg) => write!(f, "Authentication error: {}", msg),
-            ApiError::RateLimitError(msg) => write!(f, "Rate limit error: {}", msg),
+            ApiError::RateLimitError { message, retry_after_ms } => {
+                write!(f, "Rate limit error: {} (retry after {}ms)", message, retry_after_ms)
+            }
             ApiError::OrderRejected(msg) => write!(f, "Order rejected: {}", msg),
             ApiError::InsufficientBalance(msg) => write!(f, "Insufficient balance: {}", msg),
             ApiError::InvalidOrder(msg) => write!(f, "Invalid order: {}", msg),
@@ -166,6 +322,17 @@ impl std::fmt::Display for ApiError {
     }
 }
 
+impl ApiError {
+    /// Whether the same request may succeed if sent again later. Rejections and
+    /// failures after the exchange may have accepted the request are not retried.
+    pub fn is_retryable(&self) -> bool {
+        matches!(
+            self,
+            ApiError::NetworkError(_) | ApiError::Timeout(_) | ApiError::RateLimitError { .. }
+        )
+    }
+}
+
 impl std::error::Error for ApiError {}
 
 impl From<serde_json::Error> for ApiError {
@@ -189,13 +356,17 @@ pub enum ApiEvent {
         remaining_size: String,
         price: String,
         timestamp: u64,
+        cloid: Option<String>, // Ours when it decodes with CloidRegistry
     },
     Fill {
         order_id: u64,
+        coin: String,
+        side: String, // "B" for buy, "A" for sell
         fill_size: String,
         fill_price: String,
         fee: String,
         timestamp: u64,
+        cloid: Option<String>,
     },
     PositionUpdate {
         coin: String,
@@ -208,6 +379,23 @@ pub enum ApiEvent {
         margin_used: String,
         withdrawable: String,
     },
+    FundingPayment {
+        coin: String,
+        amount: String,
+        rate: String,
+        timestamp: u64,
+    },
+    OrderRetriesExhausted {
+        order_id: u64,
+        attempts: u32,
+        error: String,
+        timestamp: u64,
+    },
+    OrderRejected {
+        order_id: u64,
+        reason: String,
+        timestamp: u64,
+    },
     Error {
         error: String,
         timestamp: u64,
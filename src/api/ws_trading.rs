@@ -1,22 +1,88 @@
 use crate::api::types::*;
 use crate::api::auth::HyperLiquidAuth;
+use crate::api::account_api::AccountApi;
+use crate::model::user_msgs::*;
+use crate::trading::types::Position;
 use anyhow::Result;
 use crossbeam_channel::{Sender, Receiver, unbounded};
+use dashmap::DashMap;
 use parking_lot::RwLock;
+use rust_decimal::Decimal;
+use std::str::FromStr;
 use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::mpsc;
+use tokio::task::JoinHandle;
 use tracing::{error, info, warn, debug};
 use yawc::{frame::FrameView, Options, WebSocket};
 use futures::{StreamExt, SinkExt};
 
+const MAX_RECONNECT_ATTEMPTS: u32 = 10;
+const MAX_RECONNECT_DELAY: Duration = Duration::from_secs(30);
+
+// How often the time since the last inbound frame is checked, independent of pings
+const HEARTBEAT_CHECK_INTERVAL: Duration = Duration::from_secs(1);
+
+/// How long the trading stream has been silent, against the configured limits.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HeartbeatStatus {
+    Healthy,
+    Late,     // Past heartbeat_warning_ms: warned about and pinged early
+    TimedOut, // Past heartbeat_timeout_ms: the connection is dropped and re-established
+}
+
+/// Status of a stream whose last inbound frame arrived `age` ago.
+pub fn heartbeat_status(config: &ApiConfig, age: Duration) -> HeartbeatStatus {
+    if age > Duration::from_millis(config.heartbeat_timeout_ms) {
+        HeartbeatStatus::TimedOut
+    } else if config.heartbeat_warning_ms > 0 && age > Duration::from_millis(config.heartbeat_warning_ms) {
+        HeartbeatStatus::Late
+    } else {
+        HeartbeatStatus::Healthy
+    }
+}
+
+/// Pings must go out more often than the timeout, or a quiet but healthy stream is
+/// dropped before it has a chance to answer; likewise the warning must come first.
+pub fn validate_heartbeat(config: &ApiConfig) -> Result<(), String> {
+    if config.heartbeat_interval_ms == 0 || config.heartbeat_interval_ms >= config.heartbeat_timeout_ms {
+        return Err(format!(
+            "Heartbeat interval ({}ms) must be positive and below the heartbeat timeout ({}ms)",
+            config.heartbeat_interval_ms, config.heartbeat_timeout_ms
+        ));
+    }
+    if config.heartbeat_warning_ms >= config.heartbeat_timeout_ms {
+        return Err(format!(
+            "Heartbeat warning ({}ms) must be below the heartbeat timeout ({}ms)",
+            config.heartbeat_warning_ms, config.heartbeat_timeout_ms
+        ));
+    }
+    Ok(())
+}
+
+/// Handle to the private trading stream.
+///
+/// The socket itself is owned by a session task spawned in `connect`, which reads
+/// messages, reconnects with exponential backoff when the connection drops and
+/// re-sends every subscription recorded in `subscription_state`.
+///
+/// Position and fill messages are also written into `positions` and `account_info`.
+/// Sharing those with `AccountApi` via `with_account_cache` keeps its cache current
+/// between polls.
 pub struct TradingWebSocket {
     pub auth: HyperLiquidAuth,
     pub config: ApiConfig,
-    pub ws: Option<WebSocket>,
     pub trading_events_tx: Sender<ApiEvent>,
     pub connection_state: Arc<RwLock<ConnectionState>>,
     pub subscription_state: Arc<RwLock<SubscriptionState>>,
     pub reconnect_attempts: Arc<RwLock<u32>>,
+    pub total_reconnects: Arc<RwLock<u64>>,
     pub last_heartbeat: Arc<RwLock<std::time::Instant>>,
+    pub positions: Arc<DashMap<String, Position>>,
+    pub account_info: Arc<RwLock<Option<HyperLiquidAccountInfo>>>,
+    pub last_account_update: Arc<RwLock<std::time::Instant>>,
+    command_tx: Option<mpsc::UnboundedSender<SessionCommand>>,
+    session: Option<JoinHandle<()>>,
 }
 
 #[derive(Debug, Clone)]
@@ -28,7 +94,7 @@ pub enum ConnectionState {
     Error(String),
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Default)]
 pub struct SubscriptionState {
     pub user_events: bool,
     pub fills: bool,
@@ -36,17 +102,35 @@ pub struct SubscriptionState {
     pub positions: bool,
 }
 
-impl Default for SubscriptionState {
-    fn default() -> Self {
-        Self {
-            user_events: false,
-            fills: false,
-            orders: false,
-            positions: false,
+impl SubscriptionState {
+    pub fn active_channels(&self) -> Vec<&'static str> {
+        let mut channels = Vec::new();
+        if self.user_events {
+            channels.push("userEvents");
+        }
+        if self.fills {
+            channels.push("userFills");
+        }
+        if self.orders {
+            channels.push("orderUpdates");
         }
+        if self.positions {
+            channels.push("webData2");
+        }
+        channels
     }
 }
 
+enum SessionCommand {
+    Send(String),
+    Close,
+}
+
+enum SessionExit {
+    Closed,
+    Lost(String),
+}
+
 impl TradingWebSocket {
     pub fn new(auth: HyperLiquidAuth, config: ApiConfig) -> (Self, Receiver<ApiEvent>) {
         let (tx, rx) = unbounded();
@@ -54,18 +138,35 @@ impl TradingWebSocket {
         let ws = Self {
             auth,
             config,
-            ws: None,
             trading_events_tx: tx,
             connection_state: Arc::new(RwLock::new(ConnectionState::Disconnected)),
             subscription_state: Arc::new(RwLock::new(SubscriptionState::default())),
             reconnect_attempts: Arc::new(RwLock::new(0)),
+            total_reconnects: Arc::new(RwLock::new(0)),
             last_heartbeat: Arc::new(RwLock::new(std::time::Instant::now())),
+            positions: Arc::new(DashMap::new()),
+            account_info: Arc::new(RwLock::new(None)),
+            last_account_update: Arc::new(RwLock::new(std::time::Instant::now())),
+            command_tx: None,
+            session: None,
         };
         
         (ws, rx)
     }
 
+    /// Writes streamed positions and margin state into the account API's cache.
+    pub fn with_account_cache(mut self, account_api: &AccountApi) -> Self {
+        self.positions = Arc::clone(&account_api.positions);
+        self.account_info = Arc::clone(&account_api.account_info);
+        self.last_account_update = Arc::clone(&account_api.last_update);
+        self
+    }
+
     pub async fn connect(&mut self) -> Result<(), ApiError> {
+        if self.session.as_ref().is_some_and(|s| !s.is_finished()) {
+            return Ok(());
+        }
+
         info!("Connecting to HyperLiquid trading WebSocket");
         
         {
@@ -73,458 +174,949 @@ impl TradingWebSocket {
             *state = ConnectionState::Connecting;
         }
 
-        let ws_url = &self.config.ws_url;
-        let client = WebSocket::connect_with_options(
-            ws_url.parse::<url::Url>().map_err(|e| ApiError::NetworkError(e.to_string()))?,
-            None,
-            Options::default(),
-        ).await.map_err(|e| ApiError::NetworkError(e.to_string()))?;
+        let mut client = match open_socket(&self.config.ws_url).await {
+            Ok(client) => client,
+            Err(e) => {
+                let mut state = self.connection_state.write();
+                *state = ConnectionState::Error(e.to_string());
+                return Err(e);
+            }
+        };
 
-        self.ws = Some(client);
-        
-        {
+        let session = TradingSession {
+            config: self.config.clone(),
+            account_id: self.auth.account_id,
+            trading_events_tx: self.trading_events_tx.clone(),
+            connection_state: Arc::clone(&self.connection_state),
+            subscription_state: Arc::clone(&self.subscription_state),
+            reconnect_attempts: Arc::clone(&self.reconnect_attempts),
+            total_reconnects: Arc::clone(&self.total_reconnects),
+            last_heartbeat: Arc::clone(&self.last_heartbeat),
+            positions: Arc::clone(&self.positions),
+            account_info: Arc::clone(&self.account_info),
+            last_account_update: Arc::clone(&self.last_account_update),
+        };
+        if let Err(e) = session.resubscribe(&mut client).await {
             let mut state = self.connection_state.write();
-            *state = ConnectionState::Connected;
-        }
-
-        {
-            let mut attempts = self.reconnect_attempts.write();
-            *attempts = 0;
+            *state = ConnectionState::Error(e.to_string());
+            return Err(e);
         }
+        session.mark_connected();
 
-        {
-            let mut heartbeat = self.last_heartbeat.write();
-            *heartbeat = std::time::Instant::now();
-        }
+        let (command_tx, command_rx) = mpsc::unbounded_channel();
+        self.command_tx = Some(command_tx);
+        self.session = Some(tokio::spawn(session.run(client, command_rx)));
 
         info!("Connected to HyperLiquid trading WebSocket");
         Ok(())
     }
 
     pub async fn subscribe_to_user_events(&mut self) -> Result<(), ApiError> {
-        if self.ws.is_none() {
-            return Err(ApiError::NetworkError("WebSocket not connected".to_string()));
-        }
-
-        let subscribe_msg = serde_json::json!({
-            "method": "subscribe",
-            "subscription": {
-                "type": "userEvents",
-                "user": self.auth.account_id.map(|id| id.to_string())
-            }
-        });
-
-        let ws = self.ws.as_mut().unwrap();
-        let message = serde_json::to_string(&subscribe_msg)
-            .map_err(|e| ApiError::ParseError(e.to_string()))?;
-
-        ws.send(FrameView::text(message)).await
-            .map_err(|e| ApiError::NetworkError(e.to_string()))?;
-
-        {
-            let mut sub_state = self.subscription_state.write();
-            sub_state.user_events = true;
-        }
+        self.subscription_state.write().user_events = true;
+        self.subscribe("userEvents")?;
 
         info!("Subscribed to user events");
         Ok(())
     }
 
     pub async fn subscribe_to_fills(&mut self) -> Result<(), ApiError> {
-        if self.ws.is_none() {
-            return Err(ApiError::NetworkError("WebSocket not connected".to_string()));
-        }
+        self.subscription_state.write().fills = true;
+        self.subscribe("userFills")?;
 
-        let subscribe_msg = serde_json::json!({
-            "method": "subscribe",
-            "subscription": {
-                "type": "fills",
-                "user": self.auth.account_id.map(|id| id.to_string())
-            }
-        });
+        info!("Subscribed to fills");
+        Ok(())
+    }
 
-        let ws = self.ws.as_mut().unwrap();
-        let message = serde_json::to_string(&subscribe_msg)
-            .map_err(|e| ApiError::ParseError(e.to_string()))?;
+    pub async fn subscribe_to_orders(&mut self) -> Result<(), ApiError> {
+        self.subscription_state.write().orders = true;
+        self.subscribe("orderUpdates")?;
 
-        ws.send(FrameView::text(message)).await
-            .map_err(|e| ApiError::NetworkError(e.to_string()))?;
+        info!("Subscribed to orders");
+        Ok(())
+    }
 
-        {
-            let mut sub_state = self.subscription_state.write();
-            sub_state.fills = true;
-        }
+    pub async fn subscribe_to_positions(&mut self) -> Result<(), ApiError> {
+        self.subscription_state.write().positions = true;
+        self.subscribe("webData2")?;
 
-        info!("Subscribed to fills");
+        info!("Subscribed to positions");
         Ok(())
     }
 
-    pub async fn subscribe_to_orders(&mut self) -> Result<(), ApiError> {
-        if self.ws.is_none() {
-            return Err(ApiError::NetworkError("WebSocket not connected".to_string()));
-        }
+    pub async fn subscribe_to_all(&mut self) -> Result<(), ApiError> {
+        self.subscribe_to_user_events().await?;
+        self.subscribe_to_fills().await?;
+        self.subscribe_to_orders().await?;
+        self.subscribe_to_positions().await?;
+        Ok(())
+    }
 
-        let subscribe_msg = serde_json::json!({
-            "method": "subscribe",
-            "subscription": {
-                "type": "orders",
-                "user": self.auth.account_id.map(|id| id.to_string())
-            }
-        });
+    // Queues the subscription on the live session. Callers record it in
+    // `subscription_state` first, so a socket that drops before the message goes out
+    // still replays it once the new connection is up, and before `connect` it is
+    // simply sent when the socket opens.
+    fn subscribe(&self, channel: &str) -> Result<(), ApiError> {
+        let Some(command_tx) = self.command_tx.as_ref() else {
+            return Ok(());
+        };
+
+        let message = subscription_message(channel, self.auth.account_id)?;
+        command_tx.send(SessionCommand::Send(message))
+            .map_err(|_| ApiError::NetworkError("WebSocket session has stopped".to_string()))
+    }
+
+    /// Waits for the session task to finish, i.e. until `disconnect` is called or
+    /// reconnection attempts are exhausted.
+    pub async fn run(&mut self) -> Result<(), ApiError> {
+        let session = self.session.take()
+            .ok_or_else(|| ApiError::NetworkError("WebSocket not connected".to_string()))?;
 
-        let ws = self.ws.as_mut().unwrap();
-        let message = serde_json::to_string(&subscribe_msg)
-            .map_err(|e| ApiError::ParseError(e.to_string()))?;
+        session.await.map_err(|e| ApiError::Unknown(e.to_string()))
+    }
 
-        ws.send(FrameView::text(message)).await
-            .map_err(|e| ApiError::NetworkError(e.to_string()))?;
+    pub async fn disconnect(&mut self) -> Result<(), ApiError> {
+        if let Some(command_tx) = self.command_tx.take() {
+            let _ = command_tx.send(SessionCommand::Close);
+        }
 
+        if let Some(session) = self.session.take() {
+            session.await.map_err(|e| ApiError::Unknown(e.to_string()))?;
+        }
+        
         {
-            let mut sub_state = self.subscription_state.write();
-            sub_state.orders = true;
+            let mut state = self.connection_state.write();
+            *state = ConnectionState::Disconnected;
         }
 
-        info!("Subscribed to orders");
+        info!("Disconnected from HyperLiquid trading WebSocket");
         Ok(())
     }
 
-    pub async fn subscribe_to_positions(&mut self) -> Result<(), ApiError> {
-        if self.ws.is_none() {
-            return Err(ApiError::NetworkError("WebSocket not connected".to_string()));
-        }
+    pub fn is_connected(&self) -> bool {
+        matches!(*self.connection_state.read(), ConnectionState::Connected)
+    }
 
-        let subscribe_msg = serde_json::json!({
-            "method": "subscribe",
-            "subscription": {
-                "type": "positions",
-                "user": self.auth.account_id.map(|id| id.to_string())
-            }
-        });
+    pub fn get_connection_state(&self) -> ConnectionState {
+        self.connection_state.read().clone()
+    }
 
-        let ws = self.ws.as_mut().unwrap();
-        let message = serde_json::to_string(&subscribe_msg)
-            .map_err(|e| ApiError::ParseError(e.to_string()))?;
+    pub fn get_subscription_state(&self) -> SubscriptionState {
+        self.subscription_state.read().clone()
+    }
 
-        ws.send(FrameView::text(message)).await
-            .map_err(|e| ApiError::NetworkError(e.to_string()))?;
+    pub fn get_total_reconnects(&self) -> u64 {
+        *self.total_reconnects.read()
+    }
 
-        {
-            let mut sub_state = self.subscription_state.write();
-            sub_state.positions = true;
-        }
+    pub fn get_cached_position(&self, symbol: &str) -> Option<Position> {
+        self.positions.get(symbol).map(|entry| entry.value().clone())
+    }
+}
 
-        info!("Subscribed to positions");
-        Ok(())
+struct TradingSession {
+    config: ApiConfig,
+    account_id: Option<u64>,
+    trading_events_tx: Sender<ApiEvent>,
+    connection_state: Arc<RwLock<ConnectionState>>,
+    subscription_state: Arc<RwLock<SubscriptionState>>,
+    reconnect_attempts: Arc<RwLock<u32>>,
+    total_reconnects: Arc<RwLock<u64>>,
+    last_heartbeat: Arc<RwLock<std::time::Instant>>,
+    positions: Arc<DashMap<String, Position>>,
+    account_info: Arc<RwLock<Option<HyperLiquidAccountInfo>>>,
+    last_account_update: Arc<RwLock<std::time::Instant>>,
+}
+
+impl TradingSession {
+    async fn run(self, mut ws: WebSocket, mut commands: mpsc::UnboundedReceiver<SessionCommand>) {
+        loop {
+            match self.consume(&mut ws, &mut commands).await {
+                SessionExit::Closed => break,
+                SessionExit::Lost(reason) => {
+                    warn!("Trading WebSocket connection lost: {}", reason);
+                    {
+                        let mut state = self.connection_state.write();
+                        *state = ConnectionState::Reconnecting;
+                    }
+
+                    match self.reconnect(&mut commands).await {
+                        Some(new_ws) => ws = new_ws,
+                        None => break,
+                    }
+                }
+            }
+        }
     }
 
-    pub async fn subscribe_to_all(&mut self) -> Result<(), ApiError> {
-        self.subscribe_to_user_events().await?;
-        self.subscribe_to_fills().await?;
-        self.subscribe_to_orders().await?;
-        self.subscribe_to_positions().await?;
-        Ok(())
+    async fn consume(&self, ws: &mut WebSocket, commands: &mut mpsc::UnboundedReceiver<SessionCommand>) -> SessionExit {
+        let mut heartbeat = tokio::time::interval(Duration::from_millis(self.config.heartbeat_interval_ms));
+        heartbeat.tick().await;
+        let mut heartbeat_check = tokio::time::interval(HEARTBEAT_CHECK_INTERVAL);
+        let mut late = false;
+
+        loop {
+            tokio::select! {
+                frame = ws.next() => {
+                    let Some(frame) = frame else {
+                        return SessionExit::Lost("stream ended".to_string());
+                    };
+                    if let Some(exit) = self.handle_message(ws, frame).await {
+                        return exit;
+                    }
+                }
+                command = commands.recv() => {
+                    match command {
+                        Some(SessionCommand::Send(message)) => {
+                            if let Err(e) = ws.send(FrameView::text(message)).await {
+                                return SessionExit::Lost(e.to_string());
+                            }
+                        }
+                        Some(SessionCommand::Close) | None => {
+                            if let Err(e) = ws.close().await {
+                                warn!("Error closing trading WebSocket: {}", e);
+                            }
+                            return SessionExit::Closed;
+                        }
+                    }
+                }
+                _ = heartbeat.tick() => {
+                    if let Err(e) = send_ping(ws).await {
+                        return SessionExit::Lost(e.to_string());
+                    }
+                }
+                _ = heartbeat_check.tick() => {
+                    let heartbeat_age = self.last_heartbeat.read().elapsed();
+                    match heartbeat_status(&self.config, heartbeat_age) {
+                        HeartbeatStatus::Healthy => late = false,
+                        HeartbeatStatus::Late if !late => {
+                            late = true;
+                            warn!("Nothing received on the trading WebSocket for {}ms, pinging early", heartbeat_age.as_millis());
+                            if let Err(e) = send_ping(ws).await {
+                                return SessionExit::Lost(e.to_string());
+                            }
+                        }
+                        HeartbeatStatus::Late => {}
+                        HeartbeatStatus::TimedOut => {
+                            self.send_error("WebSocket heartbeat timeout".to_string());
+                            return SessionExit::Lost("heartbeat timeout".to_string());
+                        }
+                    }
+                }
+            }
+        }
     }
 
-    pub async fn run(&mut self) -> Result<(), ApiError> {
-        if self.ws.is_none() {
-            return Err(ApiError::NetworkError("WebSocket not connected".to_string()));
-        }
-
-        let trading_events_tx = self.trading_events_tx.clone();
-        let connection_state = Arc::clone(&self.connection_state);
-        let last_heartbeat = Arc::clone(&self.last_heartbeat);
-
-        // Start heartbeat monitor
-        let heartbeat_monitor = {
-            let connection_state = Arc::clone(&connection_state);
-            let last_heartbeat = Arc::clone(&last_heartbeat);
-            let trading_events_tx = trading_events_tx.clone();
-            
-            tokio::spawn(async move {
-                let mut interval = tokio::time::interval(std::time::Duration::from_secs(30));
-                
-                loop {
-                    interval.tick().await;
-                    
-                    let heartbeat_age = last_heartbeat.read().elapsed();
-                    if heartbeat_age > std::time::Duration::from_secs(60) {
-                        warn!("WebSocket heartbeat timeout");
-                        {
-                            let mut state = connection_state.write();
-                            *state = ConnectionState::Error("Heartbeat timeout".to_string());
+    async fn reconnect(&self, commands: &mut mpsc::UnboundedReceiver<SessionCommand>) -> Option<WebSocket> {
+        loop {
+            let attempts = {
+                let mut attempts = self.reconnect_attempts.write();
+                *attempts += 1;
+                *attempts
+            };
+
+            if attempts > MAX_RECONNECT_ATTEMPTS {
+                error!("Max reconnection attempts reached for trading WebSocket");
+                let mut state = self.connection_state.write();
+                *state = ConnectionState::Error("Max reconnection attempts reached".to_string());
+                return None;
+            }
+
+            // Back off before retrying, but stay responsive to a disconnect request.
+            // Subscriptions sent meanwhile are already recorded and get replayed below.
+            let delay = self.backoff_delay(attempts);
+            let sleep = tokio::time::sleep(delay);
+            tokio::pin!(sleep);
+            loop {
+                tokio::select! {
+                    _ = &mut sleep => break,
+                    command = commands.recv() => {
+                        if !matches!(command, Some(SessionCommand::Send(_))) {
+                            return None;
                         }
-                        
-                        let _ = trading_events_tx.send(ApiEvent::Error {
-                            error: "WebSocket heartbeat timeout".to_string(),
-                            timestamp: std::time::SystemTime::now()
-                                .duration_since(std::time::UNIX_EPOCH)
-                                .unwrap()
-                                .as_millis() as u64,
-                        });
                     }
                 }
-            })
-        };
+            }
+
+            info!("Attempting to reconnect to trading WebSocket (attempt {})", attempts);
+
+            let mut ws = match open_socket(&self.config.ws_url).await {
+                Ok(ws) => ws,
+                Err(e) => {
+                    error!("Failed to reconnect to trading WebSocket: {}", e);
+                    let mut state = self.connection_state.write();
+                    *state = ConnectionState::Error(e.to_string());
+                    continue;
+                }
+            };
 
-        // Message processing loop
-        while let Some(frame) = self.ws.as_mut().unwrap().next().await {
-            if let Err(e) = self.handle_message(frame).await {
-                error!("Error handling WebSocket message: {}", e);
+            match self.resubscribe(&mut ws).await {
+                Ok(()) => {
+                    self.mark_connected();
+                    *self.total_reconnects.write() += 1;
+                    info!("Successfully reconnected to trading WebSocket");
+                    return Some(ws);
+                }
+                Err(e) => {
+                    error!("Failed to resubscribe after reconnect: {}", e);
+                    let mut state = self.connection_state.write();
+                    *state = ConnectionState::Error(e.to_string());
+                }
             }
         }
+    }
 
-        heartbeat_monitor.abort();
+    async fn resubscribe(&self, ws: &mut WebSocket) -> Result<(), ApiError> {
+        let channels = self.subscription_state.read().active_channels();
+        for channel in channels {
+            let message = subscription_message(channel, self.account_id)?;
+            ws.send(FrameView::text(message)).await
+                .map_err(|e| ApiError::NetworkError(e.to_string()))?;
+            info!("Resubscribed to {}", channel);
+        }
         Ok(())
     }
 
-    async fn handle_message(&mut self, frame: FrameView) -> Result<(), ApiError> {
+    fn backoff_delay(&self, attempts: u32) -> Duration {
+        let base = Duration::from_millis(self.config.retry_delay_ms);
+        base.saturating_mul(2u32.saturating_pow(attempts.saturating_sub(1)))
+            .min(MAX_RECONNECT_DELAY)
+    }
+
+    fn mark_connected(&self) {
+        {
+            let mut state = self.connection_state.write();
+            *state = ConnectionState::Connected;
+        }
+
+        {
+            let mut attempts = self.reconnect_attempts.write();
+            *attempts = 0;
+        }
+
+        {
+            let mut heartbeat = self.last_heartbeat.write();
+            *heartbeat = std::time::Instant::now();
+        }
+    }
+
+    async fn handle_message(&self, ws: &mut WebSocket, frame: FrameView) -> Option<SessionExit> {
+        // Any inbound frame shows the connection is alive
+        {
+            let mut heartbeat = self.last_heartbeat.write();
+            *heartbeat = std::time::Instant::now();
+        }
+
         match frame.opcode {
             yawc::frame::OpCode::Text => {
                 if let Ok(text) = std::str::from_utf8(&frame.payload) {
                     debug!("Received WebSocket message: {}", text);
                     
-                    if let Ok(message) = serde_json::from_str::<serde_json::Value>(text) {
-                        self.process_trading_message(message).await?;
-                    }
+                    self.process_trading_message(text);
                 }
             }
             yawc::frame::OpCode::Ping => {
                 // Respond to ping with pong
-                if let Some(ws) = &mut self.ws {
-                    ws.send(FrameView::pong(frame.payload.to_vec())).await
-                        .map_err(|e| ApiError::NetworkError(e.to_string()))?;
-                }
-            }
-            yawc::frame::OpCode::Pong => {
-                // Update heartbeat
-                {
-                    let mut heartbeat = self.last_heartbeat.write();
-                    *heartbeat = std::time::Instant::now();
+                if let Err(e) = ws.send(FrameView::pong(frame.payload.to_vec())).await {
+                    return Some(SessionExit::Lost(e.to_string()));
                 }
             }
             yawc::frame::OpCode::Close => {
-                warn!("WebSocket connection closed by server");
-                {
-                    let mut state = self.connection_state.write();
-                    *state = ConnectionState::Disconnected;
-                }
+                return Some(SessionExit::Lost("closed by server".to_string()));
             }
             _ => {}
         }
-        Ok(())
+        None
     }
 
-    async fn process_trading_message(&self, message: serde_json::Value) -> Result<(), ApiError> {
-        if let Some(channel) = message.get("channel").and_then(|c| c.as_str()) {
-            match channel {
-                "userEvents" => {
-                    if let Some(data) = message.get("data") {
-                        self.process_user_event(data).await?;
-                    }
-                }
-                "fills" => {
-                    if let Some(data) = message.get("data") {
-                        self.process_fill(data).await?;
-                    }
-                }
-                "orders" => {
-                    if let Some(data) = message.get("data") {
-                        self.process_order_update(data).await?;
+    fn process_trading_message(&self, text: &str) {
+        let message = match serde_json::from_str::<UserWsMsg>(text) {
+            Ok(message) => message,
+            Err(e) => {
+                warn!("Failed to parse trading WebSocket message: {}", e);
+                self.send_error(format!("Failed to parse trading message ({}): {}", e, text));
+                return;
+            }
+        };
+
+        match message {
+            UserWsMsg::UserEvents(event) => self.process_user_event(event),
+            UserWsMsg::UserFills(data) => {
+                // The first message after subscribing replays recent history
+                if data.is_snapshot {
+                    debug!("Skipping fills snapshot with {} fills", data.fills.len());
+                } else {
+                    for fill in &data.fills {
+                        self.process_fill(fill);
                     }
                 }
-                "positions" => {
-                    if let Some(data) = message.get("data") {
-                        self.process_position_update(data).await?;
-                    }
+            }
+            UserWsMsg::OrderUpdates(updates) => {
+                for update in &updates {
+                    self.process_order_update(update);
                 }
-                "pong" => {
-                    // Update heartbeat
-                    {
-                        let mut heartbeat = self.last_heartbeat.write();
-                        *heartbeat = std::time::Instant::now();
+            }
+            UserWsMsg::WebData2(data) => self.process_position_update(&data.clearinghouse_state),
+            UserWsMsg::SubscriptionResponse(data) => {
+                debug!("Subscription acknowledged: {}", data);
+            }
+            UserWsMsg::Error(error) => {
+                error!("Trading WebSocket error: {}", error);
+                self.send_error(error);
+            }
+            UserWsMsg::Pong => {}
+        }
+    }
+
+    fn process_user_event(&self, event: UserEvent) {
+        match event {
+            UserEvent::Fills(fills) => {
+                // Fills also arrive on the userFills channel; only use these when that isn't subscribed
+                if !self.subscription_state.read().fills {
+                    for fill in &fills {
+                        self.process_fill(fill);
                     }
                 }
-                _ => {
-                    debug!("Unknown channel: {}", channel);
+            }
+            UserEvent::Funding(funding) => {
+                let _ = self.trading_events_tx.send(ApiEvent::FundingPayment {
+                    coin: funding.coin.clone(),
+                    amount: funding.usdc.clone(),
+                    rate: funding.funding_rate.clone(),
+                    timestamp: funding.time,
+                });
+                info!("Funding payment for {}: {} at rate {}", funding.coin, funding.usdc, funding.funding_rate);
+            }
+            UserEvent::Liquidation(liquidation) => {
+                warn!("Liquidation event {}: notional {}", liquidation.lid, liquidation.liquidated_ntl_pos);
+                self.send_error(format!(
+                    "Liquidation {} of {} (notional {})",
+                    liquidation.lid, liquidation.liquidated_user, liquidation.liquidated_ntl_pos
+                ));
+            }
+            UserEvent::NonUserCancel(cancels) => {
+                for cancel in cancels {
+                    warn!("Order {} on {} cancelled by exchange", cancel.oid, cancel.coin);
                 }
             }
         }
-        Ok(())
     }
 
-    async fn process_user_event(&self, data: &serde_json::Value) -> Result<(), ApiError> {
-        // Process user events like account updates
-        debug!("Processing user event: {:?}", data);
-        Ok(())
+    fn process_fill(&self, fill: &WsFill) {
+        self.apply_fill_to_cache(fill);
+
+        let event = ApiEvent::Fill {
+            order_id: fill.oid,
+            coin: fill.coin.clone(),
+            side: fill.side.clone(),
+            fill_size: fill.sz.clone(),
+            fill_price: fill.px.clone(),
+            fee: fill.fee.clone(),
+            timestamp: fill.time,
+            cloid: fill.cloid.clone(),
+        };
+
+        let _ = self.trading_events_tx.send(event);
+        info!("Processed fill for order {}: {} {} at {}", 
+              fill.oid, fill.sz, fill.coin, fill.px);
     }
 
-    async fn process_fill(&self, data: &serde_json::Value) -> Result<(), ApiError> {
-        if let Ok(fill) = serde_json::from_value::<HyperLiquidFill>(data.clone()) {
-            let event = ApiEvent::Fill {
-                order_id: fill.oid,
-                fill_size: fill.sz.clone(),
-                fill_price: fill.px.clone(),
-                fee: fill.fee.clone(),
-                timestamp: fill.time,
-            };
+    fn process_order_update(&self, update: &WsOrderUpdate) {
+        let order = &update.order;
+        let Some((filled_size, remaining_size)) = update.fill_progress() else {
+            warn!("Unparseable sizes in order update for {}: orig {} remaining {}", order.oid, order.orig_sz, order.sz);
+            self.send_error(format!("Unparseable sizes in order update for order {}", order.oid));
+            return;
+        };
 
-            let _ = self.trading_events_tx.send(event);
-            info!("Processed fill for order {}: {} {} at {}", 
-                  fill.oid, fill.sz, fill.coin, fill.px);
-        }
-        Ok(())
-    }
+        let event = ApiEvent::OrderUpdate {
+            order_id: order.oid,
+            status: update.status.clone(),
+            filled_size: filled_size.to_string(),
+            remaining_size: remaining_size.to_string(),
+            price: order.limit_px.clone(),
+            timestamp: update.status_timestamp,
+            cloid: order.cloid.clone(),
+        };
 
-    async fn process_order_update(&self, data: &serde_json::Value) -> Result<(), ApiError> {
-        if let Ok(order_status) = serde_json::from_value::<HyperLiquidOrderStatus>(data.clone()) {
-            if let Some(rest) = order_status.rest {
-                let event = ApiEvent::OrderUpdate {
-                    order_id: rest.oid,
-                    status: "rest".to_string(),
-                    filled_size: "0".to_string(),
-                    remaining_size: rest.sz.clone(),
-                    price: rest.px.clone(),
-                    timestamp: rest.timestamp,
-                };
+        let _ = self.trading_events_tx.send(event);
+        info!("Processed order update for order {}: {} with {} remaining", 
+              order.oid, update.status, remaining_size);
+    }
 
-                let _ = self.trading_events_tx.send(event);
-                info!("Processed order update for order {}: {} remaining", 
-                      rest.oid, rest.sz);
-            }
+    // Moves the cached position by the fill so it stays current until the next
+    // webData2 snapshot replaces it
+    fn apply_fill_to_cache(&self, fill: &WsFill) {
+        let (Ok(start), Ok(size), Ok(price), Ok(closed_pnl)) = (
+            Decimal::from_str(&fill.start_position),
+            Decimal::from_str(&fill.sz),
+            Decimal::from_str(&fill.px),
+            Decimal::from_str(&fill.closed_pnl),
+        ) else {
+            warn!("Unparseable fill for {}, position cache not updated", fill.coin);
+            return;
+        };
 
-            if let Some(filled) = order_status.filled {
-                let event = ApiEvent::OrderUpdate {
-                    order_id: filled.oid,
-                    status: "filled".to_string(),
-                    filled_size: filled.sz.clone(),
-                    remaining_size: "0".to_string(),
-                    price: filled.px.clone(),
-                    timestamp: filled.timestamp,
-                };
+        let fill_size = if fill.side == "B" { size } else { -size };
+        let new_size = start + fill_size;
+
+        let mut position = self.positions.entry(fill.coin.clone()).or_insert_with(|| Position {
+            symbol: fill.coin.clone(),
+            size: Decimal::ZERO,
+            entry_price: Decimal::ZERO,
+            mark_price: price,
+            unrealized_pnl: Decimal::ZERO,
+            realized_pnl: Decimal::ZERO,
+            updated_at: chrono::Utc::now(),
+        });
 
-                let _ = self.trading_events_tx.send(event);
-                info!("Processed order fill for order {}: {} filled", 
-                      filled.oid, filled.sz);
-            }
+        if new_size == Decimal::ZERO {
+            position.entry_price = Decimal::ZERO;
+        } else if start == Decimal::ZERO || start.is_sign_positive() != new_size.is_sign_positive() {
+            // Opened or flipped - the new position is entirely at the fill price
+            position.entry_price = price;
+        } else if start.is_sign_positive() == fill_size.is_sign_positive() {
+            // Adding to the position - weighted average entry
+            position.entry_price = (start * position.entry_price + fill_size * price) / new_size;
         }
-        Ok(())
+
+        position.size = new_size;
+        position.mark_price = price;
+        position.unrealized_pnl = (price - position.entry_price) * new_size;
+        position.realized_pnl += closed_pnl;
+        position.updated_at = chrono::Utc::now();
     }
 
-    async fn process_position_update(&self, data: &serde_json::Value) -> Result<(), ApiError> {
-        if let Ok(position) = serde_json::from_value::<HyperLiquidPosition>(data.clone()) {
+    fn process_position_update(&self, state: &WsClearinghouseState) {
+        // webData2 carries the full account state, so it replaces the cache outright
+        self.positions.retain(|coin, _| {
+            state.asset_positions.iter().any(|p| &p.position.coin == coin)
+        });
+
+        for asset_position in &state.asset_positions {
+            let position = &asset_position.position;
+            self.update_cached_position(position);
+
             let event = ApiEvent::PositionUpdate {
                 coin: position.coin.clone(),
                 size: position.szi.clone(),
-                entry_price: position.entry_px.clone(),
+                entry_price: position.entry_px.clone().unwrap_or_else(|| "0".to_string()),
                 unrealized_pnl: position.unrealized_pnl.clone(),
             };
 
             let _ = self.trading_events_tx.send(event);
-            info!("Processed position update for {}: {} at {}", 
-                  position.coin, position.szi, position.entry_px);
+            debug!("Processed position update for {}: {}", position.coin, position.szi);
         }
-        Ok(())
-    }
 
-    pub async fn disconnect(&mut self) -> Result<(), ApiError> {
-        if let Some(ws) = &mut self.ws {
-            ws.close().await.map_err(|e| ApiError::NetworkError(e.to_string()))?;
+        {
+            let mut account_info = self.account_info.write();
+            if let Some(info) = account_info.as_mut() {
+                info.margin_summary = HyperLiquidMarginSummary {
+                    account_value: state.margin_summary.account_value.clone(),
+                    total_margin_used: state.margin_summary.total_margin_used.clone(),
+                    total_ntl_pos: state.margin_summary.total_ntl_pos.clone(),
+                    total_raw_usd: state.margin_summary.total_raw_usd.clone(),
+                };
+                info.asset_positions = state.asset_positions
+                    .iter()
+                    .map(|p| HyperLiquidPosition {
+                        coin: p.position.coin.clone(),
+                        szi: p.position.szi.clone(),
+                        entry_px: p.position.entry_px.clone().unwrap_or_else(|| "0".to_string()),
+                        position_value: p.position.position_value.clone(),
+                        unrealized_pnl: p.position.unrealized_pnl.clone(),
+                        margin_used: p.position.margin_used.clone(),
+                    })
+                    .collect();
+            }
         }
-        
+
         {
-            let mut state = self.connection_state.write();
-            *state = ConnectionState::Disconnected;
+            let mut last_update = self.last_account_update.write();
+            *last_update = std::time::Instant::now();
         }
 
-        info!("Disconnected from HyperLiquid trading WebSocket");
-        Ok(())
+        let _ = self.trading_events_tx.send(ApiEvent::AccountUpdate {
+            account_value: state.margin_summary.account_value.clone(),
+            margin_used: state.margin_summary.total_margin_used.clone(),
+            withdrawable: state.withdrawable.clone(),
+        });
     }
 
-    pub fn is_connected(&self) -> bool {
-        matches!(*self.connection_state.read(), ConnectionState::Connected)
+    fn update_cached_position(&self, position: &WsPosition) {
+        let (Ok(size), Ok(position_value), Ok(unrealized_pnl)) = (
+            Decimal::from_str(&position.szi),
+            Decimal::from_str(&position.position_value),
+            Decimal::from_str(&position.unrealized_pnl),
+        ) else {
+            warn!("Unparseable position for {}, position cache not updated", position.coin);
+            return;
+        };
+        let entry_price = position.entry_px.as_deref()
+            .and_then(|px| Decimal::from_str(px).ok())
+            .unwrap_or(Decimal::ZERO);
+        let mark_price = if size != Decimal::ZERO {
+            position_value / size.abs()
+        } else {
+            entry_price
+        };
+
+        // Realized PnL isn't part of the snapshot; keep what fills have accumulated
+        let realized_pnl = self.positions.get(&position.coin)
+            .map_or(Decimal::ZERO, |p| p.realized_pnl);
+
+        self.positions.insert(position.coin.clone(), Position {
+            symbol: position.coin.clone(),
+            size,
+            entry_price,
+            mark_price,
+            unrealized_pnl,
+            realized_pnl,
+            updated_at: chrono::Utc::now(),
+        });
     }
 
-    pub fn get_connection_state(&self) -> ConnectionState {
-        self.connection_state.read().clone()
+    fn send_error(&self, error: String) {
+        let _ = self.trading_events_tx.send(ApiEvent::Error {
+            error,
+            timestamp: std::time::SystemTime::now()
+                .duration_since(std::time::UNIX_EPOCH)
+                .unwrap()
+                .as_millis() as u64,
+        });
     }
+}
 
-    pub fn get_subscription_state(&self) -> SubscriptionState {
-        self.subscription_state.read().clone()
+async fn open_socket(ws_url: &str) -> Result<WebSocket, ApiError> {
+    WebSocket::connect_with_options(
+        ws_url.parse::<url::Url>().map_err(|e| ApiError::NetworkError(e.to_string()))?,
+        None,
+        Options::default(),
+    ).await.map_err(|e| ApiError::NetworkError(e.to_string()))
+}
+
+async fn send_ping(ws: &mut WebSocket) -> Result<(), ApiError> {
+    let ping = r#"{"method":"ping"}"#;
+    ws.send(FrameView::text(ping.to_string())).await
+        .map_err(|e| ApiError::NetworkError(e.to_string()))
+}
+
+fn subscription_message(channel: &str, account_id: Option<u64>) -> Result<String, ApiError> {
+    let subscribe_msg = serde_json::json!({
+        "method": "subscribe",
+        "subscription": {
+            "type": channel,
+            "user": account_id.map(|id| id.to_string())
+        }
+    });
+
+    serde_json::to_string(&subscribe_msg)
+        .map_err(|e| ApiError::ParseError(e.to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::secrets::Secret;
+    use crate::utils::mock_ws::MockPeer;
+    use rust_decimal_macros::dec;
+    use tokio::net::TcpListener;
+
+    fn mock_socket(listener: &TcpListener) -> (TradingWebSocket, Receiver<ApiEvent>) {
+        mock_socket_with(listener, ApiConfig::default())
     }
 
-    pub async fn start_reconnect_loop(&mut self) {
-        let connection_state = Arc::clone(&self.connection_state);
-        let reconnect_attempts = Arc::clone(&self.reconnect_attempts);
-        let trading_events_tx = self.trading_events_tx.clone();
-        let auth = self.auth.clone();
-        let config = self.config.clone();
+    fn mock_socket_with(listener: &TcpListener, config: ApiConfig) -> (TradingWebSocket, Receiver<ApiEvent>) {
+        let config = ApiConfig {
+            ws_url: format!("ws://{}", listener.local_addr().unwrap()),
+            retry_delay_ms: 10,
+            ..config
+        };
+        let auth = HyperLiquidAuth::new(Secret::new("test".to_string())).with_account_id(1);
+        TradingWebSocket::new(auth, config)
+    }
+
+    async fn wait_until_connected(ws: &TradingWebSocket) {
+        tokio::time::timeout(Duration::from_secs(5), async {
+            while !ws.is_connected() {
+                tokio::task::yield_now().await;
+            }
+        }).await.expect("never reconnected");
+    }
+
+    fn order_update(oid: u64) -> String {
+        order_status(oid, "open", "1.0", "0.4")
+    }
+
+    fn order_status(oid: u64, status: &str, orig_sz: &str, sz: &str) -> String {
+        serde_json::json!({
+            "channel": "orderUpdates",
+            "data": [{
+                "order": {
+                    "coin": "HYPE", "side": "B", "limitPx": "25.0", "sz": sz,
+                    "oid": oid, "timestamp": 1000, "origSz": orig_sz, "cloid": null
+                },
+                "status": status,
+                "statusTimestamp": 1000 + oid
+            }]
+        }).to_string()
+    }
 
-        tokio::spawn(async move {
-            let mut interval = tokio::time::interval(std::time::Duration::from_secs(5));
-            
+    async fn next_event(rx: &Receiver<ApiEvent>) -> ApiEvent {
+        tokio::time::timeout(Duration::from_secs(5), async {
             loop {
-                interval.tick().await;
-                
-                let should_reconnect = {
-                    let state = connection_state.read();
-                    matches!(*state, ConnectionState::Disconnected | ConnectionState::Error(_))
-                };
+                if let Ok(event) = rx.try_recv() {
+                    return event;
+                }
+                tokio::time::sleep(Duration::from_millis(1)).await;
+            }
+        }).await.expect("no event")
+    }
 
-                if should_reconnect {
-                    let attempts = {
-                        let mut attempts = reconnect_attempts.write();
-                        *attempts += 1;
-                        *attempts
-                    };
+    fn heartbeat_config(interval_ms: u64, timeout_ms: u64, warning_ms: u64) -> ApiConfig {
+        ApiConfig {
+            heartbeat_interval_ms: interval_ms,
+            heartbeat_timeout_ms: timeout_ms,
+            heartbeat_warning_ms: warning_ms,
+            ..ApiConfig::default()
+        }
+    }
 
-                    if attempts <= 10 { // Max 10 reconnection attempts
-                        info!("Attempting to reconnect to trading WebSocket (attempt {})", attempts);
-                        
-                        {
-                            let mut state = connection_state.write();
-                            *state = ConnectionState::Reconnecting;
-                        }
+    fn is_ping(text: &str) -> bool {
+        serde_json::from_str::<serde_json::Value>(text).unwrap()["method"] == "ping"
+    }
 
-                        // Create new WebSocket connection
-                        let ws_result = WebSocket::connect_with_options(
-                            config.ws_url.parse().unwrap(),
-                            None,
-                            Options::default(),
-                        ).await;
-
-                        match ws_result {
-                            Ok(ws) => {
-                                // Reset connection state and attempts
-                                {
-                                    let mut state = connection_state.write();
-                                    *state = ConnectionState::Connected;
-                                }
-                                {
-                                    let mut attempts = reconnect_attempts.write();
-                                    *attempts = 0;
-                                }
-
-                                info!("Successfully reconnected to trading WebSocket");
-                                
-                                // Note: In a real implementation, you'd need to resubscribe to all channels
-                                // and handle the WebSocket in the main loop
-                            }
-                            Err(e) => {
-                                error!("Failed to reconnect to trading WebSocket: {}", e);
-                                {
-                                    let mut state = connection_state.write();
-                                    *state = ConnectionState::Error(e.to_string());
-                                }
-                            }
+    #[tokio::test]
+    async fn pings_keep_an_idle_connection_open_past_the_exchange_idle_limit() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let (mut ws, rx) = mock_socket_with(&listener, heartbeat_config(100, 1000, 0));
+
+        let (connected, mut peer) = tokio::join!(ws.connect(), MockPeer::accept(&listener));
+        connected.unwrap();
+
+        // The mock drops any client that stays silent for 300ms, as the exchange does
+        // after 60s; nothing but pings is sent, for several times that limit
+        let idle_limit = Duration::from_millis(300);
+        let started = tokio::time::Instant::now();
+        while started.elapsed() < Duration::from_millis(1500) {
+            let text = tokio::time::timeout(idle_limit, peer.recv_text()).await
+                .expect("client went idle past the limit");
+            assert!(is_ping(&text), "unexpected frame {}", text);
+            peer.send_text(r#"{"channel":"pong"}"#).await;
+        }
+
+        assert!(ws.is_connected());
+        assert_eq!(ws.get_total_reconnects(), 0);
+        assert!(rx.try_recv().is_err());
+
+        ws.disconnect().await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn a_silent_stream_times_out_and_is_reconnected() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let (mut ws, rx) = mock_socket_with(&listener, heartbeat_config(100, 500, 0));
+        ws.subscribe_to_orders().await.unwrap();
+
+        // The first peer stays open but never answers a ping
+        let (connected, mut first) = tokio::join!(ws.connect(), MockPeer::accept(&listener));
+        connected.unwrap();
+        assert_eq!(first.recv_subscriptions(1).await, ["orderUpdates"]);
+
+        match next_event(&rx).await {
+            ApiEvent::Error { error, .. } => assert_eq!(error, "WebSocket heartbeat timeout"),
+            event => panic!("unexpected event {:?}", event),
+        }
+        let mut second = MockPeer::accept(&listener).await;
+        assert_eq!(second.recv_subscriptions(1).await, ["orderUpdates"]);
+        wait_until_connected(&ws).await;
+        assert_eq!(ws.get_total_reconnects(), 1);
+
+        drop(first);
+        ws.disconnect().await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn a_pong_to_the_early_ping_in_the_grace_window_prevents_the_timeout() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let (mut ws, rx) = mock_socket_with(&listener, heartbeat_config(1900, 2000, 500));
+
+        let (connected, mut peer) = tokio::join!(ws.connect(), MockPeer::accept(&listener));
+        connected.unwrap();
+
+        // Past the warning the client pings ahead of its regular cadence
+        let started = tokio::time::Instant::now();
+        assert!(is_ping(&peer.recv_text().await));
+        assert!(started.elapsed() < Duration::from_millis(1900), "no early ping");
+
+        // Answering every ping carries the stream past the point it would time out
+        peer.send_text(r#"{"channel":"pong"}"#).await;
+        while started.elapsed() < Duration::from_millis(3500) {
+            if let Ok(text) = tokio::time::timeout(Duration::from_millis(100), peer.recv_text()).await {
+                assert!(is_ping(&text));
+                peer.send_text(r#"{"channel":"pong"}"#).await;
+            }
+        }
+
+        assert!(matches!(ws.get_connection_state(), ConnectionState::Connected));
+        assert_eq!(ws.get_total_reconnects(), 0);
+        assert!(rx.try_recv().is_err());
+
+        ws.disconnect().await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn a_position_frame_updates_the_shared_account_cache() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let (ws, rx) = mock_socket(&listener);
+        let (api, _api_rx) = AccountApi::new(ws.auth.clone(), ws.config.clone());
+        let mut ws = ws.with_account_cache(&api);
+        ws.subscribe_to_positions().await.unwrap();
+
+        // As left by an earlier poll: ETH has since been closed
+        api.positions.insert("ETH".to_string(), Position {
+            symbol: "ETH".to_string(),
+            size: dec!(1),
+            entry_price: dec!(3000),
+            mark_price: dec!(3000),
+            unrealized_pnl: Decimal::ZERO,
+            realized_pnl: Decimal::ZERO,
+            updated_at: chrono::Utc::now(),
+        });
+        *api.account_info.write() = Some(HyperLiquidAccountInfo {
+            margin_summary: HyperLiquidMarginSummary {
+                account_value: "900.0".to_string(),
+                total_margin_used: "300.0".to_string(),
+                total_ntl_pos: "3000.0".to_string(),
+                total_raw_usd: "900.0".to_string(),
+            },
+            open_orders: Vec::new(),
+            asset_positions: Vec::new(),
+        });
+
+        let (connected, mut peer) = tokio::join!(ws.connect(), MockPeer::accept(&listener));
+        connected.unwrap();
+        assert_eq!(peer.recv_subscriptions(1).await, ["webData2"]);
+        peer.send_text(&serde_json::json!({
+            "channel": "webData2",
+            "data": {
+                "clearinghouseState": {
+                    "assetPositions": [{
+                        "type": "oneWay",
+                        "position": {
+                            "coin": "HYPE", "szi": "-2.0", "entryPx": "25.0", "positionValue": "52.0",
+                            "unrealizedPnl": "-2.0", "marginUsed": "10.4"
                         }
-                    } else {
-                        error!("Max reconnection attempts reached for trading WebSocket");
-                        break;
-                    }
+                    }],
+                    "marginSummary": {
+                        "accountValue": "1000.0", "totalMarginUsed": "10.4",
+                        "totalNtlPos": "52.0", "totalRawUsd": "1052.0"
+                    },
+                    "withdrawable": "989.6"
                 }
             }
-        });
+        }).to_string()).await;
+
+        assert!(matches!(next_event(&rx).await, ApiEvent::PositionUpdate { .. }));
+        assert!(matches!(next_event(&rx).await, ApiEvent::AccountUpdate { .. }));
+
+        let hype = api.get_cached_position("HYPE").unwrap();
+        assert_eq!((hype.size, hype.entry_price, hype.mark_price), (dec!(-2), dec!(25), dec!(26)));
+        assert_eq!(hype.unrealized_pnl, dec!(-2));
+        assert!(api.get_cached_position("ETH").is_none());
+
+        let info = api.get_cached_account_info().unwrap();
+        assert_eq!(info.margin_summary.account_value, "1000.0");
+        assert_eq!(info.asset_positions.len(), 1);
+        assert!(api.is_data_fresh(1));
+
+        ws.disconnect().await.unwrap();
     }
-}
 
-// Clone implementation removed to avoid conflicts
+    #[tokio::test]
+    async fn order_updates_carry_the_filled_and_remaining_size_of_partial_fills() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let (mut ws, rx) = mock_socket(&listener);
+        ws.subscribe_to_orders().await.unwrap();
+
+        let (connected, mut peer) = tokio::join!(ws.connect(), MockPeer::accept(&listener));
+        connected.unwrap();
+        assert_eq!(peer.recv_subscriptions(1).await, ["orderUpdates"]);
+
+        let cases = [
+            (order_status(1, "open", "1.5", "0.25"), "1.25", "0.25"),
+            (order_status(2, "canceled", "2.0", "0.5"), "1.5", "0.5"),
+            // A filled order has nothing left whatever its resting size says
+            (order_status(3, "filled", "1.0", "0.3"), "1.0", "0"),
+        ];
+        for (frame, filled, remaining) in cases {
+            peer.send_text(&frame).await;
+            match next_event(&rx).await {
+                ApiEvent::OrderUpdate { filled_size, remaining_size, .. } => {
+                    assert_eq!((filled_size.as_str(), remaining_size.as_str()), (filled, remaining));
+                }
+                event => panic!("unexpected event {:?}", event),
+            }
+        }
+
+        peer.send_text(&order_status(4, "open", "1.0", "most")).await;
+        assert!(matches!(next_event(&rx).await, ApiEvent::Error { .. }));
+
+        ws.disconnect().await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn a_dropped_connection_replays_active_channels_before_it_is_marked_connected() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let (mut ws, _rx) = mock_socket(&listener);
+        ws.subscribe_to_fills().await.unwrap();
+        ws.subscribe_to_orders().await.unwrap();
+
+        let (connected, mut first) = tokio::join!(ws.connect(), MockPeer::accept(&listener));
+        connected.unwrap();
+        assert_eq!(first.recv_subscriptions(2).await, ["userFills", "orderUpdates"]);
+
+        // The upgrade reply is held back, so the session is stuck opening the new socket
+        drop(first);
+        let second = MockPeer::accept_unanswered(&listener).await;
+        assert!(matches!(ws.get_connection_state(), ConnectionState::Reconnecting));
+
+        // Once live, a fresh subscription goes out behind the replayed ones
+        let mut second = second.upgrade().await;
+        wait_until_connected(&ws).await;
+        ws.subscribe_to_positions().await.unwrap();
+        assert_eq!(second.recv_subscriptions(3).await, ["userFills", "orderUpdates", "webData2"]);
+        assert_eq!(ws.get_total_reconnects(), 1);
+
+        ws.disconnect().await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn the_stream_resumes_on_a_new_connection_after_a_drop() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let (mut ws, rx) = mock_socket(&listener);
+        ws.subscribe_to_orders().await.unwrap();
+
+        let (connected, mut first) = tokio::join!(ws.connect(), MockPeer::accept(&listener));
+        connected.unwrap();
+        assert_eq!(first.recv_subscriptions(1).await, ["orderUpdates"]);
+        first.send_text(&order_update(1)).await;
+        assert!(matches!(next_event(&rx).await, ApiEvent::OrderUpdate { order_id: 1, .. }));
+
+        drop(first);
+        let mut second = MockPeer::accept(&listener).await;
+        assert_eq!(second.recv_subscriptions(1).await, ["orderUpdates"]);
+        second.send_text(&order_update(2)).await;
+
+        match next_event(&rx).await {
+            ApiEvent::OrderUpdate { order_id, filled_size, remaining_size, .. } => {
+                assert_eq!(order_id, 2);
+                assert_eq!((filled_size.as_str(), remaining_size.as_str()), ("0.6", "0.4"));
+            }
+            event => panic!("unexpected event {:?}", event),
+        }
+        assert!(ws.is_connected());
+        assert_eq!(ws.get_total_reconnects(), 1);
+
+        ws.disconnect().await.unwrap();
+    }
+}
@@ -0,0 +1,27 @@
+use hyper_liquid_connector::{
+    strategies::market_making::MarketMakingConfig,
+    trading::backtest::Backtester,
+    trading::paper_trading::PaperTradingConfig,
+};
+
+// Usage: hl-backtest <recording.jsonl> [symbol]
+fn main() -> anyhow::Result<()> {
+    tracing_subscriber::fmt()
+        .with_max_level(tracing::Level::WARN)
+        .init();
+
+    let mut args = std::env::args().skip(1);
+    let path = args.next()
+        .ok_or_else(|| anyhow::anyhow!("Usage: hl-backtest <recording.jsonl> [symbol]"))?;
+
+    let mut config = MarketMakingConfig::default();
+    if let Some(symbol) = args.next() {
+        config.base_config.symbol = symbol;
+    }
+
+    let mut backtester = Backtester::new(config, PaperTradingConfig::default());
+    let report = backtester.run_file(&path).map_err(anyhow::Error::msg)?;
+
+    println!("{}", report);
+    Ok(())
+}
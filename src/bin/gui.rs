@@ -1,5 +1,19 @@
+use hyper_liquid_connector::api::account_api::AccountApi;
+use hyper_liquid_connector::api::assets::AssetRegistry;
+use hyper_liquid_connector::api::auth::HyperLiquidAuth;
+use hyper_liquid_connector::api::market_info::MarketInfoApi;
+use hyper_liquid_connector::api::trading_api::TradingApi;
+use hyper_liquid_connector::config::bot_config::ConfigManager;
+use hyper_liquid_connector::config::secrets::Secret;
+use hyper_liquid_connector::trading::risk_manager::RiskManager;
 use hyper_liquid_connector::ui::app::TradingApp;
+use hyper_liquid_connector::ui::order_router::OrderRouter;
 use eframe::egui;
+use std::sync::Arc;
+use tracing::{info, warn};
+
+const CONFIG_PATH: &str = "config/bot.toml";
+const ACCOUNT_REFRESH_SECS: u64 = 30;
 
 #[tokio::main]
 async fn main() -> Result<(), eframe::Error> {
@@ -20,9 +34,99 @@ async fn main() -> Result<(), eframe::Error> {
         ..Default::default()
     };
 
+    // Same config as the bot, so manual orders face the same risk limits
+    let (config_manager, config_events_rx) = ConfigManager::new();
+    let config_manager = config_manager.with_file_path(CONFIG_PATH.to_string());
+    if let Err(e) = config_manager.load_from_file(CONFIG_PATH).await {
+        warn!("{}; using default config", e);
+    }
+    let config = config_manager.get_config();
+    // Edits to the file retune the strategy while the window is open
+    if config.hot_reload {
+        if let Err(e) = config_manager.watch_file() {
+            warn!("Config hot reload disabled: {}", e);
+        }
+    }
+
+    // The GUI runs one strategy: the first market making one in the config
+    let strategy = match config.market_making_configs() {
+        Ok(configs) => configs.into_iter().next(),
+        Err(e) => {
+            warn!("Invalid strategy config, using defaults: {}", e);
+            None
+        }
+    };
+    let mut api_config = config.api_config.clone();
+
+    // Without a key nothing can be signed, so orders can only be simulated
+    let private_key = config.secrets.private_key().unwrap_or_else(|e| {
+        if !api_config.paper_trading {
+            warn!("{}; paper trading manual orders", e);
+            api_config.paper_trading = true;
+        }
+        Secret::default()
+    });
+    let mut auth = HyperLiquidAuth::new(private_key);
+    // Account value and margin can only be fetched for a known account
+    if let Ok(account_id) = std::env::var("HYPERLIQUID_ACCOUNT_ID") {
+        match account_id.parse() {
+            Ok(account_id) => auth = auth.with_account_id(account_id),
+            Err(e) => warn!("Invalid HYPERLIQUID_ACCOUNT_ID, account summary disabled: {}", e),
+        }
+    }
+
+    // Orders for assets missing from the registry are rejected, which the logs panel shows
+    let assets = Arc::new(AssetRegistry::new());
+    match MarketInfoApi::new(auth.clone(), api_config.clone()).get_meta().await {
+        Ok(meta) => {
+            assets.load(&meta);
+            info!("Loaded metadata for {} assets", assets.len());
+        }
+        Err(e) => warn!("Failed to fetch asset metadata, manual orders will be rejected: {}", e),
+    }
+
+    let account_info = match auth.account_id {
+        Some(_) => {
+            let (account_api, _account_events_rx) = AccountApi::new(auth.clone(), api_config.clone());
+            account_api.start_periodic_updates(ACCOUNT_REFRESH_SECS).await;
+            Some(Arc::clone(&account_api.account_info))
+        }
+        None => None,
+    };
+
+    let (risk_manager, risk_events_rx) = RiskManager::new();
+    if let Err(e) = risk_manager.apply_config(&config.risk_config) {
+        warn!("Invalid risk config: {}", e);
+    }
+    for strategy in config.strategies.values() {
+        risk_manager.add_risk_limits(strategy.symbol.clone(), config.risk_config.global_risk_limits.clone());
+    }
+
+    // The feed's and router's tasks run on this runtime's workers while run_native holds the main thread
+    let runtime = tokio::runtime::Handle::current();
+    let (trading_api, trading_events_rx) = TradingApi::new(auth, api_config.clone());
+    let order_router = OrderRouter::new(&runtime, trading_api.with_asset_registry(assets), trading_events_rx, risk_manager.clone());
+
     eframe::run_native(
         "HyperLiquid Trading Bot",
         options,
-        Box::new(|_cc| Ok(Box::new(TradingApp::new()))),
+        Box::new(move |_cc| {
+            let mut app = TradingApp::new();
+            if let Some((name, mm_config)) = strategy {
+                app = app.with_strategy(name, mm_config);
+            }
+            if let Some(account_info) = account_info {
+                app = app.with_account_info(account_info);
+            }
+            Ok(Box::new(
+                app.with_warm_reconnect(api_config.warm_reconnect)
+                    .with_live_market_data(runtime, &api_config)
+                    .with_order_routing(order_router)
+                    .with_risk_manager(risk_manager, risk_events_rx)
+                    .with_config_events(config_events_rx)
+                    .with_chart_points(config.ui_config.chart_points)
+                    .with_depth_window_bps(config.ui_config.depth_window_bps)
+            ))
+        }),
     )
 }
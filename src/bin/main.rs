@@ -17,7 +17,7 @@ async fn main() -> anyhow::Result<()> {
     info!("Starting HyperLiquid App");
     
     let client_url = "wss://api.hyperliquid.xyz/ws";
-    let symbol = "HYPE";
+    let symbols = ["HYPE".to_string()];
     let redundant_connections = 5;
     
     // Channels
@@ -27,7 +27,7 @@ async fn main() -> anyhow::Result<()> {
     let mut ws_manager = WsManager::new(
         redundant_connections, 
         client_url, 
-        symbol, 
+        &symbols, 
         msg_tx, 
         msg_rx
     ).await?;
@@ -1,39 +1,163 @@
 use hyper_liquid_connector::{
-    api::{auth::HyperLiquidAuth, trading_api::TradingApi, account_api::AccountApi, ws_trading::TradingWebSocket},
-    config::bot_config::ConfigManager,
-    trading::{order_manager::OrderManager, position_manager::PositionManager, risk_manager::RiskManager, order_book::OrderBook},
-    strategies::{market_making::MarketMakingStrategy, base_strategy::TradingStrategy},
-    events::event_bus::EventBus,
+    api::{types::{ApiEvent, HyperLiquidOrderRest}, assets::AssetRegistry, auth::HyperLiquidAuth, cloid::CloidRegistry, rate_limiter::RateLimiter, trading_api::{PendingOrder, TradingApi}, account_api::{self, AccountApi}, market_info::MarketInfoApi, ws_trading::TradingWebSocket},
+    config::bot_config::{BotConfig, ConfigEvent, ConfigManager, RiskConfig, StrategyConfig},
+    trading::{types::{Fill, NewOrder, Order, OrderStatus, OrderType, Position, Side}, order_manager::OrderManager, position_manager::PositionManager, risk_manager::{RiskManager, RiskEvent}, trigger_orders::{TriggerKind, TriggerOrderManager}, order_book::{OrderBook, SNAPSHOT_LEVELS}},
+    strategies::{market_making::{MarketMakingConfig, MarketMakingStrategy}, base_strategy::TradingStrategy},
+    events::{alerts::AlertManager, event_bus::{EventBus, EventBusConfig, EventPublisher, OverflowPolicy}, types::{SystemEvent, SystemLevelEvent}},
     clients::ws_manager::WsManager,
+    metrics::{registry::Metrics, server::MetricsServer},
+    reporting::session_report::SessionReport,
+    storage::{market_data_recorder::MarketDataRecorder, session_store::SessionStore},
 };
 use anyhow::Result;
+use clap::{Parser, Subcommand};
 use crossbeam_channel::{Receiver, unbounded};
 use dashmap::DashMap;
 use tokio::sync::RwLock;
 use rust_decimal::Decimal;
+use rust_decimal::prelude::ToPrimitive;
+use std::collections::{BTreeMap, BTreeSet, HashMap};
 use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::time::Duration;
 use tokio::sync::mpsc;
-use tracing::{error, info, warn, debug};
+use tracing::{error, info, warn};
 use uuid::Uuid;
 
 pub struct TradingBot {
     pub config_manager: ConfigManager,
+    pub config_events_rx: Receiver<ConfigEvent>,
     pub auth: HyperLiquidAuth,
     pub trading_api: TradingApi,
     pub account_api: AccountApi,
+    pub market_info: MarketInfoApi,
     pub trading_ws: TradingWebSocket,
     pub order_manager: OrderManager,
     pub position_manager: PositionManager,
     pub risk_manager: RiskManager,
-    pub market_making_strategy: MarketMakingStrategy,
+    pub trigger_manager: TriggerOrderManager,
+    pub strategies: SharedStrategies, // Empty runs the bot monitor-only
     pub event_bus: EventBus,
-    pub ws_manager: WsManager,
+    pub ws_manager: Option<WsManager>, // Taken by start() to run on its own task
+    pub market_data_task: Option<tokio::task::JoinHandle<()>>,
+    pub market_data_symbols: Vec<String>, // Fixed at startup; a strategy enabled later on another symbol gets no data
     pub order_books: Arc<DashMap<String, OrderBook>>,
+    pub metrics: Arc<Metrics>,
     pub is_running: Arc<RwLock<bool>>,
+    pub market_data_live: Arc<AtomicBool>, // Cleared by the watchdog while every market data connection is down
+    pub started_at: chrono::DateTime<chrono::Utc>,
     pub bot_events_tx: crossbeam_channel::Sender<BotEvent>,
 }
 
+/// The bot's strategies by config name. One store is shared by the event loop, config
+/// reloads and the bot's controls, so a change made by any of them is seen by all.
+#[derive(Debug, Default)]
+pub struct StrategyStore {
+    pub strategies: BTreeMap<String, MarketMakingStrategy>,
+    loss_disabled: BTreeSet<String>, // Disabled at their loss limit; config changes don't re-enable them
+    working_orders: HashMap<u64, Uuid>, // Client order id to internal id of strategy orders still working
+}
+
+pub type SharedStrategies = Arc<RwLock<StrategyStore>>;
+
+impl StrategyStore {
+    pub fn new(strategies: BTreeMap<String, MarketMakingStrategy>) -> Self {
+        Self { strategies, loss_disabled: BTreeSet::new(), working_orders: HashMap::new() }
+    }
+
+    /// Disables every strategy quoting `symbol` after the symbol hit its loss limit,
+    /// returning their names. They stay disabled until `enable` is called for them.
+    pub fn disable_for_loss(&mut self, symbol: &str) -> Vec<String> {
+        let mut disabled = Vec::new();
+        for (name, strategy) in self.strategies.iter_mut().filter(|(_, s)| s.config.base_config.symbol == symbol) {
+            strategy.set_enabled(false);
+            self.loss_disabled.insert(name.clone());
+            disabled.push(name.clone());
+        }
+        disabled
+    }
+
+    pub fn is_loss_disabled(&self, name: &str) -> bool {
+        self.loss_disabled.contains(name)
+    }
+
+    /// Enables a strategy on an operator's request, clearing a loss-limit disable.
+    /// Returns false for an unknown strategy.
+    pub fn enable(&mut self, name: &str) -> bool {
+        let Some(strategy) = self.strategies.get_mut(name) else {
+            return false;
+        };
+        self.loss_disabled.remove(name);
+        strategy.set_enabled(true);
+        true
+    }
+
+    /// Starts tracking an order placed for strategy `name` once `TradingApi` has
+    /// accepted it as `order_id`. `pending` is the API's view of the order at that
+    /// point; an accepted order it no longer holds has already filled.
+    pub fn track_placement(&mut self, name: &str, order_id: Uuid, order: &NewOrder, pending: Option<&PendingOrder>) {
+        let Some(strategy) = self.strategies.get_mut(name) else {
+            return;
+        };
+
+        let now = chrono::Utc::now();
+        let filled_size = pending.map_or(Decimal::ZERO, |pending| pending.filled_size);
+        let mut tracked = Order {
+            id: order_id,
+            client_id: order.client_id.clone(),
+            symbol: order.symbol.clone(),
+            side: order.side,
+            order_type: order.order_type,
+            price: order.price,
+            size: order.size,
+            filled_size,
+            remaining_size: order.size - filled_size,
+            status: if filled_size > Decimal::ZERO { OrderStatus::PartiallyFilled } else { OrderStatus::Submitted },
+            created_at: now,
+            updated_at: now,
+            exchange_oid: None,
+        };
+        strategy.track_order(&tracked);
+
+        match pending {
+            Some(pending) => {
+                self.working_orders.insert(pending.client_order_id, order_id);
+            }
+            None => {
+                tracked.status = OrderStatus::Filled;
+                tracked.filled_size = order.size;
+                tracked.remaining_size = Decimal::ZERO;
+                strategy.track_order(&tracked);
+            }
+        }
+    }
+
+    /// Applies an order update from `TradingApi` to the tracked order of strategy
+    /// `name`. Updates for orders not tracked are ignored; a placement that has yet to
+    /// be tracked picks up their effect from its pending order instead.
+    pub fn on_order_update(&mut self, name: &str, client_order_id: u64, status: OrderStatus, filled_size: Decimal) {
+        let Some(&order_id) = self.working_orders.get(&client_order_id) else {
+            return;
+        };
+        if matches!(status, OrderStatus::Filled | OrderStatus::Cancelled | OrderStatus::Rejected) {
+            self.working_orders.remove(&client_order_id);
+        }
+
+        let Some(strategy) = self.strategies.get_mut(name) else {
+            return;
+        };
+        // Orders dropped when the strategy was disabled aren't brought back
+        let Some(mut order) = strategy.active_orders.get(&order_id).cloned() else {
+            return;
+        };
+        order.status = status;
+        order.filled_size = filled_size;
+        order.remaining_size = (order.size - filled_size).max(Decimal::ZERO);
+        order.updated_at = chrono::Utc::now();
+        strategy.track_order(&order);
+    }
+}
+
 #[derive(Debug, Clone)]
 pub enum BotEvent {
     Started,
@@ -44,79 +168,180 @@ pub enum BotEvent {
     OrderFilled { order_id: Uuid, symbol: String, size: Decimal, price: Decimal },
     PositionUpdated { symbol: String, size: Decimal, pnl: Decimal },
     RiskAlert { message: String, severity: String },
+    EmergencyStop { reason: String, flattened: Vec<String> },
     Error { error: String },
 }
 
+// Upper bound on waiting for cancels before flattening regardless
+const EMERGENCY_CANCEL_TIMEOUT: Duration = Duration::from_secs(5);
+
+// Order statuses produced by our own order flow, not alerted on
+const ROUTINE_ORDER_STATUSES: [&str; 3] = ["open", "filled", "canceled"];
+
+// Pause between checks of the exchange's open orders while shutting down
+const SHUTDOWN_CANCEL_POLL_INTERVAL: Duration = Duration::from_millis(500);
+
+// How often resting quotes are checked against their strategy's age and deviation limits
+const ORDER_REAPER_INTERVAL: Duration = Duration::from_millis(500);
+
 impl TradingBot {
     pub async fn new(config_path: Option<String>) -> Result<(Self, Receiver<BotEvent>)> {
         let (bot_events_tx, bot_events_rx) = unbounded();
 
         // Initialize configuration manager
-        let (config_manager, _config_events_rx) = ConfigManager::new();
+        let (mut config_manager, config_events_rx) = ConfigManager::new();
         if let Some(path) = config_path {
-            config_manager.load_from_file(path).await
+            config_manager.load_from_file(&path).await
                 .map_err(|e| anyhow::anyhow!("Failed to load config: {}", e))?;
+            config_manager = config_manager.with_file_path(path);
         }
 
         let config = config_manager.get_config();
 
-        // Initialize authentication
-        let private_key = std::env::var("HYPERLIQUID_PRIVATE_KEY")
-            .map_err(|_| anyhow::anyhow!("HYPERLIQUID_PRIVATE_KEY environment variable not set"))?;
-        let auth = HyperLiquidAuth::new(private_key);
-        
-        // Authenticate with HyperLiquid
-        let mut auth = auth;
-        auth.authenticate().await
-            .map_err(|e| anyhow::anyhow!("Authentication failed: {}", e))?;
+        let auth = authenticate(&config).await?;
 
         // Initialize API clients
-        let (trading_api, _trading_events_rx) = TradingApi::new(auth.clone(), config.api_config.clone());
+        // Trading and account requests draw from one shared budget
+        let rate_limiter = Arc::new(RateLimiter::new(config.api_config.rate_limit.clone()));
+        // Register in a fixed order so cloids from earlier runs decode to the same names
+        let cloids = Arc::new(CloidRegistry::new());
+        let mut strategy_names: Vec<_> = config.strategies.keys().cloned().collect();
+        strategy_names.sort();
+        for name in &strategy_names {
+            cloids.register_strategy(name);
+        }
+        let mut symbols: Vec<_> = config.strategies.values().map(|s| s.symbol.clone()).collect();
+        symbols.sort();
+        for symbol in &symbols {
+            cloids.register_symbol(symbol);
+        }
+
+        let market_info = MarketInfoApi::new(auth.clone(), config.api_config.clone())
+            .with_rate_limiter(Arc::clone(&rate_limiter));
+        // Orders are addressed by asset index and rounded to each asset's precision
+        let meta = market_info.get_meta().await
+            .map_err(|e| anyhow::anyhow!("Failed to fetch asset metadata: {}", e))?;
+        let assets = Arc::new(AssetRegistry::from_meta(&meta));
+        info!("Loaded metadata for {} assets", assets.len());
+
+        let order_books = Arc::new(DashMap::new());
+        let metrics = Arc::new(Metrics::new());
+        let (trading_api, trading_events_rx) = TradingApi::new(auth.clone(), config.api_config.clone());
+        let trading_api = trading_api
+            .with_rate_limiter(Arc::clone(&rate_limiter))
+            .with_cloid_registry(cloids)
+            .with_asset_registry(assets)
+            .with_order_books(Arc::clone(&order_books))
+            .with_metrics(Arc::clone(&metrics));
+        if trading_api.is_paper() {
+            warn!("Paper trading enabled: orders are simulated against the live book and never sent");
+        }
         let (account_api, _account_events_rx) = AccountApi::new(auth.clone(), config.api_config.clone());
-        let (trading_ws, _trading_ws_events_rx) = TradingWebSocket::new(auth.clone(), config.api_config.clone());
+        let account_api = account_api.with_rate_limiter(rate_limiter);
+
+        // Fail fast on a missing account or unapproved agent rather than on the first order
+        account_api.validate_account(auth.agent_address.as_deref()).await
+            .map_err(|e| anyhow::anyhow!("Account validation failed: {}", e))?;
+        let (trading_ws, trading_ws_events_rx) = TradingWebSocket::new(auth.clone(), config.api_config.clone());
+        let trading_ws = trading_ws.with_account_cache(&account_api);
 
         // Initialize managers
-        let (order_manager, _order_events_rx) = OrderManager::new();
-        let (position_manager, _position_events_rx) = PositionManager::new();
-        let (risk_manager, _risk_events_rx) = RiskManager::new();
-
-        // Initialize market making strategy
-        let strategy_config = config.strategies.get("market_making_HYPE")
-            .ok_or_else(|| anyhow::anyhow!("Market making strategy not found in config"))?;
-        
-        let market_making_config = serde_json::from_value(strategy_config.config.clone())
-            .map_err(|e| anyhow::anyhow!("Failed to parse market making config: {}", e))?;
-        
-        let market_making_strategy = MarketMakingStrategy::new(market_making_config);
+        let (order_manager, order_events_rx) = OrderManager::new();
+        let (position_manager, position_events_rx) = PositionManager::new();
+
+        // Keep a queryable record of the session and pick up orders left open by the last one
+        if config.storage_config.enabled {
+            let store = SessionStore::open(&config.storage_config.db_path)
+                .map_err(|e| anyhow::anyhow!("Failed to open session store: {}", e))?;
+            let open_orders = store.load_open_orders()
+                .map_err(|e| anyhow::anyhow!("Failed to load open orders: {}", e))?;
+            info!("Restored {} open orders from {}", open_orders.len(), config.storage_config.db_path);
+            for order in open_orders {
+                order_manager.restore_order(order);
+            }
+            store.record_events(order_events_rx, position_events_rx);
+        }
+        let (risk_manager, risk_events_rx) = RiskManager::new();
+        risk_manager.apply_config(&config.risk_config)
+            .map_err(|e| anyhow::anyhow!("Invalid risk config: {}", e))?;
+        let (trigger_manager, _trigger_events_rx) = TriggerOrderManager::new();
+        trigger_manager.load_configs(&config.risk_config.trigger_orders);
+
+        // Initialize strategies
+        let strategies = Arc::new(RwLock::new(load_strategies(&config)?));
+        route_order_events(trading_events_rx, position_manager.clone(), risk_manager.clone(), Arc::clone(&trading_api.cloids), Arc::clone(&strategies));
+        // Global limits cap each symbol; each strategy is also held to its own limits
+        for (name, strategy) in &config.strategies {
+            risk_manager.add_risk_limits(strategy.symbol.clone(), config.risk_config.global_risk_limits.clone());
+            risk_manager.add_strategy_limits(name.clone(), strategy.risk_limits.clone());
+        }
 
         // Initialize event bus
-        let event_bus = EventBus::new(Default::default());
+        // Strategy-side consumers only care about the latest book per symbol
+        let event_bus = EventBus::new(EventBusConfig {
+            low_priority_overflow: OverflowPolicy::CoalesceBySymbol,
+            ..Default::default()
+        });
         event_bus.start_processing();
 
-        // Initialize WebSocket manager for market data
-        let (msg_tx, msg_rx) = mpsc::channel(1000);
-        let ws_manager = WsManager::new(
-            3, // 3 redundant connections
-            &config.api_config.ws_url,
-            "HYPE",
-            msg_tx,
-            msg_rx,
-        ).await?;
+        // Page on critical events and forward risk manager breaches onto the bus
+        AlertManager::new(config.alert_config.clone()).start(&event_bus);
+        forward_risk_events(risk_events_rx, event_bus.get_publisher());
+        // Exchange fills go through the trading API so its pending orders see them, and
+        // come back out on its channel alongside paper fills; everything else is alerted on
+        forward_exchange_events(trading_ws_events_rx, trading_api.clone(), event_bus.get_publisher());
+
+        // Initialize WebSocket manager for market data, one book per symbol an enabled strategy quotes
+        let market_data_symbols = config.market_data_symbols();
+        let ws_manager = if market_data_symbols.is_empty() {
+            warn!("No enabled strategies; not streaming market data");
+            None
+        } else {
+            info!("Streaming market data for {}", market_data_symbols.join(", "));
+            let (msg_tx, msg_rx) = mpsc::channel(1000);
+            let mut ws_manager = WsManager::new(
+                config.api_config.market_data_connections,
+                &config.api_config.ws_url,
+                &market_data_symbols,
+                msg_tx,
+                msg_rx,
+            ).await?
+            .with_metrics(&metrics)
+            .with_warmup_updates(config.api_config.market_data_warmup_updates)
+            .with_max_message_age(config.api_config.max_market_data_age_ms)
+            .with_order_books(Arc::clone(&order_books))
+            .with_warm_reconnect(config.api_config.warm_reconnect)
+            .with_event_publisher(event_bus.get_publisher());
+            if config.recording_config.enabled {
+                let recorder = MarketDataRecorder::start(&config.recording_config)
+                    .map_err(|e| anyhow::anyhow!("Failed to open market data recording {}: {}", config.recording_config.path, e))?;
+                ws_manager = ws_manager.with_recorder(recorder);
+            }
+            Some(ws_manager)
+        };
 
         let bot = Self {
             config_manager,
+            config_events_rx,
             auth,
             trading_api,
             account_api,
+            market_info,
             trading_ws,
             order_manager,
             position_manager,
             risk_manager,
-            market_making_strategy,
+            trigger_manager,
+            strategies,
             event_bus,
             ws_manager,
-            order_books: Arc::new(DashMap::new()),
+            market_data_task: None,
+            market_data_symbols,
+            order_books,
+            metrics,
             is_running: Arc::new(RwLock::new(false)),
+            market_data_live: Arc::new(AtomicBool::new(true)),
+            started_at: chrono::Utc::now(),
             bot_events_tx,
         };
 
@@ -130,6 +355,19 @@ impl TradingBot {
             let mut is_running = self.is_running.write().await;
             *is_running = true;
         }
+        self.started_at = chrono::Utc::now();
+
+        // Scrape endpoint for headless deployments
+        let metrics_config = self.config_manager.get_config().metrics_config;
+        if metrics_config.enabled {
+            let server = MetricsServer::bind(&metrics_config, Arc::clone(&self.metrics)).await
+                .map_err(|e| anyhow::anyhow!("Failed to start metrics server: {}", e))?;
+            server.spawn();
+            self.start_metrics_sampler();
+        }
+
+        // Pull resting quotes whenever the market data feed is lost entirely
+        self.start_market_data_watchdog();
 
         // Start risk manager daily reset timer
         self.risk_manager.start_daily_reset_timer();
@@ -138,7 +376,28 @@ impl TradingBot {
         self.trading_api.start_retry_processor().await;
 
         // Start account API periodic updates
-        self.account_api.start_periodic_updates(30).await; // Update every 30 seconds
+        // Positions and margin stream over the trading WebSocket; polling is only a safety net
+        self.account_api.start_periodic_updates(300).await; // Update every 5 minutes
+
+        // Funding dominates the carry on held inventory; keep predicted rates current
+        self.market_info.start_periodic_updates(self.config_manager.get_config().api_config.funding_refresh_interval_secs);
+        self.start_funding_monitor();
+
+        // Pick up edits to the config file without a restart
+        if self.config_manager.get_config().hot_reload && self.config_manager.file_path.is_some() {
+            if let Err(e) = self.config_manager.watch_file() {
+                warn!("Config hot-reload disabled: {}", e);
+            }
+        }
+
+        // Quote against the account's actual fee tier rather than the base rates
+        self.account_api.start_fee_refresh(self.config_manager.get_config().api_config.fee_refresh_interval_secs);
+
+        // The exchange may still hold orders and inventory from the previous run. Paper
+        // sessions start flat and must leave the real account alone
+        if !self.trading_api.is_paper() {
+            self.reconcile_with_exchange().await?;
+        }
 
         // Connect to trading WebSocket
         self.trading_ws.connect().await
@@ -148,8 +407,8 @@ impl TradingBot {
         self.trading_ws.subscribe_to_all().await
             .map_err(|e| anyhow::anyhow!("Failed to subscribe to trading events: {}", e))?;
 
-        // Start trading WebSocket reconnect loop
-        self.trading_ws.start_reconnect_loop().await;
+        // Books are written by the manager as updates arrive; quoting reads them below
+        self.start_market_data();
 
         // Start main event processing loop
         self.start_event_processing().await;
@@ -168,16 +427,36 @@ impl TradingBot {
             *is_running = false;
         }
 
-        // Cancel all open orders
-        self.trading_api.cancel_all_orders(None).await
-            .map_err(|e| anyhow::anyhow!("Failed to cancel all orders: {}", e))?;
+        // Cancel all open orders, and make sure none are left before losing the feed
+        if let Err(e) = self.trading_api.cancel_all_orders(None).await {
+            warn!("Failed to cancel all orders: {}", e);
+        }
+        if !self.trading_api.is_paper() {
+            let timeout = Duration::from_secs(self.config_manager.get_config().api_config.shutdown_cancel_timeout_secs);
+            if let Err(e) = self.confirm_orders_cancelled(timeout).await {
+                error!("{}", e);
+                let _ = self.bot_events_tx.send(BotEvent::Error { error: e });
+            }
+        }
 
         // Disconnect from WebSockets
         self.trading_ws.disconnect().await
             .map_err(|e| anyhow::anyhow!("Failed to disconnect trading WebSocket: {}", e))?;
 
-        self.ws_manager.stop().await
-            .map_err(|e| anyhow::anyhow!("Failed to stop WebSocket manager: {}", e))?;
+        // Aborting the task drops the manager, which closes its connections
+        if let Some(task) = self.market_data_task.take() {
+            task.abort();
+        }
+
+        // Emit end-of-run summary
+        let report = SessionReport::new(
+            &self.position_manager,
+            self.started_at,
+            self.trading_ws.get_total_reconnects(),
+        );
+        report.log();
+
+        self.event_bus.stop_processing(Duration::from_secs(1));
 
         let _ = self.bot_events_tx.send(BotEvent::Stopped);
         info!("Trading bot stopped successfully");
@@ -185,13 +464,60 @@ impl TradingBot {
         Ok(())
     }
 
+    /// Polls the exchange's open orders and re-cancels any still resting until none
+    /// are left or `timeout` passes. Errors name the orders that survived.
+    async fn confirm_orders_cancelled(&self, timeout: Duration) -> Result<(), String> {
+        let deadline = tokio::time::Instant::now() + timeout;
+
+        loop {
+            let remaining = match self.account_api.get_open_orders().await {
+                Ok(open_orders) if open_orders.is_empty() => {
+                    info!("Confirmed no open orders remain on the exchange");
+                    return Ok(());
+                }
+                Ok(open_orders) => open_orders.iter().map(|order| order.oid).collect(),
+                Err(e) => {
+                    warn!("Failed to fetch open orders to confirm cancels: {}", e);
+                    Vec::new()
+                }
+            };
+
+            if tokio::time::Instant::now() >= deadline {
+                return Err(if remaining.is_empty() {
+                    format!("Could not confirm open orders were cancelled within {:?}", timeout)
+                } else {
+                    format!("{} orders still open on the exchange after {:?}: {:?}", remaining.len(), timeout, remaining)
+                });
+            }
+
+            for oid in remaining {
+                match self.trading_api.cancel_exchange_order(oid).await {
+                    Ok(()) => info!("Re-cancelled order {} still open at shutdown", oid),
+                    Err(e) => warn!("Failed to cancel order {} at shutdown: {}", oid, e),
+                }
+            }
+            tokio::time::sleep_until(deadline.min(tokio::time::Instant::now() + SHUTDOWN_CANCEL_POLL_INTERVAL)).await;
+        }
+    }
+
     async fn start_event_processing(&self) {
         let is_running = Arc::clone(&self.is_running);
         let order_books = Arc::clone(&self.order_books);
-        let market_making_strategy = Arc::new(RwLock::new(self.market_making_strategy.clone()));
+        let strategies = Arc::clone(&self.strategies);
         let trading_api = self.trading_api.clone();
+        let account_api = self.account_api.clone();
+        let market_info = self.market_info.clone();
         let risk_manager = self.risk_manager.clone();
+        let position_manager = self.position_manager.clone();
+        let trigger_manager = self.trigger_manager.clone();
         let bot_events_tx = self.bot_events_tx.clone();
+        let metrics = Arc::clone(&self.metrics);
+        let market_data_live = Arc::clone(&self.market_data_live);
+        let strategy_ids: HashMap<String, u16> = self.strategies.read().await.strategies.keys()
+            .map(|name| (name.clone(), self.trading_api.cloids.register_strategy(name)))
+            .collect();
+        apply_config_changes(self.config_events_rx.clone(), Arc::clone(&strategies), risk_manager.clone(), trigger_manager.clone(), bot_events_tx.clone(), self.market_data_symbols.clone());
+        start_order_reaper(Arc::clone(&strategies), trading_api.clone(), Arc::clone(&order_books), Arc::clone(&is_running));
 
         tokio::spawn(async move {
             let mut interval = tokio::time::interval(Duration::from_millis(100));
@@ -199,29 +525,110 @@ impl TradingBot {
             while *is_running.read().await {
                 interval.tick().await;
 
+                {
+                    let mut strategies = strategies.write().await;
+                    for strategy in strategies.strategies.values_mut() {
+                        strategy.set_fee_rates(account_api.get_cached_fee_rates());
+                        if let Some(funding) = market_info.get_funding(&strategy.config.base_config.symbol) {
+                            strategy.set_funding_info(funding);
+                        }
+                    }
+                }
+
                 // Process market data and generate orders
-                for entry in order_books.iter() {
-                    let (symbol, order_book) = (entry.key(), entry.value());
-                    
-                    // Clone order book to avoid holding references across await
-                    let order_book_clone = order_book.clone();
+                // Copy out the top of each book first so no map shard stays locked across
+                // the awaits below; quoting and matching never look deeper
+                let books: Vec<(String, OrderBook)> = order_books.iter()
+                    .map(|entry| (entry.key().clone(), entry.value().top_levels(SNAPSHOT_LEVELS)))
+                    .collect();
+                for (symbol, order_book) in &books {
+                    trading_api.on_book_update(order_book);
+                    if let (Some(mid), false) = (order_book.mid_price(), order_book.is_stale()) {
+                        risk_manager.update_mark_price(symbol, mid);
+                        let spread_bps = order_book.spread_bps().and_then(|bps| bps.to_u32()).unwrap_or(0);
+                        risk_manager.update_volatility(symbol, spread_bps, mid);
+                    }
+
+                    // Flatten positions whose stop-loss or take-profit has been crossed
+                    let trigger_order = match (position_manager.get_position(symbol), order_book.mid_price()) {
+                        (Some(position), Some(mid)) if !order_book.is_stale() => trigger_manager.check(&position, mid),
+                        _ => None,
+                    };
+                    if let Some((kind, order)) = trigger_order {
+                        // Past its loss limit the symbol stops being quoted, and its resting
+                        // quotes go before they can add to the position being closed
+                        if kind == TriggerKind::MaxLoss {
+                            for name in strategies.write().await.disable_for_loss(symbol) {
+                                warn!("Strategy {} disabled until re-enabled: {} loss limit reached", name, symbol);
+                                let _ = bot_events_tx.send(BotEvent::StrategyDisabled { name });
+                            }
+                            if let Err(e) = trading_api.cancel_all_orders(Some(symbol)).await {
+                                error!("Failed to cancel {} orders after loss limit: {}", symbol, e);
+                            }
+                        }
+                        match risk_manager.check_order_risk(&order) {
+                            Ok(_) => match trading_api.place_order(order).await {
+                                Ok(order_id) => {
+                                    info!("Trigger order placed: {} for {}", order_id, symbol);
+                                    let _ = bot_events_tx.send(BotEvent::OrderPlaced {
+                                        order_id,
+                                        symbol: symbol.clone(),
+                                    });
+                                }
+                                Err(e) => {
+                                    error!("Failed to place trigger order: {}", e);
+                                    let _ = bot_events_tx.send(BotEvent::Error {
+                                        error: format!("Failed to place trigger order: {}", e),
+                                    });
+                                }
+                            },
+                            Err(e) => warn!("Trigger order rejected by risk manager: {}", e),
+                        }
+                    }
                     
                     // Extract actions without holding lock across await
                     let actions = {
-                        let strategy = market_making_strategy.read().await;
-                        // Generate actions synchronously to avoid Send issues
-                        strategy.generate_actions_sync(&order_book_clone)
+                        let mut strategies = strategies.write().await;
+                        let mut actions = Vec::new();
+                        for (strategy_name, strategy) in strategies.strategies.iter_mut() {
+                            // A stale book is the last one seen before the feed dropped, and
+                            // with the whole feed down the watchdog is pulling every quote
+                            if &strategy.config.base_config.symbol != symbol || order_book.is_stale()
+                                || !market_data_live.load(Ordering::Acquire) {
+                                continue;
+                            }
+                            if let Some(mid) = order_book.mid_price() {
+                                strategy.observe_mid(mid);
+                                strategy.observe_book_update(order_book.sequence);
+                                metrics.symbol(symbol).book_update_rate.set(strategy.book_update_rate());
+                            }
+                            // Generate actions synchronously to avoid Send issues
+                            let strategy_actions = strategy.generate_actions_sync(&order_book.snapshot(SNAPSHOT_LEVELS));
+                            // Restart the (jittered) refresh timer once quotes have been replaced
+                            if let (false, Some(mid)) = (strategy_actions.is_empty(), order_book.mid_price()) {
+                                strategy.update_last_price(mid);
+                            }
+                            actions.extend(strategy_actions.into_iter().map(|action| (strategy_name.clone(), action)));
+                        }
+                        actions
                     };
                     
-                    for action in actions {
+                    for (strategy_name, action) in actions {
                         match action.action_type {
                             hyper_liquid_connector::trading::types::OrderActionType::Place => {
                                 if let Some(new_order) = action.order {
-                                    match risk_manager.check_order_risk(&new_order) {
+                                    match risk_manager.check_strategy_order_risk(&strategy_name, &new_order) {
                                         Ok(_) => {
-                                            match trading_api.place_order(new_order.clone()).await {
+                                            risk_manager.record_order_submission(symbol);
+                                            match trading_api.place_order_for_strategy(new_order.clone(), strategy_ids[&strategy_name]).await {
                                                 Ok(order_id) => {
                                                     info!("Order placed: {} for {}", order_id, symbol);
+                                                    // Read the order's state under the lock so no update routed
+                                                    // since can be overwritten by it
+                                                    let mut strategies = strategies.write().await;
+                                                    let pending = trading_api.get_pending_order(order_id);
+                                                    strategies.track_placement(&strategy_name, order_id, &new_order, pending.as_ref());
+                                                    drop(strategies);
                                                     let _ = bot_events_tx.send(BotEvent::OrderPlaced {
                                                         order_id,
                                                         symbol: symbol.clone(),
@@ -262,6 +669,171 @@ impl TradingBot {
         });
     }
 
+    async fn reconcile_with_exchange(&mut self) -> Result<()> {
+        let open_orders = self.account_api.get_open_orders().await
+            .map_err(|e| anyhow::anyhow!("Failed to fetch open orders: {}", e))?;
+        let positions = self.account_api.get_positions().await
+            .map_err(|e| anyhow::anyhow!("Failed to fetch positions: {}", e))?;
+
+        for oid in self.apply_exchange_state(&open_orders, &positions).await {
+            match self.trading_api.cancel_exchange_order(oid).await {
+                Ok(()) => info!("Cancelled unrecognised order {} left on the exchange", oid),
+                Err(e) => warn!("Failed to cancel unrecognised order {}: {}", oid, e),
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Seeds the order and position managers and the strategy's inventory from the
+    /// exchange. Returns the oids of resting orders without one of our cloids that
+    /// should be cancelled rather than adopted.
+    async fn apply_exchange_state(&mut self, open_orders: &[HyperLiquidOrderRest], positions: &[Position]) -> Vec<u64> {
+        let adopt_unknown = self.config_manager.get_config().api_config.adopt_unknown_orders;
+        let mut resting = Vec::new();
+        let mut to_cancel = Vec::new();
+
+        for open_order in open_orders {
+            let is_ours = open_order.cloid.as_deref()
+                .and_then(|cloid| self.trading_api.cloids.attribute(cloid))
+                .is_some();
+            if !is_ours && !adopt_unknown {
+                to_cancel.push(open_order.oid);
+                continue;
+            }
+
+            match account_api::parse_open_order(open_order) {
+                Ok(order) => resting.push(order),
+                Err(e) => warn!("Skipping open order {}: {}", open_order.oid, e),
+            }
+        }
+
+        let adopted = self.order_manager.reconcile(resting);
+        info!("Reconciled {} open orders with the exchange ({} adopted, {} to cancel)",
+            open_orders.len() - to_cancel.len(), adopted, to_cancel.len());
+
+        for position in positions {
+            self.position_manager.update_position(
+                position.symbol.clone(), position.size, position.entry_price, position.mark_price,
+            );
+            self.risk_manager.update_position(&position.symbol, position.size, position.mark_price);
+            let mut strategies = self.strategies.write().await;
+            for strategy in strategies.strategies.values_mut().filter(|s| s.config.base_config.symbol == position.symbol) {
+                strategy.on_position_update(position).await;
+            }
+        }
+        info!("Reconciled {} positions with the exchange", positions.len());
+
+        to_cancel
+    }
+
+    // Dead-man's switch for market data: quotes left resting while the bot can't see
+    // the book are free options for everyone else, so cancel them all
+    fn start_market_data_watchdog(&self) {
+        let system_events = self.event_bus.subscribe("system");
+        let trading_api = self.trading_api.clone();
+        let market_data_live = Arc::clone(&self.market_data_live);
+        let handle = tokio::runtime::Handle::current();
+
+        std::thread::spawn(move || {
+            for event in system_events {
+                match event {
+                    SystemEvent::System { event: SystemLevelEvent::MarketDataLost { connections }, .. } => {
+                        error!("Market data lost on all {} connections, cancelling all orders", connections);
+                        // Stop quoting first, so the loop can't replace what is cancelled below
+                        market_data_live.store(false, Ordering::Release);
+                        if let Err(e) = handle.block_on(trading_api.cancel_all_orders(None)) {
+                            error!("Failed to cancel orders after losing market data: {}", e);
+                        }
+                    }
+                    SystemEvent::System { event: SystemLevelEvent::MarketDataRestored { connections }, .. } => {
+                        info!("Market data restored on {} connections", connections);
+                        // Books stay stale, and unquoted, until their first update
+                        market_data_live.store(true, Ordering::Release);
+                    }
+                    _ => {}
+                }
+            }
+        });
+    }
+
+    fn start_market_data(&mut self) {
+        let Some(mut ws_manager) = self.ws_manager.take() else {
+            return;
+        };
+        let bot_events_tx = self.bot_events_tx.clone();
+        self.market_data_task = Some(tokio::spawn(async move {
+            // The manager reconnects on its own; returning means it shut down or gave up
+            if let Err(e) = ws_manager.run().await {
+                error!("Market data stopped: {}", e);
+                let _ = bot_events_tx.send(BotEvent::Error { error: format!("Market data stopped: {}", e) });
+            }
+        }));
+    }
+
+    // Copies state owned by other components into the metrics, so a scrape only ever
+    // reads atomics
+    fn start_metrics_sampler(&self) {
+        let is_running = Arc::clone(&self.is_running);
+        let metrics = Arc::clone(&self.metrics);
+        let (events_processed, events_dropped) = self.event_bus.counters();
+        let total_reconnects = Arc::clone(&self.trading_ws.total_reconnects);
+        let stale_dropped = self.ws_manager.as_ref()
+            .map(|ws_manager| Arc::clone(&ws_manager.stale_dropped))
+            .unwrap_or_default();
+        let position_manager = self.position_manager.clone();
+        let risk_manager = self.risk_manager.clone();
+
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval(Duration::from_secs(1));
+            let mut ticks: u64 = 0;
+
+            while *is_running.read().await {
+                interval.tick().await;
+                ticks += 1;
+
+                if ticks.is_multiple_of(60) && metrics.tick_to_trade.count() > 0 {
+                    info!("Tick-to-trade latency: {}", metrics.tick_to_trade.summary());
+                }
+
+                metrics.events_processed.set(events_processed.load(Ordering::Relaxed));
+                metrics.events_dropped.set(events_dropped.load(Ordering::Relaxed));
+                metrics.connection("trading").reconnects.set(*total_reconnects.read());
+                metrics.market_data_stale_dropped.set(stale_dropped.load(Ordering::Relaxed));
+
+                for position in position_manager.get_all_positions() {
+                    let symbol_metrics = metrics.symbol(&position.symbol);
+                    symbol_metrics.inventory.set(position.size.to_f64().unwrap_or_default());
+                    symbol_metrics.unrealized_pnl.set(position.unrealized_pnl.to_f64().unwrap_or_default());
+                    symbol_metrics.risk_score.set(risk_manager.get_risk_score(&position.symbol).to_f64().unwrap_or_default());
+                }
+            }
+        });
+    }
+
+    // Checks held inventory against predicted funding each time the rates are refreshed
+    fn start_funding_monitor(&self) {
+        let is_running = Arc::clone(&self.is_running);
+        let market_info = self.market_info.clone();
+        let risk_manager = self.risk_manager.clone();
+        let position_manager = self.position_manager.clone();
+        let interval_secs = self.config_manager.get_config().api_config.funding_refresh_interval_secs;
+
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval(Duration::from_secs(interval_secs));
+
+            while *is_running.read().await {
+                interval.tick().await;
+
+                for position in position_manager.get_all_positions() {
+                    if let Some(funding) = market_info.get_funding(&position.symbol) {
+                        risk_manager.check_funding_risk(&funding, position.size);
+                    }
+                }
+            }
+        });
+    }
+
     pub fn get_positions(&self) -> Vec<hyper_liquid_connector::trading::types::Position> {
         self.position_manager.get_all_positions()
     }
@@ -282,6 +854,12 @@ impl TradingBot {
         *self.is_running.read().await
     }
 
+    /// True when no strategy is configured: market data, positions and risk are
+    /// tracked but nothing is quoted.
+    pub async fn is_monitor_only(&self) -> bool {
+        self.strategies.read().await.strategies.is_empty()
+    }
+
     pub async fn enable_strategy(&mut self, name: &str) -> Result<()> {
         self.config_manager.update_config(|config| {
             if let Some(strategy) = config.strategies.get_mut(name) {
@@ -289,10 +867,13 @@ impl TradingBot {
             }
         }).map_err(|e| anyhow::anyhow!("{}", e))?;
 
-        // Update strategy state
-        if name == "market_making_HYPE" {
-            self.market_making_strategy.set_enabled(true);
+        // Update strategy state; an operator enable also lifts a loss-limit disable
+        let mut strategies = self.strategies.write().await;
+        if let Some(strategy) = strategies.strategies.get(name) {
+            warn_if_unstreamed(name, &strategy.config.base_config.symbol, &self.market_data_symbols);
         }
+        strategies.enable(name);
+        drop(strategies);
 
         let _ = self.bot_events_tx.send(BotEvent::StrategyEnabled {
             name: name.to_string(),
@@ -310,8 +891,8 @@ impl TradingBot {
         }).map_err(|e| anyhow::anyhow!("{}", e))?;
 
         // Update strategy state
-        if name == "market_making_HYPE" {
-            self.market_making_strategy.set_enabled(false);
+        if let Some(strategy) = self.strategies.write().await.strategies.get_mut(name) {
+            strategy.set_enabled(false);
         }
 
         let _ = self.bot_events_tx.send(BotEvent::StrategyDisabled {
@@ -323,15 +904,463 @@ impl TradingBot {
     }
 }
 
+impl TradingBot {
+    /// Kill switch: halts new orders, disables every strategy, cancels all open orders
+    /// and then flattens every position with reduce-only market orders. Cancels are
+    /// awaited (up to `EMERGENCY_CANCEL_TIMEOUT`) before flattening so fills on
+    /// resting orders can't be counted against the flattening size. Trading stays
+    /// halted until `resume_trading` is called.
+    pub async fn emergency_stop(&mut self, reason: &str) -> Result<()> {
+        error!("Emergency stop: {}", reason);
+        self.risk_manager.halt(format!("Emergency stop: {}", reason));
+
+        self.config_manager.update_config(|config| {
+            for strategy in config.strategies.values_mut() {
+                strategy.enabled = false;
+            }
+        }).map_err(|e| anyhow::anyhow!("{}", e))?;
+        for strategy in self.strategies.write().await.strategies.values_mut() {
+            strategy.set_enabled(false);
+        }
+
+        let dropped_retries = self.trading_api.clear_retry_queue().await;
+        if dropped_retries > 0 {
+            warn!("Dropped {} orders awaiting retry", dropped_retries);
+        }
+
+        match tokio::time::timeout(EMERGENCY_CANCEL_TIMEOUT, self.trading_api.cancel_all_orders(None)).await {
+            Ok(Ok(())) => info!("All open orders cancelled"),
+            Ok(Err(e)) => error!("Failed to cancel open orders: {}", e),
+            Err(_) => error!("Timed out cancelling open orders after {:?}, flattening anyway", EMERGENCY_CANCEL_TIMEOUT),
+        }
+
+        let mut flattened = Vec::new();
+        for position in self.position_manager.get_all_positions() {
+            if position.size == Decimal::ZERO {
+                continue;
+            }
+
+            let order = flatten_order(&position, "emergency_flatten");
+
+            if let Err(e) = self.risk_manager.check_order_risk(&order) {
+                error!("Flatten order for {} rejected by risk manager: {}", position.symbol, e);
+                continue;
+            }
+            match self.trading_api.place_order(order).await {
+                Ok(order_id) => {
+                    info!("Flattening {} {} with order {}", position.size, position.symbol, order_id);
+                    flattened.push(position.symbol);
+                }
+                Err(e) => error!("Failed to flatten {}: {}", position.symbol, e),
+            }
+        }
+
+        let _ = self.bot_events_tx.send(BotEvent::EmergencyStop {
+            reason: reason.to_string(),
+            flattened,
+        });
+        Ok(())
+    }
+
+    /// Clears the halt latched by `emergency_stop`. Strategies stay disabled until
+    /// they are enabled again individually.
+    pub fn resume_trading(&self) {
+        self.risk_manager.resume();
+    }
+}
+
+// Signs with the configured key and confirms with the exchange which account it is
+// Every configured strategy, each enabled as its config says. None configured is not
+// an error: the bot then runs monitor-only.
+fn load_strategies(config: &BotConfig) -> Result<StrategyStore> {
+    let strategies: BTreeMap<String, MarketMakingStrategy> = config.market_making_configs()
+        .map_err(|e| anyhow::anyhow!("{}", e))?
+        .into_iter()
+        .map(|(name, strategy_config)| {
+            let enabled = strategy_config.base_config.enabled;
+            let mut strategy = MarketMakingStrategy::new(strategy_config);
+            strategy.set_enabled(enabled);
+            (name, strategy)
+        })
+        .collect();
+    if strategies.is_empty() {
+        warn!("No strategies configured; running in monitor-only mode");
+    } else {
+        info!("Loaded {} strategies: {}", strategies.len(), strategies.keys().cloned().collect::<Vec<_>>().join(", "));
+    }
+    Ok(StrategyStore::new(strategies))
+}
+
+async fn authenticate(config: &BotConfig) -> Result<HyperLiquidAuth> {
+    let private_key = config.secrets.private_key().map_err(anyhow::Error::msg)?;
+    let mut auth = HyperLiquidAuth::new(private_key);
+    if let Ok(account_id) = std::env::var("HYPERLIQUID_ACCOUNT_ID") {
+        let account_id = account_id.parse()
+            .map_err(|e| anyhow::anyhow!("Invalid HYPERLIQUID_ACCOUNT_ID: {}", e))?;
+        auth = auth.with_account_id(account_id);
+    }
+    if let Ok(agent_address) = std::env::var("HYPERLIQUID_AGENT_ADDRESS") {
+        auth = auth.with_agent_address(agent_address);
+    }
+
+    let identity = auth.authenticate().await
+        .map_err(|e| anyhow::anyhow!("Authentication failed: {}", e))?;
+    info!("Authenticated as account {} ({})", identity.account, identity.permissions.join(", "));
+    Ok(auth)
+}
+
+// Reduce-only market order closing all of `position`
+fn flatten_order(position: &Position, client_id: &str) -> NewOrder {
+    NewOrder {
+        symbol: position.symbol.clone(),
+        side: if position.size > Decimal::ZERO { Side::Sell } else { Side::Buy },
+        order_type: OrderType::Market,
+        price: position.mark_price,
+        size: position.size.abs(),
+        client_id: Some(client_id.to_string()),
+        reduce_only: true,
+        tick_received_at: None,
+    }
+}
+
+// Books fills into the position manager and the risk manager's trade log. When the
+// cloid names the placing strategy, fills also go to its inventory, and order updates
+// to the orders and quote ladder it tracks
+fn route_order_events(
+    trading_events_rx: Receiver<ApiEvent>,
+    position_manager: PositionManager,
+    risk_manager: RiskManager,
+    cloids: Arc<CloidRegistry>,
+    strategies: SharedStrategies,
+) -> std::thread::JoinHandle<()> {
+    let runtime = tokio::runtime::Handle::current();
+
+    std::thread::spawn(move || {
+        for event in trading_events_rx {
+            let (coin, side, fill_size, fill_price, fee, timestamp, cloid) = match event {
+                ApiEvent::Fill { coin, side, fill_size, fill_price, fee, timestamp, cloid, .. } => {
+                    (coin, side, fill_size, fill_price, fee, timestamp, cloid)
+                }
+                ApiEvent::OrderUpdate { order_id, status, filled_size, cloid, .. } => {
+                    let strategy = cloid.as_deref()
+                        .and_then(|cloid| cloids.attribute(cloid))
+                        .and_then(|(strategy, _)| strategy);
+                    let filled_size = filled_size.parse::<Decimal>().unwrap_or(Decimal::ZERO);
+                    if let (Some(name), Some(status)) = (strategy, account_api::parse_order_status(&status, filled_size)) {
+                        strategies.blocking_write().on_order_update(&name, order_id, status, filled_size);
+                    }
+                    continue;
+                }
+                _ => continue,
+            };
+
+            let (Ok(side), Ok(size), Ok(price), Ok(fee)) = (
+                account_api::parse_side(&side),
+                fill_size.parse::<Decimal>(),
+                fill_price.parse::<Decimal>(),
+                fee.parse::<Decimal>(),
+            ) else {
+                warn!("Unparseable fill for {}, not booked", coin);
+                continue;
+            };
+
+            let strategy = cloid.as_deref()
+                .and_then(|cloid| cloids.attribute(cloid))
+                .and_then(|(strategy, _)| strategy);
+            let fill = Fill {
+                id: Uuid::new_v4(),
+                order_id: Uuid::nil(), // Exchange order ids aren't mapped to internal ids
+                symbol: coin,
+                side,
+                price,
+                size,
+                fee,
+                timestamp: chrono::DateTime::from_timestamp_millis(timestamp as i64).unwrap_or_else(chrono::Utc::now),
+                strategy,
+            };
+
+            position_manager.process_fill(&fill);
+            risk_manager.record_fill(&fill);
+            if let Some(position) = position_manager.get_position(&fill.symbol) {
+                risk_manager.update_position(&fill.symbol, position.size, fill.price);
+            }
+            if let Some(name) = &fill.strategy {
+                risk_manager.record_strategy_fill(name, fill.side, fill.size);
+                // Inventory bands and skew work off the strategy's own count
+                if let Some(strategy) = strategies.blocking_write().strategies.get_mut(name) {
+                    runtime.block_on(strategy.on_fill(&fill));
+                }
+            }
+        }
+    })
+}
+
+// Market data is subscribed at startup, so a strategy enabled later may have no book to quote off
+fn warn_if_unstreamed(name: &str, symbol: &str, market_data_symbols: &[String]) {
+    if !market_data_symbols.iter().any(|streamed| streamed == symbol) {
+        warn!("Strategy {} enabled for {}, which has no market data until restart", name, symbol);
+    }
+}
+
+// Applies strategy and risk changes from the config manager, hot reloads included, to
+// the running event loop. Anything else takes effect on restart
+fn apply_config_changes(
+    config_events_rx: Receiver<ConfigEvent>,
+    strategies: SharedStrategies,
+    risk_manager: RiskManager,
+    trigger_manager: TriggerOrderManager,
+    bot_events_tx: crossbeam_channel::Sender<BotEvent>,
+    market_data_symbols: Vec<String>,
+) -> std::thread::JoinHandle<()> {
+    std::thread::spawn(move || {
+        for event in config_events_rx {
+            let (section, key, new_value) = match event {
+                ConfigEvent::ConfigChanged { section, key, new_value, .. } => (section, key, new_value),
+                ConfigEvent::ConfigError { error, .. } => {
+                    let _ = bot_events_tx.send(BotEvent::Error { error: format!("Config error: {}", error) });
+                    continue;
+                }
+                _ => continue,
+            };
+
+            let result = match section.as_str() {
+                // update_config reports every edit as a change of updated_at
+                "global" if key == "updated_at" => Ok(()),
+                "strategies" if new_value.is_null() => {
+                    warn!("Strategy {} removed from config; it keeps running until restart", key);
+                    Ok(())
+                }
+                "strategies" => serde_json::from_value::<StrategyConfig>(new_value)
+                    .map_err(|e| format!("Failed to parse strategy {}: {}", key, e))
+                    .and_then(|strategy_config| {
+                        let mut strategies = strategies.blocking_write();
+                        let loss_disabled = strategies.is_loss_disabled(&key);
+                        let Some(strategy) = strategies.strategies.get_mut(&key) else {
+                            warn!("Strategy {} added to config; it starts on restart", key);
+                            return Ok(());
+                        };
+
+                        strategy_config.apply_to(strategy)?;
+                        let enabled = strategy_config.enabled;
+                        risk_manager.add_strategy_limits(key.clone(), strategy_config.risk_limits);
+                        if enabled && loss_disabled {
+                            warn!("Strategy {} stays disabled after reaching its loss limit until enabled explicitly", key);
+                        } else if strategy.is_enabled() != enabled {
+                            if enabled {
+                                warn_if_unstreamed(&key, &strategy_config.symbol, &market_data_symbols);
+                            }
+                            strategy.set_enabled(enabled);
+                            let name = key.clone();
+                            let _ = bot_events_tx.send(if enabled { BotEvent::StrategyEnabled { name } } else { BotEvent::StrategyDisabled { name } });
+                        }
+                        info!("Strategy {} config updated", key);
+                        Ok(())
+                    }),
+                "risk" => serde_json::from_value::<RiskConfig>(new_value)
+                    .map_err(|e| format!("Failed to parse risk config: {}", e))
+                    .and_then(|risk_config| {
+                        risk_manager.apply_config(&risk_config)
+                            .map_err(|e| format!("Risk config not applied: {}", e))?;
+                        trigger_manager.load_configs(&risk_config.trigger_orders);
+                        info!("Risk config updated");
+                        Ok(())
+                    }),
+                _ => {
+                    info!("Config {}.{} changed; takes effect on restart", section, key);
+                    Ok(())
+                }
+            };
+
+            if let Err(error) = result {
+                error!("{}", error);
+                let _ = bot_events_tx.send(BotEvent::Error { error });
+            }
+        }
+    })
+}
+
+// Cancels quotes their strategy has left behind: resting past its max_order_age_ms, or
+// still there after a refresh cycle far from the mid or on a book without data. Covers
+// strategies disabled mid-session and books whose feed has stalled.
+fn start_order_reaper(
+    strategies: SharedStrategies,
+    trading_api: TradingApi,
+    order_books: Arc<DashMap<String, OrderBook>>,
+    is_running: Arc<RwLock<bool>>,
+) {
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(ORDER_REAPER_INTERVAL);
+
+        while *is_running.read().await {
+            interval.tick().await;
+            reap_stale_orders(&strategies, &trading_api, &order_books).await;
+        }
+    });
+}
+
+// One pass of the order reaper
+async fn reap_stale_orders(strategies: &SharedStrategies, trading_api: &TradingApi, order_books: &DashMap<String, OrderBook>) {
+    let configs: HashMap<String, MarketMakingConfig> = strategies.read().await.strategies.iter()
+        .map(|(name, strategy)| (name.clone(), strategy.config.clone()))
+        .collect();
+    // Orders the bot didn't place for a strategy (trigger and flatten orders) are left alone
+    let stale: Vec<(u64, String, String)> = trading_api.get_pending_orders().into_iter()
+        .filter_map(|order| {
+            let config = configs.get(&trading_api.cloids.strategy_name(order.cloid.strategy_id)?)?;
+            let mid = order_books.get(&order.symbol)
+                .filter(|book| !book.is_stale())
+                .and_then(|book| book.mid_price());
+            let age_ms = order.created_at.elapsed().as_millis() as u64;
+            let reason = config.stale_order_reason(age_ms, order.price, mid)?;
+            Some((order.client_order_id, order.symbol, reason))
+        })
+        .collect();
+
+    for (client_order_id, symbol, reason) in stale {
+        warn!("Cancelling {} order {}: {}", symbol, client_order_id, reason);
+        if let Err(e) = trading_api.cancel_order_by_client_id(client_order_id).await {
+            warn!("Failed to cancel stale order {}: {}", client_order_id, e);
+        }
+    }
+}
+
+fn forward_exchange_events(trading_ws_events_rx: Receiver<ApiEvent>, trading_api: TradingApi, publisher: EventPublisher) {
+    std::thread::spawn(move || {
+        for event in trading_ws_events_rx {
+            let bus_event = match event {
+                ApiEvent::Fill { .. } => {
+                    trading_api.on_exchange_fill(event);
+                    continue;
+                }
+                // Heartbeat timeouts and liquidations arrive as errors
+                ApiEvent::Error { error, .. } => SystemLevelEvent::Error {
+                    component: "trading_ws".to_string(),
+                    error,
+                },
+                // Our own quotes open, fill and cancel all the time; only the exchange's
+                // doing (rejections, margin or liquidation cancels) is worth a page
+                ApiEvent::OrderUpdate { status, .. } if ROUTINE_ORDER_STATUSES.contains(&status.as_str()) => continue,
+                ApiEvent::OrderUpdate { order_id, status, filled_size, remaining_size, price, .. } => SystemLevelEvent::ExchangeNotice {
+                    kind: format!("order_update:{}", status),
+                    message: format!(
+                        "Order {} {} at {}: filled {}, remaining {}",
+                        order_id, status, price, filled_size, remaining_size
+                    ),
+                },
+                ApiEvent::OrderRejected { order_id, reason, .. } => SystemLevelEvent::ExchangeNotice {
+                    kind: "order_rejected".to_string(),
+                    message: format!("Order {} rejected: {}", order_id, reason),
+                },
+                ApiEvent::OrderRetriesExhausted { order_id, attempts, error, .. } => SystemLevelEvent::ExchangeNotice {
+                    kind: "order_retries_exhausted".to_string(),
+                    message: format!("Order {} failed after {} attempts: {}", order_id, attempts, error),
+                },
+                // Snapshots refreshed on every webData2 push and hourly funding; the caches already hold them
+                ApiEvent::PositionUpdate { .. } | ApiEvent::AccountUpdate { .. } | ApiEvent::FundingPayment { .. } => continue,
+            };
+
+            if let Err(e) = publisher.publish(SystemEvent::new_system_event(bus_event)) {
+                warn!("Failed to publish exchange event: {}", e);
+            }
+        }
+    });
+}
+
+fn forward_risk_events(risk_events_rx: Receiver<RiskEvent>, publisher: EventPublisher) {
+    use hyper_liquid_connector::events::types::RiskEvent as BusRiskEvent;
+
+    std::thread::spawn(move || {
+        for event in risk_events_rx {
+            let (symbol, bus_event) = match event {
+                RiskEvent::LimitExceeded { limit_type, symbol, current_value, limit_value, .. } => (
+                    symbol,
+                    BusRiskEvent::LimitExceeded {
+                        limit_type,
+                        current_value: current_value.to_string(),
+                        limit_value: limit_value.to_string(),
+                    },
+                ),
+                RiskEvent::CircuitBreakerTriggered { breaker_id, symbol, threshold, current_value, .. } => (
+                    symbol,
+                    BusRiskEvent::LimitExceeded {
+                        limit_type: format!("circuit_breaker:{}", breaker_id),
+                        current_value: current_value.to_string(),
+                        limit_value: threshold.to_string(),
+                    },
+                ),
+                _ => continue,
+            };
+
+            if let Err(e) = publisher.publish(SystemEvent::new_risk_event(symbol, bus_event)) {
+                warn!("Failed to publish risk event: {}", e);
+            }
+        }
+    });
+}
+
+const DEFAULT_CONFIG_PATH: &str = "config/bot.toml";
+
+#[derive(Parser)]
+#[command(name = "hl-trading-bot", about = "HyperLiquid market making bot")]
+struct Cli {
+    /// Config file to load
+    #[arg(long, global = true, default_value = DEFAULT_CONFIG_PATH)]
+    config: String,
+
+    #[command(subcommand)]
+    command: Option<Command>,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Run the bot until Ctrl-C (the default)
+    Run,
+    /// Check a config file, including its strategy configs, and exit non-zero if it is invalid
+    ValidateConfig {
+        path: String,
+    },
+    /// Cancel resting orders on the exchange, including ones placed by other sessions, then exit
+    CancelAll {
+        /// Only cancel orders for this symbol
+        #[arg(long)]
+        symbol: Option<String>,
+    },
+    /// Close open positions with reduce-only market orders, then exit
+    Flatten {
+        /// Only close the position in this symbol
+        #[arg(long)]
+        symbol: Option<String>,
+    },
+    /// Print the account's balance, margin and positions
+    Account {
+        /// Print JSON instead of a table
+        #[arg(long)]
+        json: bool,
+    },
+}
+
+// Exits non-zero (via the returned error) on any failure. None of these start the strategy loop.
 #[tokio::main]
 async fn main() -> Result<()> {
-    // Initialize logging
+    // Logs go to stderr so `account --json` output can be piped
     tracing_subscriber::fmt()
         .with_max_level(tracing::Level::INFO)
+        .with_writer(std::io::stderr)
         .init();
 
+    let cli = Cli::parse();
+    match cli.command.unwrap_or(Command::Run) {
+        Command::Run => run(&cli.config).await,
+        Command::ValidateConfig { path } => validate_config(&path).await,
+        Command::CancelAll { symbol } => cancel_all(&cli.config, symbol.as_deref()).await,
+        Command::Flatten { symbol } => flatten(&cli.config, symbol.as_deref()).await,
+        Command::Account { json } => print_account(&cli.config, json).await,
+    }
+}
+
+async fn run(config_path: &str) -> Result<()> {
     // Create trading bot
-    let (mut bot, bot_events_rx) = TradingBot::new(Some("config/bot.toml".to_string())).await?;
+    let (mut bot, bot_events_rx) = TradingBot::new(Some(config_path.to_string())).await?;
 
     // Start bot
     bot.start().await?;
@@ -356,6 +1385,9 @@ async fn main() -> Result<()> {
                 BotEvent::RiskAlert { message, severity } => {
                     warn!("Risk alert [{}]: {}", severity, message);
                 }
+                BotEvent::EmergencyStop { reason, flattened } => {
+                    error!("EMERGENCY STOP: {} (flattening: {:?})", reason, flattened);
+                }
                 BotEvent::Error { error } => {
                     error!("Bot error: {}", error);
                 }
@@ -373,3 +1405,665 @@ async fn main() -> Result<()> {
 
     Ok(())
 }
+
+async fn validate_config(path: &str) -> Result<()> {
+    let (config_manager, _config_events_rx) = ConfigManager::new();
+    config_manager.load_from_file(path).await.map_err(anyhow::Error::msg)?;
+    config_manager.validate_config().map_err(anyhow::Error::msg)?;
+    let strategies = config_manager.get_config().market_making_configs().map_err(anyhow::Error::msg)?;
+    println!("{} is valid ({} strategies)", path, strategies.len());
+    Ok(())
+}
+
+async fn load_config(config_path: &str) -> Result<BotConfig> {
+    let (config_manager, _config_events_rx) = ConfigManager::new();
+    config_manager.load_from_file(config_path).await
+        .map_err(|e| anyhow::anyhow!("Failed to load config: {}", e))?;
+    Ok(config_manager.get_config())
+}
+
+// Authenticated APIs for the one-shot commands, without the bot's managers and feeds
+async fn connect(config: &BotConfig) -> Result<(TradingApi, AccountApi)> {
+    let auth = authenticate(config).await?;
+    let rate_limiter = Arc::new(RateLimiter::new(config.api_config.rate_limit.clone()));
+
+    let market_info = MarketInfoApi::new(auth.clone(), config.api_config.clone())
+        .with_rate_limiter(Arc::clone(&rate_limiter));
+    let meta = market_info.get_meta().await
+        .map_err(|e| anyhow::anyhow!("Failed to fetch asset metadata: {}", e))?;
+    let (trading_api, _trading_events_rx) = TradingApi::new(auth.clone(), config.api_config.clone());
+    let trading_api = trading_api
+        .with_rate_limiter(Arc::clone(&rate_limiter))
+        .with_asset_registry(Arc::new(AssetRegistry::from_meta(&meta)));
+    let (account_api, _account_events_rx) = AccountApi::new(auth, config.api_config.clone());
+    Ok((trading_api, account_api.with_rate_limiter(rate_limiter)))
+}
+
+async fn cancel_all(config_path: &str, symbol: Option<&str>) -> Result<()> {
+    let config = load_config(config_path).await?;
+    let (trading_api, account_api) = connect(&config).await?;
+
+    let orders: Vec<HyperLiquidOrderRest> = account_api.get_open_orders().await
+        .map_err(|e| anyhow::anyhow!("Failed to fetch open orders: {}", e))?
+        .into_iter()
+        .filter(|order| symbol.is_none_or(|symbol| order.coin == symbol))
+        .collect();
+    if trading_api.is_paper() {
+        for order in &orders {
+            println!("Paper trading: would cancel {} {} {} @ {} (oid {})", order.coin, order.side, order.sz, order.px, order.oid);
+        }
+        return Ok(());
+    }
+
+    let mut failed = 0;
+    for order in &orders {
+        match trading_api.cancel_exchange_order(order.oid).await {
+            Ok(()) => println!("Cancelled {} {} {} @ {} (oid {})", order.coin, order.side, order.sz, order.px, order.oid),
+            Err(e) => {
+                error!("Failed to cancel order {}: {}", order.oid, e);
+                failed += 1;
+            }
+        }
+    }
+    if failed > 0 {
+        anyhow::bail!("Failed to cancel {} of {} orders", failed, orders.len());
+    }
+    println!("Cancelled {} orders", orders.len());
+    Ok(())
+}
+
+async fn flatten(config_path: &str, symbol: Option<&str>) -> Result<()> {
+    let config = load_config(config_path).await?;
+    let (trading_api, account_api) = connect(&config).await?;
+
+    let positions: Vec<Position> = account_api.get_positions().await
+        .map_err(|e| anyhow::anyhow!("Failed to fetch positions: {}", e))?
+        .into_iter()
+        .filter(|position| position.size != Decimal::ZERO && symbol.is_none_or(|symbol| position.symbol == symbol))
+        .collect();
+    if trading_api.is_paper() {
+        for position in &positions {
+            let order = flatten_order(position, "cli_flatten");
+            println!("Paper trading: would {:?} {} {} to close the position", order.side, order.size, order.symbol);
+        }
+        return Ok(());
+    }
+
+    let mut failed = 0;
+    for position in &positions {
+        let order = flatten_order(position, "cli_flatten");
+        match trading_api.place_order(order).await {
+            Ok(order_id) => println!("Flattening {} {} with order {}", position.size, position.symbol, order_id),
+            Err(e) => {
+                error!("Failed to flatten {}: {}", position.symbol, e);
+                failed += 1;
+            }
+        }
+    }
+    if failed > 0 {
+        anyhow::bail!("Failed to flatten {} of {} positions", failed, positions.len());
+    }
+    println!("Flattened {} positions", positions.len());
+    Ok(())
+}
+
+async fn print_account(config_path: &str, json: bool) -> Result<()> {
+    let config = load_config(config_path).await?;
+    let (_trading_api, account_api) = connect(&config).await?;
+
+    let account_info = account_api.get_account_info().await
+        .map_err(|e| anyhow::anyhow!("Failed to fetch account: {}", e))?;
+    let positions = account_api.get_positions().await
+        .map_err(|e| anyhow::anyhow!("Failed to fetch positions: {}", e))?;
+    let summary = &account_info.margin_summary;
+
+    if json {
+        let output = serde_json::json!({
+            "account_value": summary.account_value,
+            "total_margin_used": summary.total_margin_used,
+            "total_notional": summary.total_ntl_pos,
+            "positions": positions,
+        });
+        println!("{}", serde_json::to_string_pretty(&output)?);
+        return Ok(());
+    }
+
+    println!("Account value:  {}", summary.account_value);
+    println!("Margin used:    {}", summary.total_margin_used);
+    println!("Total notional: {}", summary.total_ntl_pos);
+    println!();
+    if positions.is_empty() {
+        println!("No open positions");
+        return Ok(());
+    }
+    println!("{:<10} {:>14} {:>14} {:>14} {:>14}", "Symbol", "Size", "Entry", "Mark", "Unrealized");
+    for position in &positions {
+        println!(
+            "{:<10} {:>14} {:>14} {:>14} {:>14}",
+            position.symbol, position.size, position.entry_price, position.mark_price, position.unrealized_pnl.round_dp(2)
+        );
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use hyper_liquid_connector::api::types::{ApiConfig, HyperLiquidMarginSummary, HyperLiquidUserState, HyperLiquidUserStateResponse};
+    use hyper_liquid_connector::config::bot_config::StrategyType;
+    use hyper_liquid_connector::config::secrets::Secret;
+    use hyper_liquid_connector::trading::types::RiskLimits;
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+    fn strategy_config(name: &str, symbol: &str, spread_bps: u32) -> StrategyConfig {
+        let mut config = MarketMakingConfig { spread_bps, ..Default::default() };
+        config.base_config.symbol = symbol.to_string();
+        StrategyConfig {
+            name: name.to_string(),
+            enabled: true,
+            symbol: symbol.to_string(),
+            strategy_type: StrategyType::MarketMaking,
+            config: serde_json::to_value(&config).unwrap(),
+            risk_limits: RiskLimits::default(),
+        }
+    }
+
+    fn store(configs: &[StrategyConfig]) -> SharedStrategies {
+        let strategies = configs.iter()
+            .map(|config| (config.name.clone(), MarketMakingStrategy::new(config.market_making_config().unwrap())))
+            .collect();
+        Arc::new(RwLock::new(StrategyStore::new(strategies)))
+    }
+
+    fn book(symbol: &str, bid: Decimal, ask: Decimal) -> OrderBook {
+        let mut book = OrderBook::new(symbol.to_string());
+        book.update_level(Side::Buy, bid, Decimal::from(5));
+        book.update_level(Side::Sell, ask, Decimal::from(5));
+        book
+    }
+
+    fn strategy_changed(config: &StrategyConfig) -> ConfigEvent {
+        ConfigEvent::ConfigChanged {
+            section: "strategies".to_string(),
+            key: config.name.clone(),
+            old_value: serde_json::Value::Null,
+            new_value: serde_json::to_value(config).unwrap(),
+            timestamp: chrono::Utc::now(),
+        }
+    }
+
+    #[test]
+    fn a_reload_does_not_re_enable_a_strategy_disabled_at_its_loss_limit() {
+        let hype = strategy_config("mm_hype", "HYPE", 20);
+        let eth = strategy_config("mm_eth", "ETH", 20);
+        let strategies = store(&[hype.clone(), eth.clone()]);
+        assert_eq!(strategies.blocking_write().disable_for_loss("HYPE"), vec!["mm_hype".to_string()]);
+
+        let (config_events_tx, config_events_rx) = unbounded();
+        let (bot_events_tx, _bot_events_rx) = unbounded();
+        let applier = apply_config_changes(
+            config_events_rx,
+            Arc::clone(&strategies),
+            RiskManager::new().0,
+            TriggerOrderManager::new().0,
+            bot_events_tx,
+            vec!["ETH".to_string(), "HYPE".to_string()],
+        );
+        // The file still says enabled, and the reload retunes both strategies
+        config_events_tx.send(strategy_changed(&strategy_config("mm_hype", "HYPE", 30))).unwrap();
+        config_events_tx.send(strategy_changed(&strategy_config("mm_eth", "ETH", 30))).unwrap();
+        drop(config_events_tx);
+        applier.join().unwrap();
+
+        let mut strategies = strategies.blocking_write();
+        assert!(!strategies.strategies["mm_hype"].is_enabled());
+        assert_eq!(strategies.strategies["mm_hype"].config.spread_bps, 30);
+        assert!(strategies.strategies["mm_eth"].is_enabled());
+
+        // Only an explicit enable brings it back
+        assert!(strategies.enable("mm_hype"));
+        assert!(strategies.strategies["mm_hype"].is_enabled());
+        assert!(!strategies.is_loss_disabled("mm_hype"));
+    }
+
+    #[test]
+    fn a_spread_change_in_config_retunes_the_running_strategy_quotes() {
+        let strategies = store(&[strategy_config("mm_hype", "HYPE", 20)]);
+        let snapshot = book("HYPE", Decimal::new(9999, 2), Decimal::new(10001, 2)).snapshot(SNAPSHOT_LEVELS);
+        let best_quotes = |strategies: &SharedStrategies| -> Vec<Decimal> {
+            let mut strategies = strategies.blocking_write();
+            strategies.strategies.get_mut("mm_hype").unwrap().generate_actions_sync(&snapshot)
+                .into_iter()
+                .filter_map(|action| action.order)
+                .filter(|order| order.client_id.as_deref().is_some_and(|id| id.ends_with("_0")))
+                .map(|order| order.price)
+                .collect()
+        };
+        assert_eq!(best_quotes(&strategies), [Decimal::new(999, 1), Decimal::new(1001, 1)]);
+
+        let (config_manager, config_events_rx) = ConfigManager::new();
+        let (bot_events_tx, _bot_events_rx) = unbounded();
+        let applier = apply_config_changes(
+            config_events_rx,
+            Arc::clone(&strategies),
+            RiskManager::new().0,
+            TriggerOrderManager::new().0,
+            bot_events_tx,
+            vec!["HYPE".to_string()],
+        );
+        config_manager.update_strategy_config("mm_hype", strategy_config("mm_hype", "HYPE", 60)).unwrap();
+        drop(config_manager);
+        applier.join().unwrap();
+
+        // 60 bps of 100 is 0.6 wide
+        assert_eq!(best_quotes(&strategies), [Decimal::new(997, 1), Decimal::new(1003, 1)]);
+    }
+
+    #[tokio::test]
+    async fn a_fill_past_the_inventory_band_stops_the_strategy_buying() {
+        let mut config = strategy_config("mm_hype", "HYPE", 20);
+        config.config["max_inventory"] = serde_json::json!("1.5");
+        let strategies = store(&[config]);
+        let cloids = Arc::new(CloidRegistry::with_session(1));
+        let cloid = cloids.next(cloids.register_strategy("mm_hype"), "HYPE");
+
+        let (trading_events_tx, trading_events_rx) = unbounded();
+        let router = route_order_events(trading_events_rx, PositionManager::new().0, RiskManager::new().0, cloids, Arc::clone(&strategies));
+        trading_events_tx.send(ApiEvent::Fill {
+            order_id: cloid.sequence,
+            coin: "HYPE".to_string(),
+            side: "B".to_string(),
+            fill_size: "2".to_string(),
+            fill_price: "25".to_string(),
+            fee: "0.01".to_string(),
+            timestamp: 1,
+            cloid: Some(cloid.to_hex()),
+        }).unwrap();
+        drop(trading_events_tx);
+        router.join().unwrap();
+
+        let mut strategies = strategies.write().await;
+        let strategy = strategies.strategies.get_mut("mm_hype").unwrap();
+        assert_eq!(strategy.current_inventory, Decimal::from(2));
+        let sides: Vec<Side> = strategy.generate_actions_sync(&book("HYPE", Decimal::new(2499, 2), Decimal::new(2501, 2)).snapshot(SNAPSHOT_LEVELS))
+            .into_iter()
+            .filter_map(|action| action.order.map(|order| order.side))
+            .collect();
+        assert_eq!(strategy.stopped_side, Some(Side::Buy));
+        assert!(!sides.is_empty());
+        assert!(sides.iter().all(|side| *side == Side::Sell));
+    }
+
+    fn order_update(cloid: &hyper_liquid_connector::api::cloid::Cloid, status: &str, filled_size: &str) -> ApiEvent {
+        ApiEvent::OrderUpdate {
+            order_id: cloid.sequence,
+            status: status.to_string(),
+            filled_size: filled_size.to_string(),
+            remaining_size: String::new(),
+            price: String::new(),
+            timestamp: 1,
+            cloid: Some(cloid.to_hex()),
+        }
+    }
+
+    #[tokio::test]
+    async fn quote_ladder_rungs_follow_their_orders_from_intended_to_done() {
+        let strategies = store(&[strategy_config("mm_hype", "HYPE", 20)]);
+        let cloids = Arc::new(CloidRegistry::with_session(1));
+        let strategy_id = cloids.register_strategy("mm_hype");
+
+        let orders: Vec<NewOrder> = {
+            let mut strategies = strategies.write().await;
+            let strategy = strategies.strategies.get_mut("mm_hype").unwrap();
+            strategy.config.max_orders_per_side = 1;
+            let actions = strategy.generate_actions_sync(&book("HYPE", Decimal::new(2499, 2), Decimal::new(2501, 2)).snapshot(SNAPSHOT_LEVELS));
+            assert!(strategy.quote_ladder().rungs().all(|(_, rung)| rung.status.is_none()));
+            actions.into_iter().filter_map(|action| action.order).collect()
+        };
+        assert_eq!(orders.len(), 2);
+
+        // Both quotes are accepted and rest
+        let mut placed = Vec::new();
+        for order in &orders {
+            let cloid = cloids.next(strategy_id, &order.symbol);
+            let pending = PendingOrder {
+                internal_id: Uuid::new_v4(),
+                client_order_id: cloid.sequence,
+                cloid,
+                asset: 0,
+                symbol: order.symbol.clone(),
+                side: order.side,
+                order_type: order.order_type,
+                price: order.price,
+                size: order.size,
+                filled_size: Decimal::ZERO,
+                reduce_only: false,
+                created_at: std::time::Instant::now(),
+                retry_count: 0,
+            };
+            strategies.write().await.track_placement("mm_hype", pending.internal_id, order, Some(&pending));
+            placed.push(pending);
+        }
+        {
+            let strategies = strategies.read().await;
+            let strategy = &strategies.strategies["mm_hype"];
+            assert_eq!(strategy.active_orders.len(), 2);
+            assert_eq!(strategy.quote_ladder().live_rungs(), 2);
+        }
+
+        // The bid fills in two steps, the ask is cancelled
+        let route = |events: Vec<ApiEvent>| {
+            let (trading_events_tx, trading_events_rx) = unbounded();
+            let router = route_order_events(trading_events_rx, PositionManager::new().0, RiskManager::new().0, Arc::clone(&cloids), Arc::clone(&strategies));
+            for event in events {
+                trading_events_tx.send(event).unwrap();
+            }
+            drop(trading_events_tx);
+            router.join().unwrap();
+        };
+        let (bid, ask) = (&placed[0], &placed[1]);
+        route(vec![order_update(&bid.cloid, "open", "0.4")]);
+        {
+            let strategies = strategies.read().await;
+            let rung = &strategies.strategies["mm_hype"].quote_ladder().bids[0];
+            assert_eq!(rung.status, Some(OrderStatus::PartiallyFilled));
+            assert_eq!(rung.filled_size, Decimal::new(4, 1));
+        }
+        route(vec![order_update(&bid.cloid, "filled", "1"), order_update(&ask.cloid, "canceled", "0")]);
+
+        let strategies = strategies.read().await;
+        let strategy = &strategies.strategies["mm_hype"];
+        let ladder = strategy.quote_ladder();
+        assert_eq!(ladder.bids[0].status, Some(OrderStatus::Filled));
+        assert_eq!(ladder.bids[0].filled_size, Decimal::ONE);
+        assert_eq!(ladder.asks[0].status, Some(OrderStatus::Cancelled));
+        assert_eq!(ladder.live_rungs(), 0);
+        assert!(strategy.active_orders.is_empty());
+    }
+
+    // A bot that trades on paper against `order_books`, with account queries going to
+    // `base_url`. Nothing is started; its trading API's events come back alongside.
+    fn paper_bot(base_url: &str) -> (TradingBot, Receiver<BotEvent>, Receiver<ApiEvent>) {
+        let api_config = ApiConfig { base_url: base_url.to_string(), paper_trading: true, ..ApiConfig::default() };
+        let auth = HyperLiquidAuth::new(Secret::new("test".to_string())).with_account_id(1);
+        let meta = serde_json::from_value(serde_json::json!({
+            "universe": [{ "name": "HYPE", "szDecimals": 2, "maxLeverage": 5 }]
+        })).unwrap();
+        let order_books = Arc::new(DashMap::new());
+        let (trading_api, trading_events_rx) = TradingApi::new(auth.clone(), api_config.clone());
+        let trading_api = trading_api
+            .with_asset_registry(Arc::new(AssetRegistry::from_meta(&meta)))
+            .with_order_books(Arc::clone(&order_books));
+        let (config_manager, config_events_rx) = ConfigManager::new();
+        let (bot_events_tx, bot_events_rx) = unbounded();
+
+        let bot = TradingBot {
+            config_manager,
+            config_events_rx,
+            account_api: AccountApi::new(auth.clone(), api_config.clone()).0,
+            market_info: MarketInfoApi::new(auth.clone(), api_config.clone()),
+            trading_ws: TradingWebSocket::new(auth.clone(), api_config).0,
+            auth,
+            trading_api,
+            order_manager: OrderManager::new().0,
+            position_manager: PositionManager::new().0,
+            risk_manager: RiskManager::new().0,
+            trigger_manager: TriggerOrderManager::new().0,
+            strategies: store(&[strategy_config("mm_hype", "HYPE", 20)]),
+            event_bus: EventBus::new(EventBusConfig::default()),
+            ws_manager: None,
+            market_data_task: None,
+            market_data_symbols: vec!["HYPE".to_string()],
+            order_books,
+            metrics: Arc::new(Metrics::new()),
+            is_running: Arc::new(RwLock::new(false)),
+            market_data_live: Arc::new(AtomicBool::new(true)),
+            started_at: chrono::Utc::now(),
+            bot_events_tx,
+        };
+        (bot, bot_events_rx, trading_events_rx)
+    }
+
+    fn buy(price: Decimal, size: Decimal) -> NewOrder {
+        NewOrder {
+            symbol: "HYPE".to_string(),
+            side: Side::Buy,
+            order_type: OrderType::Limit,
+            price,
+            size,
+            client_id: None,
+            reduce_only: false,
+            tick_received_at: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn the_kill_switch_cancels_resting_orders_and_flattens_on_paper() {
+        let (mut bot, bot_events_rx, trading_events_rx) = paper_bot("http://127.0.0.1:9");
+        bot.order_books.insert("HYPE".to_string(), book("HYPE", Decimal::new(249, 1), Decimal::new(251, 1)));
+        bot.position_manager.update_position("HYPE".to_string(), Decimal::from(2), Decimal::from(25), Decimal::from(25));
+        let resting = bot.trading_api.place_order(buy(Decimal::from(20), Decimal::ONE)).await.unwrap();
+
+        bot.emergency_stop("test").await.unwrap();
+
+        assert!(bot.risk_manager.is_halted());
+        assert!(!bot.strategies.read().await.strategies["mm_hype"].is_enabled());
+        assert!(bot.trading_api.get_pending_order(resting).is_none());
+        assert!(bot.trading_api.get_pending_orders().is_empty());
+        match bot_events_rx.try_iter().last() {
+            Some(BotEvent::EmergencyStop { reason, flattened }) => {
+                assert_eq!(reason, "test");
+                assert_eq!(flattened, ["HYPE"]);
+            }
+            event => panic!("unexpected event {:?}", event),
+        }
+
+        // The resting bid goes, then the long is sold into the bid
+        let events: Vec<ApiEvent> = trading_events_rx.try_iter().collect();
+        assert!(events.iter().any(|event| matches!(event, ApiEvent::OrderUpdate { status, .. } if status == "canceled")));
+        let fills: Vec<_> = events.iter()
+            .filter_map(|event| match event {
+                ApiEvent::Fill { side, fill_size, fill_price, .. } => Some((side.as_str(), fill_size.as_str(), fill_price.as_str())),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(fills, [("A", "2", "24.9")]);
+    }
+
+    fn open_order(oid: u64, cloid: Option<String>) -> HyperLiquidOrderRest {
+        HyperLiquidOrderRest {
+            coin: "HYPE".to_string(),
+            oid,
+            total_sz: "2".to_string(),
+            sz: "1.5".to_string(),
+            px: "24.5".to_string(),
+            side: "B".to_string(),
+            cloid,
+            reduce_only: false,
+            timestamp: 1_700_000_000_000,
+        }
+    }
+
+    #[tokio::test]
+    async fn startup_reconciliation_adopts_our_orders_cancels_the_rest_and_seeds_positions() {
+        let (mut bot, _bot_events_rx, _trading_events_rx) = paper_bot("http://127.0.0.1:9");
+        let cloids = &bot.trading_api.cloids;
+        let ours = cloids.next(cloids.register_strategy("mm_hype"), "HYPE").to_hex();
+        let open_orders = [open_order(11, Some(ours)), open_order(12, None)];
+        let position = Position {
+            symbol: "HYPE".to_string(),
+            size: Decimal::from(-3),
+            entry_price: Decimal::from(25),
+            mark_price: Decimal::from(25),
+            unrealized_pnl: Decimal::ZERO,
+            realized_pnl: Decimal::ZERO,
+            updated_at: chrono::Utc::now(),
+        };
+
+        assert_eq!(bot.apply_exchange_state(&open_orders, std::slice::from_ref(&position)).await, vec![12]);
+
+        let resting = bot.order_manager.get_active_orders(Some("HYPE"));
+        assert_eq!(resting.len(), 1);
+        assert_eq!(resting[0].exchange_oid, Some(11));
+        assert_eq!(resting[0].status, OrderStatus::PartiallyFilled);
+        assert_eq!((resting[0].filled_size, resting[0].remaining_size), (Decimal::new(5, 1), Decimal::new(15, 1)));
+        assert_eq!(bot.position_manager.get_position("HYPE").unwrap().size, Decimal::from(-3));
+        assert_eq!(bot.strategies.read().await.strategies["mm_hype"].current_inventory, Decimal::from(-3));
+
+        // With adoption on, the foreign order is tracked too, and ours is not doubled
+        bot.config_manager.update_config(|config| config.api_config.adopt_unknown_orders = true).unwrap();
+        assert!(bot.apply_exchange_state(&open_orders, &[position]).await.is_empty());
+        let mut oids: Vec<_> = bot.order_manager.get_active_orders(Some("HYPE")).iter().filter_map(|order| order.exchange_oid).collect();
+        oids.sort();
+        assert_eq!(oids, [11, 12]);
+    }
+
+    // An exchange holding `open` resting orders. Cancels are answered with an error
+    // the first `failures` times for an oid in `stubborn`, then succeed
+    async fn mock_exchange(open: Vec<u64>, stubborn: u64, failures: usize) -> (String, Arc<parking_lot::Mutex<Vec<u64>>>) {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let base_url = format!("http://{}", listener.local_addr().unwrap());
+        let cancels = Arc::new(parking_lot::Mutex::new(Vec::new()));
+        let recorded = Arc::clone(&cancels);
+        let open = Arc::new(parking_lot::Mutex::new(open));
+
+        tokio::spawn(async move {
+            while let Ok((mut socket, _)) = listener.accept().await {
+                let mut buf = Vec::new();
+                let mut chunk = [0u8; 4096];
+                let (path, body) = loop {
+                    let n = socket.read(&mut chunk).await.unwrap();
+                    buf.extend_from_slice(&chunk[..n]);
+                    let Some(header_end) = buf.windows(4).position(|w| w == b"\r\n\r\n") else { continue };
+                    let headers = String::from_utf8_lossy(&buf[..header_end]).to_lowercase();
+                    let content_length: usize = headers.lines()
+                        .find_map(|line| line.strip_prefix("content-length:"))
+                        .map(|len| len.trim().parse().unwrap())
+                        .unwrap_or(0);
+                    if buf.len() >= header_end + 4 + content_length {
+                        let path = headers.split_whitespace().nth(1).unwrap().to_string();
+                        break (path, buf[header_end + 4..header_end + 4 + content_length].to_vec());
+                    }
+                };
+                let request: serde_json::Value = serde_json::from_slice(&body).unwrap();
+
+                let response = if path == "/exchange" {
+                    let oid = request["data"]["oid"].as_u64().unwrap();
+                    let mut cancels = recorded.lock();
+                    cancels.push(oid);
+                    let attempts = cancels.iter().filter(|&&cancelled| cancelled == oid).count();
+                    if oid == stubborn && attempts <= failures {
+                        serde_json::json!({ "status": "err", "response": null })
+                    } else {
+                        open.lock().retain(|&resting| resting != oid);
+                        serde_json::json!({ "status": "ok", "response": null })
+                    }
+                } else {
+                    let summary = HyperLiquidMarginSummary {
+                        account_value: "1000".to_string(),
+                        total_margin_used: "0".to_string(),
+                        total_ntl_pos: "0".to_string(),
+                        total_raw_usd: "1000".to_string(),
+                    };
+                    serde_json::to_value(HyperLiquidUserStateResponse {
+                        status: "ok".to_string(),
+                        response: Some(HyperLiquidUserState {
+                            asset_positions: Vec::new(),
+                            cross_margin_summary: summary.clone(),
+                            margin_summary: summary,
+                            withdrawable: "1000".to_string(),
+                            open_orders: open.lock().iter().map(|&oid| open_order(oid, None)).collect(),
+                            equity: "1000".to_string(),
+                        }),
+                    }).unwrap()
+                }.to_string();
+                let reply = format!(
+                    "HTTP/1.1 200 OK\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                    response.len(), response
+                );
+                socket.write_all(reply.as_bytes()).await.unwrap();
+            }
+        });
+
+        (base_url, cancels)
+    }
+
+    #[tokio::test]
+    async fn shutdown_re_cancels_an_order_that_survived_the_first_round() {
+        let (base_url, cancels) = mock_exchange(vec![11, 12], 12, 1).await;
+        let (bot, _bot_events_rx, _trading_events_rx) = paper_bot(&base_url);
+
+        bot.confirm_orders_cancelled(Duration::from_secs(5)).await.unwrap();
+
+        // 12 refused the first cancel, was still open on the next poll and went on the retry
+        assert_eq!(*cancels.lock(), [11, 12, 12]);
+    }
+
+    #[tokio::test]
+    async fn shutdown_names_the_orders_still_open_at_the_timeout() {
+        let (base_url, cancels) = mock_exchange(vec![11, 12], 12, usize::MAX).await;
+        let (bot, _bot_events_rx, _trading_events_rx) = paper_bot(&base_url);
+
+        let err = bot.confirm_orders_cancelled(Duration::from_millis(700)).await.unwrap_err();
+
+        assert_eq!(err, "1 orders still open on the exchange after 700ms: [12]");
+        assert_eq!(cancels.lock().iter().filter(|&&oid| oid == 12).count(), 2);
+    }
+
+    #[tokio::test]
+    async fn the_reaper_cancels_what_a_disabled_strategy_left_behind() {
+        let (bot, _bot_events_rx, _trading_events_rx) = paper_bot("http://127.0.0.1:9");
+        bot.order_books.insert("HYPE".to_string(), book("HYPE", Decimal::new(249, 1), Decimal::new(251, 1)));
+        let strategy_id = bot.trading_api.cloids.register_strategy("mm_hype");
+        bot.strategies.write().await.strategies.get_mut("mm_hype").unwrap().set_enabled(false);
+
+        // Backdates a resting bid so it looks placed `age` ago
+        let place = |price: Decimal, age: Duration| {
+            let trading_api = bot.trading_api.clone();
+            async move {
+                let id = trading_api.place_order_for_strategy(buy(price, Decimal::ONE), strategy_id).await.unwrap();
+                let client_order_id = trading_api.client_order_id(id).unwrap();
+                trading_api.pending_orders.get_mut(&client_order_id).unwrap().created_at -= age;
+                id
+            }
+        };
+        // Defaults: a 30s age limit, 100 bps from the mid, and a refresh cycle under 2s
+        let fresh_far = place(Decimal::from(20), Duration::ZERO).await;
+        let old_near = place(Decimal::new(249, 1), Duration::from_secs(2)).await;
+        let old_far = place(Decimal::from(20), Duration::from_secs(2)).await;
+        let expired = place(Decimal::new(249, 1), Duration::from_secs(31)).await;
+
+        reap_stale_orders(&bot.strategies, &bot.trading_api, &bot.order_books).await;
+        assert!(bot.trading_api.get_pending_order(fresh_far).is_some(), "replaced within the last refresh");
+        assert!(bot.trading_api.get_pending_order(old_near).is_some());
+        assert!(bot.trading_api.get_pending_order(old_far).is_none());
+        assert!(bot.trading_api.get_pending_order(expired).is_none());
+
+        // Once the book stops carrying data, the quote near its old mid goes too
+        bot.order_books.get_mut("HYPE").unwrap().mark_stale();
+        reap_stale_orders(&bot.strategies, &bot.trading_api, &bot.order_books).await;
+        assert!(bot.trading_api.get_pending_order(fresh_far).is_some());
+        assert!(bot.trading_api.get_pending_order(old_near).is_none());
+    }
+
+    #[tokio::test]
+    async fn a_config_without_strategies_starts_the_bot_monitor_only() {
+        let config = BotConfig::default();
+        assert!(config.strategies.is_empty());
+        ConfigManager::validate(&config).unwrap();
+
+        let (mut bot, _bot_events_rx, _trading_events_rx) = paper_bot("http://127.0.0.1:9");
+        assert!(!bot.is_monitor_only().await);
+        bot.strategies = Arc::new(RwLock::new(load_strategies(&config).unwrap()));
+        assert!(bot.is_monitor_only().await);
+
+        // Strategies under any name load, each enabled as configured
+        let mut config = BotConfig::default();
+        let paused = StrategyConfig { enabled: false, ..strategy_config("eth_quotes", "ETH", 20) };
+        config.strategies.insert("hype_quotes".to_string(), strategy_config("hype_quotes", "HYPE", 20));
+        config.strategies.insert("eth_quotes".to_string(), paused);
+        let store = load_strategies(&config).unwrap();
+        let enabled: Vec<(&str, bool)> = store.strategies.iter().map(|(name, strategy)| (name.as_str(), strategy.is_enabled())).collect();
+        assert_eq!(enabled, [("eth_quotes", false), ("hype_quotes", true)]);
+    }
+}
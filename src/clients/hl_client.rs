@@ -1,9 +1,11 @@
-use crate::{model::hl_msgs::TobMsg, utils::ws_utils::{ConnectionTimers, HypeStreamRequest, L2BookSubscription, SubscriptionType, WSState, WebSocketError}};
+use crate::{metrics::registry::ConnectionMetrics, model::hl_msgs::TobMsg, trading::order_book::OrderBook, utils::ws_utils::{ConnectionTimers, HypeStreamRequest, L2BookSubscription, SubscriptionType, WSState, WebSocketError}};
+use dashmap::DashMap;
 use futures::StreamExt;
 use tokio::{sync::mpsc, time::{sleep, Instant}};
 use tracing::{error, info, warn};
 use yawc::frame::{FrameView, OpCode};
 use std::borrow::Cow;
+use std::sync::Arc;
 use std::time::Duration;
 
 use super::ws_client::WebsocketClient;
@@ -14,14 +16,52 @@ pub struct HypeClient {
     pub msg_tx: mpsc::Sender<TobMsg>,
     pub timers: ConnectionTimers,
     pub client_no: u64,
-    pub symbol: String,
+    pub symbols: Vec<String>, // An l2Book subscription each, on the one connection
+    pub metrics: Arc<ConnectionMetrics>,
+    pub health: Arc<DashMap<u64, bool>>, // Readiness by client number, shared across a manager's clients
+    pub warmup_updates: u64, // Valid book updates after each connect before the client counts as ready
+    pub order_books: Option<Arc<DashMap<String, OrderBook>>>, // Fed by the manager, invalidated here when the last connection drops
+    pub warm_reconnect: bool, // Keep the books, marked stale, rather than emptying them when every connection is down
+    valid_updates: u64,
 }
 
 impl HypeClient {
-    pub async fn new(url: &str, symbol: &str, msg_tx: mpsc::Sender<TobMsg>, client_no: u64) -> anyhow::Result<Self>{
+    pub async fn new(url: &str, symbols: &[String], msg_tx: mpsc::Sender<TobMsg>, client_no: u64) -> anyhow::Result<Self>{
         let ws = WebsocketClient::new(url).await?;
         let timers = ConnectionTimers::default();
-        Ok(Self {ws, msg_tx, timers, client_no, symbol: symbol.to_string()})
+        Ok(Self {
+            ws, msg_tx, timers, client_no, symbols: symbols.to_vec(), metrics: Arc::default(),
+            health: Arc::default(), warmup_updates: 0, order_books: None, warm_reconnect: true, valid_updates: 0,
+        })
+    }
+
+    /// True once the current connection has delivered `warmup_updates` valid updates.
+    pub fn is_ready(&self) -> bool {
+        self.valid_updates >= self.warmup_updates
+    }
+
+    fn set_ready(&self, ready: bool) {
+        self.health.insert(self.client_no, ready);
+    }
+
+    // With no connection left to feed them, the books stay stale until their next
+    // snapshot, or are emptied without warm reconnect
+    fn on_connection_lost(&self) {
+        self.set_ready(false);
+        if self.health.iter().any(|entry| *entry.value()) {
+            return;
+        }
+        let Some(order_books) = &self.order_books else {
+            return;
+        };
+        warn!("No market data connection left, {} the books", if self.warm_reconnect { "marking stale" } else { "clearing" });
+        for mut book in order_books.iter_mut() {
+            if self.warm_reconnect {
+                book.mark_stale();
+            } else {
+                book.clear();
+            }
+        }
     }
 
     pub fn subscribe_payload<'h>(type_field: &'h str, coin: &'h str) -> HypeStreamRequest<'h> {
@@ -36,13 +76,17 @@ impl HypeClient {
     }
 
     pub async fn subscribe(&mut self) -> anyhow::Result<()> {
-        self.ws.send(HypeClient::subscribe_payload("l2Book", &self.symbol)).await?;
+        for symbol in &self.symbols {
+            self.ws.send(HypeClient::subscribe_payload("l2Book", symbol)).await?;
+        }
         Ok(())
     }
 
     pub async fn handle_msg(&mut self, frame: FrameView) -> anyhow::Result<WSState> {
         match frame.opcode {
             OpCode::Text => {
+                        let received_at = std::time::Instant::now();
+                        self.metrics.messages.inc();
                         if let Ok(text) = std::str::from_utf8(&frame.payload) {
                             // debug!("Raw WS message: {}", text);
                             if text.contains(r#""channel":"pong""#) {
@@ -53,7 +97,24 @@ impl HypeClient {
                             if text.contains(r#""channel":"subscriptionResponse""#) {
                                 return Ok(WSState::Continue);
                             }
-                            if let Ok(tob_msg) = serde_json::from_str::<TobMsg>(text) {
+                            if let Ok(mut tob_msg) = serde_json::from_str::<TobMsg>(text) {
+                                if let Err(e) = tob_msg.validate() {
+                                    self.metrics.invalid_messages.inc();
+                                    warn!("Skipping invalid book message on client={}: {}", self.client_no, e);
+                                    return Ok(WSState::Continue);
+                                }
+                                // Warm-up updates are still passed on; they only hold back readiness
+                                if !self.is_ready() {
+                                    self.valid_updates += 1;
+                                    if self.is_ready() {
+                                        info!("Client {} ready after {} warm-up updates", self.client_no, self.valid_updates);
+                                        self.set_ready(true);
+                                    }
+                                }
+                                tob_msg.data.received_at = Some(received_at);
+                                // Clamped at zero when the local clock runs behind the exchange's
+                                let skew_ms = chrono::Utc::now().timestamp_millis() - tob_msg.data.time as i64;
+                                self.metrics.exchange_skew.observe(Duration::from_millis(skew_ms.max(0) as u64));
                                 if let Err(e) = self.msg_tx.send(tob_msg).await {
                                     warn!("Failed to send message to manager: {}", e);
                                 }
@@ -63,14 +124,14 @@ impl HypeClient {
                             return Ok(WSState::Continue);
                         }
                         warn!("Received invalid UTF-8 in text frame");
-                        return Ok(WSState::Continue);
+                        Ok(WSState::Continue)
                     }
            OpCode::Binary | OpCode::Continuation | OpCode::Ping | OpCode::Pong  => {
-                        return Ok(WSState::Continue);
+                        Ok(WSState::Continue)
                     }
             OpCode::Close => {
                         warn!("Received close frame from server, client={}", self.client_no);
-                        return Ok(WSState::Closed);
+                        Ok(WSState::Closed)
                     }
         }
     }
@@ -88,7 +149,7 @@ impl HypeClient {
                             }
                         },
                         _ => {
-                            continue;
+                            return Err(WebSocketError::Error(anyhow::anyhow!("Stream closed by server")));
                         }
                     }
                 },
@@ -113,11 +174,20 @@ impl HypeClient {
         }
     }
     
-    pub async fn reconnect(&mut self) -> anyhow::Result<()> {
-        info!("Attempting to reconnect to HyperLiquid, client={}", self.client_no);
+    /// Replaces the socket with a fresh connection, without subscribing.
+    pub async fn connect(&mut self) -> anyhow::Result<()> {
+        self.metrics.reconnects.inc();
         let _ = self.ws.close().await;
         self.ws = WebsocketClient::new(&self.ws.url).await?;
         self.timers = ConnectionTimers::default();
+        self.valid_updates = 0;
+        self.set_ready(self.is_ready());
+        Ok(())
+    }
+
+    pub async fn reconnect(&mut self) -> anyhow::Result<()> {
+        info!("Attempting to reconnect to HyperLiquid, client={}", self.client_no);
+        self.connect().await?;
         self.subscribe().await?;
         info!("Successfully reconnected to HyperLiquid, client={}", self.client_no);
         Ok(())
@@ -125,6 +195,7 @@ impl HypeClient {
 
     pub async fn run(&mut self) -> anyhow::Result<()> {
         info!("Starting HyperLiquid client: {}", self.client_no);
+        self.set_ready(self.is_ready());
         self.subscribe().await?;
         info!("Client: {}, connected to HyperLiquid ", self.client_no);
 
@@ -135,9 +206,82 @@ impl HypeClient {
             ) {
                 break;
             }
+            self.on_connection_lost();
             sleep(Duration::from_millis(50)).await;
             self.reconnect().await?;
         }
         Ok(())
     }
-}
\ No newline at end of file
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::mock_ws::{install_crypto_provider, MockPeer};
+    use tokio::net::TcpListener;
+
+    fn book(time: u64) -> String {
+        serde_json::json!({
+            "channel": "l2Book",
+            "data": {
+                "coin": "HYPE",
+                "time": time,
+                "levels": [[{ "px": "25.0", "sz": "3", "n": 1 }], [{ "px": "25.1", "sz": "2", "n": 1 }]]
+            }
+        }).to_string()
+    }
+
+    #[tokio::test]
+    async fn warm_up_updates_are_passed_on_but_hold_back_readiness() {
+        install_crypto_provider();
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("ws://{}", listener.local_addr().unwrap());
+        let symbols = ["HYPE".to_string()];
+        let (msg_tx, mut msg_rx) = mpsc::channel(16);
+        let (client, mut server) = tokio::join!(
+            HypeClient::new(&url, &symbols, msg_tx, 0),
+            MockPeer::accept(&listener)
+        );
+        let mut client = client.unwrap();
+        client.warmup_updates = 3;
+        let health = Arc::clone(&client.health);
+        tokio::spawn(async move { client.run().await });
+        assert_eq!(server.recv_subscriptions(1).await, ["l2Book"]);
+
+        for time in 1..=4 {
+            server.send_text(&book(time)).await;
+            assert_eq!(msg_rx.recv().await.unwrap().data.time, time);
+            assert_eq!(health.get(&0).is_some_and(|ready| *ready), time >= 3, "after update {}", time);
+        }
+    }
+
+    #[tokio::test]
+    async fn malformed_books_are_skipped_and_counted() {
+        install_crypto_provider();
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("ws://{}", listener.local_addr().unwrap());
+        let symbols = ["HYPE".to_string()];
+        let (msg_tx, mut msg_rx) = mpsc::channel(16);
+        let (client, mut server) = tokio::join!(
+            HypeClient::new(&url, &symbols, msg_tx, 0),
+            MockPeer::accept(&listener)
+        );
+        let mut client = client.unwrap();
+        let metrics = Arc::clone(&client.metrics);
+        tokio::spawn(async move { client.run().await });
+        assert_eq!(server.recv_subscriptions(1).await, ["l2Book"]);
+
+        let malformed = |levels: serde_json::Value| serde_json::json!({
+            "channel": "l2Book",
+            "data": { "coin": "HYPE", "time": 1, "levels": levels }
+        }).to_string();
+        server.send_text(&malformed(serde_json::json!([[{ "px": "abc", "sz": "3", "n": 1 }], []]))).await;
+        server.send_text(&malformed(serde_json::json!([[], []]))).await;
+        server.send_text(&malformed(serde_json::json!([[{ "px": "25.0", "sz": "3", "n": 1 }]]))).await;
+        server.send_text(&book(2)).await;
+
+        // Only the good book gets through, after the three bad ones were counted
+        assert_eq!(msg_rx.recv().await.unwrap().data.time, 2);
+        assert_eq!(metrics.invalid_messages.get(), 3);
+        assert_eq!(metrics.messages.get(), 4);
+    }
+}
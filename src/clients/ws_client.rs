@@ -26,7 +26,7 @@ impl WebsocketClient {
 
     pub async fn send<'h>(&mut self, msg: HypeStreamRequest<'h>) -> anyhow::Result<(), yawc::WebSocketError>{
         let json_string = serde_json::to_string(&msg).unwrap();
-        Ok(self.client.send(FrameView::text(json_string)).await?)
+        self.client.send(FrameView::text(json_string)).await
     }
 
     pub async fn send_ping(&mut self) -> anyhow::Result<()> {
@@ -1,23 +1,66 @@
+use dashmap::DashMap;
 use std::sync::Arc;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
 use tokio::task::JoinSet;
+use tokio::time::sleep;
 use parking_lot::Mutex;
 use tracing::{error, info, warn};
-use crate::{datastructures::tob_cache::{TobCache, TobCacheResult}, model::hl_msgs::TobMsg};
+use crate::{datastructures::tob_cache::{TobCache, TobCacheResult}, events::{event_bus::EventPublisher, types::{SystemEvent, SystemLevelEvent}}, metrics::registry::Metrics, model::hl_msgs::TobMsg, storage::market_data_recorder::MarketDataRecorder, trading::order_book::OrderBook};
 use super::hl_client::HypeClient;
 
+// Each connection carries the same feed and the first copy of an update wins, so more
+// connections cut tail latency and ride out single drops. Every one also costs a
+// socket, a subscription against the exchange's per-IP limits and a duplicate decode
+// of every message, which stops paying off well before MAX_STREAMS.
+pub const MIN_STREAMS: u64 = 1;
+pub const MAX_STREAMS: u64 = 10;
+
+// Delay before the n-th attempt to bring back a fully failed group of connections
+const RECOVERY_INITIAL_BACKOFF: Duration = Duration::from_secs(1);
+const RECOVERY_MAX_BACKOFF: Duration = Duration::from_secs(30);
+
+fn recovery_backoff(attempt: u32) -> Duration {
+    RECOVERY_INITIAL_BACKOFF.saturating_mul(2u32.saturating_pow(attempt)).min(RECOVERY_MAX_BACKOFF)
+}
+
+pub fn validate_stream_count(no_streams: u64) -> Result<(), String> {
+    if !(MIN_STREAMS..=MAX_STREAMS).contains(&no_streams) {
+        return Err(format!(
+            "Market data connection count must be between {} and {}, got {}",
+            MIN_STREAMS, MAX_STREAMS, no_streams
+        ));
+    }
+    Ok(())
+}
+
 pub struct WsManager {
     pub clients: Vec<Option<HypeClient>>,  
     pub msg_rx: Option<tokio::sync::mpsc::Receiver<TobMsg>>,  
     pub tob_cache: Arc<parking_lot::Mutex<TobCache>>,  
+    pub publisher: Option<EventPublisher>,
+    pub recorder: Option<MarketDataRecorder>,
+    pub order_books: Option<Arc<DashMap<String, OrderBook>>>, // Kept current from every distinct update, by symbol
+    pub health: Arc<DashMap<u64, bool>>, // Whether each connection has finished warming up
+    pub max_message_age_ms: u64, // 0 processes updates however old they are
+    pub stale_dropped: Arc<AtomicU64>, // Updates dropped for exceeding max_message_age_ms
 }
 
 impl WsManager {
-    pub async fn new(no_streams: u64, url: &str, symbol: &str, msg_tx: tokio::sync::mpsc::Sender<TobMsg>, 
+    /// Opens `no_streams` connections, each subscribed to the book of every symbol in `symbols`.
+    pub async fn new(no_streams: u64, url: &str, symbols: &[String], msg_tx: tokio::sync::mpsc::Sender<TobMsg>, 
                     msg_rx: tokio::sync::mpsc::Receiver<TobMsg>) -> anyhow::Result<Self> {
-        
+        validate_stream_count(no_streams).map_err(anyhow::Error::msg)?;
+        if symbols.is_empty() {
+            anyhow::bail!("No symbols to stream market data for");
+        }
+
+        let health = Arc::new(DashMap::new());
         let mut clients = Vec::with_capacity(no_streams as usize);
         for client_no in 0..no_streams {
-            let client = HypeClient::new(url, symbol, msg_tx.clone(), client_no).await?;
+            let mut client = HypeClient::new(url, symbols, msg_tx.clone(), client_no).await?;
+            client.health = Arc::clone(&health);
+            health.insert(client_no, client.is_ready());
             clients.push(Some(client));
         }
 
@@ -27,53 +70,188 @@ impl WsManager {
             clients,
             msg_rx: Some(msg_rx),
             tob_cache,
+            publisher: None,
+            recorder: None,
+            order_books: None,
+            health,
+            max_message_age_ms: 0,
+            stale_dropped: Arc::new(AtomicU64::new(0)),
         })
     }
 
+    /// Reports each connection's message and reconnect counts as `market_data_<n>`.
+    pub fn with_metrics(mut self, metrics: &Metrics) -> Self {
+        for client in self.clients.iter_mut().flatten() {
+            client.metrics = metrics.connection(&format!("market_data_{}", client.client_no));
+        }
+        self
+    }
+
+    /// Requires each connection to deliver `updates` valid book updates, after every
+    /// connect, before it is marked ready. Its updates are passed on meanwhile.
+    pub fn with_warmup_updates(mut self, updates: u64) -> Self {
+        for client in self.clients.iter_mut().flatten() {
+            client.warmup_updates = updates;
+            self.health.insert(client.client_no, client.is_ready());
+        }
+        self
+    }
+
+    /// Drops book updates whose exchange timestamp is more than `max_age_ms` old when
+    /// they are processed, such as a backlog after a pause or reconnect. 0 disables.
+    pub fn with_max_message_age(mut self, max_age_ms: u64) -> Self {
+        self.max_message_age_ms = max_age_ms;
+        self
+    }
+
+    pub fn is_ready(&self, client_no: u64) -> bool {
+        self.health.get(&client_no).is_some_and(|ready| *ready)
+    }
+
+    pub fn ready_connections(&self) -> usize {
+        self.health.iter().filter(|entry| *entry.value()).count()
+    }
+
+    /// Publishes each distinct book update as `MarketData`, `MarketDataLost` when
+    /// every connection is down and `MarketDataRestored` once the group reconnects.
+    pub fn with_event_publisher(mut self, publisher: EventPublisher) -> Self {
+        self.publisher = Some(publisher);
+        self
+    }
+
+    /// Records every distinct book update, after duplicates across connections are
+    /// dropped, for later replay.
+    pub fn with_recorder(mut self, recorder: MarketDataRecorder) -> Self {
+        self.recorder = Some(recorder);
+        self
+    }
+
+    /// Applies every distinct book update to its symbol's book in `order_books`,
+    /// creating the book on its first update. Once every connection is down the
+    /// books are marked stale until their next update, see `with_warm_reconnect`.
+    pub fn with_order_books(mut self, order_books: Arc<DashMap<String, OrderBook>>) -> Self {
+        for client in self.clients.iter_mut().flatten() {
+            client.order_books = Some(Arc::clone(&order_books));
+        }
+        self.order_books = Some(order_books);
+        self
+    }
+
+    /// Whether the books keep their last levels, marked stale, while every connection
+    /// is down (the default), or are emptied.
+    pub fn with_warm_reconnect(mut self, warm_reconnect: bool) -> Self {
+        for client in self.clients.iter_mut().flatten() {
+            client.warm_reconnect = warm_reconnect;
+        }
+        self
+    }
+
+    /// Runs the connections until shutdown. Single failures are left to the remaining
+    /// connections; if all of them fail, the group is reconnected together with
+    /// backoff rather than leaving the process without market data.
     pub async fn run(&mut self) -> anyhow::Result<()> {
         info!("Starting ws_manager with {} redundant connections", self.clients.len());
-    
+
+        let msg_rx = self.msg_rx.take()
+            .expect("Message receiver was already taken");
+        let tob_cache = self.tob_cache.clone();
+        let recorder = self.recorder.clone();
+        let publisher = self.publisher.clone();
+        let order_books = self.order_books.clone();
+        let max_age_ms = self.max_message_age_ms;
+        let stale_dropped = Arc::clone(&self.stale_dropped);
+        
+        tokio::spawn(async move {
+            let outputs = MessageOutputs { recorder, publisher, order_books };
+            process_messages(msg_rx, tob_cache, outputs, max_age_ms, stale_dropped).await;
+        });
+
+        loop {
+            let (mut failed, shutdown) = self.run_clients().await;
+            if shutdown || failed.is_empty() {
+                break;
+            }
+
+            error!("All {} market data connections are down", failed.len());
+            self.publish(SystemLevelEvent::MarketDataLost { connections: failed.len() });
+
+            let mut attempt = 0;
+            let connected = loop {
+                let delay = recovery_backoff(attempt);
+                attempt += 1;
+                warn!("Reconnecting all market data connections in {:?} (attempt {})", delay, attempt);
+                sleep(delay).await;
+
+                let mut connected = 0;
+                for client in failed.iter_mut() {
+                    match client.connect().await {
+                        Ok(()) => connected += 1,
+                        Err(e) => warn!("Client {} failed to reconnect: {}", client.client_no, e),
+                    }
+                }
+                if connected > 0 {
+                    break connected;
+                }
+            };
+
+            info!("Market data restored on {} of {} connections", connected, failed.len());
+            self.publish(SystemLevelEvent::MarketDataRestored { connections: connected });
+            for client in failed {
+                let index = client.client_no as usize;
+                if index >= self.clients.len() {
+                    self.clients.resize_with(index + 1, || None);
+                }
+                self.clients[index] = Some(client);
+            }
+        }
+        
+        info!("All hype clients have stopped");
+        Ok(())
+    }
+
+    // Runs every client until all have returned. Returns the clients that failed and
+    // whether any stopped for shutdown; those are put back into `self.clients`
+    async fn run_clients(&mut self) -> (Vec<HypeClient>, bool) {
         let mut client_tasks = JoinSet::new();
     
         for client_index in 0..self.clients.len() {
             if let Some(mut client) = self.clients[client_index].take() {
-                let client_index = client_index; // Create a copy for the closure
-                
                 client_tasks.spawn(async move {
                     let result = client.run().await;
                     (client_index, client, result)
                 });
             }
         }
-        
-        let msg_rx = self.msg_rx.take()
-            .expect("Message receiver was already taken");
-        let tob_cache = self.tob_cache.clone();
-        
-        tokio::spawn(async move {
-            process_messages(msg_rx, tob_cache).await;
-        });
+
+        let mut failed = Vec::new();
+        let mut shutdown = false;
         
         while let Some(result) = client_tasks.join_next().await {
             match result {
                 Ok((index, client, Ok(()))) => {
                     info!("Client {} completed - shutdown received", index);
-                    if index >= self.clients.len() {
-                        self.clients.resize_with(index + 1, || None);
-                    }
-                    
+                    shutdown = true;
                     self.clients[index] = Some(client);
                 },
-                Ok((index, _client, Err(e))) => 
-                    error!("Client {} failed with error: {}", index, e),
+                Ok((index, client, Err(e))) => {
+                    error!("Client {} failed with error: {}", index, e);
+                    failed.push(client);
+                },
                 Err(e) => {
                     error!("Client task join failed with error: {}", e);
                 }
             }
         }
-        
-        info!("All hype clients have stopped");
-        Ok(())
+
+        (failed, shutdown)
+    }
+
+    fn publish(&self, event: SystemLevelEvent) {
+        if let Some(publisher) = &self.publisher {
+            if let Err(e) = publisher.publish(SystemEvent::new_system_event(event)) {
+                error!("Failed to publish market data event: {:?}", e);
+            }
+        }
     }
     
     pub async fn stop(&mut self) -> anyhow::Result<()> {
@@ -92,7 +270,15 @@ impl WsManager {
     }
 }
 
-async fn process_messages(mut msg_rx: tokio::sync::mpsc::Receiver<TobMsg>, tob_cache: Arc<Mutex<TobCache>>) {
+// Where each distinct book update goes once duplicates across connections are dropped
+struct MessageOutputs {
+    recorder: Option<MarketDataRecorder>,
+    publisher: Option<EventPublisher>,
+    order_books: Option<Arc<DashMap<String, OrderBook>>>,
+}
+
+async fn process_messages(mut msg_rx: tokio::sync::mpsc::Receiver<TobMsg>, tob_cache: Arc<Mutex<TobCache>>,
+                          outputs: MessageOutputs, max_age_ms: u64, stale_dropped: Arc<AtomicU64>) {
     info!("Message processor started");
     
     loop {
@@ -104,7 +290,16 @@ async fn process_messages(mut msg_rx: tokio::sync::mpsc::Receiver<TobMsg>, tob_c
             }
         };
         
-        if let Err(e) = process_single_message(&msg, &tob_cache).await {
+        if is_too_old(&msg, max_age_ms, now_millis()) {
+            let dropped = stale_dropped.fetch_add(1, Ordering::Relaxed) + 1;
+            // A backlog drops many at once; don't log every one
+            if dropped.is_power_of_two() {
+                warn!("Dropped stale {} update from {}ms ago ({} so far)", msg.data.coin, now_millis().saturating_sub(msg.data.time), dropped);
+            }
+            continue;
+        }
+
+        if let Err(e) = process_single_message(&msg, &tob_cache, &outputs).await {
             error!("Error processing message: {}", e);
             continue;
         }
@@ -113,7 +308,17 @@ async fn process_messages(mut msg_rx: tokio::sync::mpsc::Receiver<TobMsg>, tob_c
     info!("Message processor has shut down");
 }
 
-async fn process_single_message(msg: &TobMsg, tob_cache: &Arc<Mutex<TobCache>>) -> anyhow::Result<()> {
+/// Whether `msg`'s exchange timestamp is more than `max_age_ms` before `now_ms`.
+/// Timestamps ahead of the local clock count as fresh. Always false when `max_age_ms` is 0.
+pub fn is_too_old(msg: &TobMsg, max_age_ms: u64, now_ms: u64) -> bool {
+    max_age_ms > 0 && now_ms.saturating_sub(msg.data.time) > max_age_ms
+}
+
+fn now_millis() -> u64 {
+    SystemTime::now().duration_since(UNIX_EPOCH).map(|d| d.as_millis() as u64).unwrap_or_default()
+}
+
+async fn process_single_message(msg: &TobMsg, tob_cache: &Arc<Mutex<TobCache>>, outputs: &MessageOutputs) -> anyhow::Result<()> {
     let message_id = msg.data.generate_id();
     
     let tob = match  msg.data.top_of_book() {
@@ -128,6 +333,23 @@ async fn process_single_message(msg: &TobMsg, tob_cache: &Arc<Mutex<TobCache>>)
         guard.update(message_id.clone(), tob)
     };
     
+    if matches!(update_result, TobCacheResult::Added | TobCacheResult::AddedWithEviction(_)) {
+        if let Some(recorder) = &outputs.recorder {
+            recorder.record(msg);
+        }
+        // Written before publishing so consumers woken by the event see this update
+        if let Some(order_books) = &outputs.order_books {
+            let symbol = &msg.data.coin;
+            let mut book = order_books.entry(symbol.clone()).or_insert_with(|| OrderBook::new(symbol.clone()));
+            // A rejected snapshot leaves the book as it was; update_from_tob has logged why
+            let _ = book.update_from_tob(&msg.data);
+        }
+        if let Some(publisher) = &outputs.publisher {
+            publisher.publish(SystemEvent::new_market_data(msg.data.coin.clone(), msg.clone()))
+                .map_err(|e| anyhow::anyhow!("Failed to publish market data: {:?}", e))?;
+        }
+    }
+    
     match update_result {
         TobCacheResult::Added => {
             if let Some(top) = msg.data.top_of_book() {
@@ -149,4 +371,203 @@ async fn process_single_message(msg: &TobMsg, tob_cache: &Arc<Mutex<TobCache>>)
     }
     
     Ok(())
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::events::event_bus::{EventBus, EventBusConfig};
+    use crate::events::types::EventPriority;
+    use crate::utils::mock_ws::{install_crypto_provider, MockPeer};
+    use rust_decimal_macros::dec;
+    use tokio::net::TcpListener;
+
+    fn book_msg(coin: &str, time: u64, bid: &str, ask: &str) -> TobMsg {
+        serde_json::from_value(serde_json::json!({
+            "channel": "l2Book",
+            "data": {
+                "coin": coin,
+                "time": time,
+                "levels": [[{"px": bid, "sz": "10", "n": 1}], [{"px": ask, "sz": "5", "n": 1}]],
+            },
+        })).unwrap()
+    }
+
+    #[tokio::test]
+    async fn updates_for_two_symbols_maintain_both_books() {
+        let order_books = Arc::new(DashMap::new());
+        let outputs = MessageOutputs { recorder: None, publisher: None, order_books: Some(Arc::clone(&order_books)) };
+        let (msg_tx, msg_rx) = tokio::sync::mpsc::channel(16);
+
+        msg_tx.send(book_msg("HYPE", 1, "25.0", "25.1")).await.unwrap();
+        msg_tx.send(book_msg("BTC", 1, "60000", "60001")).await.unwrap();
+        msg_tx.send(book_msg("HYPE", 2, "25.2", "25.3")).await.unwrap();
+        // A duplicate from a redundant connection is applied once
+        msg_tx.send(book_msg("HYPE", 2, "25.2", "25.3")).await.unwrap();
+        drop(msg_tx);
+        process_messages(msg_rx, Arc::new(Mutex::new(TobCache::new())), outputs, 0, Arc::new(AtomicU64::new(0))).await;
+
+        assert_eq!(order_books.len(), 2);
+        let hype = order_books.get("HYPE").unwrap();
+        assert_eq!(hype.best_bid(), Some((dec!(25.2), dec!(10))));
+        assert_eq!(hype.best_ask(), Some((dec!(25.3), dec!(5))));
+        assert_eq!(hype.sequence, 2);
+        let btc = order_books.get("BTC").unwrap();
+        assert_eq!(btc.mid_price(), Some(dec!(60000.5)));
+        assert_eq!(btc.sequence, 1);
+    }
+
+    #[tokio::test]
+    async fn identical_updates_for_different_symbols_are_not_duplicates() {
+        let order_books = Arc::new(DashMap::new());
+        let outputs = MessageOutputs { recorder: None, publisher: None, order_books: Some(Arc::clone(&order_books)) };
+        let (msg_tx, msg_rx) = tokio::sync::mpsc::channel(16);
+
+        msg_tx.send(book_msg("PURR", 7, "0.2", "0.3")).await.unwrap();
+        msg_tx.send(book_msg("PEPE", 7, "0.2", "0.3")).await.unwrap();
+        drop(msg_tx);
+        process_messages(msg_rx, Arc::new(Mutex::new(TobCache::new())), outputs, 0, Arc::new(AtomicU64::new(0))).await;
+
+        assert!(order_books.contains_key("PURR"));
+        assert!(order_books.contains_key("PEPE"));
+    }
+
+    #[tokio::test]
+    async fn stale_updates_do_not_reach_the_books() {
+        let order_books = Arc::new(DashMap::new());
+        let outputs = MessageOutputs { recorder: None, publisher: None, order_books: Some(Arc::clone(&order_books)) };
+        let (msg_tx, msg_rx) = tokio::sync::mpsc::channel(16);
+        let stale_dropped = Arc::new(AtomicU64::new(0));
+
+        msg_tx.send(book_msg("HYPE", 1, "25.0", "25.1")).await.unwrap();
+        drop(msg_tx);
+        process_messages(msg_rx, Arc::new(Mutex::new(TobCache::new())), outputs, 1000, Arc::clone(&stale_dropped)).await;
+
+        assert!(order_books.is_empty());
+        assert_eq!(stale_dropped.load(Ordering::Relaxed), 1);
+    }
+
+    async fn wait_for(condition: impl Fn() -> bool) {
+        tokio::time::timeout(Duration::from_secs(5), async {
+            while !condition() {
+                sleep(Duration::from_millis(5)).await;
+            }
+        }).await.expect("condition never held");
+    }
+
+    async fn send_book(peer: &mut MockPeer, time: u64, bid: &str) {
+        peer.send_text(&serde_json::to_string(&book_msg("HYPE", time, bid, "30")).unwrap()).await;
+    }
+
+    // A running single-connection manager whose HYPE book has had one update
+    async fn live_manager(listener: &TcpListener, warm_reconnect: bool) -> (Arc<DashMap<String, OrderBook>>, MockPeer) {
+        install_crypto_provider();
+        let url = format!("ws://{}", listener.local_addr().unwrap());
+        let symbols = ["HYPE".to_string()];
+        let (msg_tx, msg_rx) = tokio::sync::mpsc::channel(16);
+        let (manager, mut peer) = tokio::join!(WsManager::new(1, &url, &symbols, msg_tx, msg_rx), MockPeer::accept(listener));
+
+        let order_books = Arc::new(DashMap::new());
+        let mut manager = manager.unwrap()
+            .with_order_books(Arc::clone(&order_books))
+            .with_warm_reconnect(warm_reconnect);
+        tokio::spawn(async move { manager.run().await });
+
+        assert_eq!(peer.recv_subscriptions(1).await, ["l2Book"]);
+        send_book(&mut peer, 1, "25").await;
+        wait_for(|| order_books.contains_key("HYPE")).await;
+        (order_books, peer)
+    }
+
+    #[tokio::test]
+    async fn a_reconnect_keeps_the_book_marked_stale_until_the_next_snapshot() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let (order_books, first) = live_manager(&listener, true).await;
+
+        drop(first);
+        let mut second = MockPeer::accept(&listener).await;
+        {
+            let book = order_books.get("HYPE").unwrap();
+            assert!(book.is_stale());
+            assert_eq!(book.best_bid(), Some((dec!(25), dec!(10))));
+        }
+
+        assert_eq!(second.recv_subscriptions(1).await, ["l2Book"]);
+        send_book(&mut second, 2, "26").await;
+        wait_for(|| !order_books.get("HYPE").unwrap().is_stale()).await;
+        assert_eq!(order_books.get("HYPE").unwrap().best_bid(), Some((dec!(26), dec!(10))));
+    }
+
+    #[tokio::test]
+    async fn without_warm_reconnect_a_drop_empties_the_book() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let (order_books, first) = live_manager(&listener, false).await;
+
+        drop(first);
+        let _second = MockPeer::accept(&listener).await;
+        let book = order_books.get("HYPE").unwrap();
+        assert_eq!(book.best_bid(), None);
+        assert!(!book.is_stale());
+    }
+
+    #[tokio::test]
+    async fn a_connection_count_outside_the_range_is_rejected_before_connecting() {
+        install_crypto_provider();
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("ws://{}", listener.local_addr().unwrap());
+        let symbols = ["HYPE".to_string()];
+
+        for no_streams in [0, MAX_STREAMS + 1] {
+            let (msg_tx, msg_rx) = tokio::sync::mpsc::channel(16);
+            let Err(err) = WsManager::new(no_streams, &url, &symbols, msg_tx, msg_rx).await else {
+                panic!("{} connections were accepted", no_streams);
+            };
+            assert_eq!(err.to_string(), format!("Market data connection count must be between 1 and 10, got {}", no_streams));
+        }
+
+        let (msg_tx, msg_rx) = tokio::sync::mpsc::channel(16);
+        let (manager, _first, _second, _third) = tokio::join!(
+            WsManager::new(3, &url, &symbols, msg_tx, msg_rx),
+            MockPeer::accept(&listener),
+            MockPeer::accept(&listener),
+            MockPeer::accept(&listener)
+        );
+        assert_eq!(manager.unwrap().clients.len(), 3);
+    }
+
+    #[tokio::test]
+    async fn losing_every_connection_publishes_market_data_lost() {
+        install_crypto_provider();
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("ws://{}", listener.local_addr().unwrap());
+        let symbols = ["HYPE".to_string()];
+        let (msg_tx, msg_rx) = tokio::sync::mpsc::channel(16);
+        let event_bus = EventBus::new(EventBusConfig::default());
+        event_bus.start_processing();
+        let system_events = event_bus.subscribe("system");
+
+        let (manager, first, second) = tokio::join!(
+            WsManager::new(2, &url, &symbols, msg_tx, msg_rx),
+            MockPeer::accept(&listener),
+            MockPeer::accept(&listener)
+        );
+        let mut manager = manager.unwrap().with_event_publisher(event_bus.get_publisher());
+        tokio::spawn(async move { manager.run().await });
+
+        // Nothing is listening when the clients try to reconnect
+        drop(listener);
+        drop((first, second));
+
+        let event = tokio::time::timeout(Duration::from_secs(5), async {
+            loop {
+                if let Ok(event) = system_events.try_recv() {
+                    return event;
+                }
+                sleep(Duration::from_millis(5)).await;
+            }
+        }).await.expect("no system event");
+        assert!(matches!(event, SystemEvent::System { event: SystemLevelEvent::MarketDataLost { connections: 2 }, .. }), "{:?}", event);
+        assert_eq!(event.priority(), EventPriority::Critical);
+        event_bus.stop_processing(Duration::from_secs(1));
+    }
+}
@@ -1,4 +1,4 @@
-use crate::api::types::ApiConfig;
+use crate::api::types::{ApiConfig, RateLimitConfig};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 
@@ -21,6 +21,22 @@ impl ApiConfigTemplate {
                 timeout_ms: 10000,
                 max_retries: 5,
                 retry_delay_ms: 2000,
+                max_retry_delay_ms: 30000,
+                max_retry_queue: 1000,
+                heartbeat_interval_ms: 30000,
+                heartbeat_timeout_ms: 60000,
+                heartbeat_warning_ms: 45000,
+                rate_limit: RateLimitConfig::default(),
+                warm_reconnect: true,
+                fee_refresh_interval_secs: 3600,
+                funding_refresh_interval_secs: 60,
+                market_data_connections: 3,
+                market_data_warmup_updates: 2,
+                max_market_data_age_ms: 5000,
+                adopt_unknown_orders: false,
+                paper_trading: false,
+                tick_to_trade_budget_ms: 250,
+                shutdown_cancel_timeout_secs: 10,
             },
             environment: "development".to_string(),
         }
@@ -36,6 +52,22 @@ impl ApiConfigTemplate {
                 timeout_ms: 5000,
                 max_retries: 3,
                 retry_delay_ms: 1000,
+                max_retry_delay_ms: 30000,
+                max_retry_queue: 1000,
+                heartbeat_interval_ms: 30000,
+                heartbeat_timeout_ms: 60000,
+                heartbeat_warning_ms: 45000,
+                rate_limit: RateLimitConfig::default(),
+                warm_reconnect: true,
+                fee_refresh_interval_secs: 3600,
+                funding_refresh_interval_secs: 60,
+                market_data_connections: 3,
+                market_data_warmup_updates: 2,
+                max_market_data_age_ms: 5000,
+                adopt_unknown_orders: false,
+                paper_trading: false,
+                tick_to_trade_budget_ms: 250,
+                shutdown_cancel_timeout_secs: 10,
             },
             environment: "staging".to_string(),
         }
@@ -51,6 +83,22 @@ impl ApiConfigTemplate {
                 timeout_ms: 3000,
                 max_retries: 2,
                 retry_delay_ms: 500,
+                max_retry_delay_ms: 30000,
+                max_retry_queue: 1000,
+                heartbeat_interval_ms: 30000,
+                heartbeat_timeout_ms: 60000,
+                heartbeat_warning_ms: 45000,
+                rate_limit: RateLimitConfig::default(),
+                warm_reconnect: true,
+                fee_refresh_interval_secs: 3600,
+                funding_refresh_interval_secs: 60,
+                market_data_connections: 3,
+                market_data_warmup_updates: 2,
+                max_market_data_age_ms: 5000,
+                adopt_unknown_orders: false,
+                paper_trading: false,
+                tick_to_trade_budget_ms: 250,
+                shutdown_cancel_timeout_secs: 10,
             },
             environment: "production".to_string(),
         }
@@ -1,17 +1,29 @@
 use crate::api::types::ApiConfig;
-use crate::strategies::market_making::MarketMakingConfig;
+use crate::api::ws_trading::validate_heartbeat;
+use crate::clients::ws_manager::validate_stream_count;
+use crate::config::secrets::SecretsConfig;
+use crate::events::alerts::AlertConfig;
+use crate::metrics::registry::MetricsConfig;
+use crate::storage::market_data_recorder::RecordingConfig;
+use crate::storage::session_store::StorageConfig;
+use crate::strategies::market_making::{MarketMakingConfig, MarketMakingStrategy};
+use crate::trading::trigger_orders::TriggerConfig;
 use crate::trading::types::RiskLimits;
+use crate::ui::components::price_display::PrecisionMap;
+use crate::ui::panels::chart_panel::DEFAULT_CHART_POINTS;
+use crate::ui::panels::depth_panel::DEFAULT_DEPTH_WINDOW_BPS;
 use anyhow::Result;
 use crossbeam_channel::{Sender, Receiver, unbounded};
 use dashmap::DashMap;
-use parking_lot::RwLock;
+use notify::{RecommendedWatcher, RecursiveMode, Watcher};
+use parking_lot::{Mutex, RwLock};
 use rust_decimal::Decimal;
 use serde::{Deserialize, Serialize};
 use std::sync::Arc;
-use std::collections::HashMap;
-use std::path::Path;
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::path::{Path, PathBuf};
 use std::time::Duration;
-use tracing::{error, info, warn, debug};
+use tracing::{error, info, debug, warn};
 use uuid::Uuid;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -25,6 +37,18 @@ pub struct BotConfig {
     pub ui_config: UiConfig,
     pub logging_config: LoggingConfig,
     pub performance_config: PerformanceConfig,
+    #[serde(default)]
+    pub alert_config: AlertConfig,
+    #[serde(default)]
+    pub storage_config: StorageConfig,
+    #[serde(default)]
+    pub metrics_config: MetricsConfig,
+    #[serde(default)]
+    pub recording_config: RecordingConfig,
+    #[serde(default)]
+    pub secrets: SecretsConfig,
+    #[serde(default)]
+    pub hot_reload: bool, // Apply edits to the config file while the bot runs
     pub created_at: chrono::DateTime<chrono::Utc>,
     pub updated_at: chrono::DateTime<chrono::Utc>,
 }
@@ -54,13 +78,15 @@ pub enum StrategyType {
     MeanReversion,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct RiskConfig {
     pub global_risk_limits: RiskLimits,
     pub position_limits: HashMap<String, PositionLimitConfig>,
     pub exposure_limits: HashMap<String, ExposureLimitConfig>,
     pub volatility_limits: HashMap<String, VolatilityLimitConfig>,
     pub circuit_breakers: Vec<CircuitBreakerConfig>,
+    #[serde(default)]
+    pub trigger_orders: HashMap<String, TriggerConfig>, // Stop-loss / take-profit levels and loss limits per symbol
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -99,6 +125,12 @@ pub struct UiConfig {
     pub refresh_rate_ms: u64,
     pub show_debug_info: bool,
     pub panels: HashMap<String, PanelConfig>,
+    #[serde(default)]
+    pub symbol_precision: PrecisionMap, // Display decimals per symbol, defaults to 4/4 when missing
+    #[serde(default = "default_chart_points")]
+    pub chart_points: usize, // Book samples the price chart keeps
+    #[serde(default = "default_depth_window_bps")]
+    pub depth_window_bps: u32, // Depth chart range either side of the mid
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -135,8 +167,12 @@ pub struct ConfigManager {
     pub file_path: Option<String>,
     pub auto_save: bool,
     pub save_interval: Duration,
+    file_watcher: Arc<Mutex<Option<RecommendedWatcher>>>,
 }
 
+// Editors often write a file in several steps; wait for them to settle before reloading
+const RELOAD_DEBOUNCE: Duration = Duration::from_millis(250);
+
 #[derive(Debug, Clone)]
 pub enum ConfigEvent {
     ConfigLoaded {
@@ -179,21 +215,59 @@ impl Default for BotConfig {
             ui_config: UiConfig::default(),
             logging_config: LoggingConfig::default(),
             performance_config: PerformanceConfig::default(),
+            alert_config: AlertConfig::default(),
+            storage_config: StorageConfig::default(),
+            metrics_config: MetricsConfig::default(),
+            recording_config: RecordingConfig::default(),
+            secrets: SecretsConfig::default(),
+            hot_reload: false,
             created_at: chrono::Utc::now(),
             updated_at: chrono::Utc::now(),
         }
     }
 }
 
-impl Default for RiskConfig {
-    fn default() -> Self {
-        Self {
-            global_risk_limits: RiskLimits::default(),
-            position_limits: HashMap::new(),
-            exposure_limits: HashMap::new(),
-            volatility_limits: HashMap::new(),
-            circuit_breakers: Vec::new(),
+impl BotConfig {
+    /// Parsed settings of every configured market making strategy, by strategy name.
+    /// Other strategy types have no implementation yet and are skipped with a warning.
+    /// An empty result is not an error; the bot then only monitors.
+    pub fn market_making_configs(&self) -> Result<BTreeMap<String, MarketMakingConfig>, String> {
+        let mut configs = BTreeMap::new();
+        for (name, strategy) in &self.strategies {
+            if !matches!(strategy.strategy_type, StrategyType::MarketMaking) {
+                warn!("Strategy {} has unsupported type {:?}, skipping", name, strategy.strategy_type);
+                continue;
+            }
+
+            configs.insert(name.clone(), strategy.market_making_config()?);
         }
+        Ok(configs)
+    }
+
+    /// Symbols of the enabled strategies, each once and sorted: what market data is streamed for.
+    pub fn market_data_symbols(&self) -> Vec<String> {
+        let symbols: BTreeSet<String> = self.strategies.values()
+            .filter(|strategy| strategy.enabled)
+            .map(|strategy| strategy.symbol.clone())
+            .collect();
+        symbols.into_iter().collect()
+    }
+}
+
+impl StrategyConfig {
+    /// Settings for a market making strategy, with `enabled` taken from this config.
+    pub fn market_making_config(&self) -> Result<MarketMakingConfig, String> {
+        let mut config: MarketMakingConfig = serde_json::from_value(self.config.clone())
+            .map_err(|e| format!("Failed to parse market making config for {}: {}", self.name, e))?;
+        config.base_config.enabled = self.enabled;
+        Ok(config)
+    }
+
+    /// Retunes `strategy`, the running instance of this config, from its next quote
+    /// on. Whether it is enabled is left to the caller.
+    pub fn apply_to(&self, strategy: &mut MarketMakingStrategy) -> Result<(), String> {
+        strategy.apply_config(self.market_making_config()?)
+            .map_err(|errors| format!("Strategy {} config not applied: {}", self.name, errors.join("; ")))
     }
 }
 
@@ -206,10 +280,21 @@ impl Default for UiConfig {
             refresh_rate_ms: 100,
             show_debug_info: false,
             panels: HashMap::new(),
+            symbol_precision: PrecisionMap::new(),
+            chart_points: default_chart_points(),
+            depth_window_bps: default_depth_window_bps(),
         }
     }
 }
 
+fn default_chart_points() -> usize {
+    DEFAULT_CHART_POINTS
+}
+
+fn default_depth_window_bps() -> u32 {
+    DEFAULT_DEPTH_WINDOW_BPS
+}
+
 impl Default for LoggingConfig {
     fn default() -> Self {
         Self {
@@ -235,6 +320,176 @@ impl Default for PerformanceConfig {
     }
 }
 
+/// On-disk config format, chosen from the file extension. Files without an
+/// extension are TOML.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ConfigFormat {
+    Toml,
+    Json,
+    Yaml,
+}
+
+impl ConfigFormat {
+    pub fn from_path(path: &Path) -> Result<Self, String> {
+        let Some(extension) = path.extension() else {
+            return Ok(ConfigFormat::Toml);
+        };
+
+        match extension.to_string_lossy().to_ascii_lowercase().as_str() {
+            "toml" => Ok(ConfigFormat::Toml),
+            "json" => Ok(ConfigFormat::Json),
+            "yaml" | "yml" => Ok(ConfigFormat::Yaml),
+            other => Err(format!(
+                "Unsupported config file extension '.{}' (expected .toml, .json, .yaml or .yml)", other
+            )),
+        }
+    }
+
+    pub fn parse(&self, content: &str) -> Result<BotConfig, String> {
+        match self {
+            ConfigFormat::Toml => toml::from_str(content).map_err(|e| e.to_string()),
+            ConfigFormat::Json => serde_json::from_str(content).map_err(|e| e.to_string()),
+            ConfigFormat::Yaml => serde_yaml::from_str(content).map_err(|e| e.to_string()),
+        }
+        .map_err(|e| format!("Failed to parse {:?} config: {}", self, e))
+    }
+
+    pub fn serialize(&self, config: &BotConfig) -> Result<String, String> {
+        match self {
+            ConfigFormat::Toml => {
+                // TOML has no null, so options left unset in a strategy's config are left out
+                let mut config = config.clone();
+                for strategy in config.strategies.values_mut() {
+                    strip_nulls(&mut strategy.config);
+                }
+                toml::to_string_pretty(&config).map_err(|e| e.to_string())
+            }
+            ConfigFormat::Json => serde_json::to_string_pretty(config).map_err(|e| e.to_string()),
+            ConfigFormat::Yaml => serde_yaml::to_string(config).map_err(|e| e.to_string()),
+        }
+        .map_err(|e| format!("Failed to serialize {:?} config: {}", self, e))
+    }
+}
+
+fn strip_nulls(value: &mut serde_json::Value) {
+    match value {
+        serde_json::Value::Object(map) => {
+            map.retain(|_, value| !value.is_null());
+            map.values_mut().for_each(strip_nulls);
+        }
+        serde_json::Value::Array(values) => values.iter_mut().for_each(strip_nulls),
+        _ => {}
+    }
+}
+
+/// Replaces each `${NAME}` in `content` with the value of the environment variable
+/// `NAME`, so secrets and endpoints can stay out of the config file. Errors name the
+/// first unset variable. Values are inserted verbatim, and saving the config writes
+/// them back expanded.
+pub fn expand_env_vars(content: &str) -> Result<String, String> {
+    let mut expanded = String::with_capacity(content.len());
+    let mut rest = content;
+
+    while let Some(start) = rest.find("${") {
+        expanded.push_str(&rest[..start]);
+        let after = &rest[start + 2..];
+        let end = after.find('}')
+            .ok_or_else(|| format!("Unterminated ${{ in config: {}", &rest[start..rest.len().min(start + 40)]))?;
+
+        let name = &after[..end];
+        if name.is_empty() {
+            return Err("Empty ${} in config".to_string());
+        }
+        let value = std::env::var(name)
+            .map_err(|_| format!("Environment variable {} referenced in config is not set", name))?;
+        expanded.push_str(&value);
+        rest = &after[end + 1..];
+    }
+
+    expanded.push_str(rest);
+    Ok(expanded)
+}
+
+/// Environment variables starting with this override config values: the rest of the
+/// name is the path to the value, `__` separated, so `HLBOT__API_CONFIG__BASE_URL`
+/// sets `api_config.base_url`.
+pub const ENV_OVERRIDE_PREFIX: &str = "HLBOT__";
+
+/// Applies `HLBOT__`-prefixed overrides from `vars` on top of `config`, which wins
+/// over the file and its `${NAME}` interpolations. Path segments match existing keys
+/// ignoring case. A value takes the type of the one it replaces; a new key takes
+/// the value as JSON if it parses, else as a string.
+pub fn apply_env_overrides(config: BotConfig, vars: impl IntoIterator<Item = (String, String)>) -> Result<BotConfig, String> {
+    let mut overrides: Vec<(String, String)> = vars.into_iter()
+        .filter(|(name, _)| name.starts_with(ENV_OVERRIDE_PREFIX))
+        .collect();
+    if overrides.is_empty() {
+        return Ok(config);
+    }
+    overrides.sort();
+
+    let mut root = serde_json::to_value(&config)
+        .map_err(|e| format!("Failed to serialize config for overrides: {}", e))?;
+    for (name, raw) in &overrides {
+        let path: Vec<&str> = name[ENV_OVERRIDE_PREFIX.len()..].split("__").collect();
+        if path.iter().any(|segment| segment.is_empty()) {
+            return Err(format!("Invalid config override {}", name));
+        }
+        set_override(&mut root, &path, raw).map_err(|e| format!("Config override {}: {}", name, e))?;
+        info!("Config {} overridden from the environment", path.join(".").to_ascii_lowercase());
+    }
+
+    let mut overridden: BotConfig = serde_json::from_value(root)
+        .map_err(|e| format!("Config overrides from the environment are invalid: {}", e))?;
+    // Never serialized, so the round trip drops it unless it was overridden itself
+    if overridden.secrets.private_key.is_none() {
+        overridden.secrets.private_key = config.secrets.private_key;
+    }
+    Ok(overridden)
+}
+
+fn set_override(root: &mut serde_json::Value, path: &[&str], raw: &str) -> Result<(), String> {
+    use serde_json::Value;
+
+    let (leaf, sections) = path.split_last().ok_or("empty path")?;
+    let mut node = root;
+    for (depth, segment) in sections.iter().enumerate() {
+        let map = as_section(node, &path[..depth])?;
+        let key = find_key(map, segment, depth);
+        node = map.entry(key).or_insert_with(|| Value::Object(Default::default()));
+    }
+
+    let map = as_section(node, sections)?;
+    let key = find_key(map, leaf, sections.len());
+    let value = match map.get(&key) {
+        Some(Value::String(_)) => Value::String(raw.to_string()),
+        Some(Value::Bool(_)) => raw.parse().map(Value::Bool)
+            .map_err(|_| format!("expected true or false, got {}", raw))?,
+        Some(Value::Number(_)) => serde_json::from_str::<serde_json::Number>(raw).map(Value::Number)
+            .map_err(|_| format!("expected a number, got {}", raw))?,
+        _ => serde_json::from_str(raw).unwrap_or_else(|_| Value::String(raw.to_string())),
+    };
+    map.insert(key, value);
+    Ok(())
+}
+
+fn as_section<'a>(node: &'a mut serde_json::Value, path: &[&str]) -> Result<&'a mut serde_json::Map<String, serde_json::Value>, String> {
+    node.as_object_mut()
+        .ok_or_else(|| format!("{} is not a section", path.join(".").to_ascii_lowercase()))
+}
+
+fn find_key(map: &serde_json::Map<String, serde_json::Value>, segment: &str, depth: usize) -> String {
+    match map.keys().find(|key| key.eq_ignore_ascii_case(segment)) {
+        Some(key) => key.clone(),
+        None => {
+            if depth == 0 {
+                warn!("Config override names unknown section {}", segment.to_ascii_lowercase());
+            }
+            segment.to_ascii_lowercase()
+        }
+    }
+}
+
 impl ConfigManager {
     pub fn new() -> (Self, Receiver<ConfigEvent>) {
         let (tx, rx) = unbounded();
@@ -246,6 +501,7 @@ impl ConfigManager {
             file_path: None,
             auto_save: true,
             save_interval: Duration::from_secs(30),
+            file_watcher: Arc::new(Mutex::new(None)),
         };
         
         (manager, rx)
@@ -269,11 +525,7 @@ impl ConfigManager {
             return Err(format!("Config file does not exist: {}", path_str));
         }
 
-        let content = std::fs::read_to_string(path.as_ref())
-            .map_err(|e| format!("Failed to read config file: {}", e))?;
-
-        let config: BotConfig = toml::from_str(&content)
-            .map_err(|e| format!("Failed to parse config file: {}", e))?;
+        let config = Self::read_config_file(path.as_ref())?;
 
         {
             let mut current_config = self.config.write();
@@ -292,9 +544,9 @@ impl ConfigManager {
     pub async fn save_to_file<P: AsRef<Path>>(&self, path: P) -> Result<(), String> {
         let path_str = path.as_ref().to_string_lossy().to_string();
         
+        let format = ConfigFormat::from_path(path.as_ref())?;
         let config = self.config.read().clone();
-        let content = toml::to_string_pretty(&config)
-            .map_err(|e| format!("Failed to serialize config: {}", e))?;
+        let content = format.serialize(&config)?;
 
         std::fs::write(path.as_ref(), content)
             .map_err(|e| format!("Failed to write config file: {}", e))?;
@@ -443,9 +695,9 @@ impl ConfigManager {
     }
 
     async fn save_config_to_file(config: &Arc<RwLock<BotConfig>>, path: &str) -> Result<(), String> {
+        let format = ConfigFormat::from_path(Path::new(path))?;
         let config = config.read().clone();
-        let content = toml::to_string_pretty(&config)
-            .map_err(|e| format!("Failed to serialize config: {}", e))?;
+        let content = format.serialize(&config)?;
 
         std::fs::write(path, content)
             .map_err(|e| format!("Failed to write config file: {}", e))?;
@@ -453,13 +705,160 @@ impl ConfigManager {
         Ok(())
     }
 
+    /// Reloads the config whenever the file set with `with_file_path` changes on disk.
+    ///
+    /// A reloaded config only replaces the current one if it parses and passes
+    /// `validate`; otherwise the current config is kept and a `ConfigError` is sent.
+    /// Watching stops when the manager and all its clones are dropped.
+    pub fn watch_file(&self) -> Result<(), String> {
+        let path = self.file_path.clone()
+            .ok_or_else(|| "No config file path set".to_string())?;
+        let path = PathBuf::from(path);
+        let file_name = path.file_name()
+            .ok_or_else(|| format!("Invalid config file path: {}", path.display()))?
+            .to_os_string();
+
+        // Watch the directory rather than the file so saves that replace the file are seen
+        let dir = match path.parent() {
+            Some(dir) if !dir.as_os_str().is_empty() => dir.to_path_buf(),
+            _ => PathBuf::from("."),
+        };
+
+        let (changes_tx, changes_rx) = unbounded::<()>();
+        let mut watcher = notify::recommended_watcher(move |res: notify::Result<notify::Event>| {
+            match res {
+                Ok(event) => {
+                    let touches_file = event.paths.iter()
+                        .any(|p| p.file_name() == Some(file_name.as_os_str()));
+                    if touches_file && (event.kind.is_create() || event.kind.is_modify()) {
+                        let _ = changes_tx.send(());
+                    }
+                }
+                Err(e) => warn!("Config file watch error: {}", e),
+            }
+        }).map_err(|e| format!("Failed to create config file watcher: {}", e))?;
+
+        watcher.watch(&dir, RecursiveMode::NonRecursive)
+            .map_err(|e| format!("Failed to watch {}: {}", dir.display(), e))?;
+
+        let config = Arc::clone(&self.config);
+        let config_events_tx = self.config_events_tx.clone();
+        std::thread::spawn(move || {
+            // Ends once the watcher, and with it the sender, is dropped
+            while changes_rx.recv().is_ok() {
+                while changes_rx.recv_timeout(RELOAD_DEBOUNCE).is_ok() {}
+                Self::reload_from_file(&config, &config_events_tx, &path);
+            }
+        });
+
+        *self.file_watcher.lock() = Some(watcher);
+        info!("Watching config file for changes: {}", self.file_path.as_deref().unwrap_or_default());
+        Ok(())
+    }
+
+    fn reload_from_file(config: &Arc<RwLock<BotConfig>>, config_events_tx: &Sender<ConfigEvent>, path: &Path) {
+        let result = Self::read_config_file(path).and_then(|new_config| {
+            Self::validate(&new_config)?;
+            Ok(new_config)
+        });
+
+        match result {
+            Ok(new_config) => {
+                // Our own saves (auto-save included) also trigger the watcher
+                let unchanged = serde_json::to_value(&*config.read()).ok() == serde_json::to_value(&new_config).ok();
+                if unchanged {
+                    debug!("Config file changed but contents match the current config");
+                    return;
+                }
+
+                let config_id = new_config.bot_id.clone();
+                let changes = Self::section_changes(&config.read(), &new_config);
+                *config.write() = new_config;
+
+                let _ = config_events_tx.send(ConfigEvent::ConfigLoaded {
+                    config_id,
+                    timestamp: chrono::Utc::now(),
+                });
+                info!("Config reloaded from file: {} ({} sections changed)", path.display(), changes.len());
+                for event in changes {
+                    let _ = config_events_tx.send(event);
+                }
+            }
+            Err(e) => {
+                error!("Config reload rejected, keeping current config: {}", e);
+                let _ = config_events_tx.send(ConfigEvent::ConfigError {
+                    error: format!("Reload failed: {}", e),
+                    timestamp: chrono::Utc::now(),
+                });
+            }
+        }
+    }
+
+    // One ConfigChanged per top-level section that differs, named as the update_*
+    // methods name them, and one per added, changed or removed strategy. A removed
+    // strategy's new value is null.
+    fn section_changes(old: &BotConfig, new: &BotConfig) -> Vec<ConfigEvent> {
+        let timestamp = chrono::Utc::now();
+        let changed = |section: &str, key: &str, old_value: serde_json::Value, new_value: serde_json::Value| ConfigEvent::ConfigChanged {
+            section: section.to_string(),
+            key: key.to_string(),
+            old_value,
+            new_value,
+            timestamp,
+        };
+
+        let mut events = Vec::new();
+        let names: BTreeSet<&String> = old.strategies.keys().chain(new.strategies.keys()).collect();
+        for name in names {
+            let old_value = old.strategies.get(name).map(|s| serde_json::to_value(s).unwrap_or_default()).unwrap_or_default();
+            let new_value = new.strategies.get(name).map(|s| serde_json::to_value(s).unwrap_or_default()).unwrap_or_default();
+            if old_value != new_value {
+                events.push(changed("strategies", name, old_value, new_value));
+            }
+        }
+
+        let (serde_json::Value::Object(old_sections), serde_json::Value::Object(new_sections)) =
+            (serde_json::to_value(old).unwrap_or_default(), serde_json::to_value(new).unwrap_or_default()) else {
+            return events;
+        };
+        for (key, new_value) in new_sections {
+            if matches!(key.as_str(), "strategies" | "created_at" | "updated_at") {
+                continue;
+            }
+            let old_value = old_sections.get(&key).cloned().unwrap_or_default();
+            if old_value == new_value {
+                continue;
+            }
+            let section = match key.as_str() {
+                "risk_config" => "risk",
+                "api_config" => "api",
+                _ => "global",
+            };
+            events.push(changed(section, &key, old_value, new_value));
+        }
+        events
+    }
+
+    fn read_config_file(path: &Path) -> Result<BotConfig, String> {
+        let format = ConfigFormat::from_path(path)?;
+        let content = std::fs::read_to_string(path)
+            .map_err(|e| format!("Failed to read config file: {}", e))?;
+
+        let config = format.parse(&expand_env_vars(&content)?)?;
+        apply_env_overrides(config, std::env::vars())
+    }
+
     pub fn validate_config(&self) -> Result<(), String> {
-        let config = self.config.read();
-        
+        Self::validate(&self.config.read())
+    }
+
+    pub fn validate(config: &BotConfig) -> Result<(), String> {
         // Validate API config
         if config.api_config.base_url.is_empty() {
             return Err("API base URL cannot be empty".to_string());
         }
+        validate_stream_count(config.api_config.market_data_connections)?;
+        validate_heartbeat(&config.api_config)?;
 
         // Validate strategies
         for (name, strategy) in &config.strategies {
@@ -470,6 +869,19 @@ impl ConfigManager {
                 return Err(format!("Strategy symbol cannot be empty for strategy: {}", name));
             }
         }
+        for (name, mm_config) in config.market_making_configs()? {
+            mm_config.validate()
+                .map_err(|errors| format!("Invalid config for strategy {}: {}", name, errors.join("; ")))?;
+            // Past the risk limit the move is already flagged, so the strategy should have pulled by then
+            if let Some(limit) = config.risk_config.volatility_limits.get(&mm_config.base_config.symbol) {
+                if mm_config.max_price_change_bps > limit.max_price_change_bps {
+                    return Err(format!(
+                        "Strategy {} max price change ({} bps) exceeds the {} volatility limit ({} bps)",
+                        name, mm_config.max_price_change_bps, mm_config.base_config.symbol, limit.max_price_change_bps
+                    ));
+                }
+            }
+        }
 
         // Validate risk config
         if config.risk_config.global_risk_limits.max_position_size <= Decimal::ZERO {
@@ -514,6 +926,159 @@ impl Clone for ConfigManager {
             file_path: self.file_path.clone(),
             auto_save: self.auto_save,
             save_interval: self.save_interval,
+            file_watcher: Arc::clone(&self.file_watcher),
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::secrets::Secret;
+
+    fn strategy(name: &str, symbol: &str, enabled: bool) -> StrategyConfig {
+        StrategyConfig {
+            name: name.to_string(),
+            enabled,
+            symbol: symbol.to_string(),
+            strategy_type: StrategyType::MarketMaking,
+            config: serde_json::Value::Null,
+            risk_limits: RiskLimits::default(),
+        }
+    }
+
+    #[test]
+    fn market_data_symbols_are_the_enabled_strategies_symbols_once_each() {
+        let mut config = BotConfig::default();
+        config.strategies.clear();
+        for (name, symbol, enabled) in [("mm_btc", "BTC", true), ("mm_hype", "HYPE", true), ("mm_hype_wide", "HYPE", true), ("mm_eth", "ETH", false)] {
+            config.strategies.insert(name.to_string(), strategy(name, symbol, enabled));
+        }
+
+        assert_eq!(config.market_data_symbols(), vec!["BTC".to_string(), "HYPE".to_string()]);
+    }
+
+    #[test]
+    fn no_enabled_strategies_stream_nothing() {
+        let mut config = BotConfig::default();
+        config.strategies.clear();
+        config.strategies.insert("mm_eth".to_string(), strategy("mm_eth", "ETH", false));
+
+        assert!(config.market_data_symbols().is_empty());
+    }
+
+    #[test]
+    fn configs_round_trip_through_every_format() {
+        let (manager, _events) = ConfigManager::new();
+        manager.create_default_market_making_strategy("HYPE".to_string()).unwrap();
+        let config = manager.get_config();
+
+        // Unset options come back unset, though TOML leaves them out rather than writing null
+        let without_nulls = |config: &BotConfig| {
+            let mut value = serde_json::to_value(config).unwrap();
+            strip_nulls(&mut value);
+            value
+        };
+        for format in [ConfigFormat::Toml, ConfigFormat::Json, ConfigFormat::Yaml] {
+            let parsed = format.parse(&format.serialize(&config).unwrap()).unwrap();
+            assert_eq!(without_nulls(&parsed), without_nulls(&config), "{:?}", format);
+            assert_eq!(parsed.market_making_configs().unwrap().len(), 1, "{:?}", format);
+        }
+    }
+
+    #[test]
+    fn the_format_follows_the_file_extension() {
+        assert_eq!(ConfigFormat::from_path(Path::new("bot.toml")), Ok(ConfigFormat::Toml));
+        assert_eq!(ConfigFormat::from_path(Path::new("bot.JSON")), Ok(ConfigFormat::Json));
+        assert_eq!(ConfigFormat::from_path(Path::new("bot.yml")), Ok(ConfigFormat::Yaml));
+        assert_eq!(ConfigFormat::from_path(Path::new("bot")), Ok(ConfigFormat::Toml));
+        assert!(ConfigFormat::from_path(Path::new("bot.ini")).unwrap_err().contains(".ini"));
+    }
+
+    #[test]
+    fn env_vars_are_substituted_into_the_config() {
+        std::env::set_var("HL_CONFIG_TEST_BASE_URL", "https://api.example.test");
+        let content = "base_url = \"${HL_CONFIG_TEST_BASE_URL}\"\nws_url = \"${HL_CONFIG_TEST_BASE_URL}/ws\"";
+
+        assert_eq!(
+            expand_env_vars(content).unwrap(),
+            "base_url = \"https://api.example.test\"\nws_url = \"https://api.example.test/ws\""
+        );
+    }
+
+    #[test]
+    fn an_unset_or_malformed_reference_is_an_error() {
+        let unset = expand_env_vars("key = \"${HL_CONFIG_TEST_NEVER_SET}\"").unwrap_err();
+        assert!(unset.contains("HL_CONFIG_TEST_NEVER_SET"), "{}", unset);
+        assert!(expand_env_vars("key = \"${HL_CONFIG_TEST").unwrap_err().starts_with("Unterminated"));
+        assert!(expand_env_vars("key = \"${}\"").is_err());
+    }
+
+    fn vars(pairs: &[(&str, &str)]) -> Vec<(String, String)> {
+        pairs.iter().map(|(name, value)| (name.to_string(), value.to_string())).collect()
+    }
+
+    #[test]
+    fn overrides_win_over_the_file_and_keep_each_values_type() {
+        std::env::set_var("HL_CONFIG_TEST_FILE_URL", "https://from-file.test");
+        let mut file = serde_json::to_value(BotConfig::default()).unwrap();
+        file["api_config"]["base_url"] = "${HL_CONFIG_TEST_FILE_URL}".into();
+        let config = ConfigFormat::Json.parse(&expand_env_vars(&file.to_string()).unwrap()).unwrap();
+        assert_eq!(config.api_config.base_url, "https://from-file.test");
+
+        let config = apply_env_overrides(config, vars(&[
+            ("HLBOT__API_CONFIG__BASE_URL", "https://from-env.test"),
+            ("HLBOT__Api_Config__Timeout_Ms", "1500"),
+            ("HLBOT__HOT_RELOAD", "true"),
+            ("OTHER__API_CONFIG__WS_URL", "ignored"),
+        ])).unwrap();
+
+        assert_eq!(config.api_config.base_url, "https://from-env.test");
+        assert_eq!(config.api_config.timeout_ms, 1500);
+        assert!(config.hot_reload);
+        assert_eq!(config.api_config.ws_url, ApiConfig::default().ws_url);
+    }
+
+    #[test]
+    fn a_mistyped_override_is_rejected_and_the_private_key_survives_the_rest() {
+        let mut config = BotConfig::default();
+        config.secrets.private_key = Some(Secret::new("0xkey".to_string()));
+
+        let error = apply_env_overrides(config.clone(), vars(&[("HLBOT__HOT_RELOAD", "yes")])).unwrap_err();
+        assert!(error.contains("HLBOT__HOT_RELOAD"), "{}", error);
+
+        let config = apply_env_overrides(config, vars(&[("HLBOT__HOT_RELOAD", "true")])).unwrap();
+        assert_eq!(config.secrets.private_key.unwrap().expose(), "0xkey");
+    }
+
+    #[test]
+    fn editing_the_watched_file_reloads_the_config_and_a_bad_edit_is_rejected() {
+        let dir = std::env::temp_dir().join(format!("hl_config_reload_{}", Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("bot.json");
+
+        let (manager, events) = ConfigManager::new();
+        let manager = manager.with_file_path(path.to_string_lossy().into_owned());
+        let mut config = manager.get_config();
+        std::fs::write(&path, ConfigFormat::Json.serialize(&config).unwrap()).unwrap();
+        manager.watch_file().unwrap();
+
+        config.risk_config.global_risk_limits.max_daily_loss += Decimal::ONE;
+        std::fs::write(&path, ConfigFormat::Json.serialize(&config).unwrap()).unwrap();
+
+        let timeout = Duration::from_secs(5);
+        assert!(matches!(events.recv_timeout(timeout).unwrap(), ConfigEvent::ConfigLoaded { .. }));
+        match events.recv_timeout(timeout).unwrap() {
+            ConfigEvent::ConfigChanged { section, key, .. } => assert_eq!((section.as_str(), key.as_str()), ("risk", "risk_config")),
+            other => panic!("expected a risk change, got {:?}", other),
+        }
+        assert_eq!(manager.get_config().risk_config.global_risk_limits.max_daily_loss, config.risk_config.global_risk_limits.max_daily_loss);
+
+        config.api_config.base_url.clear();
+        std::fs::write(&path, ConfigFormat::Json.serialize(&config).unwrap()).unwrap();
+
+        assert!(matches!(events.recv_timeout(timeout).unwrap(), ConfigEvent::ConfigError { .. }));
+        assert!(!manager.get_config().api_config.base_url.is_empty());
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
@@ -2,3 +2,4 @@ pub mod bot_config;
 pub mod strategy_config;
 pub mod risk_config;
 pub mod api_config;
+pub mod secrets;
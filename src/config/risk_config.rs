@@ -61,6 +61,8 @@ impl RiskConfigTemplate {
                 max_daily_loss: Decimal::from(100),
                 max_order_size: Decimal::from(1),
                 max_orders_per_side: 3,
+                max_price_deviation_bps: 200,
+                max_funding_rate: Decimal::new(5, 5),
             },
             position_limits,
             exposure_limits,
@@ -96,6 +98,8 @@ impl RiskConfigTemplate {
                 max_daily_loss: Decimal::from(500),
                 max_order_size: Decimal::from(5),
                 max_orders_per_side: 5,
+                max_price_deviation_bps: 500,
+                max_funding_rate: Decimal::new(1, 4),
             },
             position_limits,
             exposure_limits,
@@ -131,6 +135,8 @@ impl RiskConfigTemplate {
                 max_daily_loss: Decimal::from(1000),
                 max_order_size: Decimal::from(10),
                 max_orders_per_side: 10,
+                max_price_deviation_bps: 1000,
+                max_funding_rate: Decimal::new(3, 4),
             },
             position_limits,
             exposure_limits,
@@ -0,0 +1,90 @@
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+pub const DEFAULT_PRIVATE_KEY_ENV: &str = "HYPERLIQUID_PRIVATE_KEY";
+
+/// A value that must never reach a log. Debug and Display print a placeholder; the
+/// value itself is only available through `expose`.
+#[derive(Clone, Default, PartialEq, Eq, Deserialize)]
+#[serde(transparent)]
+pub struct Secret(String);
+
+impl Secret {
+    pub fn new(value: String) -> Self {
+        Self(value)
+    }
+
+    pub fn expose(&self) -> &str {
+        &self.0
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+}
+
+impl fmt::Debug for Secret {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("Secret(<redacted>)")
+    }
+}
+
+impl fmt::Display for Secret {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("<redacted>")
+    }
+}
+
+/// Where the signing key comes from. The first of these that is set wins: the
+/// environment variable named by `private_key_env`, the file at `private_key_file`,
+/// then `private_key` itself. The inline key is never written back when the config
+/// is saved.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SecretsConfig {
+    #[serde(default = "default_private_key_env")]
+    pub private_key_env: String,
+    #[serde(default)]
+    pub private_key_file: Option<String>,
+    #[serde(default, skip_serializing)]
+    pub private_key: Option<Secret>,
+}
+
+fn default_private_key_env() -> String {
+    DEFAULT_PRIVATE_KEY_ENV.to_string()
+}
+
+impl Default for SecretsConfig {
+    fn default() -> Self {
+        Self {
+            private_key_env: default_private_key_env(),
+            private_key_file: None,
+            private_key: None,
+        }
+    }
+}
+
+impl SecretsConfig {
+    pub fn private_key(&self) -> Result<Secret, String> {
+        if let Some(value) = std::env::var(&self.private_key_env).ok().filter(|value| !value.is_empty()) {
+            return Ok(Secret::new(value));
+        }
+
+        if let Some(path) = &self.private_key_file {
+            let value = std::fs::read_to_string(path)
+                .map_err(|e| format!("Failed to read private key file {}: {}", path, e))?;
+            let value = value.trim();
+            if value.is_empty() {
+                return Err(format!("Private key file {} is empty", path));
+            }
+            return Ok(Secret::new(value.to_string()));
+        }
+
+        match &self.private_key {
+            Some(key) if !key.is_empty() => Ok(key.clone()),
+            _ => Err(format!(
+                "No private key: set {}, secrets.private_key_file or secrets.private_key",
+                self.private_key_env
+            )),
+        }
+    }
+}
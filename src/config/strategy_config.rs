@@ -1,5 +1,4 @@
 use crate::strategies::market_making::MarketMakingConfig;
-use crate::trading::types::RiskLimits;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 
@@ -31,6 +30,14 @@ impl StrategyConfigTemplate {
                 "inventory_skew_factor".to_string(),
                 "min_edge_bps".to_string(),
                 "order_refresh_interval_ms".to_string(),
+                "max_quote_spread_bps".to_string(),
+                "order_refresh_jitter_ms".to_string(),
+                "reference_volatility".to_string(),
+                "max_volatility_widening".to_string(),
+                "update_rate_window_ms".to_string(),
+                "fast_market_update_rate".to_string(),
+                "fast_market_widening".to_string(),
+                "max_update_rate".to_string(),
             ],
         }
     }
@@ -8,6 +8,12 @@ pub struct TobCache {
     capacity: usize,
 }
 
+impl Default for TobCache {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl TobCache {
     pub fn new() -> Self {
         let mp: HashMap<String, (PriceLevel, PriceLevel)> = HashMap::new();
@@ -0,0 +1,419 @@
+use crate::events::event_bus::EventBus;
+use crate::events::types::*;
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::thread;
+use std::time::{Duration, Instant};
+use tracing::{error, info, warn};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AlertConfig {
+    pub enabled: bool,
+    pub webhook_url: Option<String>,
+    pub severities: Vec<EventPriority>, // Event priorities forwarded to the sinks
+    pub timeout_ms: u64,
+    #[serde(default = "default_cooldown_ms")]
+    pub cooldown_ms: u64, // Minimum gap between alerts with the same key
+}
+
+fn default_cooldown_ms() -> u64 {
+    300_000
+}
+
+impl Default for AlertConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            webhook_url: None,
+            severities: vec![EventPriority::Critical, EventPriority::High],
+            timeout_ms: 5000,
+            cooldown_ms: default_cooldown_ms(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Alert {
+    pub key: String, // Identifies repeats of the same condition for deduplication
+    pub severity: EventPriority,
+    pub source: String,
+    pub message: String,
+    pub suppressed_count: u32, // Identical alerts dropped since the last one was sent
+    pub timestamp: DateTime<Utc>,
+}
+
+impl Alert {
+    pub fn from_event(event: &SystemEvent) -> Self {
+        let message = match event {
+            SystemEvent::Risk { symbol, event, .. } => format!("Risk event on {}: {:?}", symbol, event),
+            SystemEvent::Connection { connection_id, event, .. } => {
+                format!("Connection {}: {:?}", connection_id, event)
+            }
+            SystemEvent::Strategy { strategy_name, event, .. } => {
+                format!("Strategy {}: {:?}", strategy_name, event)
+            }
+            SystemEvent::System { event: SystemLevelEvent::Error { component, error }, .. } => {
+                format!("{} error: {}", component, error)
+            }
+            SystemEvent::System { event: SystemLevelEvent::ExchangeNotice { message, .. }, .. } => message.clone(),
+            other => format!("{:?}", other),
+        };
+
+        // Risk events carry changing values, so key them on what was breached instead
+        let key = match event {
+            SystemEvent::Risk { symbol, event, .. } => match event {
+                RiskEvent::LimitExceeded { limit_type, .. } => format!("risk:{}:limit:{}", symbol, limit_type),
+                RiskEvent::PositionSizeWarning { .. } => format!("risk:{}:position_size", symbol),
+                RiskEvent::PnlWarning { .. } => format!("risk:{}:pnl", symbol),
+                RiskEvent::OrderRejected { reason, .. } => format!("risk:{}:rejected:{}", symbol, reason),
+            },
+            SystemEvent::System { event: SystemLevelEvent::ExchangeNotice { kind, .. }, .. } => format!("exchange:{}", kind),
+            _ => format!("{}:{}", event.source(), message),
+        };
+
+        Self {
+            key,
+            severity: event.priority(),
+            source: event.source(),
+            message,
+            suppressed_count: 0,
+            timestamp: Utc::now(),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+struct ThrottleEntry {
+    last_sent: Instant,
+    suppressed: u32,
+    last_suppressed: Option<Alert>, // Most recent repeat dropped, reported when the entry expires
+}
+
+/// Lets through at most one alert per key per cooldown. The next alert sent for a
+/// key carries the number of repeats dropped in between; if none comes, `flush_expired`
+/// reports them once the cooldown runs out.
+#[derive(Debug, Clone)]
+pub struct AlertThrottle {
+    cooldown: Duration,
+    entries: HashMap<String, ThrottleEntry>,
+}
+
+impl AlertThrottle {
+    pub fn new(cooldown: Duration) -> Self {
+        Self {
+            cooldown,
+            entries: HashMap::new(),
+        }
+    }
+
+    /// Returns whether the alert should be sent, filling in its suppression summary.
+    pub fn check(&mut self, alert: &mut Alert, now: Instant) -> bool {
+        let cooldown = self.cooldown;
+        self.entries.retain(|_, entry| {
+            entry.suppressed > 0 || now.duration_since(entry.last_sent) < cooldown
+        });
+
+        let Some(entry) = self.entries.get_mut(&alert.key) else {
+            self.entries.insert(alert.key.clone(), ThrottleEntry { last_sent: now, suppressed: 0, last_suppressed: None });
+            return true;
+        };
+
+        if now.duration_since(entry.last_sent) < cooldown {
+            entry.suppressed += 1;
+            entry.last_suppressed = Some(alert.clone());
+            return false;
+        }
+
+        if entry.suppressed > 0 {
+            alert.suppressed_count = entry.suppressed;
+            alert.message = format!(
+                "{} ({} similar alerts suppressed in the last {:?})",
+                alert.message,
+                entry.suppressed,
+                now.duration_since(entry.last_sent)
+            );
+        }
+        entry.last_sent = now;
+        entry.suppressed = 0;
+        entry.last_suppressed = None;
+        true
+    }
+
+    /// Drops entries whose cooldown has passed, returning a summary alert for each
+    /// one that still had suppressed repeats so they aren't lost.
+    pub fn flush_expired(&mut self, now: Instant) -> Vec<Alert> {
+        let cooldown = self.cooldown;
+        let mut summaries = Vec::new();
+        self.entries.retain(|_, entry| {
+            let elapsed = now.duration_since(entry.last_sent);
+            if elapsed < cooldown {
+                return true;
+            }
+            if let Some(mut alert) = entry.last_suppressed.take() {
+                alert.suppressed_count = entry.suppressed;
+                alert.message = format!(
+                    "{} ({} similar alerts suppressed in the last {:?})",
+                    alert.message, entry.suppressed, elapsed
+                );
+                alert.timestamp = Utc::now();
+                summaries.push(alert);
+            }
+            false
+        });
+        summaries
+    }
+
+    pub fn suppressed_count(&self, key: &str) -> u32 {
+        self.entries.get(key).map_or(0, |entry| entry.suppressed)
+    }
+}
+
+#[async_trait]
+pub trait AlertSink: Send + Sync {
+    async fn send_alert(&self, alert: &Alert) -> Result<(), String>;
+    fn name(&self) -> &str;
+}
+
+/// Posts each alert as JSON to a configured URL.
+pub struct WebhookAlertSink {
+    pub url: String,
+    pub client: reqwest::Client,
+}
+
+impl WebhookAlertSink {
+    pub fn new(url: String, timeout: Duration) -> Self {
+        let client = reqwest::Client::builder()
+            .timeout(timeout)
+            .build()
+            .unwrap_or_default();
+
+        Self { url, client }
+    }
+}
+
+#[async_trait]
+impl AlertSink for WebhookAlertSink {
+    async fn send_alert(&self, alert: &Alert) -> Result<(), String> {
+        let response = self.client
+            .post(&self.url)
+            .json(alert)
+            .send()
+            .await
+            .map_err(|e| e.to_string())?;
+
+        if !response.status().is_success() {
+            return Err(format!("Webhook returned status {}", response.status()));
+        }
+
+        Ok(())
+    }
+
+    fn name(&self) -> &str {
+        "webhook"
+    }
+}
+
+/// Forwards event bus events matching the configured severities to every sink.
+pub struct AlertManager {
+    pub config: AlertConfig,
+    pub sinks: Vec<Arc<dyn AlertSink>>,
+}
+
+impl AlertManager {
+    pub fn new(config: AlertConfig) -> Self {
+        let mut sinks: Vec<Arc<dyn AlertSink>> = Vec::new();
+        if let Some(url) = &config.webhook_url {
+            sinks.push(Arc::new(WebhookAlertSink::new(
+                url.clone(),
+                Duration::from_millis(config.timeout_ms),
+            )));
+        }
+
+        Self { config, sinks }
+    }
+
+    pub fn with_sink(mut self, sink: Arc<dyn AlertSink>) -> Self {
+        self.sinks.push(sink);
+        self
+    }
+
+    pub fn should_alert(&self, event: &SystemEvent) -> bool {
+        self.config.severities.contains(&event.priority())
+    }
+
+    /// Subscribes to the event bus and dispatches alerts from a background thread.
+    /// Must be called from within a tokio runtime, which is used to drive the sinks.
+    pub fn start(self, event_bus: &EventBus) {
+        if !self.config.enabled || self.sinks.is_empty() {
+            info!("Alerting disabled");
+            return;
+        }
+
+        let events_rx = event_bus.subscribe("*");
+        let runtime = tokio::runtime::Handle::current();
+        let cooldown = Duration::from_millis(self.config.cooldown_ms);
+        let mut throttle = AlertThrottle::new(cooldown);
+        // Wake up at least this often so suppressed counts are flushed even when quiet
+        let flush_interval = cooldown.clamp(Duration::from_secs(1), Duration::from_secs(60));
+
+        thread::spawn(move || {
+            info!("Alert dispatcher started with {} sink(s)", self.sinks.len());
+            loop {
+                match events_rx.recv_timeout(flush_interval) {
+                    Ok(event) => {
+                        if self.should_alert(&event) {
+                            let mut alert = Alert::from_event(&event);
+                            if throttle.check(&mut alert, Instant::now()) {
+                                self.dispatch(&runtime, &alert);
+                            }
+                        }
+                    }
+                    Err(crossbeam_channel::RecvTimeoutError::Timeout) => {}
+                    Err(crossbeam_channel::RecvTimeoutError::Disconnected) => break,
+                }
+
+                for alert in throttle.flush_expired(Instant::now()) {
+                    self.dispatch(&runtime, &alert);
+                }
+            }
+            info!("Alert dispatcher stopped");
+        });
+    }
+
+    fn dispatch(&self, runtime: &tokio::runtime::Handle, alert: &Alert) {
+        warn!("Alert [{:?}] {}", alert.severity, alert.message);
+        for sink in &self.sinks {
+            if let Err(e) = runtime.block_on(sink.send_alert(alert)) {
+                error!("Failed to send alert via {}: {}", sink.name(), e);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+    use tokio::net::TcpListener;
+
+    fn alert(key: &str) -> Alert {
+        Alert {
+            key: key.to_string(),
+            severity: EventPriority::High,
+            source: "test".to_string(),
+            message: format!("{} happened", key),
+            suppressed_count: 0,
+            timestamp: Utc::now(),
+        }
+    }
+
+    #[test]
+    fn repeats_within_the_cooldown_are_suppressed_and_reported_on_the_next_send() {
+        let mut throttle = AlertThrottle::new(Duration::from_secs(10));
+        let start = Instant::now();
+
+        assert!(throttle.check(&mut alert("a"), start));
+        assert!(!throttle.check(&mut alert("a"), start + Duration::from_secs(1)));
+        assert!(!throttle.check(&mut alert("a"), start + Duration::from_secs(2)));
+        assert!(throttle.check(&mut alert("b"), start + Duration::from_secs(2)));
+        assert_eq!(throttle.suppressed_count("a"), 2);
+
+        let mut next = alert("a");
+        assert!(throttle.check(&mut next, start + Duration::from_secs(11)));
+        assert_eq!(next.suppressed_count, 2);
+        assert_eq!(throttle.suppressed_count("a"), 0);
+    }
+
+    #[test]
+    fn expired_entries_flush_their_suppressed_count_and_are_dropped() {
+        let mut throttle = AlertThrottle::new(Duration::from_secs(10));
+        let start = Instant::now();
+
+        assert!(throttle.check(&mut alert("a"), start));
+        assert!(!throttle.check(&mut alert("a"), start + Duration::from_secs(1)));
+        assert!(throttle.check(&mut alert("b"), start));
+        assert!(throttle.flush_expired(start + Duration::from_secs(5)).is_empty());
+
+        let flushed = throttle.flush_expired(start + Duration::from_secs(10));
+        assert_eq!(flushed.len(), 1);
+        assert_eq!(flushed[0].key, "a");
+        assert_eq!(flushed[0].suppressed_count, 1);
+        assert!(throttle.entries.is_empty());
+
+        // Nothing is held back anymore, so the next one goes straight out
+        let mut next = alert("a");
+        assert!(throttle.check(&mut next, start + Duration::from_secs(12)));
+        assert_eq!(next.suppressed_count, 0);
+    }
+
+    #[test]
+    fn exchange_notices_throttle_by_kind() {
+        let notice = |message: &str| SystemEvent::new_system_event(SystemLevelEvent::ExchangeNotice {
+            kind: "order_update:rejected".to_string(),
+            message: message.to_string(),
+        });
+
+        let first = Alert::from_event(&notice("order 1 rejected"));
+        let second = Alert::from_event(&notice("order 2 rejected"));
+        assert_eq!(first.key, second.key);
+        assert_eq!(first.severity, EventPriority::High);
+        assert_eq!(second.message, "order 2 rejected");
+    }
+
+    // Answers each request with the next of `statuses`, recording the JSON bodies
+    async fn mock_webhook(statuses: Vec<u16>) -> (String, Arc<parking_lot::Mutex<Vec<serde_json::Value>>>) {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}/alerts", listener.local_addr().unwrap());
+        let bodies = Arc::new(parking_lot::Mutex::new(Vec::new()));
+        let recorded = Arc::clone(&bodies);
+
+        tokio::spawn(async move {
+            for status in statuses {
+                let (mut socket, _) = listener.accept().await.unwrap();
+                let mut buf = Vec::new();
+                let mut chunk = [0u8; 4096];
+                let body = loop {
+                    let n = socket.read(&mut chunk).await.unwrap();
+                    buf.extend_from_slice(&chunk[..n]);
+                    let Some(header_end) = buf.windows(4).position(|w| w == b"\r\n\r\n") else { continue };
+                    let headers = String::from_utf8_lossy(&buf[..header_end]).to_lowercase();
+                    let content_length: usize = headers.lines()
+                        .find_map(|line| line.strip_prefix("content-length:"))
+                        .map(|len| len.trim().parse().unwrap())
+                        .unwrap_or(0);
+                    if buf.len() >= header_end + 4 + content_length {
+                        break buf[header_end + 4..header_end + 4 + content_length].to_vec();
+                    }
+                };
+
+                recorded.lock().push(serde_json::from_slice(&body).unwrap());
+                let reply = format!("HTTP/1.1 {} Status\r\nContent-Length: 0\r\nConnection: close\r\n\r\n", status);
+                socket.write_all(reply.as_bytes()).await.unwrap();
+            }
+        });
+
+        (url, bodies)
+    }
+
+    #[tokio::test]
+    async fn the_webhook_posts_the_alert_as_json_and_fails_on_an_error_status() {
+        let (url, bodies) = mock_webhook(vec![200, 500]).await;
+        let sink = WebhookAlertSink::new(url, Duration::from_secs(5));
+        let mut sent = alert("risk:HYPE:pnl");
+        sent.suppressed_count = 3;
+
+        sink.send_alert(&sent).await.unwrap();
+        let error = sink.send_alert(&alert("risk:HYPE:pnl")).await.unwrap_err();
+
+        assert!(error.contains("500"), "{}", error);
+        let bodies = bodies.lock();
+        assert_eq!(bodies.len(), 2);
+        assert_eq!(bodies[0]["key"], "risk:HYPE:pnl");
+        assert_eq!(bodies[0]["message"], "risk:HYPE:pnl happened");
+        assert_eq!(bodies[0]["severity"], serde_json::to_value(EventPriority::High).unwrap());
+        assert_eq!(bodies[0]["suppressed_count"], 3);
+    }
+}
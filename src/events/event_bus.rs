@@ -1,9 +1,13 @@
+use crate::events::journal::{EventJournal, JournalConfig};
 use crate::events::types::*;
 use crossbeam_channel::{Sender, Receiver, bounded, unbounded};
 use dashmap::DashMap;
 use parking_lot::RwLock;
+use std::collections::HashMap;
+use std::io;
+use std::path::Path;
 use std::sync::Arc;
-use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::atomic::{AtomicBool, AtomicU64, AtomicUsize, Ordering};
 use std::thread;
 use std::time::{Duration, Instant};
 use tracing::{error, info, warn, debug};
@@ -18,18 +22,25 @@ pub struct EventBus {
     normal_priority_rx: Receiver<SystemEvent>,
     
     // Low priority channel for market data (high volume)
-    low_priority_tx: Sender<SystemEvent>,
+    low_priority: LowPriorityQueue,
     low_priority_rx: Receiver<SystemEvent>,
     
     // Subscriber management
-    subscribers: Arc<DashMap<String, Vec<Sender<SystemEvent>>>>,
+    subscribers: Arc<Subscribers>,
     
     // Event filtering
     filters: Arc<RwLock<Vec<Box<dyn EventFilter + Send + Sync>>>>,
     
     // Performance metrics
     events_processed: Arc<AtomicU64>,
-    events_dropped: Arc<AtomicU64>,
+    events_dropped: DropCounters,
+    
+    // Optional record of every published event, for replay
+    journal: Option<EventJournal>,
+    
+    // Processor thread lifecycle
+    shutdown: Arc<AtomicBool>,
+    running_processors: Arc<AtomicUsize>,
     
     // Configuration
     config: EventBusConfig,
@@ -44,6 +55,18 @@ pub struct EventBusConfig {
     pub enable_metrics: bool,
     pub batch_size: usize,
     pub batch_timeout_ms: u64,
+    pub blocking_publish_timeout_ms: u64, // How long Critical/High events wait for room before failing
+    pub journal: Option<JournalConfig>,   // Record published events to disk when set
+    pub low_priority_overflow: OverflowPolicy,
+}
+
+/// What happens to market data when the low priority channel can't keep up.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum OverflowPolicy {
+    #[default]
+    DropNewest,       // The event being published is dropped
+    DropOldest,       // The oldest queued event is dropped to make room
+    CoalesceBySymbol, // At most one update per symbol is pending; newer ones replace it
 }
 
 impl Default for EventBusConfig {
@@ -56,8 +79,242 @@ impl Default for EventBusConfig {
             enable_metrics: true,
             batch_size: 100,
             batch_timeout_ms: 10,
+            blocking_publish_timeout_ms: 1000,
+            journal: None,
+            low_priority_overflow: OverflowPolicy::default(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PublishError {
+    ChannelFull,       // Dropped without waiting (Normal/Low priority)
+    Timeout(Duration), // Critical/High event still had no room after waiting this long
+    Disconnected,
+}
+
+impl std::fmt::Display for PublishError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            PublishError::ChannelFull => write!(f, "Event bus channel full"),
+            PublishError::Timeout(timeout) => write!(f, "Event bus channel still full after {:?}", timeout),
+            PublishError::Disconnected => write!(f, "Event bus disconnected"),
+        }
+    }
+}
+
+impl std::error::Error for PublishError {}
+
+// Dropped events by the priority they were published at. `total` is the sum, and is
+// the counter `EventBus::counters` hands out
+#[derive(Debug, Clone, Default)]
+struct DropCounters {
+    total: Arc<AtomicU64>,
+    high: Arc<AtomicU64>, // Critical and High
+    normal: Arc<AtomicU64>,
+    low: Arc<AtomicU64>,
+}
+
+impl DropCounters {
+    fn record(&self, priority: EventPriority) {
+        let counter = match priority {
+            EventPriority::Critical | EventPriority::High => &self.high,
+            EventPriority::Normal => &self.normal,
+            EventPriority::Low => &self.low,
+        };
+        counter.fetch_add(1, Ordering::Relaxed);
+        self.total.fetch_add(1, Ordering::Relaxed);
+    }
+}
+
+// Critical and High events wait up to `timeout` for room, since losing a risk or
+// error event is worse than stalling the publisher. Normal events are dropped
+// immediately when their channel is full; Low ones follow the overflow policy.
+fn send_by_priority(
+    high_priority_tx: &Sender<SystemEvent>,
+    normal_priority_tx: &Sender<SystemEvent>,
+    low_priority: &LowPriorityQueue,
+    timeout: Duration,
+    event: SystemEvent,
+) -> Result<(), PublishError> {
+    match event.priority() {
+        EventPriority::Critical | EventPriority::High => {
+            high_priority_tx.send_timeout(event, timeout).map_err(|e| match e {
+                crossbeam_channel::SendTimeoutError::Timeout(_) => PublishError::Timeout(timeout),
+                crossbeam_channel::SendTimeoutError::Disconnected(_) => PublishError::Disconnected,
+            })
+        }
+        EventPriority::Normal => try_send(normal_priority_tx, event),
+        EventPriority::Low => low_priority.send(event),
+    }
+}
+
+fn try_send(tx: &Sender<SystemEvent>, event: SystemEvent) -> Result<(), PublishError> {
+    tx.try_send(event).map_err(|e| match e {
+        crossbeam_channel::TrySendError::Full(_) => PublishError::ChannelFull,
+        crossbeam_channel::TrySendError::Disconnected(_) => PublishError::Disconnected,
+    })
+}
+
+/// The low priority channel together with its overflow policy, shared by the bus
+/// and every publisher.
+///
+/// With `CoalesceBySymbol` the latest market data event per symbol is kept in
+/// `pending` and the channel only carries one placeholder per pending symbol, which
+/// the processor swaps for the latest event when it gets to it.
+#[derive(Debug, Clone)]
+struct LowPriorityQueue {
+    tx: Sender<SystemEvent>,
+    rx: Receiver<SystemEvent>,
+    policy: OverflowPolicy,
+    pending: Arc<DashMap<String, SystemEvent>>,
+    dropped: DropCounters,     // Counts events evicted by DropOldest
+    coalesced: Arc<AtomicU64>, // Events replaced by a newer one for the same symbol
+}
+
+impl LowPriorityQueue {
+    fn send(&self, event: SystemEvent) -> Result<(), PublishError> {
+        match (self.policy, &event) {
+            (OverflowPolicy::CoalesceBySymbol, SystemEvent::MarketData { symbol, .. }) => {
+                let symbol = symbol.clone();
+                if self.pending.insert(symbol.clone(), event.clone()).is_some() {
+                    self.coalesced.fetch_add(1, Ordering::Relaxed);
+                    return Ok(());
+                }
+                try_send(&self.tx, event).inspect_err(|_| {
+                    self.pending.remove(&symbol);
+                })
+            }
+            (OverflowPolicy::DropOldest, _) => match self.tx.try_send(event) {
+                Err(crossbeam_channel::TrySendError::Full(event)) => {
+                    if self.rx.try_recv().is_ok() {
+                        self.dropped.record(EventPriority::Low);
+                    }
+                    try_send(&self.tx, event)
+                }
+                result => result.map_err(|_| PublishError::Disconnected),
+            },
+            _ => try_send(&self.tx, event),
+        }
+    }
+
+    // Swaps a coalescing placeholder for the latest event for its symbol. None if
+    // that event was already delivered.
+    fn resolve(&self, event: SystemEvent) -> Option<SystemEvent> {
+        match (self.policy, &event) {
+            (OverflowPolicy::CoalesceBySymbol, SystemEvent::MarketData { symbol, .. }) => {
+                self.pending.remove(symbol).map(|(_, latest)| latest)
+            }
+            _ => Some(event),
+        }
+    }
+}
+
+/// Handle returned by `EventBus::subscribe_with_handle`, used to unsubscribe.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Subscription {
+    pub topic: String,
+    id: u64,
+}
+
+struct Subscriber {
+    id: u64,
+    tx: Sender<SystemEvent>,
+    top_of_book: bool, // Gets market data as TopOfBook events
+}
+
+// Subscribers keyed by exact topic, plus glob patterns such as `market_data.*`.
+// Patterns are kept apart so exact topics stay a single map lookup. A topic's
+// entry is removed once its last subscriber is gone.
+#[derive(Default)]
+struct Subscribers {
+    exact: DashMap<String, Vec<Subscriber>>,
+    patterns: DashMap<String, Vec<Subscriber>>,
+    next_id: AtomicU64,
+}
+
+impl Subscribers {
+    fn is_pattern(topic: &str) -> bool {
+        topic != "*" && topic.contains(['*', '?'])
+    }
+
+    fn map_for(&self, topic: &str) -> &DashMap<String, Vec<Subscriber>> {
+        if Self::is_pattern(topic) {
+            &self.patterns
+        } else {
+            &self.exact
+        }
+    }
+
+    fn len(&self) -> usize {
+        self.exact.iter().chain(self.patterns.iter()).map(|entry| entry.value().len()).sum()
+    }
+
+    // Must not be called while holding a guard into either map
+    fn remove_if_empty(&self, topic: &str) {
+        self.map_for(topic).remove_if(topic, |_, subs| subs.is_empty());
+    }
+}
+
+// Sends to every subscriber in `subs`, dropping those whose receiver is gone. The
+// compact form of a market data event is built at most once per event, in `compact`.
+fn send_to_subscribers(subs: &mut Vec<Subscriber>, event: &SystemEvent, compact: &mut Option<SystemEvent>, topic: &str) {
+    let before = subs.len();
+    
+    subs.retain(|subscriber| match subscriber.tx.try_send(event_for(subscriber, event, compact)) {
+        Ok(_) => true,
+        Err(crossbeam_channel::TrySendError::Full(_)) => {
+            debug!("Subscriber channel full for topic: {}", topic);
+            true
+        },
+        Err(crossbeam_channel::TrySendError::Disconnected(_)) => false,
+    });
+    
+    let removed = before - subs.len();
+    if removed > 0 {
+        debug!("Removed {} disconnected subscribers for topic: {}", removed, topic);
+    }
+}
+
+fn event_for(subscriber: &Subscriber, event: &SystemEvent, compact: &mut Option<SystemEvent>) -> SystemEvent {
+    match event {
+        SystemEvent::MarketData { symbol, data, timestamp } if subscriber.top_of_book => compact
+            .get_or_insert_with(|| SystemEvent::TopOfBook {
+                symbol: symbol.clone(),
+                data: TopOfBook::from_book(data),
+                timestamp: *timestamp,
+            })
+            .clone(),
+        _ => event.clone(),
+    }
+}
+
+/// Glob match where `*` matches any run of characters (including `.`) and `?`
+/// matches exactly one.
+pub fn topic_matches(pattern: &str, topic: &str) -> bool {
+    let pattern: Vec<char> = pattern.chars().collect();
+    let topic: Vec<char> = topic.chars().collect();
+    let (mut p, mut t) = (0, 0);
+    let mut backtrack: Option<(usize, usize)> = None;
+
+    while t < topic.len() {
+        if p < pattern.len() && (pattern[p] == '?' || pattern[p] == topic[t]) {
+            p += 1;
+            t += 1;
+        } else if p < pattern.len() && pattern[p] == '*' {
+            backtrack = Some((p, t));
+            p += 1;
+        } else if let Some((star_p, star_t)) = backtrack {
+            // Let the last `*` swallow one more character and retry
+            p = star_p + 1;
+            t = star_t + 1;
+            backtrack = Some((star_p, star_t + 1));
+        } else {
+            return false;
         }
     }
+
+    pattern[p..].iter().all(|c| *c == '*')
 }
 
 pub trait EventFilter {
@@ -73,7 +330,7 @@ pub struct TopicFilter {
 impl EventFilter for TopicFilter {
     fn should_process(&self, event: &SystemEvent) -> bool {
         let topic = match event {
-            SystemEvent::MarketData { symbol, .. } => format!("market_data.{}", symbol),
+            SystemEvent::MarketData { symbol, .. } | SystemEvent::TopOfBook { symbol, .. } => format!("market_data.{}", symbol),
             SystemEvent::Order(_) => "orders".to_string(),
             SystemEvent::Position(_) => "positions".to_string(),
             SystemEvent::Strategy { strategy_name, .. } => format!("strategy.{}", strategy_name),
@@ -98,22 +355,46 @@ impl EventBus {
         let (normal_priority_tx, normal_priority_rx) = bounded(config.normal_priority_buffer_size);
         let (low_priority_tx, low_priority_rx) = bounded(config.market_data_buffer_size);
         
+        let journal = config.journal.clone().and_then(|journal_config| {
+            let path = journal_config.path.clone();
+            match EventJournal::start(journal_config) {
+                Ok(journal) => Some(journal),
+                Err(e) => {
+                    error!("Failed to open event journal {}, continuing without it: {}", path.display(), e);
+                    None
+                }
+            }
+        });
+        
+        let events_dropped = DropCounters::default();
+        let low_priority = LowPriorityQueue {
+            tx: low_priority_tx,
+            rx: low_priority_rx.clone(),
+            policy: config.low_priority_overflow,
+            pending: Arc::new(DashMap::new()),
+            dropped: events_dropped.clone(),
+            coalesced: Arc::new(AtomicU64::new(0)),
+        };
+        
         Self {
             high_priority_tx,
             high_priority_rx,
             normal_priority_tx,
             normal_priority_rx,
-            low_priority_tx,
+            low_priority,
             low_priority_rx,
-            subscribers: Arc::new(DashMap::new()),
+            subscribers: Arc::new(Subscribers::default()),
             filters: Arc::new(RwLock::new(Vec::new())),
             events_processed: Arc::new(AtomicU64::new(0)),
-            events_dropped: Arc::new(AtomicU64::new(0)),
+            events_dropped,
+            journal,
+            shutdown: Arc::new(AtomicBool::new(false)),
+            running_processors: Arc::new(AtomicUsize::new(0)),
             config,
         }
     }
     
-    pub fn publish(&self, event: SystemEvent) -> Result<(), String> {
+    pub fn publish(&self, event: SystemEvent) -> Result<(), PublishError> {
         // Apply filters
         {
             let filters = self.filters.read();
@@ -126,52 +407,99 @@ impl EventBus {
         }
         
         // Route to appropriate priority channel
-        let result = match event.priority() {
-            EventPriority::Critical | EventPriority::High => {
-                self.high_priority_tx.try_send(event)
-            }
-            EventPriority::Normal => {
-                self.normal_priority_tx.try_send(event)
-            }
-            EventPriority::Low => {
-                self.low_priority_tx.try_send(event)
-            }
-        };
+        let priority = event.priority();
+        let journaled = self.journal.as_ref().map(|journal| (journal, event.clone()));
+        let result = send_by_priority(
+            &self.high_priority_tx,
+            &self.normal_priority_tx,
+            &self.low_priority,
+            Duration::from_millis(self.config.blocking_publish_timeout_ms),
+            event,
+        );
         
-        match result {
+        match &result {
             Ok(_) => {
+                if let Some((journal, event)) = journaled {
+                    journal.record(event);
+                }
                 if self.config.enable_metrics {
                     self.events_processed.fetch_add(1, Ordering::Relaxed);
                 }
-                Ok(())
             }
-            Err(crossbeam_channel::TrySendError::Full(_)) => {
+            Err(PublishError::ChannelFull) => {
                 if self.config.enable_metrics {
-                    self.events_dropped.fetch_add(1, Ordering::Relaxed);
+                    self.events_dropped.record(priority);
                 }
                 warn!("Event bus channel full, dropping event");
-                Err("Event bus channel full".to_string())
             }
-            Err(crossbeam_channel::TrySendError::Disconnected(_)) => {
+            Err(PublishError::Timeout(timeout)) => {
+                if self.config.enable_metrics {
+                    self.events_dropped.record(priority);
+                }
+                error!("High priority event dropped after waiting {:?} for room", timeout);
+            }
+            Err(PublishError::Disconnected) => {
                 error!("Event bus disconnected");
-                Err("Event bus disconnected".to_string())
             }
         }
+        result
     }
     
+    /// Subscribes to an exact topic, or to every topic matching a glob pattern such
+    /// as `market_data.*` or `risk.*`. `*` alone receives everything.
     pub fn subscribe(&self, topic: &str) -> Receiver<SystemEvent> {
+        self.subscribe_with_handle(topic).1
+    }
+
+    /// Like `subscribe`, but also returns a handle for `unsubscribe`. Dropping the
+    /// receiver alone only frees the slot the next time an event hits the topic.
+    pub fn subscribe_with_handle(&self, topic: &str) -> (Subscription, Receiver<SystemEvent>) {
+        self.add_subscriber(topic, false)
+    }
+
+    /// Like `subscribe_with_handle`, but market data arrives as `SystemEvent::TopOfBook`
+    /// with just the best bid and ask, rather than as the full book update. Other
+    /// events on the topic are delivered unchanged.
+    pub fn subscribe_top_of_book(&self, topic: &str) -> (Subscription, Receiver<SystemEvent>) {
+        self.add_subscriber(topic, true)
+    }
+
+    fn add_subscriber(&self, topic: &str, top_of_book: bool) -> (Subscription, Receiver<SystemEvent>) {
         let (tx, rx) = unbounded();
+        let subscription = Subscription {
+            topic: topic.to_string(),
+            id: self.subscribers.next_id.fetch_add(1, Ordering::Relaxed),
+        };
         
-        let mut subscribers = self.subscribers.entry(topic.to_string()).or_insert_with(Vec::new);
+        let mut subscribers = self.subscribers.map_for(topic).entry(topic.to_string()).or_default();
         
         if subscribers.len() >= self.config.max_subscribers_per_topic {
             warn!("Max subscribers reached for topic: {}", topic);
-            return rx; // Return empty receiver
+            return (subscription, rx); // Return empty receiver
         }
         
-        subscribers.push(tx);
+        subscribers.push(Subscriber { id: subscription.id, tx, top_of_book });
         info!("New subscriber for topic: {}", topic);
-        rx
+        (subscription, rx)
+    }
+
+    /// Removes the subscriber immediately, disconnecting its receiver. Returns false
+    /// if it was already gone.
+    pub fn unsubscribe(&self, subscription: &Subscription) -> bool {
+        let removed = self.subscribers.map_for(&subscription.topic)
+            .get_mut(&subscription.topic)
+            .and_then(|mut subs| {
+                let index = subs.iter().position(|subscriber| subscriber.id == subscription.id)?;
+                subs.swap_remove(index);
+                Some(())
+            })
+            .is_some();
+        
+        if removed {
+            self.subscribers.remove_if_empty(&subscription.topic);
+            debug!("Unsubscribed from topic: {}", subscription.topic);
+        }
+        removed
     }
     
     pub fn add_filter(&self, filter: Box<dyn EventFilter + Send + Sync>) {
@@ -180,7 +508,10 @@ impl EventBus {
         info!("Added event filter");
     }
     
-    pub fn start_processing(&self) {
+    /// Spawns the high, normal and low priority processors. The handles are only for
+    /// noticing a processor that died; `stop_processing` is how the threads are ended.
+    pub fn start_processing(&self) -> Vec<thread::JoinHandle<()>> {
+        self.shutdown.store(false, Ordering::SeqCst);
         let subscribers = Arc::clone(&self.subscribers);
         let events_processed = Arc::clone(&self.events_processed);
         let batch_size = self.config.batch_size;
@@ -190,13 +521,16 @@ impl EventBus {
         let high_rx = self.high_priority_rx.clone();
         let high_subscribers = Arc::clone(&subscribers);
         let high_events_processed = Arc::clone(&events_processed);
+        let high_shutdown = Arc::clone(&self.shutdown);
+        let high_guard = ProcessorGuard::register(&self.running_processors);
         
-        thread::spawn(move || {
+        let high_handle = thread::spawn(move || {
+            let _guard = high_guard;
             info!("High priority event processor started");
             let mut batch = Vec::with_capacity(batch_size);
             let mut last_batch_time = Instant::now();
             
-            loop {
+            while !high_shutdown.load(Ordering::Relaxed) {
                 // Try to collect a batch of events
                 while batch.len() < batch_size && last_batch_time.elapsed() < batch_timeout {
                     match high_rx.try_recv() {
@@ -232,37 +566,54 @@ impl EventBus {
                     }
                 }
             }
+            info!("High priority event processor stopped");
         });
         
         // Normal priority processor
         let normal_rx = self.normal_priority_rx.clone();
         let normal_subscribers = Arc::clone(&subscribers);
         let normal_events_processed = Arc::clone(&events_processed);
+        let normal_shutdown = Arc::clone(&self.shutdown);
+        let normal_guard = ProcessorGuard::register(&self.running_processors);
         
-        thread::spawn(move || {
+        let normal_handle = thread::spawn(move || {
+            let _guard = normal_guard;
             info!("Normal priority event processor started");
-            for event in normal_rx {
-                Self::distribute_event(&event, &normal_subscribers);
-                normal_events_processed.fetch_add(1, Ordering::Relaxed);
+            while !normal_shutdown.load(Ordering::Relaxed) {
+                match normal_rx.recv_timeout(batch_timeout) {
+                    Ok(event) => {
+                        Self::distribute_event(&event, &normal_subscribers);
+                        normal_events_processed.fetch_add(1, Ordering::Relaxed);
+                    }
+                    Err(crossbeam_channel::RecvTimeoutError::Timeout) => continue,
+                    Err(crossbeam_channel::RecvTimeoutError::Disconnected) => {
+                        warn!("Normal priority channel disconnected");
+                        return;
+                    }
+                }
             }
-            warn!("Normal priority event processor stopped");
+            info!("Normal priority event processor stopped");
         });
         
         // Low priority processor (market data)
         let low_rx = self.low_priority_rx.clone();
+        let low_queue = self.low_priority.clone();
         let low_subscribers = Arc::clone(&subscribers);
         let low_events_processed = Arc::clone(&events_processed);
+        let low_shutdown = Arc::clone(&self.shutdown);
+        let low_guard = ProcessorGuard::register(&self.running_processors);
         
-        thread::spawn(move || {
+        let low_handle = thread::spawn(move || {
+            let _guard = low_guard;
             info!("Low priority event processor started");
             let mut batch = Vec::with_capacity(batch_size * 2); // Larger batches for market data
             let mut last_batch_time = Instant::now();
             
-            loop {
+            while !low_shutdown.load(Ordering::Relaxed) {
                 // Collect larger batches for market data
                 while batch.len() < batch_size * 2 && last_batch_time.elapsed() < batch_timeout {
                     match low_rx.try_recv() {
-                        Ok(event) => batch.push(event),
+                        Ok(event) => batch.extend(low_queue.resolve(event)),
                         Err(crossbeam_channel::TryRecvError::Empty) => break,
                         Err(crossbeam_channel::TryRecvError::Disconnected) => {
                             warn!("Low priority channel disconnected");
@@ -281,7 +632,7 @@ impl EventBus {
                 // Wait for more events if batch is empty
                 if batch.is_empty() {
                     match low_rx.recv_timeout(batch_timeout) {
-                        Ok(event) => batch.push(event),
+                        Ok(event) => batch.extend(low_queue.resolve(event)),
                         Err(crossbeam_channel::RecvTimeoutError::Timeout) => continue,
                         Err(crossbeam_channel::RecvTimeoutError::Disconnected) => {
                             warn!("Low priority channel disconnected");
@@ -290,40 +641,73 @@ impl EventBus {
                     }
                 }
             }
+            info!("Low priority event processor stopped");
         });
         
         info!("Event bus processing started");
+        vec![high_handle, normal_handle, low_handle]
     }
     
-    fn distribute_event(event: &SystemEvent, subscribers: &DashMap<String, Vec<Sender<SystemEvent>>>) {
+    /// Signals the processors to exit and waits up to `timeout` for them to do so.
+    /// Events still queued are not delivered. Returns false if a processor was still
+    /// running when the timeout expired.
+    pub fn stop_processing(&self, timeout: Duration) -> bool {
+        self.shutdown.store(true, Ordering::SeqCst);
+        
+        let deadline = Instant::now() + timeout;
+        while self.running_processors.load(Ordering::SeqCst) > 0 {
+            if Instant::now() >= deadline {
+                warn!("{} event processor(s) still running after {:?}", self.running_processors.load(Ordering::SeqCst), timeout);
+                return false;
+            }
+            thread::sleep(Duration::from_millis(1));
+        }
+        
+        info!("Event bus processing stopped");
+        true
+    }
+    
+    pub fn running_processors(&self) -> usize {
+        self.running_processors.load(Ordering::SeqCst)
+    }
+    
+    fn distribute_event(event: &SystemEvent, subscribers: &Subscribers) {
         let topics = Self::get_event_topics(event);
         
+        let mut emptied = Vec::new();
+        let mut compact = None;
+        
         for topic in &topics {
-            if let Some(subs) = subscribers.get(topic) {
-                let mut disconnected_indices = Vec::new();
-                
-                for (i, sender) in subs.iter().enumerate() {
-                    match sender.try_send(event.clone()) {
-                        Ok(_) => {},
-                        Err(crossbeam_channel::TrySendError::Full(_)) => {
-                            debug!("Subscriber channel full for topic: {}", topic);
-                        },
-                        Err(crossbeam_channel::TrySendError::Disconnected(_)) => {
-                            disconnected_indices.push(i);
-                        }
-                    }
+            // Write guard on this topic only; sending never touches the map
+            if let Some(mut subs) = subscribers.exact.get_mut(topic) {
+                send_to_subscribers(&mut subs, event, &mut compact, topic);
+                if subs.is_empty() {
+                    emptied.push(topic.clone());
                 }
-                
-                // Clean up disconnected subscribers
-                if !disconnected_indices.is_empty() {
-                    debug!("Cleaning up {} disconnected subscribers for topic: {}", 
-                          disconnected_indices.len(), topic);
+            }
+        }
+        
+        // Each pattern receives the event once, however many of its topics match
+        if !subscribers.patterns.is_empty() {
+            for mut entry in subscribers.patterns.iter_mut() {
+                let pattern = entry.key().clone();
+                if topics.iter().any(|topic| topic_matches(&pattern, topic)) {
+                    send_to_subscribers(entry.value_mut(), event, &mut compact, &pattern);
+                    if entry.value().is_empty() {
+                        emptied.push(pattern);
+                    }
                 }
             }
         }
+        
+        // Guards are released, so the entries can go; a subscriber that joined in
+        // between keeps its entry alive
+        for topic in emptied {
+            subscribers.remove_if_empty(&topic);
+        }
     }
     
-    fn process_event_batch(batch: &[SystemEvent], subscribers: &DashMap<String, Vec<Sender<SystemEvent>>>) {
+    fn process_event_batch(batch: &[SystemEvent], subscribers: &Subscribers) {
         for event in batch {
             Self::distribute_event(event, subscribers);
         }
@@ -333,7 +717,7 @@ impl EventBus {
         let mut topics = vec!["*".to_string()]; // Global topic
         
         match event {
-            SystemEvent::MarketData { symbol, .. } => {
+            SystemEvent::MarketData { symbol, .. } | SystemEvent::TopOfBook { symbol, .. } => {
                 topics.push("market_data".to_string());
                 topics.push(format!("market_data.{}", symbol));
             },
@@ -366,53 +750,114 @@ impl EventBus {
     pub fn get_metrics(&self) -> EventBusMetrics {
         EventBusMetrics {
             events_processed: self.events_processed.load(Ordering::Relaxed),
-            events_dropped: self.events_dropped.load(Ordering::Relaxed),
+            events_dropped: self.events_dropped.total.load(Ordering::Relaxed),
+            high_priority_dropped: self.events_dropped.high.load(Ordering::Relaxed),
+            normal_priority_dropped: self.events_dropped.normal.load(Ordering::Relaxed),
+            low_priority_dropped: self.events_dropped.low.load(Ordering::Relaxed),
             subscriber_count: self.subscribers.len(),
             high_priority_queue_len: self.high_priority_rx.len(),
             normal_priority_queue_len: self.normal_priority_rx.len(),
             low_priority_queue_len: self.low_priority_rx.len(),
+            events_coalesced: self.low_priority.coalesced.load(Ordering::Relaxed),
         }
     }
     
+    /// Shared handles to the processed and dropped counters, for reading them without
+    /// a reference to the bus.
+    pub fn counters(&self) -> (Arc<AtomicU64>, Arc<AtomicU64>) {
+        (Arc::clone(&self.events_processed), Arc::clone(&self.events_dropped.total))
+    }
+
+    /// Subscribers and undelivered events per subscribed topic. Pattern subscriptions
+    /// are listed under their pattern.
+    pub fn get_topic_metrics(&self) -> HashMap<String, TopicMetrics> {
+        self.subscribers.exact.iter()
+            .chain(self.subscribers.patterns.iter())
+            .map(|entry| {
+                let metrics = TopicMetrics {
+                    subscriber_count: entry.value().len(),
+                    queued_events: entry.value().iter().map(|subscriber| subscriber.tx.len()).sum(),
+                };
+                (entry.key().clone(), metrics)
+            })
+            .collect()
+    }
+
     pub fn get_publisher(&self) -> EventPublisher {
         EventPublisher {
             high_priority_tx: self.high_priority_tx.clone(),
             normal_priority_tx: self.normal_priority_tx.clone(),
-            low_priority_tx: self.low_priority_tx.clone(),
+            low_priority: self.low_priority.clone(),
+            blocking_timeout: Duration::from_millis(self.config.blocking_publish_timeout_ms),
+            journal: self.journal.clone(),
+            dropped: self.config.enable_metrics.then(|| self.events_dropped.clone()),
+        }
+    }
+    
+    /// Re-publishes a journal file onto this bus with its original relative timing,
+    /// blocking until done. Replayed events are not journaled again.
+    pub fn replay_from(&self, path: impl AsRef<Path>) -> io::Result<usize> {
+        let mut publisher = self.get_publisher();
+        publisher.journal = None;
+        EventJournal::replay(path, &publisher)
+    }
+}
+
+impl Drop for EventBus {
+    fn drop(&mut self) {
+        if self.running_processors() > 0 {
+            self.stop_processing(Duration::from_secs(1));
         }
     }
 }
 
+// Counts a processor as running until its thread exits, panics included
+struct ProcessorGuard(Arc<AtomicUsize>);
+
+impl ProcessorGuard {
+    fn register(running: &Arc<AtomicUsize>) -> Self {
+        running.fetch_add(1, Ordering::SeqCst);
+        Self(Arc::clone(running))
+    }
+}
+
+impl Drop for ProcessorGuard {
+    fn drop(&mut self) {
+        self.0.fetch_sub(1, Ordering::SeqCst);
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct EventPublisher {
     high_priority_tx: Sender<SystemEvent>,
     normal_priority_tx: Sender<SystemEvent>,
-    low_priority_tx: Sender<SystemEvent>,
+    low_priority: LowPriorityQueue,
+    blocking_timeout: Duration,
+    journal: Option<EventJournal>,
+    dropped: Option<DropCounters>, // None when the bus has metrics disabled
 }
 
 impl EventPublisher {
-    pub fn publish(&self, event: SystemEvent) -> Result<(), String> {
-        let result = match event.priority() {
-            EventPriority::Critical | EventPriority::High => {
-                self.high_priority_tx.try_send(event)
-            }
-            EventPriority::Normal => {
-                self.normal_priority_tx.try_send(event)
-            }
-            EventPriority::Low => {
-                self.low_priority_tx.try_send(event)
-            }
-        };
-        
-        match result {
-            Ok(_) => Ok(()),
-            Err(crossbeam_channel::TrySendError::Full(_)) => {
-                Err("Channel full".to_string())
-            }
-            Err(crossbeam_channel::TrySendError::Disconnected(_)) => {
-                Err("Channel disconnected".to_string())
+    pub fn publish(&self, event: SystemEvent) -> Result<(), PublishError> {
+        let priority = event.priority();
+        let journaled = self.journal.as_ref().map(|journal| (journal, event.clone()));
+        let result = send_by_priority(
+            &self.high_priority_tx,
+            &self.normal_priority_tx,
+            &self.low_priority,
+            self.blocking_timeout,
+            event,
+        );
+        match (&result, journaled) {
+            (Ok(_), Some((journal, event))) => journal.record(event),
+            (Err(PublishError::ChannelFull | PublishError::Timeout(_)), _) => {
+                if let Some(dropped) = &self.dropped {
+                    dropped.record(priority);
+                }
             }
+            _ => {}
         }
+        result
     }
 }
 
@@ -420,8 +865,360 @@ impl EventPublisher {
 pub struct EventBusMetrics {
     pub events_processed: u64,
     pub events_dropped: u64,
+    pub high_priority_dropped: u64, // Critical and High
+    pub normal_priority_dropped: u64,
+    pub low_priority_dropped: u64,
     pub subscriber_count: usize,
     pub high_priority_queue_len: usize,
     pub normal_priority_queue_len: usize,
     pub low_priority_queue_len: usize,
+    pub events_coalesced: u64, // Market data superseded before delivery under CoalesceBySymbol
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct TopicMetrics {
+    pub subscriber_count: usize,
+    pub queued_events: usize, // Sent to the topic's subscribers but not yet received
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::hl_msgs::TobMsg;
+    use crate::trading::order_manager::OrderEvent;
+    use uuid::Uuid;
+
+    fn book(coin: &str) -> SystemEvent {
+        book_at(coin, 1)
+    }
+
+    fn book_at(coin: &str, time: u64) -> SystemEvent {
+        let data: TobMsg = serde_json::from_value(serde_json::json!({
+            "channel": "l2Book",
+            "data": {
+                "coin": coin,
+                "time": time,
+                "levels": [[{"px": "25.0", "sz": "10", "n": 1}], [{"px": "25.1", "sz": "5", "n": 1}]],
+            },
+        })).unwrap();
+        SystemEvent::new_market_data(coin.to_string(), data)
+    }
+
+    fn topics_of(rx: &Receiver<SystemEvent>) -> Vec<String> {
+        rx.try_iter()
+            .map(|event| match event {
+                SystemEvent::MarketData { symbol, .. } => format!("market_data.{}", symbol),
+                SystemEvent::Order(_) => "orders".to_string(),
+                SystemEvent::Risk { symbol, .. } => format!("risk.{}", symbol),
+                SystemEvent::Strategy { strategy_name, .. } => format!("strategy.{}", strategy_name),
+                other => panic!("unexpected event {:?}", other),
+            })
+            .collect()
+    }
+
+    #[test]
+    fn wildcard_and_exact_subscriptions_receive_their_share_of_a_mixed_stream() {
+        let bus = EventBus::new(EventBusConfig::default());
+        let everything = bus.subscribe("*");
+        let market_data = bus.subscribe("market_data.*");
+        let orders = bus.subscribe("orders");
+        let risk = bus.subscribe("risk.*");
+
+        let events = [
+            book("HYPE"),
+            SystemEvent::Order(OrderEvent::OrderCancelled(Uuid::new_v4())),
+            book("BTC"),
+            SystemEvent::new_risk_event("HYPE".to_string(), RiskEvent::PnlWarning {
+                current_pnl: "-90".to_string(),
+                limit: "-100".to_string(),
+            }),
+            SystemEvent::new_strategy_event("mm_hype".to_string(), StrategyEvent::Started),
+        ];
+        for event in &events {
+            EventBus::distribute_event(event, &bus.subscribers);
+        }
+
+        assert_eq!(topics_of(&everything), ["market_data.HYPE", "orders", "market_data.BTC", "risk.HYPE", "strategy.mm_hype"]);
+        // Each book matches under two of its topics but is delivered once
+        assert_eq!(topics_of(&market_data), ["market_data.HYPE", "market_data.BTC"]);
+        assert_eq!(topics_of(&orders), ["orders"]);
+        assert_eq!(topics_of(&risk), ["risk.HYPE"]);
+    }
+
+    #[test]
+    fn glob_patterns_match_whole_topics() {
+        assert!(topic_matches("market_data.*", "market_data.HYPE"));
+        assert!(topic_matches("*", "strategy.mm_hype"));
+        assert!(topic_matches("risk.???E", "risk.HYPE"));
+        assert!(!topic_matches("market_data.*", "market_data"));
+        assert!(!topic_matches("risk.*", "strategy.risk.HYPE"));
+        assert!(!topic_matches("orders", "orders.HYPE"));
+    }
+
+    fn error(component: &str) -> SystemEvent {
+        SystemEvent::new_system_event(SystemLevelEvent::Error {
+            component: component.to_string(),
+            error: "failed".to_string(),
+        })
+    }
+
+    #[test]
+    fn a_critical_event_waits_for_room_and_times_out_when_none_frees_up() {
+        let bus = EventBus::new(EventBusConfig {
+            high_priority_buffer_size: 1,
+            normal_priority_buffer_size: 1,
+            blocking_publish_timeout_ms: 50,
+            ..EventBusConfig::default()
+        });
+        bus.publish(error("first")).unwrap();
+
+        let start = Instant::now();
+        assert_eq!(bus.publish(error("second")), Err(PublishError::Timeout(Duration::from_millis(50))));
+        assert!(start.elapsed() >= Duration::from_millis(50));
+        assert_eq!(bus.get_metrics().high_priority_dropped, 1);
+
+        // Room made while it waits lets it through
+        let high_priority_rx = bus.high_priority_rx.clone();
+        let consumer = thread::spawn(move || {
+            thread::sleep(Duration::from_millis(10));
+            high_priority_rx.recv().unwrap()
+        });
+        bus.get_publisher().publish(error("third")).unwrap();
+        consumer.join().unwrap();
+
+        // Normal events never wait
+        let order = || SystemEvent::Order(OrderEvent::OrderCancelled(Uuid::new_v4()));
+        bus.publish(order()).unwrap();
+        let start = Instant::now();
+        assert_eq!(bus.publish(order()), Err(PublishError::ChannelFull));
+        assert!(start.elapsed() < Duration::from_millis(50));
+        assert_eq!(bus.get_metrics().normal_priority_dropped, 1);
+    }
+
+    #[test]
+    fn a_dropped_subscriber_is_pruned_on_the_next_event_for_its_topic() {
+        let bus = EventBus::new(EventBusConfig::default());
+        let kept = bus.subscribe("orders");
+        let dropped = bus.subscribe("orders");
+        let market_data = bus.subscribe("market_data.*");
+        drop(dropped);
+        assert_eq!(bus.get_metrics().subscriber_count, 3);
+
+        // Events for other topics leave it alone
+        EventBus::distribute_event(&book("HYPE"), &bus.subscribers);
+        assert_eq!(bus.get_metrics().subscriber_count, 3);
+
+        EventBus::distribute_event(&SystemEvent::Order(OrderEvent::OrderCancelled(Uuid::new_v4())), &bus.subscribers);
+        assert_eq!(bus.get_metrics().subscriber_count, 2);
+        assert_eq!(topics_of(&kept), ["orders"]);
+
+        // The topic itself goes with its last subscriber
+        drop(kept);
+        EventBus::distribute_event(&SystemEvent::Order(OrderEvent::OrderCancelled(Uuid::new_v4())), &bus.subscribers);
+        assert!(!bus.get_topic_metrics().contains_key("orders"));
+        drop(market_data);
+        EventBus::distribute_event(&book("HYPE"), &bus.subscribers);
+        assert!(bus.get_topic_metrics().is_empty());
+    }
+
+    #[test]
+    fn subscriber_counts_track_random_subscribes_drops_and_unsubscribes() {
+        use rand::{rngs::StdRng, Rng, SeedableRng};
+
+        #[derive(PartialEq)]
+        enum State { Live, Dropped, Gone }
+
+        let topics = ["*", "market_data.*", "market_data.HYPE", "orders", "risk.*", "system"];
+        let events = [
+            book("HYPE"),
+            book("BTC"),
+            SystemEvent::Order(OrderEvent::OrderCancelled(Uuid::new_v4())),
+            SystemEvent::new_risk_event("HYPE".to_string(), RiskEvent::PnlWarning { current_pnl: "-90".to_string(), limit: "-100".to_string() }),
+            SystemEvent::new_system_event(SystemLevelEvent::Startup),
+        ];
+        let bus = EventBus::new(EventBusConfig::default());
+        let mut rng = StdRng::seed_from_u64(7);
+        let mut subs: Vec<(Subscription, Option<Receiver<SystemEvent>>, State)> = Vec::new();
+
+        for _ in 0..1000 {
+            match rng.gen_range(0..4) {
+                0 => {
+                    let (subscription, rx) = bus.subscribe_with_handle(topics[rng.gen_range(0..topics.len())]);
+                    subs.push((subscription, Some(rx), State::Live));
+                }
+                1 if !subs.is_empty() => {
+                    let index = rng.gen_range(0..subs.len());
+                    let (_, rx, state) = &mut subs[index];
+                    if *state == State::Live {
+                        *rx = None;
+                        *state = State::Dropped;
+                    }
+                }
+                2 if !subs.is_empty() => {
+                    let index = rng.gen_range(0..subs.len());
+                    let (subscription, rx, state) = &mut subs[index];
+                    assert_eq!(bus.unsubscribe(subscription), *state != State::Gone);
+                    *rx = None;
+                    *state = State::Gone;
+                }
+                _ => {
+                    let event = &events[rng.gen_range(0..events.len())];
+                    EventBus::distribute_event(event, &bus.subscribers);
+                    let topics = EventBus::get_event_topics(event);
+                    for (subscription, rx, state) in &mut subs {
+                        let hit = topics.iter().any(|topic| *topic == subscription.topic || topic_matches(&subscription.topic, topic));
+                        match state {
+                            State::Live => assert_eq!(rx.as_ref().unwrap().try_iter().count(), hit as usize),
+                            State::Dropped if hit => *state = State::Gone,
+                            _ => {}
+                        }
+                    }
+                }
+            }
+
+            let mut expected: HashMap<String, usize> = HashMap::new();
+            for (subscription, _, state) in &subs {
+                if *state != State::Gone {
+                    *expected.entry(subscription.topic.clone()).or_default() += 1;
+                }
+            }
+            let counts: HashMap<String, usize> = bus.get_topic_metrics().into_iter()
+                .map(|(topic, metrics)| (topic, metrics.subscriber_count))
+                .collect();
+            assert_eq!(counts, expected);
+            assert_eq!(bus.get_metrics().subscriber_count, expected.values().sum::<usize>());
+        }
+    }
+
+    #[test]
+    fn topic_metrics_count_subscribers_and_their_undelivered_events() {
+        let bus = EventBus::new(EventBusConfig::default());
+        let (first, first_rx) = bus.subscribe_with_handle("orders");
+        let _second_rx = bus.subscribe("orders");
+        let _market_data = bus.subscribe("market_data.*");
+        for _ in 0..3 {
+            EventBus::distribute_event(&SystemEvent::Order(OrderEvent::OrderCancelled(Uuid::new_v4())), &bus.subscribers);
+        }
+
+        let metrics = bus.get_topic_metrics();
+        assert_eq!(metrics["orders"], TopicMetrics { subscriber_count: 2, queued_events: 6 });
+        assert_eq!(metrics["market_data.*"], TopicMetrics { subscriber_count: 1, queued_events: 0 });
+        assert_eq!(bus.get_metrics().subscriber_count, 3);
+
+        assert_eq!(first_rx.try_iter().count(), 3);
+        assert_eq!(bus.get_topic_metrics()["orders"].queued_events, 3);
+        assert!(bus.unsubscribe(&first));
+        assert_eq!(bus.get_topic_metrics()["orders"], TopicMetrics { subscriber_count: 1, queued_events: 3 });
+        assert_eq!(bus.get_metrics().subscriber_count, 2);
+    }
+
+    // Market data queued on the low priority channel, as the processor would deliver it
+    fn queued_books(bus: &EventBus) -> Vec<(String, u64)> {
+        bus.low_priority_rx.try_iter()
+            .filter_map(|event| bus.low_priority.resolve(event))
+            .map(|event| match event {
+                SystemEvent::MarketData { symbol, data, .. } => (symbol, data.data.time),
+                other => panic!("unexpected event {:?}", other),
+            })
+            .collect()
+    }
+
+    fn low_priority_bus(policy: OverflowPolicy) -> EventBus {
+        EventBus::new(EventBusConfig { market_data_buffer_size: 2, low_priority_overflow: policy, ..EventBusConfig::default() })
+    }
+
+    #[test]
+    fn drop_newest_keeps_what_is_queued() {
+        let bus = low_priority_bus(OverflowPolicy::DropNewest);
+        bus.publish(book_at("HYPE", 1)).unwrap();
+        bus.publish(book_at("BTC", 1)).unwrap();
+        assert_eq!(bus.publish(book_at("HYPE", 2)), Err(PublishError::ChannelFull));
+
+        assert_eq!(queued_books(&bus), [("HYPE".to_string(), 1), ("BTC".to_string(), 1)]);
+        assert_eq!(bus.get_metrics().low_priority_dropped, 1);
+    }
+
+    #[test]
+    fn drop_oldest_makes_room_for_the_newest() {
+        let bus = low_priority_bus(OverflowPolicy::DropOldest);
+        bus.publish(book_at("HYPE", 1)).unwrap();
+        bus.publish(book_at("BTC", 1)).unwrap();
+        bus.publish(book_at("HYPE", 2)).unwrap();
+
+        assert_eq!(queued_books(&bus), [("BTC".to_string(), 1), ("HYPE".to_string(), 2)]);
+        assert_eq!(bus.get_metrics().low_priority_dropped, 1);
+    }
+
+    #[test]
+    fn coalescing_delivers_the_latest_update_per_symbol_once() {
+        let bus = low_priority_bus(OverflowPolicy::CoalesceBySymbol);
+        for (coin, time) in [("HYPE", 1), ("BTC", 1), ("HYPE", 2), ("HYPE", 3), ("BTC", 2)] {
+            bus.publish(book_at(coin, time)).unwrap();
+        }
+
+        assert_eq!(queued_books(&bus), [("HYPE".to_string(), 3), ("BTC".to_string(), 2)]);
+        let metrics = bus.get_metrics();
+        assert_eq!(metrics.events_coalesced, 3);
+        assert_eq!(metrics.low_priority_dropped, 0);
+
+        // Once delivered, the next update for the symbol is queued afresh
+        bus.publish(book_at("HYPE", 4)).unwrap();
+        assert_eq!(queued_books(&bus), [("HYPE".to_string(), 4)]);
+    }
+
+    #[test]
+    fn stopping_ends_every_processor_and_the_bus_can_start_again() {
+        let bus = EventBus::new(EventBusConfig::default());
+        let system = bus.subscribe("system");
+
+        let handles = bus.start_processing();
+        assert_eq!(bus.running_processors(), 3);
+        assert!(bus.stop_processing(Duration::from_secs(1)));
+        assert_eq!(bus.running_processors(), 0);
+        for handle in handles {
+            handle.join().unwrap();
+        }
+
+        bus.start_processing();
+        assert_eq!(bus.running_processors(), 3);
+        bus.publish(error("restarted")).unwrap();
+        assert!(matches!(system.recv_timeout(Duration::from_secs(1)), Ok(SystemEvent::System { .. })));
+
+        // Dropping a running bus stops it too
+        let running = Arc::clone(&bus.running_processors);
+        drop(bus);
+        assert_eq!(running.load(Ordering::SeqCst), 0);
+    }
+
+    #[test]
+    fn top_of_book_subscribers_get_the_compact_form_and_others_the_full_book() {
+        let bus = EventBus::new(EventBusConfig::default());
+        let (_, compact) = bus.subscribe_top_of_book("market_data.*");
+        let (_, compact_everything) = bus.subscribe_top_of_book("*");
+        let full = bus.subscribe("market_data.HYPE");
+
+        EventBus::distribute_event(&book_at("HYPE", 7), &bus.subscribers);
+        EventBus::distribute_event(&SystemEvent::Order(OrderEvent::OrderCancelled(Uuid::new_v4())), &bus.subscribers);
+
+        let expected = TopOfBook {
+            coin: "HYPE".to_string(),
+            bid: Some(rust_decimal::Decimal::new(250, 1)),
+            bid_sz: Some(rust_decimal::Decimal::from(10)),
+            ask: Some(rust_decimal::Decimal::new(251, 1)),
+            ask_sz: Some(rust_decimal::Decimal::from(5)),
+            time: 7,
+        };
+        for rx in [&compact, &compact_everything] {
+            match rx.try_recv() {
+                Ok(SystemEvent::TopOfBook { symbol, data, .. }) => {
+                    assert_eq!(symbol, "HYPE");
+                    assert_eq!(data, expected);
+                }
+                other => panic!("expected top of book, got {:?}", other),
+            }
+        }
+        // Anything that isn't market data arrives unchanged
+        assert!(matches!(compact_everything.try_recv(), Ok(SystemEvent::Order(_))));
+        assert!(matches!(full.try_recv(), Ok(SystemEvent::MarketData { .. })));
+    }
 }
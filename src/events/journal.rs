@@ -0,0 +1,243 @@
+use crate::events::event_bus::{EventPublisher, PublishError};
+use crate::events::types::SystemEvent;
+use crossbeam_channel::{Sender, unbounded};
+use serde::{Deserialize, Serialize};
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, BufRead, BufReader, BufWriter, Write};
+use std::path::{Path, PathBuf};
+use std::thread;
+use std::time::Duration;
+use tracing::{error, info, warn};
+
+#[derive(Debug, Clone)]
+pub struct JournalConfig {
+    pub path: PathBuf,
+    pub max_file_size_bytes: u64, // Rotate once the active file would grow past this
+    pub max_rotated_files: usize, // Older files beyond `path.N` are deleted
+}
+
+impl Default for JournalConfig {
+    fn default() -> Self {
+        Self {
+            path: PathBuf::from("logs/events.jsonl"),
+            max_file_size_bytes: 100 * 1024 * 1024,
+            max_rotated_files: 5,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct JournalEntry {
+    pub recorded_at_ms: i64,
+    pub event: SystemEvent,
+}
+
+/// Appends published events to a newline-delimited JSON file.
+///
+/// Writes happen on a dedicated thread so publishers only pay for a channel send.
+/// Events are written in the order they were recorded. When the active file would
+/// exceed `max_file_size_bytes` it is renamed to `path.1`, shifting older files up.
+#[derive(Debug, Clone)]
+pub struct EventJournal {
+    entries_tx: Sender<JournalEntry>,
+}
+
+impl EventJournal {
+    pub fn start(config: JournalConfig) -> io::Result<Self> {
+        if let Some(dir) = config.path.parent().filter(|dir| !dir.as_os_str().is_empty()) {
+            fs::create_dir_all(dir)?;
+        }
+        let mut writer = JournalWriter::open(config)?;
+        let (entries_tx, entries_rx) = unbounded::<JournalEntry>();
+
+        thread::spawn(move || {
+            while let Ok(entry) = entries_rx.recv() {
+                if let Err(e) = writer.write(&entry) {
+                    error!("Failed to write event journal entry: {}", e);
+                }
+
+                // Flush at the end of each burst so the file stays current
+                if entries_rx.is_empty() {
+                    if let Err(e) = writer.flush() {
+                        error!("Failed to flush event journal: {}", e);
+                    }
+                }
+            }
+            let _ = writer.flush();
+        });
+
+        Ok(Self { entries_tx })
+    }
+
+    pub fn record(&self, event: SystemEvent) {
+        let entry = JournalEntry {
+            recorded_at_ms: chrono::Utc::now().timestamp_millis(),
+            event,
+        };
+        if self.entries_tx.send(entry).is_err() {
+            warn!("Event journal writer stopped, event not recorded");
+        }
+    }
+
+    pub fn read_entries(path: impl AsRef<Path>) -> io::Result<Vec<JournalEntry>> {
+        let reader = BufReader::new(File::open(path)?);
+        let mut entries = Vec::new();
+
+        for (line_number, line) in reader.lines().enumerate() {
+            let line = line?;
+            if line.trim().is_empty() {
+                continue;
+            }
+            // A crash can leave a partial last line; skip it rather than failing the replay
+            match serde_json::from_str(&line) {
+                Ok(entry) => entries.push(entry),
+                Err(e) => warn!("Skipping malformed journal line {}: {}", line_number + 1, e),
+            }
+        }
+
+        Ok(entries)
+    }
+
+    /// Re-publishes every event in the journal at `path` in order, sleeping between
+    /// events to reproduce their original relative timing. Blocks until done and
+    /// returns the number of events published.
+    pub fn replay(path: impl AsRef<Path>, publisher: &EventPublisher) -> io::Result<usize> {
+        let entries = Self::read_entries(path.as_ref())?;
+        let mut previous_at: Option<i64> = None;
+        let mut published = 0;
+
+        for entry in entries {
+            if let Some(previous_at) = previous_at {
+                let gap_ms = (entry.recorded_at_ms - previous_at).max(0) as u64;
+                if gap_ms > 0 {
+                    thread::sleep(Duration::from_millis(gap_ms));
+                }
+            }
+            previous_at = Some(entry.recorded_at_ms);
+
+            match publisher.publish(entry.event) {
+                Ok(_) => published += 1,
+                Err(PublishError::Disconnected) => {
+                    return Err(io::Error::new(io::ErrorKind::BrokenPipe, "Event bus disconnected during replay"));
+                }
+                Err(e) => warn!("Replayed event not delivered: {}", e),
+            }
+        }
+
+        info!("Replayed {} events from {}", published, path.as_ref().display());
+        Ok(published)
+    }
+}
+
+// Shared with the market data recorder, which uses the same file layout
+pub(crate) struct JournalWriter {
+    config: JournalConfig,
+    file: BufWriter<File>,
+    bytes_written: u64,
+}
+
+impl JournalWriter {
+    pub(crate) fn open(config: JournalConfig) -> io::Result<Self> {
+        let file = OpenOptions::new().create(true).append(true).open(&config.path)?;
+        let bytes_written = file.metadata()?.len();
+
+        Ok(Self {
+            config,
+            file: BufWriter::new(file),
+            bytes_written,
+        })
+    }
+
+    pub(crate) fn write<T: Serialize>(&mut self, entry: &T) -> io::Result<()> {
+        let mut line = serde_json::to_vec(entry)?;
+        line.push(b'\n');
+
+        if self.bytes_written > 0 && self.bytes_written + line.len() as u64 > self.config.max_file_size_bytes {
+            self.rotate()?;
+        }
+
+        self.file.write_all(&line)?;
+        self.bytes_written += line.len() as u64;
+        Ok(())
+    }
+
+    pub(crate) fn flush(&mut self) -> io::Result<()> {
+        self.file.flush()
+    }
+
+    fn rotate(&mut self) -> io::Result<()> {
+        self.file.flush()?;
+
+        let rotated = |n: usize| PathBuf::from(format!("{}.{}", self.config.path.display(), n));
+        if self.config.max_rotated_files == 0 {
+            fs::remove_file(&self.config.path)?;
+        } else {
+            let _ = fs::remove_file(rotated(self.config.max_rotated_files));
+            for n in (1..self.config.max_rotated_files).rev() {
+                let from = rotated(n);
+                if from.exists() {
+                    fs::rename(&from, rotated(n + 1))?;
+                }
+            }
+            fs::rename(&self.config.path, rotated(1))?;
+        }
+
+        let file = OpenOptions::new().create(true).append(true).open(&self.config.path)?;
+        self.file = BufWriter::new(file);
+        self.bytes_written = 0;
+        info!("Rotated {}", self.config.path.display());
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::events::event_bus::{EventBus, EventBusConfig};
+    use crate::events::types::StrategyEvent;
+
+    fn strategy_names(events: impl Iterator<Item = SystemEvent>) -> Vec<String> {
+        events
+            .map(|event| match event {
+                SystemEvent::Strategy { strategy_name, .. } => strategy_name,
+                other => panic!("unexpected event {:?}", other),
+            })
+            .collect()
+    }
+
+    #[test]
+    fn a_journaled_session_replays_every_event_in_order() {
+        let dir = std::env::temp_dir().join(format!("hl_journal_{}", uuid::Uuid::new_v4()));
+        let path = dir.join("events.jsonl");
+        let bus = EventBus::new(EventBusConfig {
+            journal: Some(JournalConfig { path: path.clone(), ..JournalConfig::default() }),
+            ..EventBusConfig::default()
+        });
+
+        let names: Vec<String> = (0..100).map(|i| format!("mm_{}", i)).collect();
+        for name in &names {
+            bus.publish(SystemEvent::new_strategy_event(name.clone(), StrategyEvent::Started)).unwrap();
+        }
+        for _ in 0..100 {
+            if fs::read_to_string(&path).map(|content| content.lines().count()).unwrap_or(0) == 100 {
+                break;
+            }
+            thread::sleep(Duration::from_millis(10));
+        }
+        // A partial line, as a crash mid-write leaves, is skipped
+        fs::OpenOptions::new().append(true).open(&path).unwrap().write_all(b"{\"recorded_at_ms\":").unwrap();
+        assert_eq!(strategy_names(EventJournal::read_entries(&path).unwrap().into_iter().map(|entry| entry.event)), names);
+
+        let strategies = bus.subscribe("strategy");
+        bus.start_processing();
+        assert_eq!(bus.replay_from(&path).unwrap(), 100);
+        let replayed = strategy_names((0..100).map(|_| strategies.recv_timeout(Duration::from_secs(1)).unwrap()));
+        assert_eq!(replayed, names);
+
+        // Replayed events are not journaled a second time
+        thread::sleep(Duration::from_millis(50));
+        assert_eq!(EventJournal::read_entries(&path).unwrap().len(), 100);
+        drop(bus);
+        let _ = fs::remove_dir_all(&dir);
+    }
+}
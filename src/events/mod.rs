@@ -1,2 +1,4 @@
+pub mod alerts;
 pub mod event_bus;
+pub mod journal;
 pub mod types;
\ No newline at end of file
@@ -3,7 +3,9 @@ use crate::trading::order_manager::OrderEvent;
 use crate::trading::position_manager::PositionEvent;
 use crate::model::hl_msgs::TobMsg;
 use chrono::{DateTime, Utc};
+use rust_decimal::Decimal;
 use serde::{Deserialize, Serialize};
+use std::str::FromStr;
 use uuid::Uuid;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -14,6 +16,12 @@ pub enum SystemEvent {
         data: TobMsg,
         timestamp: DateTime<Utc>,
     },
+    // A MarketData event cut down to its best levels, for subscribers that opted in
+    TopOfBook {
+        symbol: String,
+        data: TopOfBook,
+        timestamp: DateTime<Utc>,
+    },
     
     // Order events
     Order(OrderEvent),
@@ -49,6 +57,37 @@ pub enum SystemEvent {
     },
 }
 
+/// Best bid and ask of a book update. A side with no levels is None.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TopOfBook {
+    pub coin: String,
+    pub bid: Option<Decimal>,
+    pub bid_sz: Option<Decimal>,
+    pub ask: Option<Decimal>,
+    pub ask_sz: Option<Decimal>,
+    pub time: u64,
+}
+
+impl TopOfBook {
+    pub fn from_book(book: &TobMsg) -> Self {
+        let best = |side: usize| {
+            book.data.levels.get(side)
+                .and_then(|levels| levels.first())
+                .map(|level| (Decimal::from_str(&level.px).ok(), Decimal::from_str(&level.sz).ok()))
+                .unwrap_or((None, None))
+        };
+        let ((bid, bid_sz), (ask, ask_sz)) = (best(0), best(1));
+        Self {
+            coin: book.data.coin.clone(),
+            bid,
+            bid_sz,
+            ask,
+            ask_sz,
+            time: book.data.time,
+        }
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum StrategyEvent {
     Started,
@@ -103,6 +142,17 @@ pub enum SystemLevelEvent {
         component: String,
         error: String,
     },
+    MarketDataLost {
+        connections: usize, // Redundant connections that all failed
+    },
+    MarketDataRestored {
+        connections: usize,
+    },
+    // Account activity reported by the exchange outside our own order flow
+    ExchangeNotice {
+        kind: String, // e.g. "order_update:rejected" or "order_rejected"; repeats of a kind throttle together
+        message: String,
+    },
 }
 
 #[derive(Debug, Clone)]
@@ -113,7 +163,7 @@ pub struct EventMetadata {
     pub priority: EventPriority,
 }
 
-#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
 pub enum EventPriority {
     Low,
     Normal,
@@ -165,18 +215,20 @@ impl SystemEvent {
         match self {
             Self::Risk { .. } => EventPriority::High,
             Self::System { event: SystemLevelEvent::Error { .. }, .. } => EventPriority::Critical,
+            Self::System { event: SystemLevelEvent::MarketDataLost { .. }, .. } => EventPriority::Critical,
+            Self::System { event: SystemLevelEvent::ExchangeNotice { .. }, .. } => EventPriority::High,
             Self::Connection { event: ConnectionEvent::Error(_), .. } => EventPriority::High,
             Self::Strategy { event: StrategyEvent::Error(_), .. } => EventPriority::High,
             Self::Order(_) => EventPriority::Normal,
             Self::Position(_) => EventPriority::Normal,
-            Self::MarketData { .. } => EventPriority::Low,
+            Self::MarketData { .. } | Self::TopOfBook { .. } => EventPriority::Low,
             _ => EventPriority::Normal,
         }
     }
     
     pub fn source(&self) -> String {
         match self {
-            Self::MarketData { .. } => "market_data".to_string(),
+            Self::MarketData { .. } | Self::TopOfBook { .. } => "market_data".to_string(),
             Self::Order(_) => "order_manager".to_string(),
             Self::Position(_) => "position_manager".to_string(),
             Self::Strategy { strategy_name, .. } => format!("strategy:{}", strategy_name),
@@ -3,7 +3,10 @@ pub mod clients;
 pub mod config;
 pub mod datastructures;
 pub mod events;
+pub mod metrics;
 pub mod model;
+pub mod reporting;
+pub mod storage;
 pub mod strategies;
 pub mod trading;
 pub mod ui;
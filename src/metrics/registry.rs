@@ -0,0 +1,271 @@
+use dashmap::DashMap;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::fmt::Write;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::Duration;
+
+// Upper bounds in seconds for order round-trip latencies
+const LATENCY_BUCKETS: [f64; 10] = [0.005, 0.01, 0.025, 0.05, 0.1, 0.25, 0.5, 1.0, 2.5, 5.0];
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MetricsConfig {
+    pub enabled: bool,
+    pub bind_address: String,
+    pub port: u16,
+}
+
+impl Default for MetricsConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            bind_address: "0.0.0.0".to_string(),
+            port: 9090,
+        }
+    }
+}
+
+#[derive(Debug, Default)]
+pub struct Counter(AtomicU64);
+
+impl Counter {
+    pub fn inc(&self) {
+        self.0.fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// Mirrors a count kept elsewhere; the source must itself only increase.
+    pub fn set(&self, value: u64) {
+        self.0.store(value, Ordering::Relaxed);
+    }
+
+    pub fn get(&self) -> u64 {
+        self.0.load(Ordering::Relaxed)
+    }
+}
+
+/// An f64 stored as its bit pattern so it can be set without a lock.
+#[derive(Debug, Default)]
+pub struct Gauge(AtomicU64);
+
+impl Gauge {
+    pub fn set(&self, value: f64) {
+        self.0.store(value.to_bits(), Ordering::Relaxed);
+    }
+
+    pub fn get(&self) -> f64 {
+        f64::from_bits(self.0.load(Ordering::Relaxed))
+    }
+}
+
+#[derive(Debug, Default)]
+pub struct Histogram {
+    buckets: [AtomicU64; LATENCY_BUCKETS.len()],
+    sum_micros: AtomicU64,
+    count: AtomicU64,
+}
+
+impl Histogram {
+    pub fn observe(&self, elapsed: Duration) {
+        let seconds = elapsed.as_secs_f64();
+        if let Some(bucket) = LATENCY_BUCKETS.iter().position(|bound| seconds <= *bound) {
+            self.buckets[bucket].fetch_add(1, Ordering::Relaxed);
+        }
+        self.sum_micros.fetch_add(elapsed.as_micros() as u64, Ordering::Relaxed);
+        self.count.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub fn count(&self) -> u64 {
+        self.count.load(Ordering::Relaxed)
+    }
+
+    /// Upper bound of the bucket holding the `q` quantile, in seconds. Infinite when
+    /// it falls past the last bucket, `None` before anything was observed.
+    pub fn quantile(&self, q: f64) -> Option<f64> {
+        let count = self.count();
+        if count == 0 {
+            return None;
+        }
+
+        let rank = ((q * count as f64).ceil() as u64).max(1);
+        let mut cumulative = 0;
+        for (bound, bucket) in LATENCY_BUCKETS.iter().zip(self.buckets.iter()) {
+            cumulative += bucket.load(Ordering::Relaxed);
+            if cumulative >= rank {
+                return Some(*bound);
+            }
+        }
+        Some(f64::INFINITY)
+    }
+
+    pub fn summary(&self) -> LatencySummary {
+        LatencySummary {
+            count: self.count(),
+            p50: self.quantile(0.50),
+            p95: self.quantile(0.95),
+            p99: self.quantile(0.99),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct LatencySummary {
+    pub count: u64,
+    pub p50: Option<f64>,
+    pub p95: Option<f64>,
+    pub p99: Option<f64>,
+}
+
+impl fmt::Display for LatencySummary {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let ms = |value: Option<f64>| match value {
+            Some(seconds) if seconds.is_finite() => format!("<={}ms", seconds * 1000.0),
+            Some(_) => format!(">{}ms", LATENCY_BUCKETS[LATENCY_BUCKETS.len() - 1] * 1000.0),
+            None => "n/a".to_string(),
+        };
+        write!(f, "count={} p50={} p95={} p99={}", self.count, ms(self.p50), ms(self.p95), ms(self.p99))
+    }
+}
+
+#[derive(Debug, Default)]
+pub struct ConnectionMetrics {
+    pub messages: Counter,
+    pub reconnects: Counter,
+    pub invalid_messages: Counter, // Parsed but failed TobMsg::validate
+    pub exchange_skew: Histogram, // Local receive time minus the message's exchange timestamp
+}
+
+#[derive(Debug, Default)]
+pub struct SymbolMetrics {
+    pub inventory: Gauge,
+    pub unrealized_pnl: Gauge,
+    pub risk_score: Gauge,
+    pub book_update_rate: Gauge, // Book updates per second seen by the strategy
+}
+
+/// Instruments shared by the components that update them and the exporter that
+/// renders them. Updates are single atomic operations so the hot paths never wait on
+/// a scrape, and a scrape never touches the managers the values come from.
+#[derive(Debug, Default)]
+pub struct Metrics {
+    pub events_processed: Counter,
+    pub events_dropped: Counter,
+    pub market_data_stale_dropped: Counter, // Book updates past max_market_data_age_ms
+    pub orders_placed: Counter,
+    pub orders_cancelled: Counter,
+    pub orders_rejected: Counter,
+    pub place_latency: Histogram,
+    pub cancel_latency: Histogram,
+    pub tick_to_trade: Histogram, // Market data receipt to order submission
+    connections: DashMap<String, Arc<ConnectionMetrics>>,
+    symbols: DashMap<String, Arc<SymbolMetrics>>,
+}
+
+impl Metrics {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Instruments for one WebSocket connection, created on first use.
+    pub fn connection(&self, name: &str) -> Arc<ConnectionMetrics> {
+        Arc::clone(self.connections.entry(name.to_string()).or_default().value())
+    }
+
+    pub fn symbol(&self, symbol: &str) -> Arc<SymbolMetrics> {
+        Arc::clone(self.symbols.entry(symbol.to_string()).or_default().value())
+    }
+
+    /// Prometheus text exposition format, version 0.0.4.
+    pub fn render(&self) -> String {
+        let mut out = String::new();
+
+        render_counter(&mut out, "hl_event_bus_events_processed_total", "Events delivered by the event bus", &[("", self.events_processed.get())]);
+        render_counter(&mut out, "hl_event_bus_events_dropped_total", "Events dropped by the event bus", &[("", self.events_dropped.get())]);
+
+        let mut connections: Vec<_> = self.connections.iter()
+            .map(|entry| (entry.key().clone(), Arc::clone(entry.value())))
+            .collect();
+        connections.sort_by(|a, b| a.0.cmp(&b.0));
+        let labels = |value: fn(&ConnectionMetrics) -> u64| -> Vec<(String, u64)> {
+            connections.iter().map(|(name, conn)| (format!("connection=\"{}\"", name), value(conn))).collect()
+        };
+        let messages = labels(|conn| conn.messages.get());
+        let reconnects = labels(|conn| conn.reconnects.get());
+        let invalid = labels(|conn| conn.invalid_messages.get());
+        render_counter(&mut out, "hl_ws_messages_total", "Messages received per WebSocket connection", &as_refs(&messages));
+        render_counter(&mut out, "hl_ws_reconnects_total", "Reconnects per WebSocket connection", &as_refs(&reconnects));
+        render_counter(&mut out, "hl_ws_invalid_messages_total", "Malformed book messages skipped per WebSocket connection", &as_refs(&invalid));
+        let skew: Vec<(String, &Histogram)> = connections.iter()
+            .map(|(name, conn)| (format!("connection=\"{}\"", name), &conn.exchange_skew))
+            .collect();
+        let skew: Vec<(&str, &Histogram)> = skew.iter().map(|(labels, histogram)| (labels.as_str(), *histogram)).collect();
+        render_histogram(&mut out, "hl_ws_exchange_skew_seconds", "Delay between the exchange timestamp and local receipt", &skew);
+        render_counter(&mut out, "hl_market_data_stale_dropped_total", "Book updates dropped for being older than the max age", &[("", self.market_data_stale_dropped.get())]);
+
+        render_counter(&mut out, "hl_orders_placed_total", "Orders accepted by the exchange", &[("", self.orders_placed.get())]);
+        render_counter(&mut out, "hl_orders_cancelled_total", "Orders cancelled", &[("", self.orders_cancelled.get())]);
+        render_counter(&mut out, "hl_orders_rejected_total", "Orders rejected by the exchange", &[("", self.orders_rejected.get())]);
+        render_histogram(&mut out, "hl_order_place_latency_seconds", "Order placement round trip", &[("", &self.place_latency)]);
+        render_histogram(&mut out, "hl_order_cancel_latency_seconds", "Order cancel round trip", &[("", &self.cancel_latency)]);
+        render_histogram(&mut out, "hl_tick_to_trade_seconds", "Market data receipt to order submission", &[("", &self.tick_to_trade)]);
+
+        let mut symbols: Vec<_> = self.symbols.iter()
+            .map(|entry| (entry.key().clone(), Arc::clone(entry.value())))
+            .collect();
+        symbols.sort_by(|a, b| a.0.cmp(&b.0));
+        let gauges = |value: fn(&SymbolMetrics) -> f64| -> Vec<(String, f64)> {
+            symbols.iter().map(|(symbol, metrics)| (format!("symbol=\"{}\"", symbol), value(metrics))).collect()
+        };
+        render_gauge(&mut out, "hl_position_size", "Signed position size", &gauges(|m| m.inventory.get()));
+        render_gauge(&mut out, "hl_unrealized_pnl", "Unrealized PnL of the open position", &gauges(|m| m.unrealized_pnl.get()));
+        render_gauge(&mut out, "hl_risk_score", "Risk manager score from 0 to 100", &gauges(|m| m.risk_score.get()));
+        render_gauge(&mut out, "hl_book_updates_per_second", "Order book updates per second", &gauges(|m| m.book_update_rate.get()));
+
+        out
+    }
+}
+
+fn as_refs(samples: &[(String, u64)]) -> Vec<(&str, u64)> {
+    samples.iter().map(|(labels, value)| (labels.as_str(), *value)).collect()
+}
+
+fn render_counter(out: &mut String, name: &str, help: &str, samples: &[(&str, u64)]) {
+    let _ = writeln!(out, "# HELP {} {}\n# TYPE {} counter", name, help, name);
+    for (labels, value) in samples {
+        let _ = writeln!(out, "{}{} {}", name, wrap_labels(labels), value);
+    }
+}
+
+fn render_gauge(out: &mut String, name: &str, help: &str, samples: &[(String, f64)]) {
+    let _ = writeln!(out, "# HELP {} {}\n# TYPE {} gauge", name, help, name);
+    for (labels, value) in samples {
+        let _ = writeln!(out, "{}{} {}", name, wrap_labels(labels), value);
+    }
+}
+
+fn render_histogram(out: &mut String, name: &str, help: &str, samples: &[(&str, &Histogram)]) {
+    let _ = writeln!(out, "# HELP {} {}\n# TYPE {} histogram", name, help, name);
+
+    for (labels, histogram) in samples {
+        let prefix = if labels.is_empty() { String::new() } else { format!("{},", labels) };
+
+        // Buckets are stored individually and reported cumulatively
+        let mut cumulative = 0;
+        for (bound, bucket) in LATENCY_BUCKETS.iter().zip(histogram.buckets.iter()) {
+            cumulative += bucket.load(Ordering::Relaxed);
+            let _ = writeln!(out, "{}_bucket{{{}le=\"{}\"}} {}", name, prefix, bound, cumulative);
+        }
+        let count = histogram.count();
+        let _ = writeln!(out, "{}_bucket{{{}le=\"+Inf\"}} {}", name, prefix, count);
+        let _ = writeln!(out, "{}_sum{} {}", name, wrap_labels(labels), histogram.sum_micros.load(Ordering::Relaxed) as f64 / 1_000_000.0);
+        let _ = writeln!(out, "{}_count{} {}", name, wrap_labels(labels), count);
+    }
+}
+
+fn wrap_labels(labels: &str) -> String {
+    if labels.is_empty() {
+        String::new()
+    } else {
+        format!("{{{}}}", labels)
+    }
+}
@@ -0,0 +1,74 @@
+use crate::metrics::registry::{Metrics, MetricsConfig};
+use std::sync::Arc;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::{TcpListener, TcpStream};
+use tracing::{debug, info, warn};
+
+const CONTENT_TYPE: &str = "text/plain; version=0.0.4; charset=utf-8";
+
+/// Minimal HTTP exporter: `GET /metrics` returns the rendered metrics and every
+/// other request gets a 404. One request per connection, which is all a
+/// Prometheus scraper needs.
+pub struct MetricsServer {
+    listener: TcpListener,
+    metrics: Arc<Metrics>,
+}
+
+impl MetricsServer {
+    pub async fn bind(config: &MetricsConfig, metrics: Arc<Metrics>) -> std::io::Result<Self> {
+        let listener = TcpListener::bind((config.bind_address.as_str(), config.port)).await?;
+        info!("Serving metrics on http://{}/metrics", listener.local_addr()?);
+        Ok(Self { listener, metrics })
+    }
+
+    pub fn local_addr(&self) -> std::io::Result<std::net::SocketAddr> {
+        self.listener.local_addr()
+    }
+
+    pub fn spawn(self) -> tokio::task::JoinHandle<()> {
+        tokio::spawn(async move {
+            loop {
+                match self.listener.accept().await {
+                    Ok((stream, _)) => {
+                        let metrics = Arc::clone(&self.metrics);
+                        tokio::spawn(async move {
+                            if let Err(e) = handle_connection(stream, &metrics).await {
+                                debug!("Metrics request failed: {}", e);
+                            }
+                        });
+                    }
+                    Err(e) => warn!("Failed to accept metrics connection: {}", e),
+                }
+            }
+        })
+    }
+}
+
+async fn handle_connection(mut stream: TcpStream, metrics: &Metrics) -> std::io::Result<()> {
+    let mut buf = [0u8; 1024];
+    let mut request = Vec::new();
+
+    // Only the request line matters; stop once the headers are complete
+    while !request.windows(4).any(|w| w == b"\r\n\r\n") && request.len() < 8192 {
+        let read = stream.read(&mut buf).await?;
+        if read == 0 {
+            break;
+        }
+        request.extend_from_slice(&buf[..read]);
+    }
+
+    let request = String::from_utf8_lossy(&request);
+    let mut parts = request.lines().next().unwrap_or_default().split_whitespace();
+
+    let (status, body) = match (parts.next(), parts.next()) {
+        (Some("GET"), Some("/metrics")) => ("200 OK", metrics.render()),
+        _ => ("404 Not Found", "Not found\n".to_string()),
+    };
+
+    let response = format!(
+        "HTTP/1.1 {}\r\nContent-Type: {}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+        status, CONTENT_TYPE, body.len(), body
+    );
+    stream.write_all(response.as_bytes()).await?;
+    stream.shutdown().await
+}
@@ -1,4 +1,8 @@
+use rust_decimal::Decimal;
 use serde::{Serialize, Deserialize};
+use std::str::FromStr;
+
+pub const L2_BOOK_CHANNEL: &str = "l2Book";
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct TobMsg {
@@ -11,6 +15,8 @@ pub struct OrderBookData {
     pub coin: String,
     pub time: u64,
     pub levels: Vec<Vec<PriceLevel>>,
+    #[serde(skip)]
+    pub received_at: Option<std::time::Instant>, // Set by the client that read it off the socket
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -21,16 +27,83 @@ pub struct PriceLevel {
 }
 
 
+impl TobMsg {
+    /// Checks what deserialization lets through: the channel, a `[bids, asks]` pair
+    /// with at least one level, and positive decimal prices and sizes throughout.
+    pub fn validate(&self) -> Result<(), String> {
+        if self.channel != L2_BOOK_CHANNEL {
+            return Err(format!("Unexpected channel {}", self.channel));
+        }
+
+        let [bids, asks] = self.data.levels.as_slice() else {
+            return Err(format!(
+                "Expected [bids, asks] for {}, got {} sides",
+                self.data.coin, self.data.levels.len()
+            ));
+        };
+        if bids.is_empty() && asks.is_empty() {
+            return Err(format!("Empty book for {}", self.data.coin));
+        }
+
+        for (side, levels) in [("bid", bids), ("ask", asks)] {
+            for level in levels {
+                for (field, value) in [("px", &level.px), ("sz", &level.sz)] {
+                    match Decimal::from_str(value) {
+                        Ok(parsed) if parsed > Decimal::ZERO => {}
+                        _ => return Err(format!(
+                            "Invalid {} {} {:?} for {}", side, field, value, self.data.coin
+                        )),
+                    }
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
+
 impl OrderBookData {
     pub fn top_of_book(&self) -> Option<(PriceLevel, PriceLevel)> {
-        let best_bid = self.levels.get(0)?.get(0)?;
-        let best_ask = self.levels.get(1)?.get(0)?;
+        let best_bid = self.levels.first()?.first()?;
+        let best_ask = self.levels.get(1)?.first()?;
 
         Some((best_bid.clone(), best_ask.clone()))
     }
 
+    // Includes the coin: one connection carries several books, which can update in the same millisecond
     pub fn generate_id(&self) -> String {
         let tob_string = format!("{:?}", self.top_of_book());
-        self.time.to_string() + &tob_string
+        format!("{}{}{}", self.coin, self.time, tob_string)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::events::types::SystemEvent;
+
+    const BOOK: &str = r#"{"channel":"l2Book","data":{"coin":"HYPE","time":1718049600123,"levels":[[{"px":"25.012","sz":"140.5","n":3},{"px":"25.011","sz":"12","n":1}],[{"px":"25.02","sz":"8.75","n":2}]]}}"#;
+
+    #[test]
+    fn a_book_round_trips_through_json_byte_for_byte() {
+        let msg: TobMsg = serde_json::from_str(BOOK).unwrap();
+        assert_eq!(serde_json::to_string(&msg).unwrap(), BOOK);
+
+        let again: TobMsg = serde_json::from_str(&serde_json::to_string(&msg).unwrap()).unwrap();
+        assert_eq!(again.data.generate_id(), msg.data.generate_id());
+    }
+
+    #[test]
+    fn market_data_events_round_trip() {
+        let event = SystemEvent::new_market_data("HYPE".to_string(), serde_json::from_str(BOOK).unwrap());
+        let json = serde_json::to_string(&event).unwrap();
+
+        let SystemEvent::MarketData { symbol, data, timestamp } = serde_json::from_str(&json).unwrap() else {
+            panic!("not a MarketData event: {}", json);
+        };
+        let SystemEvent::MarketData { timestamp: sent_at, .. } = event else { unreachable!() };
+        assert_eq!(symbol, "HYPE");
+        assert_eq!(timestamp, sent_at);
+        assert_eq!(serde_json::to_string(&data).unwrap(), BOOK);
     }
 }
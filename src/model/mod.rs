@@ -1 +1,2 @@
-pub mod hl_msgs;
\ No newline at end of file
+pub mod hl_msgs;
+pub mod user_msgs;
\ No newline at end of file
@@ -0,0 +1 @@
+pub mod session_report;
\ No newline at end of file
@@ -0,0 +1,179 @@
+use crate::trading::position_manager::PositionManager;
+use chrono::{DateTime, Utc};
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use tracing::info;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SymbolReport {
+    pub symbol: String,
+    pub fills: u64,
+    pub volume: Decimal,
+    pub position: Decimal,
+    pub realized_pnl: Decimal,
+    pub unrealized_pnl: Decimal,
+    pub fees: Decimal,
+    pub spread_captured: Decimal,
+}
+
+/// End-of-run summary assembled from the managers' state.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SessionReport {
+    pub started_at: DateTime<Utc>,
+    pub ended_at: DateTime<Utc>,
+    pub uptime_secs: i64,
+    pub total_fills: u64,
+    pub total_volume: Decimal,
+    pub realized_pnl: Decimal,
+    pub unrealized_pnl: Decimal,
+    pub total_fees: Decimal,
+    pub net_pnl: Decimal,
+    pub spread_captured: Decimal,
+    pub max_drawdown: Decimal,
+    pub reconnects: u64,
+    pub symbols: Vec<SymbolReport>,
+}
+
+impl SessionReport {
+    pub fn new(position_manager: &PositionManager, started_at: DateTime<Utc>, reconnects: u64) -> Self {
+        let ended_at = Utc::now();
+
+        let mut symbols: Vec<SymbolReport> = position_manager
+            .get_all_fill_stats()
+            .into_iter()
+            .map(|(symbol, stats)| {
+                let position = position_manager.get_position(&symbol);
+                SymbolReport {
+                    fills: stats.fill_count,
+                    volume: stats.volume(),
+                    position: position.as_ref().map_or(Decimal::ZERO, |p| p.size),
+                    realized_pnl: position.as_ref().map_or(Decimal::ZERO, |p| p.realized_pnl),
+                    unrealized_pnl: position.as_ref().map_or(Decimal::ZERO, |p| p.unrealized_pnl),
+                    fees: stats.fees,
+                    spread_captured: stats.spread_captured(),
+                    symbol,
+                }
+            })
+            .collect();
+        symbols.sort_by(|a, b| a.symbol.cmp(&b.symbol));
+
+        let realized_pnl = position_manager.get_realized_pnl();
+        let unrealized_pnl = position_manager.get_total_unrealized_pnl();
+        let total_fees = position_manager.get_total_fees();
+
+        Self {
+            started_at,
+            ended_at,
+            uptime_secs: (ended_at - started_at).num_seconds(),
+            total_fills: symbols.iter().map(|s| s.fills).sum(),
+            total_volume: symbols.iter().map(|s| s.volume).sum(),
+            realized_pnl,
+            unrealized_pnl,
+            total_fees,
+            net_pnl: realized_pnl + unrealized_pnl - total_fees,
+            spread_captured: symbols.iter().map(|s| s.spread_captured).sum(),
+            max_drawdown: position_manager.get_max_drawdown(),
+            reconnects,
+            symbols,
+        }
+    }
+
+    pub fn log(&self) {
+        for line in self.to_string().lines() {
+            info!("{}", line);
+        }
+    }
+}
+
+impl fmt::Display for SessionReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let hours = self.uptime_secs / 3600;
+        let minutes = (self.uptime_secs % 3600) / 60;
+        let seconds = self.uptime_secs % 60;
+
+        writeln!(f, "=== Session Report ===")?;
+        writeln!(f, "Uptime:          {:02}:{:02}:{:02}", hours, minutes, seconds)?;
+        writeln!(f, "Fills:           {}", self.total_fills)?;
+        writeln!(f, "Volume:          {:.2}", self.total_volume)?;
+        writeln!(f, "Realized PnL:    {:.4}", self.realized_pnl)?;
+        writeln!(f, "Unrealized PnL:  {:.4}", self.unrealized_pnl)?;
+        writeln!(f, "Fees:            {:.4}", self.total_fees)?;
+        writeln!(f, "Net PnL:         {:.4}", self.net_pnl)?;
+        writeln!(f, "Spread captured: {:.4}", self.spread_captured)?;
+        writeln!(f, "Max drawdown:    {:.4}", self.max_drawdown)?;
+        writeln!(f, "Reconnects:      {}", self.reconnects)?;
+
+        for symbol in &self.symbols {
+            writeln!(
+                f,
+                "[{}] fills={} volume={:.2} position={} realized={:.4} unrealized={:.4} fees={:.4} spread={:.4}",
+                symbol.symbol,
+                symbol.fills,
+                symbol.volume,
+                symbol.position,
+                symbol.realized_pnl,
+                symbol.unrealized_pnl,
+                symbol.fees,
+                symbol.spread_captured,
+            )?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::trading::types::{Fill, Side};
+    use rust_decimal_macros::dec;
+    use uuid::Uuid;
+
+    fn fill(symbol: &str, side: Side, price: Decimal, size: Decimal, fee: Decimal) -> Fill {
+        Fill {
+            id: Uuid::new_v4(),
+            order_id: Uuid::new_v4(),
+            symbol: symbol.to_string(),
+            side,
+            price,
+            size,
+            fee,
+            timestamp: Utc::now(),
+            strategy: None,
+        }
+    }
+
+    #[test]
+    fn totals_add_up_the_symbols_fills_and_positions() {
+        let (manager, _rx) = PositionManager::new();
+        // A HYPE round trip for +10, and BTC left long 1 with the mark 1000 above entry
+        manager.process_fill(&fill("HYPE", Side::Buy, dec!(100), dec!(2), dec!(0.1)));
+        manager.process_fill(&fill("HYPE", Side::Sell, dec!(105), dec!(2), dec!(0.1)));
+        manager.process_fill(&fill("BTC", Side::Buy, dec!(60000), dec!(1), dec!(1)));
+        manager.update_mark_prices("BTC", dec!(61000));
+
+        let report = SessionReport::new(&manager, Utc::now() - chrono::Duration::seconds(3725), 2);
+
+        assert_eq!(report.total_fills, 3);
+        assert_eq!(report.total_volume, dec!(60410));
+        assert_eq!(report.realized_pnl, dec!(10));
+        assert_eq!(report.unrealized_pnl, dec!(1000));
+        assert_eq!(report.total_fees, dec!(1.2));
+        assert_eq!(report.net_pnl, dec!(1008.8));
+        assert_eq!(report.spread_captured, dec!(10));
+        assert_eq!(report.reconnects, 2);
+
+        let symbols: Vec<_> = report.symbols.iter()
+            .map(|s| (s.symbol.as_str(), s.fills, s.position, s.realized_pnl, s.unrealized_pnl))
+            .collect();
+        assert_eq!(symbols, [
+            ("BTC", 1, dec!(1), Decimal::ZERO, dec!(1000)),
+            ("HYPE", 2, Decimal::ZERO, dec!(10), Decimal::ZERO),
+        ]);
+
+        let text = report.to_string();
+        assert!(text.contains("Uptime:          01:02:05"), "{}", text);
+        assert!(text.contains("Net PnL:         1008.8000"), "{}", text);
+    }
+}
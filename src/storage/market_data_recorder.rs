@@ -0,0 +1,194 @@
+use crate::events::journal::{JournalConfig, JournalWriter};
+use crate::model::hl_msgs::TobMsg;
+use crossbeam_channel::{Sender, unbounded};
+use serde::{Deserialize, Serialize};
+use std::fs::{self, File};
+use std::io::{self, BufRead, BufReader};
+use std::path::{Path, PathBuf};
+use std::thread;
+use tracing::{error, info, warn};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RecordingConfig {
+    pub enabled: bool,
+    pub path: String,
+    pub max_file_size_bytes: u64, // Rotate once the active file would grow past this
+    pub max_rotated_files: usize, // Older files beyond `path.N` are deleted
+}
+
+impl Default for RecordingConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            path: "data/market_data.jsonl".to_string(),
+            max_file_size_bytes: 256 * 1024 * 1024,
+            max_rotated_files: 10,
+        }
+    }
+}
+
+/// One recorded book message. The message is flattened next to the timestamp, so a
+/// recording can also be read line by line as plain `TobMsg`s (see `Backtester`).
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RecordedTob {
+    pub received_at_ms: i64,
+    #[serde(flatten)]
+    pub msg: TobMsg,
+}
+
+/// Writes book messages to rotating newline-delimited JSON files on a background
+/// thread, in the same layout as the event journal.
+#[derive(Debug, Clone)]
+pub struct MarketDataRecorder {
+    entries_tx: Sender<RecordedTob>,
+}
+
+impl MarketDataRecorder {
+    pub fn start(config: &RecordingConfig) -> io::Result<Self> {
+        let path = PathBuf::from(&config.path);
+        if let Some(dir) = path.parent().filter(|dir| !dir.as_os_str().is_empty()) {
+            fs::create_dir_all(dir)?;
+        }
+        let mut writer = JournalWriter::open(JournalConfig {
+            path,
+            max_file_size_bytes: config.max_file_size_bytes,
+            max_rotated_files: config.max_rotated_files,
+        })?;
+        let (entries_tx, entries_rx) = unbounded::<RecordedTob>();
+
+        thread::spawn(move || {
+            while let Ok(entry) = entries_rx.recv() {
+                if let Err(e) = writer.write(&entry) {
+                    error!("Failed to write market data recording: {}", e);
+                }
+                if entries_rx.is_empty() {
+                    if let Err(e) = writer.flush() {
+                        error!("Failed to flush market data recording: {}", e);
+                    }
+                }
+            }
+            let _ = writer.flush();
+        });
+
+        info!("Recording market data to {}", config.path);
+        Ok(Self { entries_tx })
+    }
+
+    /// Records `msg` with the wall clock time it came off the socket, falling back to
+    /// now for messages that were never stamped.
+    pub fn record(&self, msg: &TobMsg) {
+        let since_receipt = msg.data.received_at.map_or(0, |at| at.elapsed().as_millis() as i64);
+        let entry = RecordedTob {
+            received_at_ms: chrono::Utc::now().timestamp_millis() - since_receipt,
+            msg: msg.clone(),
+        };
+        if self.entries_tx.send(entry).is_err() {
+            warn!("Market data recorder stopped, message not recorded");
+        }
+    }
+
+    pub fn read_entries(path: impl AsRef<Path>) -> io::Result<Vec<RecordedTob>> {
+        let reader = BufReader::new(File::open(path)?);
+        let mut entries = Vec::new();
+
+        for (line_number, line) in reader.lines().enumerate() {
+            let line = line?;
+            if line.trim().is_empty() {
+                continue;
+            }
+            match serde_json::from_str(&line) {
+                Ok(entry) => entries.push(entry),
+                Err(e) => warn!("Skipping malformed recording line {}: {}", line_number + 1, e),
+            }
+        }
+
+        Ok(entries)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::{Duration, Instant};
+
+    fn book(time: u64) -> TobMsg {
+        serde_json::from_value(serde_json::json!({
+            "channel": "l2Book",
+            "data": {
+                "coin": "HYPE",
+                "time": time,
+                "levels": [[{"px": "25.0", "sz": "10", "n": 1}], [{"px": "25.1", "sz": "5", "n": 2}]],
+            },
+        })).unwrap()
+    }
+
+    #[test]
+    fn recorded_books_read_back_as_recordings_and_as_plain_messages() {
+        let dir = std::env::temp_dir().join(format!("hl_recording_{}", uuid::Uuid::new_v4()));
+        let path = dir.join("market_data.jsonl");
+        let recorder = MarketDataRecorder::start(&RecordingConfig {
+            enabled: true,
+            path: path.to_string_lossy().into_owned(),
+            ..RecordingConfig::default()
+        }).unwrap();
+
+        let mut stamped = book(1);
+        stamped.data.received_at = Some(Instant::now() - Duration::from_millis(500));
+        let before = chrono::Utc::now().timestamp_millis();
+        recorder.record(&stamped);
+        for time in 2..=10 {
+            recorder.record(&book(time));
+        }
+        drop(recorder);
+
+        let mut entries = Vec::new();
+        for _ in 0..100 {
+            entries = MarketDataRecorder::read_entries(&path).unwrap_or_default();
+            if entries.len() == 10 {
+                break;
+            }
+            thread::sleep(Duration::from_millis(10));
+        }
+        assert_eq!(entries.iter().map(|entry| entry.msg.data.time).collect::<Vec<_>>(), (1..=10).collect::<Vec<_>>());
+        // Stamped with when it came off the socket rather than when it was recorded
+        assert!(entries[0].received_at_ms <= before - 490, "{} vs {}", entries[0].received_at_ms, before);
+        assert_eq!(entries[0].msg.data.levels[1][0].px, "25.1");
+
+        let first_line = fs::read_to_string(&path).unwrap().lines().next().unwrap().to_string();
+        let plain: TobMsg = serde_json::from_str(&first_line).unwrap();
+        assert_eq!((plain.channel.as_str(), plain.data.coin.as_str(), plain.data.time), ("l2Book", "HYPE", 1));
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn a_full_recording_rotates_into_numbered_files() {
+        let dir = std::env::temp_dir().join(format!("hl_recording_{}", uuid::Uuid::new_v4()));
+        let path = dir.join("market_data.jsonl");
+        let line_len = serde_json::to_vec(&RecordedTob { received_at_ms: 0, msg: book(1) }).unwrap().len() as u64;
+        let recorder = MarketDataRecorder::start(&RecordingConfig {
+            enabled: true,
+            path: path.to_string_lossy().into_owned(),
+            max_file_size_bytes: line_len * 3, // Room for two lines, whatever the timestamps
+            max_rotated_files: 1,
+        }).unwrap();
+
+        for time in 1..=5 {
+            recorder.record(&book(time));
+        }
+        drop(recorder);
+
+        let rotated = dir.join("market_data.jsonl.1");
+        let times = |path: &Path| MarketDataRecorder::read_entries(path).unwrap_or_default().iter().map(|entry| entry.msg.data.time).collect::<Vec<_>>();
+        for _ in 0..100 {
+            if times(&path).last() == Some(&5) {
+                break;
+            }
+            thread::sleep(Duration::from_millis(10));
+        }
+        // The oldest file is deleted beyond max_rotated_files
+        assert_eq!(times(&rotated), [3, 4]);
+        assert_eq!(times(&path), [5]);
+        assert!(!dir.join("market_data.jsonl.2").exists());
+        let _ = fs::remove_dir_all(&dir);
+    }
+}
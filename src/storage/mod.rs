@@ -0,0 +1,2 @@
+pub mod market_data_recorder;
+pub mod session_store;
\ No newline at end of file
@@ -0,0 +1,461 @@
+use crate::trading::order_manager::OrderEvent;
+use crate::trading::position_manager::PositionEvent;
+use crate::trading::types::*;
+use chrono::{DateTime, NaiveDate, Utc};
+use crossbeam_channel::{Receiver, Sender, unbounded};
+use rusqlite::{params, Connection, OptionalExtension, Row};
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use serde::de::DeserializeOwned;
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+use std::thread;
+use tracing::{error, info, warn};
+use uuid::Uuid;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StorageConfig {
+    pub enabled: bool,
+    pub db_path: String,
+}
+
+impl Default for StorageConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            db_path: "data/session.db".to_string(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DailyPnl {
+    pub date: NaiveDate,
+    pub realized_pnl: Decimal, // Gross of fees
+    pub fees: Decimal,
+    pub net_pnl: Decimal,
+}
+
+#[derive(Debug)]
+enum StorageWrite {
+    Order(Order),
+    OrderCancelled(Uuid, DateTime<Utc>),
+    Fill(Fill),
+    Position(Position),
+    RealizedPnl(Decimal, DateTime<Utc>),
+}
+
+const SCHEMA: &str = "
+    CREATE TABLE IF NOT EXISTS orders (
+        id TEXT PRIMARY KEY,
+        client_id TEXT,
+        symbol TEXT NOT NULL,
+        side TEXT NOT NULL,
+        order_type TEXT NOT NULL,
+        price TEXT NOT NULL,
+        size TEXT NOT NULL,
+        filled_size TEXT NOT NULL,
+        remaining_size TEXT NOT NULL,
+        status TEXT NOT NULL,
+        created_at INTEGER NOT NULL,
+        updated_at INTEGER NOT NULL
+    );
+    CREATE INDEX IF NOT EXISTS orders_status ON orders (status);
+    CREATE TABLE IF NOT EXISTS fills (
+        id TEXT PRIMARY KEY,
+        order_id TEXT NOT NULL,
+        symbol TEXT NOT NULL,
+        side TEXT NOT NULL,
+        price TEXT NOT NULL,
+        size TEXT NOT NULL,
+        fee TEXT NOT NULL,
+        timestamp INTEGER NOT NULL
+    );
+    CREATE INDEX IF NOT EXISTS fills_timestamp ON fills (timestamp);
+    CREATE TABLE IF NOT EXISTS positions (
+        symbol TEXT PRIMARY KEY,
+        size TEXT NOT NULL,
+        entry_price TEXT NOT NULL,
+        mark_price TEXT NOT NULL,
+        unrealized_pnl TEXT NOT NULL,
+        realized_pnl TEXT NOT NULL,
+        updated_at INTEGER NOT NULL
+    );
+    CREATE TABLE IF NOT EXISTS realized_pnl (
+        recorded_at INTEGER NOT NULL,
+        amount TEXT NOT NULL
+    );
+    CREATE INDEX IF NOT EXISTS realized_pnl_recorded_at ON realized_pnl (recorded_at);
+";
+
+/// SQLite record of a bot's orders, fills, positions and realized PnL, kept for
+/// analysis after the session and for restoring open orders on restart.
+///
+/// All writes go through a channel to a dedicated writer thread, so recording never
+/// blocks the caller on disk. Queries open their own read connection. Decimals are
+/// stored as text to keep them exact; timestamps are Unix millis.
+#[derive(Debug, Clone)]
+pub struct SessionStore {
+    path: PathBuf,
+    writes_tx: Sender<StorageWrite>,
+}
+
+impl SessionStore {
+    pub fn open(path: impl AsRef<Path>) -> Result<Self, String> {
+        let path = path.as_ref().to_path_buf();
+        if let Some(dir) = path.parent().filter(|dir| !dir.as_os_str().is_empty()) {
+            std::fs::create_dir_all(dir)
+                .map_err(|e| format!("Failed to create storage directory {}: {}", dir.display(), e))?;
+        }
+
+        let conn = Connection::open(&path)
+            .map_err(|e| format!("Failed to open session store {}: {}", path.display(), e))?;
+        // WAL lets queries read while the writer thread is mid-transaction
+        conn.pragma_update(None, "journal_mode", "WAL")
+            .map_err(|e| format!("Failed to enable WAL: {}", e))?;
+        conn.execute_batch(SCHEMA)
+            .map_err(|e| format!("Failed to create session store schema: {}", e))?;
+
+        let (writes_tx, writes_rx) = unbounded();
+        thread::spawn(move || run_writer(conn, writes_rx));
+
+        info!("Session store opened at {}", path.display());
+        Ok(Self { path, writes_tx })
+    }
+
+    pub fn record_order(&self, order: &Order) {
+        self.send(StorageWrite::Order(order.clone()));
+    }
+
+    pub fn record_fill(&self, fill: &Fill) {
+        self.send(StorageWrite::Fill(fill.clone()));
+    }
+
+    pub fn record_position(&self, position: &Position) {
+        self.send(StorageWrite::Position(position.clone()));
+    }
+
+    pub fn record_order_event(&self, event: &OrderEvent) {
+        match event {
+            OrderEvent::OrderPlaced(order) | OrderEvent::OrderUpdated(order) | OrderEvent::OrderFilled(order) => {
+                self.record_order(order)
+            }
+            OrderEvent::OrderCancelled(id) => self.send(StorageWrite::OrderCancelled(*id, Utc::now())),
+        }
+    }
+
+    pub fn record_position_event(&self, event: &PositionEvent) {
+        match event {
+            PositionEvent::PositionUpdated(position) => self.record_position(position),
+            PositionEvent::FillProcessed(fill) => self.record_fill(fill),
+            PositionEvent::PnlRealized(amount) => self.send(StorageWrite::RealizedPnl(*amount, Utc::now())),
+        }
+    }
+
+    /// Records every event from the order and position managers until their channels close.
+    pub fn record_events(&self, order_events_rx: Receiver<OrderEvent>, position_events_rx: Receiver<PositionEvent>) {
+        let store = self.clone();
+        thread::spawn(move || {
+            for event in order_events_rx {
+                store.record_order_event(&event);
+            }
+        });
+
+        let store = self.clone();
+        thread::spawn(move || {
+            for event in position_events_rx {
+                store.record_position_event(&event);
+            }
+        });
+    }
+
+    /// Orders last recorded as pending, submitted or partially filled.
+    pub fn load_open_orders(&self) -> Result<Vec<Order>, String> {
+        let conn = self.read_connection()?;
+        let mut stmt = conn.prepare(
+            "SELECT id, client_id, symbol, side, order_type, price, size, filled_size, remaining_size, status, created_at, updated_at
+             FROM orders WHERE status IN ('Pending', 'Submitted', 'PartiallyFilled') ORDER BY created_at"
+        ).map_err(query_error)?;
+
+        let rows = stmt.query_map([], |row| Ok(order_from_row(row))).map_err(query_error)?;
+        rows.map(|row| row.map_err(query_error)?).collect()
+    }
+
+    pub fn fills_between(&self, start: DateTime<Utc>, end: DateTime<Utc>) -> Result<Vec<Fill>, String> {
+        let conn = self.read_connection()?;
+        let mut stmt = conn.prepare(
+            "SELECT id, order_id, symbol, side, price, size, fee, timestamp
+             FROM fills WHERE timestamp >= ?1 AND timestamp < ?2 ORDER BY timestamp"
+        ).map_err(query_error)?;
+
+        let rows = stmt.query_map(params![start.timestamp_millis(), end.timestamp_millis()], |row| Ok(fill_from_row(row)))
+            .map_err(query_error)?;
+        rows.map(|row| row.map_err(query_error)?).collect()
+    }
+
+    /// Realized PnL and fees per UTC day, oldest first.
+    pub fn daily_realized_pnl(&self) -> Result<Vec<DailyPnl>, String> {
+        let conn = self.read_connection()?;
+        let mut days: BTreeMap<NaiveDate, (Decimal, Decimal)> = BTreeMap::new();
+
+        for (sql, is_fee) in [
+            ("SELECT recorded_at, amount FROM realized_pnl", false),
+            ("SELECT timestamp, fee FROM fills", true),
+        ] {
+            let mut stmt = conn.prepare(sql).map_err(query_error)?;
+            let rows = stmt.query_map([], |row| Ok((row.get::<_, i64>(0)?, row.get::<_, String>(1)?)))
+                .map_err(query_error)?;
+
+            for row in rows {
+                let (at, amount) = row.map_err(query_error)?;
+                let date = parse_millis(at)?.date_naive();
+                let (pnl, fees) = days.entry(date).or_default();
+                if is_fee {
+                    *fees += parse_decimal(&amount)?;
+                } else {
+                    *pnl += parse_decimal(&amount)?;
+                }
+            }
+        }
+
+        Ok(days.into_iter()
+            .map(|(date, (realized_pnl, fees))| DailyPnl { date, realized_pnl, fees, net_pnl: realized_pnl - fees })
+            .collect())
+    }
+
+    pub fn get_position(&self, symbol: &str) -> Result<Option<Decimal>, String> {
+        let conn = self.read_connection()?;
+        let size: Option<String> = conn.query_row(
+            "SELECT size FROM positions WHERE symbol = ?1", params![symbol], |row| row.get(0)
+        ).optional().map_err(query_error)?;
+
+        size.map(|size| parse_decimal(&size)).transpose()
+    }
+
+    fn read_connection(&self) -> Result<Connection, String> {
+        Connection::open(&self.path)
+            .map_err(|e| format!("Failed to open session store {}: {}", self.path.display(), e))
+    }
+
+    fn send(&self, write: StorageWrite) {
+        if self.writes_tx.send(write).is_err() {
+            warn!("Session store writer stopped, record dropped");
+        }
+    }
+}
+
+fn run_writer(mut conn: Connection, writes_rx: Receiver<StorageWrite>) {
+    while let Ok(first) = writes_rx.recv() {
+        // Commit whatever has queued up as one transaction
+        let result = conn.transaction().and_then(|tx| {
+            apply_write(&tx, &first)?;
+            while let Ok(write) = writes_rx.try_recv() {
+                apply_write(&tx, &write)?;
+            }
+            tx.commit()
+        });
+
+        if let Err(e) = result {
+            error!("Failed to write to session store: {}", e);
+        }
+    }
+}
+
+fn apply_write(conn: &Connection, write: &StorageWrite) -> rusqlite::Result<()> {
+    match write {
+        StorageWrite::Order(order) => conn.execute(
+            "INSERT OR REPLACE INTO orders
+             (id, client_id, symbol, side, order_type, price, size, filled_size, remaining_size, status, created_at, updated_at)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12)",
+            params![
+                order.id.to_string(), order.client_id, order.symbol, enum_name(&order.side), enum_name(&order.order_type),
+                order.price.to_string(), order.size.to_string(), order.filled_size.to_string(),
+                order.remaining_size.to_string(), enum_name(&order.status),
+                order.created_at.timestamp_millis(), order.updated_at.timestamp_millis(),
+            ],
+        ),
+        StorageWrite::OrderCancelled(id, at) => conn.execute(
+            "UPDATE orders SET status = 'Cancelled', updated_at = ?2 WHERE id = ?1",
+            params![id.to_string(), at.timestamp_millis()],
+        ),
+        StorageWrite::Fill(fill) => conn.execute(
+            "INSERT OR REPLACE INTO fills (id, order_id, symbol, side, price, size, fee, timestamp)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)",
+            params![
+                fill.id.to_string(), fill.order_id.to_string(), fill.symbol, enum_name(&fill.side),
+                fill.price.to_string(), fill.size.to_string(), fill.fee.to_string(), fill.timestamp.timestamp_millis(),
+            ],
+        ),
+        StorageWrite::Position(position) => conn.execute(
+            "INSERT OR REPLACE INTO positions (symbol, size, entry_price, mark_price, unrealized_pnl, realized_pnl, updated_at)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+            params![
+                position.symbol, position.size.to_string(), position.entry_price.to_string(),
+                position.mark_price.to_string(), position.unrealized_pnl.to_string(),
+                position.realized_pnl.to_string(), position.updated_at.timestamp_millis(),
+            ],
+        ),
+        StorageWrite::RealizedPnl(amount, at) => conn.execute(
+            "INSERT INTO realized_pnl (recorded_at, amount) VALUES (?1, ?2)",
+            params![at.timestamp_millis(), amount.to_string()],
+        ),
+    }?;
+    Ok(())
+}
+
+fn order_from_row(row: &Row) -> Result<Order, String> {
+    let text = |idx: usize| row.get::<_, String>(idx).map_err(query_error);
+    let millis = |idx: usize| row.get::<_, i64>(idx).map_err(query_error).and_then(parse_millis);
+
+    Ok(Order {
+        id: parse_uuid(&text(0)?)?,
+        client_id: row.get(1).map_err(query_error)?,
+        symbol: text(2)?,
+        side: parse_enum(&text(3)?)?,
+        order_type: parse_enum(&text(4)?)?,
+        price: parse_decimal(&text(5)?)?,
+        size: parse_decimal(&text(6)?)?,
+        filled_size: parse_decimal(&text(7)?)?,
+        remaining_size: parse_decimal(&text(8)?)?,
+        status: parse_enum(&text(9)?)?,
+        created_at: millis(10)?,
+        updated_at: millis(11)?,
+        exchange_oid: None,
+    })
+}
+
+fn fill_from_row(row: &Row) -> Result<Fill, String> {
+    let text = |idx: usize| row.get::<_, String>(idx).map_err(query_error);
+
+    Ok(Fill {
+        id: parse_uuid(&text(0)?)?,
+        order_id: parse_uuid(&text(1)?)?,
+        symbol: text(2)?,
+        side: parse_enum(&text(3)?)?,
+        price: parse_decimal(&text(4)?)?,
+        size: parse_decimal(&text(5)?)?,
+        fee: parse_decimal(&text(6)?)?,
+        timestamp: row.get::<_, i64>(7).map_err(query_error).and_then(parse_millis)?,
+        strategy: None, // Not stored
+    })
+}
+
+// Unit enums are stored by variant name
+fn enum_name<T: Serialize>(value: &T) -> String {
+    match serde_json::to_value(value) {
+        Ok(serde_json::Value::String(name)) => name,
+        other => format!("{:?}", other),
+    }
+}
+
+fn parse_enum<T: DeserializeOwned>(name: &str) -> Result<T, String> {
+    serde_json::from_value(serde_json::Value::String(name.to_string()))
+        .map_err(|e| format!("Invalid stored value {}: {}", name, e))
+}
+
+fn parse_decimal(value: &str) -> Result<Decimal, String> {
+    Decimal::from_str(value).map_err(|e| format!("Invalid stored decimal {}: {}", value, e))
+}
+
+fn parse_uuid(value: &str) -> Result<Uuid, String> {
+    Uuid::parse_str(value).map_err(|e| format!("Invalid stored id {}: {}", value, e))
+}
+
+fn parse_millis(millis: i64) -> Result<DateTime<Utc>, String> {
+    DateTime::from_timestamp_millis(millis)
+        .ok_or_else(|| format!("Invalid stored timestamp {}", millis))
+}
+
+fn query_error(e: rusqlite::Error) -> String {
+    format!("Session store query failed: {}", e)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rust_decimal_macros::dec;
+    use std::time::Duration;
+
+    fn order(status: OrderStatus, price: Decimal) -> Order {
+        let now = Utc::now();
+        Order {
+            id: Uuid::new_v4(),
+            client_id: Some("mm_hype".to_string()),
+            symbol: "HYPE".to_string(),
+            side: Side::Buy,
+            order_type: OrderType::Limit,
+            price,
+            size: dec!(2.5),
+            filled_size: Decimal::ZERO,
+            remaining_size: dec!(2.5),
+            status,
+            created_at: now,
+            updated_at: now,
+            exchange_oid: None,
+        }
+    }
+
+    #[test]
+    fn recorded_orders_fills_and_positions_read_back_after_a_restart() {
+        let path = std::env::temp_dir().join(format!("hl_session_{}", Uuid::new_v4())).join("session.db");
+        let store = SessionStore::open(&path).unwrap();
+
+        let resting = order(OrderStatus::Submitted, dec!(24.125));
+        let cancelled = order(OrderStatus::PartiallyFilled, dec!(24.100));
+        store.record_order(&resting);
+        store.record_order(&cancelled);
+        store.record_order(&order(OrderStatus::Filled, dec!(24.150)));
+        store.record_order_event(&OrderEvent::OrderCancelled(cancelled.id));
+
+        let fill = Fill {
+            id: Uuid::new_v4(),
+            order_id: resting.id,
+            symbol: "HYPE".to_string(),
+            side: Side::Sell,
+            price: dec!(24.2),
+            size: dec!(1.5),
+            fee: dec!(0.25),
+            timestamp: Utc::now(),
+            strategy: None,
+        };
+        store.record_position_event(&PositionEvent::FillProcessed(fill.clone()));
+        store.record_position(&Position {
+            symbol: "HYPE".to_string(),
+            size: dec!(-1.5),
+            entry_price: dec!(24.2),
+            mark_price: dec!(24.2),
+            unrealized_pnl: Decimal::ZERO,
+            realized_pnl: dec!(12.5),
+            updated_at: Utc::now(),
+        });
+        store.record_position_event(&PositionEvent::PnlRealized(dec!(12.5)));
+
+        // Writes land in order, so once the last one is visible the rest are too
+        for _ in 0..100 {
+            if !store.daily_realized_pnl().unwrap().is_empty() {
+                break;
+            }
+            thread::sleep(Duration::from_millis(20));
+        }
+
+        let store = SessionStore::open(&path).unwrap();
+        let open = store.load_open_orders().unwrap();
+        assert_eq!(open.len(), 1);
+        assert_eq!((open[0].id, open[0].status, open[0].price, open[0].size), (resting.id, OrderStatus::Submitted, dec!(24.125), dec!(2.5)));
+        assert_eq!(open[0].created_at.timestamp_millis(), resting.created_at.timestamp_millis());
+
+        let fills = store.fills_between(fill.timestamp - chrono::Duration::hours(1), fill.timestamp + chrono::Duration::hours(1)).unwrap();
+        assert_eq!(fills.len(), 1);
+        assert_eq!((fills[0].order_id, fills[0].side, fills[0].price, fills[0].size, fills[0].fee), (resting.id, Side::Sell, dec!(24.2), dec!(1.5), dec!(0.25)));
+
+        assert_eq!(store.get_position("HYPE").unwrap(), Some(dec!(-1.5)));
+        assert_eq!(store.get_position("BTC").unwrap(), None);
+
+        let days = store.daily_realized_pnl().unwrap();
+        assert_eq!(days.len(), 1);
+        assert_eq!((days[0].realized_pnl, days[0].fees, days[0].net_pnl), (dec!(12.5), dec!(0.25), dec!(12.25)));
+
+        let _ = std::fs::remove_dir_all(path.parent().unwrap());
+    }
+}
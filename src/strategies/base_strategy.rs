@@ -1,11 +1,11 @@
 use crate::trading::types::*;
-use crate::trading::order_book::OrderBook;
+use crate::trading::order_book::BookSnapshot;
 use async_trait::async_trait;
 use serde::{Serialize, Deserialize};
 
 #[async_trait]
 pub trait TradingStrategy: Send + Sync {
-    async fn on_market_data(&mut self, order_book: &OrderBook) -> Vec<OrderAction>;
+    async fn on_market_data(&mut self, book: &BookSnapshot) -> Vec<OrderAction>;
     async fn on_order_update(&mut self, order: &Order) -> Vec<OrderAction>;
     async fn on_position_update(&mut self, position: &Position) -> Vec<OrderAction>;
     async fn on_fill(&mut self, fill: &Fill) -> Vec<OrderAction>;
@@ -1,14 +1,30 @@
 use crate::strategies::base_strategy::{TradingStrategy, StrategyConfig};
+use crate::strategies::quote_ladder::QuoteLadder;
 use crate::trading::types::*;
-use crate::trading::order_book::OrderBook;
+use crate::trading::order_book::{BookSnapshot, SNAPSHOT_LEVELS};
+use crate::trading::update_rate::{UpdateRate, DEFAULT_UPDATE_RATE_WINDOW_MS};
+use crate::trading::volatility::VolatilityEstimator;
+use crate::utils::decimal::{round_price, Bps};
 use async_trait::async_trait;
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
 use rust_decimal::Decimal;
+use rust_decimal::prelude::{FromPrimitive, ToPrimitive};
 use rust_decimal_macros::dec;
 use serde::{Serialize, Deserialize};
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 use uuid::Uuid;
 use chrono::{DateTime, Utc, Duration};
 
+/// Price quotes are centred on, before inventory skew.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum FairPriceSource {
+    #[default]
+    Mid,
+    Microprice,  // Top of book weighted by the opposite side's size
+    Vwap(usize), // Mid of each side's VWAP over this many levels
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct MarketMakingConfig {
     pub base_config: StrategyConfig,
@@ -19,6 +35,89 @@ pub struct MarketMakingConfig {
     pub inventory_skew_factor: Decimal, // How much to skew based on inventory
     pub min_edge_bps: u32,            // Minimum edge required
     pub order_refresh_interval_ms: u64, // How often to refresh orders
+    #[serde(default = "default_max_quote_spread_bps")]
+    pub max_quote_spread_bps: u32,    // Pull quotes while the book is wider than this
+    #[serde(default = "default_order_refresh_jitter_ms")]
+    pub order_refresh_jitter_ms: u64, // Up to this much is added to each refresh interval
+    #[serde(default = "default_reference_volatility")]
+    pub reference_volatility: Decimal, // Annualized vol spread_bps is meant for; quotes widen above it, 0 disables
+    #[serde(default = "default_max_volatility_widening")]
+    pub max_volatility_widening: Decimal, // Cap on the spread multiple from volatility
+    #[serde(default = "default_update_rate_window_ms")]
+    pub update_rate_window_ms: u64,   // Window the book update rate is measured over
+    #[serde(default)]
+    pub fast_market_update_rate: Decimal, // Book updates/sec above which the spread is widened, 0 disables
+    #[serde(default = "default_fast_market_widening")]
+    pub fast_market_widening: Decimal, // Spread multiple while above fast_market_update_rate
+    #[serde(default)]
+    pub max_update_rate: Decimal,     // Pull quotes while book updates/sec are above this, 0 disables
+    #[serde(default)]
+    pub max_price_change_bps: u32,    // Pull quotes while the last mid move is above this, as VolatilityLimit measures it; 0 disables
+    #[serde(default)]
+    pub fair_price_source: FairPriceSource,
+    #[serde(default = "default_max_order_age_ms")]
+    pub max_order_age_ms: u64,        // Quotes resting longer than this are cancelled, 0 disables
+    #[serde(default = "default_max_order_deviation_bps")]
+    pub max_order_deviation_bps: u32, // Quotes this far from the mid after a refresh cycle are cancelled, 0 disables
+    #[serde(default)]
+    pub max_inventory: Option<Decimal>, // No more buying at or above this inventory
+    #[serde(default)]
+    pub min_inventory: Option<Decimal>, // No more selling at or below this inventory
+    #[serde(default = "default_inventory_resume_fraction")]
+    pub inventory_resume_fraction: Decimal, // A stopped side resumes this far from the target towards its band
+    #[serde(default)]
+    pub unwind_aggression_bps: Option<u32>, // While a side is stopped, one reduce-only order this far through the fair price
+    #[serde(default)]
+    pub symbol_overrides: BTreeMap<String, SymbolOverride>, // Per symbol spread and size, looked up by the quoted symbol
+}
+
+/// Replaces the base spread or order size when quoting one symbol. Unset fields keep
+/// the base value.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SymbolOverride {
+    #[serde(default)]
+    pub spread_bps: Option<u32>,
+    #[serde(default)]
+    pub order_size: Option<Decimal>,
+}
+
+// Realized volatility is too noisy to widen on before this many returns
+const MIN_VOLATILITY_RETURNS: u64 = 20;
+
+fn default_max_quote_spread_bps() -> u32 {
+    500
+}
+
+fn default_order_refresh_jitter_ms() -> u64 {
+    200
+}
+
+fn default_reference_volatility() -> Decimal {
+    dec!(1.0)
+}
+
+fn default_max_volatility_widening() -> Decimal {
+    dec!(3.0)
+}
+
+fn default_update_rate_window_ms() -> u64 {
+    DEFAULT_UPDATE_RATE_WINDOW_MS
+}
+
+fn default_fast_market_widening() -> Decimal {
+    dec!(2.0)
+}
+
+fn default_max_order_age_ms() -> u64 {
+    30_000
+}
+
+fn default_max_order_deviation_bps() -> u32 {
+    100
+}
+
+fn default_inventory_resume_fraction() -> Decimal {
+    dec!(0.8)
 }
 
 impl Default for MarketMakingConfig {
@@ -32,7 +131,153 @@ impl Default for MarketMakingConfig {
             inventory_skew_factor: dec!(0.1), // 10% skew per unit
             min_edge_bps: 5,              // 5 bps minimum edge
             order_refresh_interval_ms: 1000, // 1 second refresh
+            max_quote_spread_bps: default_max_quote_spread_bps(),
+            order_refresh_jitter_ms: default_order_refresh_jitter_ms(),
+            reference_volatility: default_reference_volatility(),
+            max_volatility_widening: default_max_volatility_widening(),
+            update_rate_window_ms: default_update_rate_window_ms(),
+            fast_market_update_rate: Decimal::ZERO,
+            fast_market_widening: default_fast_market_widening(),
+            max_update_rate: Decimal::ZERO,
+            max_price_change_bps: 0,
+            fair_price_source: FairPriceSource::Mid,
+            max_order_age_ms: default_max_order_age_ms(),
+            max_order_deviation_bps: default_max_order_deviation_bps(),
+            max_inventory: None,
+            min_inventory: None,
+            inventory_resume_fraction: default_inventory_resume_fraction(),
+            unwind_aggression_bps: None,
+            symbol_overrides: BTreeMap::new(),
+        }
+    }
+}
+
+impl MarketMakingConfig {
+    /// Every problem with the config, or Ok if it can be quoted with.
+    pub fn validate(&self) -> Result<(), Vec<String>> {
+        let mut errors = Vec::new();
+
+        if self.base_config.symbol.is_empty() {
+            errors.push("Symbol cannot be empty".to_string());
+        }
+        if self.spread_bps == 0 {
+            errors.push("Spread must be at least 1 bps".to_string());
+        }
+        if self.min_edge_bps > self.spread_bps {
+            errors.push(format!("Min edge ({} bps) cannot exceed the spread ({} bps)", self.min_edge_bps, self.spread_bps));
+        }
+        if self.order_size <= Decimal::ZERO {
+            errors.push("Order size must be positive".to_string());
+        }
+        if self.max_orders_per_side == 0 {
+            errors.push("Max orders per side must be at least 1".to_string());
+        }
+        for (symbol, symbol_override) in &self.symbol_overrides {
+            if let Some(spread_bps) = symbol_override.spread_bps {
+                if spread_bps == 0 {
+                    errors.push(format!("{} spread must be at least 1 bps", symbol));
+                } else if self.min_edge_bps > spread_bps {
+                    errors.push(format!("Min edge ({} bps) cannot exceed the {} spread ({} bps)", self.min_edge_bps, symbol, spread_bps));
+                }
+            }
+            if symbol_override.order_size.is_some_and(|size| size <= Decimal::ZERO) {
+                errors.push(format!("{} order size must be positive", symbol));
+            }
+        }
+        if self.order_refresh_interval_ms == 0 {
+            errors.push("Refresh interval must be positive".to_string());
+        }
+        if self.inventory_skew_factor < Decimal::ZERO {
+            errors.push("Inventory skew cannot be negative".to_string());
+        }
+        if self.reference_volatility < Decimal::ZERO {
+            errors.push("Reference volatility cannot be negative".to_string());
+        }
+        if self.max_volatility_widening < Decimal::ONE {
+            errors.push("Max volatility widening must be at least 1".to_string());
+        }
+        if self.update_rate_window_ms == 0 {
+            errors.push("Update rate window must be positive".to_string());
+        }
+        if self.fast_market_update_rate < Decimal::ZERO || self.max_update_rate < Decimal::ZERO {
+            errors.push("Update rate thresholds cannot be negative".to_string());
+        }
+        if self.max_order_age_ms > 0 && self.max_order_age_ms <= self.refresh_cycle_ms() {
+            errors.push(format!(
+                "Max order age ({}ms) must exceed the refresh interval plus jitter ({}ms)",
+                self.max_order_age_ms, self.refresh_cycle_ms()
+            ));
+        }
+        if self.max_inventory.is_some_and(|max| max <= self.inventory_target) {
+            errors.push("Max inventory must be above the inventory target".to_string());
+        }
+        if self.min_inventory.is_some_and(|min| min >= self.inventory_target) {
+            errors.push("Min inventory must be below the inventory target".to_string());
+        }
+        if self.inventory_resume_fraction < Decimal::ZERO || self.inventory_resume_fraction >= Decimal::ONE {
+            errors.push("Inventory resume fraction must be at least 0 and below 1".to_string());
+        }
+        if let FairPriceSource::Vwap(depth) = self.fair_price_source {
+            if depth == 0 || depth > SNAPSHOT_LEVELS {
+                errors.push(format!("VWAP depth must be between 1 and {} levels", SNAPSHOT_LEVELS));
+            }
+        }
+        if self.fast_market_widening < Decimal::ONE {
+            errors.push("Fast market widening must be at least 1".to_string());
+        }
+        // Past max_update_rate nothing is quoted, so widening above it would never apply
+        if self.max_update_rate > Decimal::ZERO && self.fast_market_update_rate >= self.max_update_rate {
+            errors.push(format!(
+                "Fast market update rate ({}/s) must be below the max update rate ({}/s)",
+                self.fast_market_update_rate, self.max_update_rate
+            ));
+        }
+
+        if errors.is_empty() { Ok(()) } else { Err(errors) }
+    }
+
+    pub fn spread_bps_for(&self, symbol: &str) -> u32 {
+        self.symbol_overrides.get(symbol)
+            .and_then(|symbol_override| symbol_override.spread_bps)
+            .unwrap_or(self.spread_bps)
+    }
+
+    pub fn order_size_for(&self, symbol: &str) -> Decimal {
+        self.symbol_overrides.get(symbol)
+            .and_then(|symbol_override| symbol_override.order_size)
+            .unwrap_or(self.order_size)
+    }
+
+    // Longest the strategy can go between refreshes of a quote it is still making
+    fn refresh_cycle_ms(&self) -> u64 {
+        self.order_refresh_interval_ms + self.order_refresh_jitter_ms
+    }
+
+    /// Inventory a side stopped at `band` must get back to before it quotes again.
+    pub fn inventory_resume_level(&self, band: Decimal) -> Decimal {
+        self.inventory_target + (band - self.inventory_target) * self.inventory_resume_fraction
+    }
+
+    /// Why a quote `age_ms` old at `price` should be cancelled rather than left
+    /// resting, given the book's current `mid` (None when the book has no usable
+    /// data). Within one refresh cycle only the age limit applies, as the strategy
+    /// will replace the quote itself.
+    pub fn stale_order_reason(&self, age_ms: u64, price: Decimal, mid: Option<Decimal>) -> Option<String> {
+        if self.max_order_age_ms > 0 && age_ms > self.max_order_age_ms {
+            return Some(format!("rested {}ms, over the {}ms limit", age_ms, self.max_order_age_ms));
+        }
+        if age_ms <= self.refresh_cycle_ms() {
+            return None;
+        }
+
+        let Some(mid) = mid else {
+            return Some("no market data for its book".to_string());
+        };
+        let deviation_bps = Bps::from_ratio((price - mid).abs(), mid);
+        if self.max_order_deviation_bps > 0 && deviation_bps > Bps::from(self.max_order_deviation_bps) {
+            return Some(format!("{} bps from the mid {}, over the {} bps limit", deviation_bps.value().round_dp(1), mid, self.max_order_deviation_bps));
         }
+        None
     }
 }
 
@@ -44,26 +289,227 @@ pub struct MarketMakingStrategy {
     pub last_price: Option<Decimal>,
     pub current_inventory: Decimal,
     pub enabled: bool,
+    pub fee_rates: FeeRates, // Account's current tier, refreshed by the caller
+    pub funding: Option<FundingInfo>, // Latest predicted funding for the symbol, refreshed by the caller
+    pub refresh_jitter_ms: u64, // Drawn after every refresh so symbols on one interval drift apart
+    pub simulated_now: Option<DateTime<Utc>>, // Replaces the wall clock when replaying recorded data
+    pub volatility: VolatilityEstimator, // Realized vol of the mid, fed by observe_mid
+    pub book_updates: UpdateRate, // Fed by observe_book_update
+    pub last_price_change: Bps, // Move of the latest observed mid from the one before
+    pub stopped_side: Option<Side>, // Side not quoted while inventory is outside its band
+    quote_ladder: QuoteLadder, // Quotes of the latest refresh cycle and their order states
+    pending_config: Option<MarketMakingConfig>, // Swapped in at the start of the next generate cycle
+    force_refresh: bool, // Requote on the next cycle regardless of timing, e.g. after a config change
+    last_mid_at: Option<DateTime<Utc>>,
+    last_mid: Option<Decimal>,
+    last_book_sequence: Option<u64>,
+    rng: StdRng,
 }
 
 impl MarketMakingStrategy {
     pub fn new(config: MarketMakingConfig) -> Self {
-        Self {
+        Self::with_rng(config, StdRng::from_entropy())
+    }
+
+    /// Seeds the refresh jitter, for reproducible requote timing.
+    pub fn with_seed(config: MarketMakingConfig, seed: u64) -> Self {
+        Self::with_rng(config, StdRng::seed_from_u64(seed))
+    }
+
+    fn with_rng(config: MarketMakingConfig, rng: StdRng) -> Self {
+        let book_updates = UpdateRate::new(config.update_rate_window_ms);
+        let mut strategy = Self {
             config,
             active_orders: HashMap::new(),
             last_order_time: Utc::now() - Duration::hours(1),
             last_price: None,
             current_inventory: dec!(0.0),
             enabled: true,
+            fee_rates: FeeRates::default(),
+            funding: None,
+            refresh_jitter_ms: 0,
+            simulated_now: None,
+            volatility: VolatilityEstimator::default(),
+            book_updates,
+            stopped_side: None,
+            quote_ladder: QuoteLadder::default(),
+            pending_config: None,
+            force_refresh: false,
+            last_price_change: Bps::default(),
+            last_mid_at: None,
+            last_mid: None,
+            last_book_sequence: None,
+            rng,
+        };
+        strategy.roll_refresh_jitter();
+        strategy
+    }
+
+    /// Validates `config` and queues it to replace the current one at the start of the
+    /// next generate cycle, so both sides of a quote always come from one config. The
+    /// swap forces a requote. A disabled strategy isn't quoting and swaps straight away.
+    pub fn apply_config(&mut self, config: MarketMakingConfig) -> Result<(), Vec<String>> {
+        config.validate()?;
+        if config.base_config.symbol != self.config.base_config.symbol {
+            return Err(vec![format!(
+                "Symbol cannot be changed from {} on a running strategy",
+                self.config.base_config.symbol
+            )]);
+        }
+
+        self.pending_config = Some(config);
+        if !self.enabled {
+            self.swap_pending_config();
+        }
+        Ok(())
+    }
+
+    pub fn quote_ladder(&self) -> &QuoteLadder {
+        &self.quote_ladder
+    }
+
+    /// The config waiting for the next generate cycle, if any.
+    pub fn pending_config(&self) -> Option<&MarketMakingConfig> {
+        self.pending_config.as_ref()
+    }
+
+    fn swap_pending_config(&mut self) {
+        let Some(config) = self.pending_config.take() else {
+            return;
+        };
+        if config.update_rate_window_ms != self.config.update_rate_window_ms {
+            self.book_updates = UpdateRate::new(config.update_rate_window_ms);
+        }
+        self.config = config;
+        self.roll_refresh_jitter();
+        self.force_refresh = true;
+    }
+
+    pub fn set_fee_rates(&mut self, fee_rates: FeeRates) {
+        self.fee_rates = fee_rates;
+    }
+
+    pub fn set_funding_info(&mut self, funding: FundingInfo) {
+        self.funding = Some(funding);
+    }
+
+    /// Predicted hourly funding rate for the strategy's symbol, once it has been fetched.
+    pub fn funding_rate(&self) -> Option<Decimal> {
+        self.funding.as_ref().map(|funding| funding.funding_rate)
+    }
+
+    /// Smallest spread that still earns `min_edge_bps` after paying the maker fee on
+    /// both legs of a round trip. Maker rebates narrow it.
+    pub fn min_spread(&self, fair_price: Decimal) -> Decimal {
+        let min_edge = Bps::from(self.config.min_edge_bps).to_fraction();
+        let round_trip_fees = dec!(2) * self.fee_rates.maker_rate;
+        (fair_price * (min_edge + round_trip_fees)).max(Decimal::ZERO)
+    }
+
+    /// Drives refresh timing from recorded timestamps instead of the wall clock.
+    pub fn set_simulated_time(&mut self, now: DateTime<Utc>) {
+        self.simulated_now = Some(now);
+    }
+
+    fn now(&self) -> DateTime<Utc> {
+        self.simulated_now.unwrap_or_else(Utc::now)
+    }
+
+    /// Feeds the book's mid into the realized volatility estimate. Call on every book
+    /// update, whether or not quotes are refreshed.
+    pub fn observe_mid(&mut self, mid: Decimal) {
+        let now = self.now();
+        let elapsed = self.last_mid_at
+            .and_then(|last_mid_at| now.signed_duration_since(last_mid_at).to_std().ok())
+            .unwrap_or_default();
+        self.last_mid_at = Some(now);
+        self.volatility.update(mid, elapsed);
+
+        if let Some(last_mid) = self.last_mid {
+            self.last_price_change = Bps::from_ratio((mid - last_mid).abs(), last_mid);
+        }
+        self.last_mid = Some(mid);
+    }
+
+    /// Whether the last mid move was too large to quote through, which is usually a
+    /// sweep or news rather than noise the spread can absorb.
+    pub fn is_price_change_too_large(&self) -> bool {
+        self.config.max_price_change_bps > 0 && self.last_price_change > Bps::from(self.config.max_price_change_bps)
+    }
+
+    /// Multiple applied to the base spread: realized over reference volatility,
+    /// kept between 1 and `max_volatility_widening`.
+    pub fn volatility_multiplier(&self) -> Decimal {
+        if self.config.reference_volatility <= Decimal::ZERO {
+            return Decimal::ONE;
+        }
+        let Some(vol) = self.volatility.current_vol()
+            .filter(|estimate| estimate.returns >= MIN_VOLATILITY_RETURNS)
+            .and_then(|estimate| Decimal::from_f64(estimate.annualized)) else {
+            return Decimal::ONE;
+        };
+
+        (vol / self.config.reference_volatility)
+            .min(self.config.max_volatility_widening)
+            .max(Decimal::ONE)
+    }
+
+    /// Counts a book update towards the update rate. `sequence` is the book's, so a
+    /// book seen again without a new snapshot isn't counted twice.
+    pub fn observe_book_update(&mut self, sequence: u64) {
+        if self.last_book_sequence == Some(sequence) {
+            return;
+        }
+        self.last_book_sequence = Some(sequence);
+        let now = self.now();
+        self.book_updates.record(now);
+    }
+
+    /// Book updates per second over `update_rate_window_ms`.
+    pub fn book_update_rate(&self) -> f64 {
+        self.book_updates.per_second(self.now())
+    }
+
+    fn update_rate_exceeds(&self, threshold: Decimal) -> bool {
+        threshold > Decimal::ZERO && self.book_update_rate() > threshold.to_f64().unwrap_or(f64::MAX)
+    }
+
+    /// Spread multiple for a fast market: `fast_market_widening` while the book is
+    /// updating faster than `fast_market_update_rate`, which often precedes a move.
+    pub fn fast_market_multiplier(&self) -> Decimal {
+        if self.update_rate_exceeds(self.config.fast_market_update_rate) {
+            self.config.fast_market_widening.max(Decimal::ONE)
+        } else {
+            Decimal::ONE
         }
     }
 
+    /// Whether the book is updating too fast to quote at all.
+    pub fn is_market_too_fast(&self) -> bool {
+        self.update_rate_exceeds(self.config.max_update_rate)
+    }
+
+    fn roll_refresh_jitter(&mut self) {
+        self.refresh_jitter_ms = self.rng.gen_range(0..=self.config.order_refresh_jitter_ms);
+    }
+
+    /// Time of the next scheduled requote, absent a large price move.
+    pub fn next_refresh_time(&self) -> DateTime<Utc> {
+        self.last_order_time
+            + Duration::milliseconds((self.config.order_refresh_interval_ms + self.refresh_jitter_ms) as i64)
+    }
+
     fn should_refresh_orders(&self, current_price: Decimal) -> bool {
-        let time_elapsed = Utc::now().signed_duration_since(self.last_order_time);
-        let time_threshold = Duration::milliseconds(self.config.order_refresh_interval_ms as i64);
+        let time_elapsed = self.now().signed_duration_since(self.last_order_time);
+        let time_threshold = Duration::milliseconds((self.config.order_refresh_interval_ms + self.refresh_jitter_ms) as i64);
         
         // Refresh if enough time has passed
-        if time_elapsed > time_threshold {
+        if self.force_refresh || time_elapsed > time_threshold {
+            return true;
+        }
+        
+        // Pull resting quotes as soon as the market gets too fast or jumps
+        if (self.is_market_too_fast() || self.is_price_change_too_large()) && !self.active_orders.is_empty() {
             return true;
         }
         
@@ -78,23 +524,91 @@ impl MarketMakingStrategy {
         false
     }
 
-    fn calculate_fair_price(&self, order_book: &OrderBook) -> Option<Decimal> {
-        order_book.mid_price()
+    /// Stops quoting the side that would grow inventory once it reaches a band, and
+    /// resumes it only once inventory is back within `inventory_resume_fraction` of
+    /// the band, so quotes don't flap at the edge.
+    pub fn update_stopped_side(&mut self) {
+        let inventory = self.current_inventory;
+        let max = self.config.max_inventory;
+        let min = self.config.min_inventory;
+
+        if max.is_some_and(|max| inventory >= max) {
+            self.stopped_side = Some(Side::Buy);
+        } else if min.is_some_and(|min| inventory <= min) {
+            self.stopped_side = Some(Side::Sell);
+        } else {
+            let resumed = match (self.stopped_side, max, min) {
+                (Some(Side::Buy), Some(max), _) => inventory <= self.config.inventory_resume_level(max),
+                (Some(Side::Sell), _, Some(min)) => inventory >= self.config.inventory_resume_level(min),
+                // The band was removed by a config change
+                (Some(_), _, _) => true,
+                (None, _, _) => false,
+            };
+            if resumed {
+                self.stopped_side = None;
+            }
+        }
+    }
+
+    // Reduce-only order taking inventory back to where the stopped side resumes
+    fn unwind_order(&self, fair_price: Decimal, price_scale: Option<u32>, tick_received_at: Option<std::time::Instant>) -> Option<OrderAction> {
+        let aggression_bps = self.config.unwind_aggression_bps?;
+        let aggression = Bps::from(aggression_bps).of(fair_price);
+        let (side, band, price) = match self.stopped_side? {
+            Side::Buy => (Side::Sell, self.config.max_inventory?, fair_price - aggression),
+            Side::Sell => (Side::Buy, self.config.min_inventory?, fair_price + aggression),
+        };
+        let size = (self.current_inventory - self.config.inventory_resume_level(band)).abs();
+        if size.is_zero() {
+            return None;
+        }
+
+        Some(OrderAction {
+            action_type: OrderActionType::Place,
+            order: Some(NewOrder {
+                symbol: self.config.base_config.symbol.clone(),
+                side,
+                order_type: OrderType::Limit,
+                price: price_scale.map_or(price, |scale| round_price(price, scale, side)),
+                size,
+                client_id: Some("mm_unwind".to_string()),
+                reduce_only: true,
+                tick_received_at,
+            }),
+            order_id: None,
+        })
     }
 
-    fn calculate_spread(&self, _order_book: &OrderBook, fair_price: Decimal) -> Decimal {
-        let base_spread = fair_price * Decimal::from(self.config.spread_bps) / dec!(10000);
+    fn calculate_fair_price(&self, book: &BookSnapshot) -> Option<Decimal> {
+        match self.config.fair_price_source {
+            FairPriceSource::Mid => book.mid_price(),
+            FairPriceSource::Microprice => book.microprice(),
+            FairPriceSource::Vwap(depth) => book.volume_weighted_mid(depth),
+        }
+    }
+
+    fn calculate_spread(&self, _book: &BookSnapshot, fair_price: Decimal) -> Decimal {
+        let spread_bps = self.config.spread_bps_for(&self.config.base_config.symbol);
+        let base_spread = Bps::from(spread_bps).of(fair_price)
+            * self.volatility_multiplier()
+            * self.fast_market_multiplier();
         
         // Add inventory skew
         let inventory_adjustment = self.current_inventory * self.config.inventory_skew_factor;
         
-        // Ensure minimum spread
-        let min_spread = fair_price * Decimal::from(self.config.min_edge_bps) / dec!(10000);
-        
-        (base_spread + inventory_adjustment.abs()).max(min_spread)
+        // Ensure minimum spread net of fees
+        (base_spread + inventory_adjustment.abs()).max(self.min_spread(fair_price))
     }
 
-    fn generate_orders(&self, fair_price: Decimal, spread: Decimal) -> Vec<OrderAction> {
+    // A book much wider than normal means fair price and spread can't be trusted, and
+    // one updating too fast or that has just jumped is likely about to move
+    fn is_book_quotable(&self, book: &BookSnapshot) -> bool {
+        !self.is_market_too_fast() && !self.is_price_change_too_large() && book.spread_bps()
+            .is_some_and(|spread_bps| spread_bps <= Decimal::from(self.config.max_quote_spread_bps))
+    }
+
+    // Prices are rounded to `price_scale`, the book's tick scale, when it is known
+    fn generate_orders(&self, fair_price: Decimal, spread: Decimal, price_scale: Option<u32>, tick_received_at: Option<std::time::Instant>) -> Vec<OrderAction> {
         let mut actions = Vec::new();
         
         // Calculate bid/ask prices with inventory skew
@@ -103,17 +617,26 @@ impl MarketMakingStrategy {
         
         let bid_price = fair_price - half_spread - inventory_skew;
         let ask_price = fair_price + half_spread - inventory_skew;
+        let round = |price: Decimal, side: Side| match price_scale {
+            Some(scale) => round_price(price, scale, side),
+            None => price,
+        };
+        
+        let order_size = self.config.order_size_for(&self.config.base_config.symbol);
+        let quoted_per_side = |side| if self.stopped_side == Some(side) { 0 } else { self.config.max_orders_per_side };
         
         // Generate buy orders
-        for i in 0..self.config.max_orders_per_side {
+        for i in 0..quoted_per_side(Side::Buy) {
             let price_offset = Decimal::from(i) * (spread / dec!(4.0)); // Ladder orders
             let order = NewOrder {
                 symbol: self.config.base_config.symbol.clone(),
                 side: Side::Buy,
                 order_type: OrderType::Limit,
-                price: bid_price - price_offset,
-                size: self.config.order_size,
+                price: round(bid_price - price_offset, Side::Buy),
+                size: order_size,
                 client_id: Some(format!("mm_buy_{}", i)),
+                reduce_only: false,
+                tick_received_at,
             };
             
             actions.push(OrderAction {
@@ -124,15 +647,17 @@ impl MarketMakingStrategy {
         }
         
         // Generate sell orders
-        for i in 0..self.config.max_orders_per_side {
+        for i in 0..quoted_per_side(Side::Sell) {
             let price_offset = Decimal::from(i) * (spread / dec!(4.0)); // Ladder orders
             let order = NewOrder {
                 symbol: self.config.base_config.symbol.clone(),
                 side: Side::Sell,
                 order_type: OrderType::Limit,
-                price: ask_price + price_offset,
-                size: self.config.order_size,
+                price: round(ask_price + price_offset, Side::Sell),
+                size: order_size,
                 client_id: Some(format!("mm_sell_{}", i)),
+                reduce_only: false,
+                tick_received_at,
             };
             
             actions.push(OrderAction {
@@ -145,18 +670,32 @@ impl MarketMakingStrategy {
         actions
     }
 
-    pub fn generate_actions_sync(&self, order_book: &OrderBook) -> Vec<OrderAction> {
+    pub fn generate_actions_sync(&mut self, book: &BookSnapshot) -> Vec<OrderAction> {
+        self.refresh_quotes(book, false).map(|(actions, _)| actions).unwrap_or_default()
+    }
+
+    // The cancels and new quotes for `book`, with the fair price they were priced off,
+    // or None when disabled, without a fair price or with no refresh due. `observe`
+    // feeds the book into volatility and update-rate tracking first.
+    fn refresh_quotes(&mut self, book: &BookSnapshot, observe: bool) -> Option<(Vec<OrderAction>, Decimal)> {
+        self.swap_pending_config();
         if !self.enabled {
-            return vec![];
+            return None;
         }
 
-        let Some(fair_price) = self.calculate_fair_price(order_book) else {
-            return vec![];
-        };
+        let fair_price = self.calculate_fair_price(book)?;
+        if observe && !book.is_stale() {
+            self.observe_mid(fair_price);
+            self.observe_book_update(book.sequence);
+        }
+
+        // A side stopping or resuming changes what should be quoted straight away
+        let stopped_side = self.stopped_side;
+        self.update_stopped_side();
 
         // Check if we should refresh orders
-        if !self.should_refresh_orders(fair_price) {
-            return vec![];
+        if self.stopped_side == stopped_side && !self.should_refresh_orders(fair_price) {
+            return None;
         }
 
         let mut actions = Vec::new();
@@ -166,16 +705,42 @@ impl MarketMakingStrategy {
             actions.extend(self.cancel_all_orders());
         }
 
-        // Calculate new spread and generate orders
-        let spread = self.calculate_spread(order_book, fair_price);
-        actions.extend(self.generate_orders(fair_price, spread));
+        // Calculate new spread and generate orders, but never quote off a stale or dislocated book
+        if !book.is_stale() && self.is_book_quotable(book) {
+            let spread = self.calculate_spread(book, fair_price);
+            actions.extend(self.generate_orders(fair_price, spread, book.price_scale(), book.received_at));
+            actions.extend(self.unwind_order(fair_price, book.price_scale(), book.received_at));
+        }
+        self.quote_ladder = QuoteLadder::from_actions(&actions, fair_price, self.now());
 
-        actions
+        Some((actions, fair_price))
     }
 
     pub fn update_last_price(&mut self, price: Decimal) {
         self.last_price = Some(price);
-        self.last_order_time = Utc::now();
+        self.last_order_time = self.now();
+        self.force_refresh = false;
+        self.roll_refresh_jitter();
+    }
+
+    /// Whether `order` is one of this strategy's quotes.
+    pub fn owns_order(&self, order: &Order) -> bool {
+        order.symbol == self.config.base_config.symbol
+            && order.client_id.as_deref().is_some_and(|id| id.starts_with("mm_"))
+    }
+
+    /// Keeps `active_orders` in step with an order's state, so quotes still resting
+    /// are cancelled before the next refresh replaces them.
+    pub fn track_order(&mut self, order: &Order) {
+        self.quote_ladder.track_order(order);
+        match order.status {
+            OrderStatus::Pending | OrderStatus::Submitted | OrderStatus::PartiallyFilled => {
+                self.active_orders.insert(order.id, order.clone());
+            }
+            OrderStatus::Filled | OrderStatus::Cancelled | OrderStatus::Rejected => {
+                self.active_orders.remove(&order.id);
+            }
+        }
     }
 
     fn cancel_all_orders(&self) -> Vec<OrderAction> {
@@ -192,50 +757,17 @@ impl MarketMakingStrategy {
 
 #[async_trait]
 impl TradingStrategy for MarketMakingStrategy {
-    async fn on_market_data(&mut self, order_book: &OrderBook) -> Vec<OrderAction> {
-        if !self.enabled {
-            return vec![];
-        }
-
-        let Some(fair_price) = self.calculate_fair_price(order_book) else {
+    async fn on_market_data(&mut self, book: &BookSnapshot) -> Vec<OrderAction> {
+        let Some((actions, fair_price)) = self.refresh_quotes(book, true) else {
             return vec![];
         };
-
-        // Check if we should refresh orders
-        if !self.should_refresh_orders(fair_price) {
-            return vec![];
-        }
-
-        let mut actions = Vec::new();
-
-        // Cancel existing orders first
-        if !self.active_orders.is_empty() {
-            actions.extend(self.cancel_all_orders());
-        }
-
-        // Calculate new spread and generate orders
-        let spread = self.calculate_spread(order_book, fair_price);
-        actions.extend(self.generate_orders(fair_price, spread));
-
-        self.last_price = Some(fair_price);
-        self.last_order_time = Utc::now();
+        self.update_last_price(fair_price);
 
         actions
     }
 
     async fn on_order_update(&mut self, order: &Order) -> Vec<OrderAction> {
-        match order.status {
-            OrderStatus::Submitted => {
-                self.active_orders.insert(order.id, order.clone());
-            }
-            OrderStatus::Filled | OrderStatus::Cancelled | OrderStatus::Rejected => {
-                self.active_orders.remove(&order.id);
-            }
-            OrderStatus::PartiallyFilled => {
-                self.active_orders.insert(order.id, order.clone());
-            }
-            _ => {}
-        }
+        self.track_order(order);
         vec![]
     }
 
@@ -268,6 +800,292 @@ impl TradingStrategy for MarketMakingStrategy {
         if !enabled {
             // Cancel all orders when disabled
             self.active_orders.clear();
+            self.quote_ladder = QuoteLadder::default();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::hl_msgs::TobMsg;
+    use crate::trading::order_book::OrderBook;
+
+    fn config_with_overrides() -> MarketMakingConfig {
+        let mut config = MarketMakingConfig { max_orders_per_side: 1, ..Default::default() };
+        config.symbol_overrides.insert("BTC".to_string(), SymbolOverride { spread_bps: Some(10), order_size: Some(dec!(0.01)) });
+        config.symbol_overrides.insert("PEPE".to_string(), SymbolOverride { spread_bps: Some(80), order_size: None });
+        config
+    }
+
+    // One bid and one ask quoted by a fresh strategy for `symbol` on a 99.99 / 100.01 book
+    fn quotes(config: &MarketMakingConfig, symbol: &str) -> Vec<NewOrder> {
+        let mut config = config.clone();
+        config.base_config.symbol = symbol.to_string();
+        let mut strategy = MarketMakingStrategy::with_seed(config, 7);
+        let mut book = OrderBook::new(symbol.to_string());
+        book.update_level(Side::Buy, dec!(99.99), dec!(5));
+        book.update_level(Side::Sell, dec!(100.01), dec!(5));
+        strategy.observe_mid(dec!(100));
+        strategy.generate_actions_sync(&book.snapshot(SNAPSHOT_LEVELS))
+            .into_iter()
+            .filter_map(|action| action.order)
+            .collect()
+    }
+
+    fn book(bid: Decimal, ask: Decimal) -> BookSnapshot {
+        let mut book = OrderBook::new("HYPE".to_string());
+        book.update_level(Side::Buy, bid, dec!(5));
+        book.update_level(Side::Sell, ask, dec!(5));
+        book.snapshot(SNAPSHOT_LEVELS)
+    }
+
+    fn quote_prices(strategy: &mut MarketMakingStrategy, book: &BookSnapshot) -> Vec<(Side, Decimal)> {
+        strategy.generate_actions_sync(book)
+            .into_iter()
+            .filter_map(|action| action.order)
+            .map(|order| (order.side, order.price))
+            .collect()
+    }
+
+    #[test]
+    fn a_new_fee_tier_moves_the_minimum_spread_and_the_quotes() {
+        // 1 bps is inside the minimum, so the fee-aware floor sets the width
+        let config = MarketMakingConfig { spread_bps: 1, min_edge_bps: 5, max_orders_per_side: 1, ..Default::default() };
+        let mut strategy = MarketMakingStrategy::with_seed(config, 7);
+        let book = book(dec!(99.99), dec!(100.01));
+
+        // Base tier: 5 bps edge plus 2 x 1.5 bps maker fee
+        assert_eq!(strategy.min_spread(dec!(100)), dec!(0.08));
+        assert_eq!(quote_prices(&mut strategy, &book), vec![(Side::Buy, dec!(99.96)), (Side::Sell, dec!(100.04))]);
+
+        // A tier with a 1 bps maker rebate narrows the floor by the rebate on both legs
+        strategy.set_fee_rates(FeeRates { maker_rate: dec!(-0.0001), taker_rate: dec!(0.0003) });
+        assert_eq!(strategy.min_spread(dec!(100)), dec!(0.03));
+        assert_eq!(quote_prices(&mut strategy, &book), vec![(Side::Buy, dec!(99.98)), (Side::Sell, dec!(100.02))]);
+    }
+
+    #[test]
+    fn a_book_wider_than_max_quote_spread_gets_no_quotes() {
+        let config = MarketMakingConfig { max_quote_spread_bps: 500, max_orders_per_side: 1, ..Default::default() };
+        let mut strategy = MarketMakingStrategy::with_seed(config, 7);
+
+        // 90 / 110 is 2000 bps wide
+        assert!(quote_prices(&mut strategy, &book(dec!(90), dec!(110))).is_empty());
+        assert_eq!(quote_prices(&mut strategy, &book(dec!(99.99), dec!(100.01))).len(), 2);
+        // 4.9% wide is still within 500 bps
+        assert_eq!(quote_prices(&mut strategy, &book(dec!(97.55), dec!(102.45))).len(), 2);
+    }
+
+    #[test]
+    fn each_fair_price_source_reads_its_own_price_off_the_book() {
+        let mut book = OrderBook::new("HYPE".to_string());
+        book.update_level(Side::Buy, dec!(99.9), dec!(1));
+        book.update_level(Side::Buy, dec!(99.5), dec!(3));
+        book.update_level(Side::Sell, dec!(100.1), dec!(3));
+        book.update_level(Side::Sell, dec!(100.3), dec!(1));
+        let book = book.snapshot(SNAPSHOT_LEVELS);
+        let fair_price = |fair_price_source| {
+            let config = MarketMakingConfig { fair_price_source, ..Default::default() };
+            MarketMakingStrategy::with_seed(config, 7).calculate_fair_price(&book).unwrap()
+        };
+
+        assert_eq!(fair_price(FairPriceSource::Mid), dec!(100));
+        // The ask is three times the bid's size, so the price leans towards the bid
+        assert_eq!(fair_price(FairPriceSource::Microprice), dec!(99.95));
+        // Bid VWAP 99.6, ask VWAP 100.15
+        assert_eq!(fair_price(FairPriceSource::Vwap(2)), dec!(99.875));
+        assert_eq!(fair_price(FairPriceSource::Vwap(1)), dec!(100));
+    }
+
+    // One mid a second, alternating between 100 and `high`
+    fn observe_choppy_mids(strategy: &mut MarketMakingStrategy, high: Decimal, count: i64) {
+        let start = strategy.simulated_now.unwrap_or_else(Utc::now);
+        for i in 0..count {
+            strategy.set_simulated_time(start + Duration::seconds(i + 1));
+            strategy.observe_mid(if i % 2 == 0 { dec!(100) } else { high });
         }
     }
+
+    #[test]
+    fn realized_volatility_widens_the_spread_up_to_its_cap() {
+        let config = MarketMakingConfig {
+            spread_bps: 20,
+            max_orders_per_side: 1,
+            reference_volatility: dec!(1.0),
+            max_volatility_widening: dec!(2.0),
+            ..Default::default()
+        };
+        let book = book(dec!(99.99), dec!(100.01));
+
+        // 0.1 bps a second is about 6% a year, well under the reference
+        let mut calm = MarketMakingStrategy::with_seed(config.clone(), 7);
+        observe_choppy_mids(&mut calm, dec!(100.001), 40);
+        assert_eq!(calm.volatility_multiplier(), Decimal::ONE);
+        assert_eq!(quote_prices(&mut calm, &book), vec![(Side::Buy, dec!(99.9)), (Side::Sell, dec!(100.1))]);
+
+        // Too few returns to trust yet
+        let mut choppy = MarketMakingStrategy::with_seed(config, 7);
+        observe_choppy_mids(&mut choppy, dec!(100.5), 10);
+        assert_eq!(choppy.volatility_multiplier(), Decimal::ONE);
+
+        // 50 bps a second is far above it, so the spread is doubled and no more
+        observe_choppy_mids(&mut choppy, dec!(100.5), 40);
+        assert_eq!(choppy.volatility_multiplier(), dec!(2.0));
+        assert_eq!(quote_prices(&mut choppy, &book), vec![(Side::Buy, dec!(99.8)), (Side::Sell, dec!(100.2))]);
+    }
+
+    #[test]
+    fn a_mid_jump_past_max_price_change_pulls_the_quotes() {
+        let config = MarketMakingConfig { max_price_change_bps: 50, max_orders_per_side: 1, ..Default::default() };
+        let mut strategy = MarketMakingStrategy::with_seed(config, 7);
+        let book = book(dec!(100.99), dec!(101.01));
+
+        strategy.observe_mid(dec!(100));
+        strategy.observe_mid(dec!(101));
+        assert!(strategy.is_price_change_too_large());
+        assert!(quote_prices(&mut strategy, &book).is_empty());
+
+        // A small move afterwards quotes again
+        strategy.observe_mid(dec!(101.1));
+        assert_eq!(quote_prices(&mut strategy, &book).len(), 2);
+    }
+
+    #[test]
+    fn symbols_on_one_refresh_interval_requote_at_different_times() {
+        let book = book(dec!(99.99), dec!(100.01));
+        let start = Utc::now();
+        // Milliseconds after `start` at which each strategy first requotes
+        let requote_after = |symbol: &str, seed: u64| {
+            let mut config = MarketMakingConfig { order_refresh_interval_ms: 1000, order_refresh_jitter_ms: 200, ..Default::default() };
+            config.base_config.symbol = symbol.to_string();
+            let mut strategy = MarketMakingStrategy::with_seed(config, seed);
+            strategy.set_simulated_time(start);
+            strategy.update_last_price(dec!(100));
+
+            (1..=1300).step_by(5).find(|&ms| {
+                strategy.set_simulated_time(start + Duration::milliseconds(ms));
+                !strategy.generate_actions_sync(&book).is_empty()
+            }).unwrap()
+        };
+
+        let hype = requote_after("HYPE", 1);
+        let eth = requote_after("ETH", 2);
+        assert_ne!(hype, eth);
+        assert!((1000..=1205).contains(&hype) && (1000..=1205).contains(&eth));
+        // The same seed requotes at the same time
+        assert_eq!(requote_after("HYPE", 1), hype);
+    }
+
+    #[test]
+    fn quotes_carry_the_receipt_time_of_the_book_update_behind_them() {
+        let received_at = std::time::Instant::now();
+        let mut tob: TobMsg = serde_json::from_value(serde_json::json!({
+            "channel": "l2Book",
+            "data": {
+                "coin": "HYPE",
+                "time": 1,
+                "levels": [[{"px": "99.99", "sz": "5", "n": 1}], [{"px": "100.01", "sz": "5", "n": 1}]],
+            },
+        })).unwrap();
+        tob.data.received_at = Some(received_at);
+        let mut book = OrderBook::new("HYPE".to_string());
+        book.update_from_tob(&tob.data).unwrap();
+
+        let mut strategy = MarketMakingStrategy::with_seed(MarketMakingConfig::default(), 7);
+        let orders: Vec<NewOrder> = strategy.generate_actions_sync(&book.snapshot(SNAPSHOT_LEVELS))
+            .into_iter()
+            .filter_map(|action| action.order)
+            .collect();
+
+        assert_eq!(orders.len(), 6);
+        assert!(orders.iter().all(|order| order.tick_received_at == Some(received_at)));
+    }
+
+    #[test]
+    fn quote_prices_are_on_the_book_tick_without_trailing_digits() {
+        let config = MarketMakingConfig { max_orders_per_side: 1, ..Default::default() };
+        let mut strategy = MarketMakingStrategy::with_seed(config, 7);
+
+        // A 20 bps spread around a 25.025 mid falls between ticks
+        let prices: Vec<String> = quote_prices(&mut strategy, &book(dec!(25.01), dec!(25.04)))
+            .into_iter()
+            .map(|(_, price)| price.to_string())
+            .collect();
+        assert_eq!(prices, ["24.99", "25.06"]);
+    }
+
+    #[test]
+    fn a_burst_of_book_updates_widens_and_then_pulls_the_quotes() {
+        let config = MarketMakingConfig {
+            max_orders_per_side: 1,
+            update_rate_window_ms: 1000,
+            fast_market_update_rate: dec!(10),
+            fast_market_widening: dec!(2),
+            max_update_rate: dec!(50),
+            ..Default::default()
+        };
+        let mut strategy = MarketMakingStrategy::with_seed(config, 7);
+        let book = book(dec!(99.99), dec!(100.01));
+        let start = Utc::now();
+        // Each book's sequence is its millisecond offset
+        let updates = |strategy: &mut MarketMakingStrategy, count: i64, every_ms: i64, from_ms: i64| {
+            for ms in (0..count).map(|i| from_ms + i * every_ms) {
+                strategy.set_simulated_time(start + Duration::milliseconds(ms));
+                strategy.observe_book_update(ms as u64);
+            }
+        };
+
+        // Five a second, and a book seen twice is one update
+        updates(&mut strategy, 5, 200, 0);
+        strategy.observe_book_update(800);
+        assert_eq!(strategy.book_update_rate(), 5.0);
+        assert_eq!(quote_prices(&mut strategy, &book), vec![(Side::Buy, dec!(99.9)), (Side::Sell, dec!(100.1))]);
+
+        // 20 more in 200ms takes the last second to 24
+        updates(&mut strategy, 20, 10, 1000);
+        assert_eq!(strategy.book_update_rate(), 24.0);
+        assert_eq!(strategy.fast_market_multiplier(), dec!(2));
+        assert_eq!(quote_prices(&mut strategy, &book), vec![(Side::Buy, dec!(99.8)), (Side::Sell, dec!(100.2))]);
+
+        // Past max_update_rate nothing is quoted
+        updates(&mut strategy, 40, 5, 1200);
+        assert!(strategy.is_market_too_fast());
+        assert!(quote_prices(&mut strategy, &book).is_empty());
+    }
+
+    #[test]
+    fn overrides_resolve_per_symbol_and_fall_back_to_the_base() {
+        let config = config_with_overrides();
+
+        assert_eq!(config.spread_bps_for("BTC"), 10);
+        assert_eq!(config.order_size_for("BTC"), dec!(0.01));
+        // Unset fields keep the base value
+        assert_eq!(config.spread_bps_for("PEPE"), 80);
+        assert_eq!(config.order_size_for("PEPE"), config.order_size);
+        assert_eq!(config.spread_bps_for("HYPE"), config.spread_bps);
+        assert_eq!(config.order_size_for("HYPE"), config.order_size);
+    }
+
+    #[test]
+    fn two_symbols_quote_their_own_spread_and_size_from_one_base_config() {
+        let config = config_with_overrides();
+
+        let btc = quotes(&config, "BTC");
+        let pepe = quotes(&config, "PEPE");
+        let prices_and_sizes = |orders: &[NewOrder]| orders.iter().map(|order| (order.side, order.price, order.size)).collect::<Vec<_>>();
+        // 10 bps of 100 is 0.10 wide, 80 bps is 0.80
+        assert_eq!(prices_and_sizes(&btc), vec![(Side::Buy, dec!(99.95), dec!(0.01)), (Side::Sell, dec!(100.05), dec!(0.01))]);
+        assert_eq!(prices_and_sizes(&pepe), vec![(Side::Buy, dec!(99.6), dec!(1.0)), (Side::Sell, dec!(100.4), dec!(1.0))]);
+    }
+
+    #[test]
+    fn invalid_overrides_are_reported() {
+        let mut config = config_with_overrides();
+        config.symbol_overrides.insert("X".to_string(), SymbolOverride { spread_bps: Some(0), order_size: Some(Decimal::ZERO) });
+
+        let errors = config.validate().unwrap_err();
+        assert!(errors.contains(&"X spread must be at least 1 bps".to_string()));
+        assert!(errors.contains(&"X order size must be positive".to_string()));
+    }
 }
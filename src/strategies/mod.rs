@@ -1,2 +1,3 @@
 pub mod base_strategy;
-pub mod market_making;
\ No newline at end of file
+pub mod market_making;
+pub mod quote_ladder;
\ No newline at end of file
@@ -0,0 +1,90 @@
+use crate::trading::types::{Order, OrderAction, OrderActionType, OrderStatus, Side};
+use chrono::{DateTime, Utc};
+use rust_decimal::Decimal;
+use uuid::Uuid;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct LadderRung {
+    pub client_id: Option<String>,
+    pub price: Decimal,
+    pub size: Decimal,
+    pub filled_size: Decimal,
+    pub order_id: Option<Uuid>, // Set once the order placed for this rung is seen
+    pub status: Option<OrderStatus>, // None until then: intended but not yet live
+}
+
+/// The quotes of the latest refresh cycle next to what became of each of them. Rungs
+/// are in quoting order, best price first. Orders left from earlier cycles are not on it.
+#[derive(Debug, Clone, Default)]
+pub struct QuoteLadder {
+    pub bids: Vec<LadderRung>,
+    pub asks: Vec<LadderRung>,
+    pub fair_price: Option<Decimal>,
+    pub quoted_at: Option<DateTime<Utc>>,
+}
+
+impl QuoteLadder {
+    /// One intended rung per place action. A cycle that only cancels gives an empty ladder.
+    pub fn from_actions(actions: &[OrderAction], fair_price: Decimal, quoted_at: DateTime<Utc>) -> Self {
+        let mut ladder = Self {
+            fair_price: Some(fair_price),
+            quoted_at: Some(quoted_at),
+            ..Self::default()
+        };
+        for action in actions {
+            let (OrderActionType::Place, Some(order)) = (&action.action_type, &action.order) else {
+                continue;
+            };
+            let rung = LadderRung {
+                client_id: order.client_id.clone(),
+                price: order.price,
+                size: order.size,
+                filled_size: Decimal::ZERO,
+                order_id: None,
+                status: None,
+            };
+            match order.side {
+                Side::Buy => ladder.bids.push(rung),
+                Side::Sell => ladder.asks.push(rung),
+            }
+        }
+        ladder
+    }
+
+    /// Updates the rung `order` was placed for. An order is bound to an unbound rung with
+    /// the same client id and price the first time it is seen live; after that only its
+    /// id matches, so an earlier cycle's order reusing the client id is left alone.
+    pub fn track_order(&mut self, order: &Order) {
+        let rungs = match order.side {
+            Side::Buy => &mut self.bids,
+            Side::Sell => &mut self.asks,
+        };
+        let live = matches!(order.status, OrderStatus::Pending | OrderStatus::Submitted | OrderStatus::PartiallyFilled);
+        let index = rungs.iter().position(|rung| rung.order_id == Some(order.id)).or_else(|| {
+            rungs.iter().position(|rung| {
+                live && rung.order_id.is_none() && rung.client_id == order.client_id && rung.price == order.price
+            })
+        });
+        if let Some(rung) = index.map(|index| &mut rungs[index]) {
+            rung.order_id = Some(order.id);
+            rung.status = Some(order.status);
+            rung.filled_size = order.filled_size;
+        }
+    }
+
+    pub fn rungs(&self) -> impl Iterator<Item = (Side, &LadderRung)> {
+        self.bids.iter().map(|rung| (Side::Buy, rung))
+            .chain(self.asks.iter().map(|rung| (Side::Sell, rung)))
+    }
+
+    /// Rungs whose order is resting, partially filled or not.
+    pub fn live_rungs(&self) -> usize {
+        self.rungs()
+            .filter(|(_, rung)| matches!(rung.status, Some(OrderStatus::Pending | OrderStatus::Submitted | OrderStatus::PartiallyFilled)))
+            .count()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.bids.is_empty() && self.asks.is_empty()
+    }
+}
@@ -1,5 +1,13 @@
+pub mod backtest;
+pub mod clock;
 pub mod order_book;
 pub mod order_manager;
+pub mod paper_trading;
+pub mod performance;
+pub mod pnl_reconstruction;
 pub mod position_manager;
 pub mod risk_manager;
-pub mod types;
\ No newline at end of file
+pub mod trigger_orders;
+pub mod types;
+pub mod update_rate;
+pub mod volatility;
\ No newline at end of file
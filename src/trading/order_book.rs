@@ -1,9 +1,86 @@
-use crate::model::hl_msgs::PriceLevel;
-use crate::trading::types::*;
+use crate::trading::types::Side;
+use crate::utils::decimal::{max_scale, Bps};
 use chrono::{DateTime, Utc};
 use std::collections::BTreeMap;
 use rust_decimal::Decimal;
 use std::str::FromStr;
+use tracing::{error, warn};
+
+pub type DepthLevels = Vec<(Decimal, Decimal)>;
+
+// Book depth the event loop and backtester hand to strategies; quoting never looks further
+pub const SNAPSHOT_LEVELS: usize = 50;
+
+/// The top of an `OrderBook` at one moment, as strategies see it. Plain vectors, so
+/// it is cheap to take every tick and can be moved across tasks freely.
+#[derive(Debug, Clone)]
+pub struct BookSnapshot {
+    pub symbol: String,
+    pub bids: DepthLevels, // Best first
+    pub asks: DepthLevels, // Best first
+    pub mid: Option<Decimal>,
+    pub ts: DateTime<Utc>, // The book's last_update
+    pub sequence: u64,
+    pub stale: bool,
+    pub received_at: Option<std::time::Instant>,
+}
+
+impl BookSnapshot {
+    pub fn best_bid(&self) -> Option<(Decimal, Decimal)> {
+        self.bids.first().copied()
+    }
+
+    pub fn best_ask(&self) -> Option<(Decimal, Decimal)> {
+        self.asks.first().copied()
+    }
+
+    pub fn mid_price(&self) -> Option<Decimal> {
+        self.mid
+    }
+
+    pub fn is_stale(&self) -> bool {
+        self.stale
+    }
+
+    pub fn spread_bps(&self) -> Option<Decimal> {
+        match (self.best_bid(), self.best_ask(), self.mid) {
+            (Some((bid, _)), Some((ask, _)), Some(mid)) if mid > Decimal::ZERO => Some(Bps::from_ratio(ask - bid, mid).value()),
+            (Some(_), Some(_), Some(_)) => Some(Decimal::ZERO),
+            _ => None,
+        }
+    }
+
+    /// Decimal places of the finest price in the snapshot, taken as the book's tick scale.
+    pub fn price_scale(&self) -> Option<u32> {
+        max_scale(self.bids.iter().chain(self.asks.iter()).map(|(price, _)| price))
+    }
+
+    /// Midpoint of the bid and ask VWAPs over the best `depth` levels of each side.
+    pub fn volume_weighted_mid(&self, depth: usize) -> Option<Decimal> {
+        let bid_vwap = vwap(self.bids.iter().take(depth).map(|(price, size)| (price, size)))?;
+        let ask_vwap = vwap(self.asks.iter().take(depth).map(|(price, size)| (price, size)))?;
+        Some((bid_vwap + ask_vwap) / Decimal::from(2))
+    }
+
+    /// Top-of-book prices weighted by the opposite side's size, so the price leans
+    /// toward the side more likely to be taken out.
+    pub fn microprice(&self) -> Option<Decimal> {
+        let ((bid, bid_size), (ask, ask_size)) = (self.best_bid()?, self.best_ask()?);
+        let total_size = bid_size + ask_size;
+        if total_size <= Decimal::ZERO {
+            return self.mid;
+        }
+        Some((bid * ask_size + ask * bid_size) / total_size)
+    }
+}
+
+// Size-weighted average price of `levels`; None when they hold no size
+fn vwap<'a>(levels: impl Iterator<Item = (&'a Decimal, &'a Decimal)>) -> Option<Decimal> {
+    let (volume, weighted_sum) = levels.fold((Decimal::ZERO, Decimal::ZERO), |(volume, sum), (price, size)| {
+        (volume + size, sum + price * size)
+    });
+    (volume > Decimal::ZERO).then(|| weighted_sum / volume)
+}
 
 #[derive(Debug, Clone)]
 pub struct OrderBook {
@@ -12,6 +89,10 @@ pub struct OrderBook {
     pub asks: BTreeMap<Decimal, Decimal>, // price -> size
     pub last_update: DateTime<Utc>,
     pub sequence: u64,
+    pub stale: bool, // Kept from before a reconnect; cleared by the next snapshot
+    pub verify_integrity: bool, // Run check_integrity after every update; panics on violation in debug builds
+    pub received_at: Option<std::time::Instant>, // Local receipt of the snapshot behind the current levels
+    pub crossed_updates: u64, // Snapshots rejected because best bid >= best ask
 }
 
 impl OrderBook {
@@ -22,40 +103,181 @@ impl OrderBook {
             asks: BTreeMap::new(),
             last_update: Utc::now(),
             sequence: 0,
+            stale: false,
+            verify_integrity: false,
+            received_at: None,
+            crossed_updates: 0,
+        }
+    }
+
+    pub fn with_integrity_checks(mut self, enabled: bool) -> Self {
+        self.verify_integrity = enabled;
+        self
+    }
+
+    /// The best `levels` on each side with the book's mid and update state, as
+    /// strategies take it.
+    pub fn snapshot(&self, levels: usize) -> BookSnapshot {
+        let (bids, asks) = self.get_depth(levels);
+        BookSnapshot {
+            symbol: self.symbol.clone(),
+            bids,
+            asks,
+            mid: self.mid_price(),
+            ts: self.last_update,
+            sequence: self.sequence,
+            stale: self.stale,
+            received_at: self.received_at,
         }
     }
 
-    pub fn update_from_tob(&mut self, tob_data: &crate::model::hl_msgs::OrderBookData) {
+    /// Copy of the book with only the best `levels` on each side, for readers that
+    /// don't need full depth. Much cheaper than `clone` on a deep book.
+    pub fn top_levels(&self, levels: usize) -> OrderBook {
+        OrderBook {
+            symbol: self.symbol.clone(),
+            bids: self.bids.iter().rev().take(levels).map(|(price, size)| (*price, *size)).collect(),
+            asks: self.asks.iter().take(levels).map(|(price, size)| (*price, *size)).collect(),
+            last_update: self.last_update,
+            sequence: self.sequence,
+            stale: self.stale,
+            verify_integrity: false,
+            received_at: self.received_at,
+            crossed_updates: self.crossed_updates,
+        }
+    }
+
+    /// Keeps the current levels but flags them as untrustworthy until the next
+    /// snapshot replaces them. Used across reconnects instead of clearing the book.
+    pub fn mark_stale(&mut self) {
+        self.stale = true;
+    }
+
+    pub fn is_stale(&self) -> bool {
+        self.stale
+    }
+
+    pub fn clear(&mut self) {
         self.bids.clear();
         self.asks.clear();
+        self.stale = false;
+    }
 
-        // Extract bids and asks from levels array
-        if let Some(bid_levels) = tob_data.levels.get(0) {
-            for level in bid_levels {
-                if let (Ok(price), Ok(size)) = (
-                    Decimal::from_str(&level.px),
-                    Decimal::from_str(&level.sz),
-                ) {
-                    self.bids.insert(price, size);
-                }
-            }
-        }
+    /// Replaces the book with a snapshot. `levels` must be `[bids, asks]` and must not
+    /// be crossed or locked; otherwise the snapshot is rejected and the current book
+    /// is left untouched.
+    pub fn update_from_tob(&mut self, tob_data: &crate::model::hl_msgs::OrderBookData) -> Result<(), String> {
+        let [bid_levels, ask_levels] = tob_data.levels.as_slice() else {
+            let error = format!(
+                "Malformed levels for {}: expected [bids, asks], got {} sides",
+                tob_data.coin, tob_data.levels.len()
+            );
+            warn!("{}", error);
+            return Err(error);
+        };
 
-        if let Some(ask_levels) = tob_data.levels.get(1) {
-            for level in ask_levels {
-                if let (Ok(price), Ok(size)) = (
-                    Decimal::from_str(&level.px),
-                    Decimal::from_str(&level.sz),
-                ) {
-                    self.asks.insert(price, size);
-                }
-            }
+        let bids = Self::parse_levels(bid_levels);
+        let asks = Self::parse_levels(ask_levels);
+
+        if let Some((bid, ask)) = Self::crossed_prices(&bids, &asks) {
+            self.crossed_updates += 1;
+            let error = format!(
+                "Rejected crossed book for {}: best bid {} >= best ask {} ({} rejected)",
+                tob_data.coin, bid, ask, self.crossed_updates
+            );
+            warn!("{}", error);
+            return Err(error);
         }
 
+        self.bids = bids;
+        self.asks = asks;
+
         // BTreeMap is automatically sorted by key
 
         self.last_update = Utc::now();
+        self.received_at = tob_data.received_at;
         self.sequence += 1;
+        self.stale = false;
+        self.run_integrity_check();
+        Ok(())
+    }
+
+    /// Sets the size resting at `price`; a size of zero or less removes the level.
+    pub fn update_level(&mut self, side: Side, price: Decimal, size: Decimal) {
+        let levels = match side {
+            Side::Buy => &mut self.bids,
+            Side::Sell => &mut self.asks,
+        };
+        if size > Decimal::ZERO {
+            levels.insert(price, size);
+        } else {
+            levels.remove(&price);
+        }
+
+        self.last_update = Utc::now();
+        self.sequence += 1;
+        self.run_integrity_check();
+    }
+
+    /// Verifies both sides are strictly sorted, every level has a positive price and
+    /// size, and the best bid is below the best ask.
+    pub fn check_integrity(&self) -> Result<(), String> {
+        for (side, levels) in [("bid", &self.bids), ("ask", &self.asks)] {
+            let mut previous: Option<Decimal> = None;
+            for (price, size) in levels {
+                if previous.is_some_and(|previous| previous >= *price) {
+                    return Err(format!("{} {} levels out of order at {}", self.symbol, side, price));
+                }
+                if *price <= Decimal::ZERO {
+                    return Err(format!("{} {} level has non-positive price {}", self.symbol, side, price));
+                }
+                if *size <= Decimal::ZERO {
+                    return Err(format!("{} {} level at {} has non-positive size {}", self.symbol, side, price, size));
+                }
+                previous = Some(*price);
+            }
+        }
+
+        if let Some((bid, ask)) = Self::crossed_prices(&self.bids, &self.asks) {
+            return Err(format!("{} book crossed: best bid {} >= best ask {}", self.symbol, bid, ask));
+        }
+
+        Ok(())
+    }
+
+    /// True when the best bid is at or above the best ask, i.e. crossed or locked.
+    pub fn is_crossed(&self) -> bool {
+        Self::crossed_prices(&self.bids, &self.asks).is_some()
+    }
+
+    fn crossed_prices(bids: &BTreeMap<Decimal, Decimal>, asks: &BTreeMap<Decimal, Decimal>) -> Option<(Decimal, Decimal)> {
+        match (bids.keys().next_back(), asks.keys().next()) {
+            (Some(bid), Some(ask)) if bid >= ask => Some((*bid, *ask)),
+            _ => None,
+        }
+    }
+
+    fn run_integrity_check(&self) {
+        if !self.verify_integrity {
+            return;
+        }
+        if let Err(violation) = self.check_integrity() {
+            error!("Order book integrity violation (sequence {}): {}", self.sequence, violation);
+            debug_assert!(false, "Order book integrity violation: {}", violation);
+        }
+    }
+
+    fn parse_levels(levels: &[crate::model::hl_msgs::PriceLevel]) -> BTreeMap<Decimal, Decimal> {
+        let mut parsed = BTreeMap::new();
+        for level in levels {
+            if let (Ok(price), Ok(size)) = (
+                Decimal::from_str(&level.px),
+                Decimal::from_str(&level.sz),
+            ) {
+                parsed.insert(price, size);
+            }
+        }
+        parsed
     }
 
     pub fn best_bid(&self) -> Option<(Decimal, Decimal)> {
@@ -80,11 +302,16 @@ impl OrderBook {
         }
     }
 
+    /// Decimal places of the finest price on the book, taken as its tick scale.
+    pub fn price_scale(&self) -> Option<u32> {
+        max_scale(self.bids.keys().chain(self.asks.keys()))
+    }
+
     pub fn spread_bps(&self) -> Option<Decimal> {
         self.spread().and_then(|spread| {
             self.mid_price().map(|mid| {
                 if mid > Decimal::ZERO {
-                    spread / mid * Decimal::from(10000)
+                    Bps::from_ratio(spread, mid).value()
                 } else {
                     Decimal::ZERO
                 }
@@ -93,41 +320,20 @@ impl OrderBook {
     }
 
     pub fn volume_weighted_mid(&self, depth: usize) -> Option<Decimal> {
-        let mut bid_vol = Decimal::ZERO;
-        let mut ask_vol = Decimal::ZERO;
-        let mut bid_weighted_sum = Decimal::ZERO;
-        let mut ask_weighted_sum = Decimal::ZERO;
-
-        for (i, (price, size)) in self.bids.iter().rev().enumerate() {
-            if i >= depth { break; }
-            bid_vol += size;
-            bid_weighted_sum += price * size;
-        }
-
-        for (i, (price, size)) in self.asks.iter().enumerate() {
-            if i >= depth { break; }
-            ask_vol += size;
-            ask_weighted_sum += price * size;
-        }
-
-        if bid_vol > Decimal::ZERO && ask_vol > Decimal::ZERO {
-            let bid_vwap = bid_weighted_sum / bid_vol;
-            let ask_vwap = ask_weighted_sum / ask_vol;
-            Some((bid_vwap + ask_vwap) / Decimal::from(2))
-        } else {
-            None
-        }
+        let bid_vwap = vwap(self.bids.iter().rev().take(depth))?;
+        let ask_vwap = vwap(self.asks.iter().take(depth))?;
+        Some((bid_vwap + ask_vwap) / Decimal::from(2))
     }
 
-    pub fn get_depth(&self, levels: usize) -> (Vec<(Decimal, Decimal)>, Vec<(Decimal, Decimal)>) {
-        let bids: Vec<(Decimal, Decimal)> = self.bids
+    pub fn get_depth(&self, levels: usize) -> (DepthLevels, DepthLevels) {
+        let bids: DepthLevels = self.bids
             .iter()
             .rev()
             .take(levels)
             .map(|(p, s)| (*p, *s))
             .collect();
         
-        let asks: Vec<(Decimal, Decimal)> = self.asks
+        let asks: DepthLevels = self.asks
             .iter()
             .take(levels)
             .map(|(p, s)| (*p, *s))
@@ -135,4 +341,178 @@ impl OrderBook {
 
         (bids, asks)
     }
+
+    /// Up to `levels` prices on one side of the book, from the best outwards, each with
+    /// the total size resting at it and every better price. `Side::Buy` is the bids.
+    pub fn cumulative_depth(&self, side: Side, levels: usize) -> DepthLevels {
+        let book_side: Box<dyn Iterator<Item = (&Decimal, &Decimal)>> = match side {
+            Side::Buy => Box::new(self.bids.iter().rev()),
+            Side::Sell => Box::new(self.asks.iter()),
+        };
+
+        book_side
+            .take(levels)
+            .scan(Decimal::ZERO, |total, (price, size)| {
+                *total += size;
+                Some((*price, *total))
+            })
+            .collect()
+    }
+
+    /// Volume-weighted average price and filled quantity for sweeping `size` through
+    /// the book: asks from the best upwards for a buy, bids downwards for a sell. The
+    /// quantity falls short of `size` when the book is thinner than the order. `None`
+    /// for a non-positive size or an empty side.
+    pub fn estimated_fill_price(&self, side: Side, size: Decimal) -> Option<(Decimal, Decimal)> {
+        if size <= Decimal::ZERO {
+            return None;
+        }
+
+        let levels: Box<dyn Iterator<Item = (&Decimal, &Decimal)>> = match side {
+            Side::Buy => Box::new(self.asks.iter()),
+            Side::Sell => Box::new(self.bids.iter().rev()),
+        };
+
+        let mut filled = Decimal::ZERO;
+        let mut notional = Decimal::ZERO;
+        for (price, level_size) in levels {
+            let take = (*level_size).min(size - filled);
+            filled += take;
+            notional += price * take;
+            if filled >= size {
+                break;
+            }
+        }
+
+        if filled == Decimal::ZERO {
+            return None;
+        }
+        Some((notional / filled, filled))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::hl_msgs::{OrderBookData, PriceLevel};
+    use rust_decimal_macros::dec;
+
+    fn snapshot(bids: &[(&str, &str)], asks: &[(&str, &str)]) -> OrderBookData {
+        let levels = |side: &[(&str, &str)]| side.iter()
+            .map(|(px, sz)| PriceLevel { px: px.to_string(), sz: sz.to_string(), n: 1 })
+            .collect();
+        OrderBookData {
+            coin: "HYPE".to_string(),
+            time: 1,
+            levels: vec![levels(bids), levels(asks)],
+            received_at: None,
+        }
+    }
+
+    #[test]
+    fn crossed_and_locked_snapshots_are_rejected_and_the_last_good_book_kept() {
+        let mut book = OrderBook::new("HYPE".to_string());
+        book.update_from_tob(&snapshot(&[("25.0", "10")], &[("25.1", "5")])).unwrap();
+        assert!(!book.is_crossed());
+
+        assert!(book.update_from_tob(&snapshot(&[("25.2", "10")], &[("25.1", "5")])).is_err());
+        assert!(book.update_from_tob(&snapshot(&[("25.1", "10")], &[("25.1", "5")])).is_err());
+
+        assert_eq!(book.crossed_updates, 2);
+        assert_eq!(book.sequence, 1);
+        assert_eq!(book.best_bid(), Some((dec!(25.0), dec!(10))));
+        assert_eq!(book.best_ask(), Some((dec!(25.1), dec!(5))));
+        assert!(book.check_integrity().is_ok());
+
+        // Level updates are not screened, but the crossing still shows
+        book.update_level(Side::Buy, dec!(25.1), dec!(1));
+        assert!(book.is_crossed());
+        assert!(book.check_integrity().is_err());
+    }
+
+    #[test]
+    fn a_malformed_levels_array_is_rejected_and_the_book_left_as_it_was() {
+        let mut book = OrderBook::new("HYPE".to_string());
+        book.update_from_tob(&snapshot(&[("25.0", "10")], &[("25.1", "5")])).unwrap();
+        let last_update = book.last_update;
+
+        let mut one_sided = snapshot(&[("24.0", "1")], &[]);
+        one_sided.levels.pop();
+        let mut three_sided = snapshot(&[("24.0", "1")], &[("26.0", "1")]);
+        three_sided.levels.push(Vec::new());
+        let mut empty = snapshot(&[], &[]);
+        empty.levels.clear();
+
+        for malformed in [one_sided, three_sided, empty] {
+            let error = book.update_from_tob(&malformed).unwrap_err();
+            assert!(error.contains("Malformed levels"), "{}", error);
+        }
+        assert_eq!(book.sequence, 1);
+        assert_eq!(book.last_update, last_update);
+        assert_eq!(book.best_bid(), Some((dec!(25.0), dec!(10))));
+        assert_eq!(book.best_ask(), Some((dec!(25.1), dec!(5))));
+    }
+
+    #[test]
+    fn a_snapshot_keeps_the_mid_and_the_best_levels_best_first() {
+        let mut book = OrderBook::new("HYPE".to_string());
+        book.update_from_tob(&snapshot(
+            &[("24.7", "3"), ("24.9", "1"), ("24.8", "2")],
+            &[("25.3", "3"), ("25.1", "1"), ("25.2", "2")],
+        )).unwrap();
+        book.mark_stale();
+
+        let top = book.snapshot(2);
+
+        assert_eq!(top.bids, [(dec!(24.9), dec!(1)), (dec!(24.8), dec!(2))]);
+        assert_eq!(top.asks, [(dec!(25.1), dec!(1)), (dec!(25.2), dec!(2))]);
+        assert_eq!(top.mid_price(), Some(dec!(25.0)));
+        assert_eq!(top.mid_price(), book.mid_price());
+        assert_eq!(top.spread_bps(), book.spread_bps());
+        assert_eq!((top.ts, top.sequence, top.is_stale()), (book.last_update, 1, true));
+        // Asking for more levels than the book has returns all of them
+        assert_eq!(book.snapshot(10).bids.len(), 3);
+    }
+
+    #[test]
+    fn estimated_fill_price_sweeps_levels_and_reports_short_fills() {
+        let mut book = OrderBook::new("HYPE".to_string());
+        book.update_from_tob(&snapshot(&[("9", "1"), ("8", "4")], &[("10", "2"), ("11", "3"), ("12", "5")])).unwrap();
+
+        // 2 at 10 and 2 at 11
+        assert_eq!(book.estimated_fill_price(Side::Buy, dec!(4)), Some((dec!(10.5), dec!(4))));
+        // 1 at 9 and 4 at 8
+        assert_eq!(book.estimated_fill_price(Side::Sell, dec!(5)), Some((dec!(8.2), dec!(5))));
+        // The whole ask side, 10 of the 20 asked for
+        assert_eq!(book.estimated_fill_price(Side::Buy, dec!(20)), Some((dec!(11.3), dec!(10))));
+        assert_eq!(book.estimated_fill_price(Side::Buy, Decimal::ZERO), None);
+
+        book.update_level(Side::Buy, dec!(9), Decimal::ZERO);
+        book.update_level(Side::Buy, dec!(8), Decimal::ZERO);
+        assert_eq!(book.estimated_fill_price(Side::Sell, dec!(1)), None);
+    }
+
+    #[test]
+    fn a_corrupted_book_fails_the_integrity_check() {
+        let mut book = OrderBook::new("HYPE".to_string());
+        book.update_from_tob(&snapshot(&[("25.0", "10")], &[("25.1", "5")])).unwrap();
+
+        // Written straight into the levels, past update_level's screening
+        book.asks.insert(dec!(25.2), Decimal::ZERO);
+        assert!(book.check_integrity().unwrap_err().contains("non-positive size"));
+
+        book.asks.remove(&dec!(25.2));
+        book.bids.insert(dec!(-1), dec!(3));
+        assert!(book.check_integrity().unwrap_err().contains("non-positive price"));
+    }
+
+    #[test]
+    #[cfg(debug_assertions)]
+    #[should_panic(expected = "Order book integrity violation")]
+    fn a_checked_book_panics_on_a_violating_update_in_debug_builds() {
+        let mut book = OrderBook::new("HYPE".to_string()).with_integrity_checks(true);
+        book.update_from_tob(&snapshot(&[("25.0", "10")], &[("25.1", "5")])).unwrap();
+
+        book.update_level(Side::Buy, dec!(25.3), dec!(1));
+    }
 }
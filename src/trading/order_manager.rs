@@ -39,10 +39,10 @@ impl OrderManager {
 
     pub fn add_order(&self, new_order: NewOrder) -> Uuid {
         let order_id = Uuid::new_v4();
-        let order = Order {
+        self.track_order(Order {
             id: order_id,
             client_id: new_order.client_id,
-            symbol: new_order.symbol.clone(),
+            symbol: new_order.symbol,
             side: new_order.side,
             order_type: new_order.order_type,
             price: new_order.price,
@@ -52,20 +52,77 @@ impl OrderManager {
             status: OrderStatus::Pending,
             created_at: Utc::now(),
             updated_at: Utc::now(),
-        };
+            exchange_oid: None,
+        });
+
+        order_id
+    }
 
-        self.orders.insert(order_id, order.clone());
+    /// Adds an order that was placed elsewhere, keeping the id it was given there.
+    pub fn track_order(&self, order: Order) {
+        self.orders.insert(order.id, order.clone());
         
         // Index by symbol
         self.orders_by_symbol
-            .entry(new_order.symbol)
-            .or_insert_with(Vec::new)
-            .push(order_id);
+            .entry(order.symbol.clone())
+            .or_default()
+            .push(order.id);
 
         // Send event
         let _ = self.order_events_tx.send(OrderEvent::OrderPlaced(order));
+    }
 
-        order_id
+    /// Re-inserts an order from a previous session without emitting an event.
+    pub fn restore_order(&self, order: Order) {
+        self.orders_by_symbol
+            .entry(order.symbol.clone())
+            .or_default()
+            .push(order.id);
+        self.orders.insert(order.id, order);
+    }
+
+    /// Makes the active orders match `resting`, the orders live on the exchange.
+    /// Resting orders are matched to local ones by exchange oid, then client id, and
+    /// take their sizes from the exchange; unmatched ones are added. Local orders no
+    /// longer resting are marked cancelled. Returns the number of orders added.
+    pub fn reconcile(&self, resting: Vec<Order>) -> usize {
+        let mut matched = std::collections::HashSet::new();
+        let mut added = 0;
+
+        for exchange_order in resting {
+            let local = self.get_active_orders(Some(&exchange_order.symbol)).into_iter()
+                .find(|order| {
+                    !matched.contains(&order.id) && (
+                        (order.exchange_oid.is_some() && order.exchange_oid == exchange_order.exchange_oid)
+                        || (order.client_id.is_some() && order.client_id == exchange_order.client_id)
+                    )
+                });
+
+            match local {
+                Some(local) => {
+                    matched.insert(local.id);
+                    self.orders.insert(local.id, Order {
+                        id: local.id,
+                        created_at: local.created_at,
+                        ..exchange_order
+                    });
+                }
+                None => {
+                    matched.insert(exchange_order.id);
+                    self.restore_order(exchange_order);
+                    added += 1;
+                }
+            }
+        }
+
+        // Filled or cancelled while we were down; the fill history settles which
+        for order in self.get_active_orders(None) {
+            if !matched.contains(&order.id) {
+                self.update_order(order.id, OrderStatus::Cancelled, None);
+            }
+        }
+
+        added
     }
 
     pub fn update_order(&self, order_id: Uuid, status: OrderStatus, filled_size: Option<Decimal>) {
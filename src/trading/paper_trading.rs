@@ -0,0 +1,290 @@
+use crate::trading::order_book::OrderBook;
+use crate::trading::types::*;
+use rust_decimal::Decimal;
+use rust_decimal_macros::dec;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use uuid::Uuid;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PaperTradingConfig {
+    pub queue_position: Decimal,       // Fraction of the displayed level size assumed ahead of us (1.0 = back of queue)
+    pub volume_participation: Decimal, // Fraction of volume trading through our level that is allocated to us
+    pub min_fill_size: Decimal,        // Partial fills below this size are ignored
+    pub fill_on_cross: bool,           // Fill the full remaining size when the opposite touch crosses our price
+    pub maker_fee_rate: Decimal,
+    pub taker_fee_rate: Decimal,
+}
+
+impl Default for PaperTradingConfig {
+    fn default() -> Self {
+        Self {
+            queue_position: dec!(1.0),
+            volume_participation: dec!(0.5),
+            min_fill_size: dec!(0.0),
+            fill_on_cross: true,
+            maker_fee_rate: dec!(0.00015), // 1.5 bps base maker fee
+            taker_fee_rate: dec!(0.00045), // 4.5 bps base taker fee
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct PaperOrder {
+    pub id: Uuid,
+    pub symbol: String,
+    pub side: Side,
+    pub price: Decimal,
+    pub size: Decimal,
+    pub remaining: Decimal,
+    pub queue_ahead: Decimal,
+    pub last_level_size: Decimal,
+}
+
+/// Simulated matching engine for resting maker orders.
+///
+/// Orders are filled when the opposite touch crosses them, or partially when volume
+/// trades through their level once the simulated queue ahead of them is consumed.
+/// The size removed from a level between two book updates is used as the traded
+/// volume proxy, since the L2 feed carries no trade prints.
+#[derive(Debug, Clone, Default)]
+pub struct PaperMatcher {
+    pub config: PaperTradingConfig,
+    pub resting: HashMap<Uuid, PaperOrder>,
+}
+
+impl PaperMatcher {
+    pub fn new(config: PaperTradingConfig) -> Self {
+        Self {
+            config,
+            resting: HashMap::new(),
+        }
+    }
+
+    /// Simulates fills at the account's live fee tier.
+    pub fn set_fee_rates(&mut self, fee_rates: FeeRates) {
+        self.config.maker_fee_rate = fee_rates.maker_rate;
+        self.config.taker_fee_rate = fee_rates.taker_rate;
+    }
+
+    /// Registers a new order. Marketable orders are filled immediately against the book
+    /// as taker; the rest joins the simulated queue at its level.
+    pub fn add_order(&mut self, id: Uuid, order: &NewOrder, book: &OrderBook) -> Result<Vec<Fill>, String> {
+        let crosses = match order.order_type {
+            OrderType::Market => true,
+            _ => Self::is_marketable(order.side, order.price, book),
+        };
+
+        if crosses && order.order_type == OrderType::PostOnly {
+            return Err(format!("Post-only order would cross the book at {}", order.price));
+        }
+
+        let mut fills = Vec::new();
+        let mut remaining = order.size;
+
+        if crosses {
+            let limit = match order.order_type {
+                OrderType::Market => None,
+                _ => Some(order.price),
+            };
+            for (price, size) in Self::opposite_levels(order.side, book) {
+                if remaining <= Decimal::ZERO {
+                    break;
+                }
+                if let Some(limit) = limit {
+                    let within_limit = match order.side {
+                        Side::Buy => price <= limit,
+                        Side::Sell => price >= limit,
+                    };
+                    if !within_limit {
+                        break;
+                    }
+                }
+                let fill_size = remaining.min(size);
+                remaining -= fill_size;
+                fills.push(self.make_fill(id, &order.symbol, order.side, price, fill_size, self.config.taker_fee_rate));
+            }
+        }
+
+        // Market orders never rest
+        if remaining > Decimal::ZERO && order.order_type != OrderType::Market {
+            let level_size = Self::level_size(order.side, order.price, book);
+            self.resting.insert(id, PaperOrder {
+                id,
+                symbol: order.symbol.clone(),
+                side: order.side,
+                price: order.price,
+                size: order.size,
+                remaining,
+                queue_ahead: level_size * self.config.queue_position,
+                last_level_size: level_size,
+            });
+        }
+
+        Ok(fills)
+    }
+
+    pub fn cancel_order(&mut self, id: &Uuid) -> Option<PaperOrder> {
+        self.resting.remove(id)
+    }
+
+    /// Applies a new book state to all resting orders for the book's symbol and
+    /// returns the simulated maker fills.
+    pub fn on_book_update(&mut self, book: &OrderBook) -> Vec<Fill> {
+        let mut fills = Vec::new();
+        let mut completed = Vec::new();
+        let config = self.config.clone();
+
+        for order in self.resting.values_mut().filter(|o| o.symbol == book.symbol) {
+            let level_size = Self::level_size(order.side, order.price, book);
+
+            let fill_size = if config.fill_on_cross && Self::is_crossed_through(order.side, order.price, book) {
+                order.remaining
+            } else {
+                let traded = (order.last_level_size - level_size).max(Decimal::ZERO);
+                if traded <= order.queue_ahead {
+                    order.queue_ahead -= traded;
+                    Decimal::ZERO
+                } else {
+                    let through = traded - order.queue_ahead;
+                    order.queue_ahead = Decimal::ZERO;
+                    (through * config.volume_participation).min(order.remaining)
+                }
+            };
+            order.last_level_size = level_size;
+
+            // Nobody left ahead of us if our level is now the touch and empty
+            if level_size == Decimal::ZERO {
+                order.queue_ahead = Decimal::ZERO;
+            }
+
+            if fill_size <= Decimal::ZERO || (fill_size < order.remaining && fill_size < config.min_fill_size) {
+                continue;
+            }
+
+            order.remaining -= fill_size;
+            fills.push(Fill {
+                id: Uuid::new_v4(),
+                order_id: order.id,
+                symbol: order.symbol.clone(),
+                side: order.side,
+                price: order.price,
+                size: fill_size,
+                fee: order.price * fill_size * config.maker_fee_rate,
+                timestamp: chrono::Utc::now(),
+                strategy: None,
+            });
+
+            if order.remaining <= Decimal::ZERO {
+                completed.push(order.id);
+            }
+        }
+
+        for id in completed {
+            self.resting.remove(&id);
+        }
+
+        fills
+    }
+
+    pub fn get_resting_orders(&self, symbol: Option<&str>) -> Vec<PaperOrder> {
+        self.resting
+            .values()
+            .filter(|o| symbol.is_none_or(|s| o.symbol == s))
+            .cloned()
+            .collect()
+    }
+
+    fn make_fill(&self, order_id: Uuid, symbol: &str, side: Side, price: Decimal, size: Decimal, fee_rate: Decimal) -> Fill {
+        Fill {
+            id: Uuid::new_v4(),
+            order_id,
+            symbol: symbol.to_string(),
+            side,
+            price,
+            size,
+            fee: price * size * fee_rate,
+            timestamp: chrono::Utc::now(),
+            strategy: None,
+        }
+    }
+
+    fn is_marketable(side: Side, price: Decimal, book: &OrderBook) -> bool {
+        match side {
+            Side::Buy => book.best_ask().is_some_and(|(ask, _)| price >= ask),
+            Side::Sell => book.best_bid().is_some_and(|(bid, _)| price <= bid),
+        }
+    }
+
+    fn is_crossed_through(side: Side, price: Decimal, book: &OrderBook) -> bool {
+        match side {
+            Side::Buy => book.best_ask().is_some_and(|(ask, _)| ask <= price),
+            Side::Sell => book.best_bid().is_some_and(|(bid, _)| bid >= price),
+        }
+    }
+
+    fn level_size(side: Side, price: Decimal, book: &OrderBook) -> Decimal {
+        let levels = match side {
+            Side::Buy => &book.bids,
+            Side::Sell => &book.asks,
+        };
+        levels.get(&price).copied().unwrap_or(Decimal::ZERO)
+    }
+
+    fn opposite_levels(side: Side, book: &OrderBook) -> Vec<(Decimal, Decimal)> {
+        match side {
+            Side::Buy => book.asks.iter().map(|(p, s)| (*p, *s)).collect(),
+            Side::Sell => book.bids.iter().rev().map(|(p, s)| (*p, *s)).collect(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn book(bid_at_25: Decimal) -> OrderBook {
+        let mut book = OrderBook::new("HYPE".to_string());
+        book.update_level(Side::Buy, dec!(24.9), dec!(20));
+        book.update_level(Side::Buy, dec!(25.0), bid_at_25);
+        book.update_level(Side::Sell, dec!(25.1), dec!(5));
+        book
+    }
+
+    #[test]
+    fn a_resting_maker_order_fills_partially_across_two_trades() {
+        let mut matcher = PaperMatcher::new(PaperTradingConfig {
+            queue_position: dec!(0.5),
+            volume_participation: dec!(0.5),
+            ..PaperTradingConfig::default()
+        });
+        let id = Uuid::new_v4();
+        let order = NewOrder {
+            symbol: "HYPE".to_string(),
+            side: Side::Buy,
+            order_type: OrderType::PostOnly,
+            price: dec!(25.0),
+            size: dec!(2.5),
+            client_id: None,
+            reduce_only: false,
+            tick_received_at: None,
+        };
+
+        // Joins behind half of the 10 displayed
+        assert!(matcher.add_order(id, &order, &book(dec!(10))).unwrap().is_empty());
+        assert_eq!(matcher.resting[&id].queue_ahead, dec!(5));
+
+        // 8 trade: 5 clear the queue, we get half of the 3 beyond it
+        let fills = matcher.on_book_update(&book(dec!(2)));
+        assert_eq!(fills.len(), 1);
+        assert_eq!((fills[0].order_id, fills[0].price, fills[0].size), (id, dec!(25.0), dec!(1.5)));
+        assert_eq!(fills[0].fee, dec!(25.0) * dec!(1.5) * dec!(0.00015));
+        assert_eq!(matcher.resting[&id].remaining, dec!(1.0));
+
+        // The last 2 trade; half of it is the rest of the order
+        let fills = matcher.on_book_update(&book(Decimal::ZERO));
+        assert_eq!(fills.len(), 1);
+        assert_eq!(fills[0].size, dec!(1.0));
+        assert!(!matcher.resting.contains_key(&id));
+    }
+}
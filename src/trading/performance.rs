@@ -0,0 +1,180 @@
+use crate::trading::types::*;
+use chrono::{DateTime, NaiveDate, Utc};
+use rust_decimal::Decimal;
+use rust_decimal::prelude::{FromPrimitive, ToPrimitive};
+use std::collections::{HashMap, VecDeque};
+
+// Equity samples kept for charting; drawdown is tracked incrementally so it survives trimming
+const MAX_EQUITY_SAMPLES: usize = 10_000;
+
+#[derive(Debug, Clone, Default)]
+struct RoundTrip {
+    size: Decimal,
+    entry_price: Decimal,
+    pnl: Decimal, // Realized PnL net of fees since the position was opened
+}
+
+/// Equity curve and trade log behind the performance fields of `RiskMetrics`.
+///
+/// Equity is cumulative PnL, sampled on every `record_pnl`. A round trip runs from
+/// flat back to flat (or through a flip); it counts as a win when its realized PnL
+/// net of fees is positive.
+#[derive(Debug, Clone)]
+pub struct PerformanceTracker {
+    pub sharpe_window_days: usize,
+    equity: Decimal,
+    peak_equity: Decimal,
+    max_drawdown: Decimal,
+    equity_curve: VecDeque<(DateTime<Utc>, Decimal)>,
+    daily_pnl: VecDeque<(NaiveDate, Decimal)>,
+    open_trips: HashMap<String, RoundTrip>,
+    round_trips: u32,
+    winning_trips: u32,
+    trade_count: u64,
+    traded_size: Decimal,
+}
+
+impl PerformanceTracker {
+    pub fn new(sharpe_window_days: usize) -> Self {
+        Self {
+            sharpe_window_days,
+            equity: Decimal::ZERO,
+            peak_equity: Decimal::ZERO,
+            max_drawdown: Decimal::ZERO,
+            equity_curve: VecDeque::new(),
+            daily_pnl: VecDeque::new(),
+            open_trips: HashMap::new(),
+            round_trips: 0,
+            winning_trips: 0,
+            trade_count: 0,
+            traded_size: Decimal::ZERO,
+        }
+    }
+
+    pub fn record_pnl(&mut self, pnl: Decimal, at: DateTime<Utc>) {
+        self.equity += pnl;
+        self.peak_equity = self.peak_equity.max(self.equity);
+        self.max_drawdown = self.max_drawdown.max(self.peak_equity - self.equity);
+
+        self.equity_curve.push_back((at, self.equity));
+        if self.equity_curve.len() > MAX_EQUITY_SAMPLES {
+            self.equity_curve.pop_front();
+        }
+
+        let day = at.date_naive();
+        match self.daily_pnl.back_mut() {
+            Some((last_day, total)) if *last_day == day => *total += pnl,
+            _ => self.daily_pnl.push_back((day, pnl)),
+        }
+        while self.daily_pnl.len() > self.sharpe_window_days.max(1) {
+            self.daily_pnl.pop_front();
+        }
+    }
+
+    /// Updates the trade log with `fill` and returns the PnL it realized net of its fee.
+    pub fn record_fill(&mut self, fill: &Fill) -> Decimal {
+        self.trade_count += 1;
+        self.traded_size += fill.size;
+
+        let trip = self.open_trips.entry(fill.symbol.clone()).or_default();
+        let signed_size = match fill.side {
+            Side::Buy => fill.size,
+            Side::Sell => -fill.size,
+        };
+
+        let is_reducing = trip.size != Decimal::ZERO
+            && trip.size.is_sign_positive() != signed_size.is_sign_positive();
+        let closed_size = if is_reducing { fill.size.min(trip.size.abs()) } else { Decimal::ZERO };
+
+        let realized = if closed_size > Decimal::ZERO {
+            let pnl_per_unit = match fill.side {
+                Side::Sell => fill.price - trip.entry_price,
+                Side::Buy => trip.entry_price - fill.price,
+            };
+            pnl_per_unit * closed_size
+        } else {
+            Decimal::ZERO
+        };
+        let net = realized - fill.fee;
+        trip.pnl += net;
+
+        let new_size = trip.size + signed_size;
+        let closed_trip = is_reducing && (new_size == Decimal::ZERO
+            || new_size.is_sign_positive() != trip.size.is_sign_positive());
+
+        if trip.size == Decimal::ZERO || (is_reducing && closed_trip) {
+            trip.entry_price = fill.price;
+        } else if !is_reducing {
+            trip.entry_price = (trip.size * trip.entry_price + signed_size * fill.price) / new_size;
+        }
+        trip.size = new_size;
+
+        if closed_trip {
+            self.round_trips += 1;
+            if trip.pnl > Decimal::ZERO {
+                self.winning_trips += 1;
+            }
+            trip.pnl = Decimal::ZERO;
+        }
+        if trip.size == Decimal::ZERO {
+            self.open_trips.remove(&fill.symbol);
+        }
+
+        net
+    }
+
+    pub fn equity(&self) -> Decimal {
+        self.equity
+    }
+
+    pub fn max_drawdown(&self) -> Decimal {
+        self.max_drawdown
+    }
+
+    /// Mean over standard deviation of daily PnL across the window. Zero until there
+    /// are two days with differing PnL.
+    pub fn sharpe_ratio(&self) -> Decimal {
+        let returns: Vec<f64> = self.daily_pnl.iter().filter_map(|(_, pnl)| pnl.to_f64()).collect();
+        if returns.len() < 2 {
+            return Decimal::ZERO;
+        }
+
+        let n = returns.len() as f64;
+        let mean = returns.iter().sum::<f64>() / n;
+        let variance = returns.iter().map(|r| (r - mean).powi(2)).sum::<f64>() / (n - 1.0);
+        let std_dev = variance.sqrt();
+        if std_dev == 0.0 {
+            return Decimal::ZERO;
+        }
+
+        Decimal::from_f64(mean / std_dev).unwrap_or(Decimal::ZERO).round_dp(4)
+    }
+
+    pub fn win_rate(&self) -> Decimal {
+        if self.round_trips == 0 {
+            return Decimal::ZERO;
+        }
+        Decimal::from(self.winning_trips) / Decimal::from(self.round_trips)
+    }
+
+    pub fn avg_trade_size(&self) -> Decimal {
+        if self.trade_count == 0 {
+            return Decimal::ZERO;
+        }
+        self.traded_size / Decimal::from(self.trade_count)
+    }
+
+    pub fn round_trips(&self) -> u32 {
+        self.round_trips
+    }
+
+    pub fn equity_curve(&self) -> Vec<(DateTime<Utc>, Decimal)> {
+        self.equity_curve.iter().cloned().collect()
+    }
+}
+
+impl Default for PerformanceTracker {
+    fn default() -> Self {
+        Self::new(30)
+    }
+}
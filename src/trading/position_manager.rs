@@ -1,4 +1,5 @@
 use crate::trading::types::*;
+use crate::utils::decimal::normalize_computed;
 use rust_decimal::Decimal;
 use dashmap::DashMap;
 use crossbeam_channel::{Sender, Receiver};
@@ -10,9 +11,16 @@ pub struct PositionManager {
     pub positions: Arc<DashMap<String, Position>>,
     pub realized_pnl: Arc<RwLock<Decimal>>,
     pub total_fees: Arc<RwLock<Decimal>>,
+    pub fill_stats: Arc<DashMap<String, FillStats>>,
+    pub strategy_pnl: Arc<DashMap<String, StrategyPnl>>, // Keyed by Fill::strategy, MANUAL_STRATEGY for untagged fills
+    pub peak_pnl: Arc<RwLock<Decimal>>,
+    pub max_drawdown: Arc<RwLock<Decimal>>,
     pub position_events_tx: Sender<PositionEvent>,
 }
 
+// Fills with no strategy are booked under this name
+pub const MANUAL_STRATEGY: &str = "manual";
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum PositionEvent {
     PositionUpdated(Position),
@@ -20,6 +28,50 @@ pub enum PositionEvent {
     PnlRealized(Decimal),
 }
 
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct FillStats {
+    pub fill_count: u64,
+    pub buy_size: Decimal,
+    pub sell_size: Decimal,
+    pub buy_notional: Decimal,
+    pub sell_notional: Decimal,
+    pub fees: Decimal,
+}
+
+impl FillStats {
+    pub fn volume(&self) -> Decimal {
+        self.buy_notional + self.sell_notional
+    }
+
+    /// Spread earned on completed round trips: the gap between the average sell and
+    /// average buy price over the size that was both bought and sold.
+    pub fn spread_captured(&self) -> Decimal {
+        let matched_size = self.buy_size.min(self.sell_size);
+        if matched_size == Decimal::ZERO {
+            return Decimal::ZERO;
+        }
+
+        let avg_buy = self.buy_notional / self.buy_size;
+        let avg_sell = self.sell_notional / self.sell_size;
+        normalize_computed((avg_sell - avg_buy) * matched_size)
+    }
+}
+
+/// Realized PnL is booked to the strategy whose fill closed the position, so the
+/// strategies' figures add up to the manager's totals.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct StrategyPnl {
+    pub realized_pnl: Decimal,
+    pub fees: Decimal,
+    pub fill_count: u64,
+}
+
+impl StrategyPnl {
+    pub fn net_pnl(&self) -> Decimal {
+        self.realized_pnl - self.fees
+    }
+}
+
 impl PositionManager {
     pub fn new() -> (Self, Receiver<PositionEvent>) {
         let (tx, rx) = crossbeam_channel::unbounded();
@@ -28,6 +80,10 @@ impl PositionManager {
             positions: Arc::new(DashMap::new()),
             realized_pnl: Arc::new(RwLock::new(Decimal::ZERO)),
             total_fees: Arc::new(RwLock::new(Decimal::ZERO)),
+            fill_stats: Arc::new(DashMap::new()),
+            strategy_pnl: Arc::new(DashMap::new()),
+            peak_pnl: Arc::new(RwLock::new(Decimal::ZERO)),
+            max_drawdown: Arc::new(RwLock::new(Decimal::ZERO)),
             position_events_tx: tx,
         };
         
@@ -77,69 +133,108 @@ impl PositionManager {
             Side::Sell => -fill.size,
         };
 
-        // Calculate realized PnL if reducing position
-        if position.size != Decimal::ZERO && position.size.is_sign_positive() != fill_size.is_sign_positive() {
-            let reducing_size = fill_size.abs().min(position.size.abs());
-            let pnl_per_unit = match fill.side {
-                Side::Sell => fill.price - position.entry_price,
-                Side::Buy => position.entry_price - fill.price,
-            };
-            let realized_pnl = pnl_per_unit * reducing_size;
-            position.realized_pnl += realized_pnl;
-            
+        // A fill against the position is split in two legs: the part that offsets it
+        // closes at the fill price, and anything beyond opens a new position the other
+        // way, as if it were a separate trade at the same price
+        let is_reducing = position.size != Decimal::ZERO
+            && position.size.is_sign_positive() != fill_size.is_sign_positive();
+        let closing_size = if is_reducing {
+            fill.size.min(position.size.abs())
+        } else {
+            Decimal::ZERO
+        };
+        let opening_size = fill.size - closing_size;
+
+        let mut realized_pnl = Decimal::ZERO;
+        if closing_size > Decimal::ZERO {
+            realized_pnl = close_leg(&mut position, fill.price, closing_size);
+
             // Update global realized PnL
             *self.realized_pnl.write() += realized_pnl;
-            
+
             // Send event
             let _ = self.position_events_tx.send(PositionEvent::PnlRealized(realized_pnl));
         }
-
-        // Update position
-        let new_size = position.size + fill_size;
-        
-        if new_size == Decimal::ZERO {
-            // Position closed
-            position.size = Decimal::ZERO;
-            position.entry_price = Decimal::ZERO;
-            position.unrealized_pnl = Decimal::ZERO;
-        } else if position.size == Decimal::ZERO || position.size.is_sign_positive() != new_size.is_sign_positive() {
-            // New position or flipped position
-            position.size = new_size;
-            position.entry_price = fill.price;
-        } else {
-            // Adding to existing position - update weighted average entry price
-            let total_cost = position.size * position.entry_price + fill_size * fill.price;
-            position.size = new_size;
-            position.entry_price = total_cost / new_size;
+        if opening_size > Decimal::ZERO {
+            open_leg(&mut position, fill.side, fill.price, opening_size);
         }
 
         position.mark_price = fill.price;
         position.updated_at = chrono::Utc::now();
         
-        // Update total fees
+        // The exchange charges one fee per fill, so a split fill's fee is not split
         *self.total_fees.write() += fill.fee;
 
-        // Recalculate unrealized PnL
-        if position.size != Decimal::ZERO {
-            position.unrealized_pnl = (position.mark_price - position.entry_price) * position.size;
+        // Update fill statistics
+        {
+            let mut stats = self.fill_stats.entry(fill.symbol.clone()).or_default();
+            stats.fill_count += 1;
+            stats.fees += fill.fee;
+            match fill.side {
+                Side::Buy => {
+                    stats.buy_size += fill.size;
+                    stats.buy_notional += fill.size * fill.price;
+                }
+                Side::Sell => {
+                    stats.sell_size += fill.size;
+                    stats.sell_notional += fill.size * fill.price;
+                }
+            }
+        }
+
+        {
+            let strategy = fill.strategy.as_deref().unwrap_or(MANUAL_STRATEGY);
+            let mut strategy_pnl = self.strategy_pnl.entry(strategy.to_string()).or_default();
+            strategy_pnl.realized_pnl += realized_pnl;
+            strategy_pnl.fees += fill.fee;
+            strategy_pnl.fill_count += 1;
         }
 
+        // Recalculate unrealized PnL
+        position.unrealized_pnl = if position.size != Decimal::ZERO {
+            (position.mark_price - position.entry_price) * position.size
+        } else {
+            Decimal::ZERO
+        };
+
         // Send events
         let _ = self.position_events_tx.send(PositionEvent::FillProcessed(fill.clone()));
         let _ = self.position_events_tx.send(PositionEvent::PositionUpdated(position.clone()));
+
+        drop(position);
+        self.update_drawdown();
     }
 
     pub fn update_mark_prices(&self, symbol: &str, mark_price: Decimal) {
-        if let Some(mut position) = self.positions.get_mut(symbol) {
-            position.mark_price = mark_price;
-            if position.size != Decimal::ZERO {
-                position.unrealized_pnl = (mark_price - position.entry_price) * position.size;
-            }
-            position.updated_at = chrono::Utc::now();
+        let Some(mut position) = self.positions.get_mut(symbol) else {
+            return;
+        };
 
-            // Send event
-            let _ = self.position_events_tx.send(PositionEvent::PositionUpdated(position.clone()));
+        position.mark_price = mark_price;
+        if position.size != Decimal::ZERO {
+            position.unrealized_pnl = (mark_price - position.entry_price) * position.size;
         }
+        position.updated_at = chrono::Utc::now();
+
+        // Send event
+        let _ = self.position_events_tx.send(PositionEvent::PositionUpdated(position.clone()));
+
+        drop(position);
+        self.update_drawdown();
+    }
+
+    // Track the largest peak-to-trough decline of total PnL. Must not be called while
+    // holding a guard into `positions`.
+    fn update_drawdown(&self) {
+        let total_pnl = self.get_total_pnl();
+
+        let mut peak = self.peak_pnl.write();
+        if total_pnl > *peak {
+            *peak = total_pnl;
+        }
+
+        let mut max_drawdown = self.max_drawdown.write();
+        *max_drawdown = (*max_drawdown).max(*peak - total_pnl);
     }
 
     pub fn get_position(&self, symbol: &str) -> Option<Position> {
@@ -206,6 +301,35 @@ impl PositionManager {
         *self.total_fees.read()
     }
 
+    pub fn get_max_drawdown(&self) -> Decimal {
+        *self.max_drawdown.read()
+    }
+
+    pub fn get_fill_stats(&self, symbol: &str) -> Option<FillStats> {
+        self.fill_stats.get(symbol).map(|s| s.clone())
+    }
+
+    pub fn get_all_fill_stats(&self) -> Vec<(String, FillStats)> {
+        self.fill_stats
+            .iter()
+            .map(|entry| (entry.key().clone(), entry.value().clone()))
+            .collect()
+    }
+
+    pub fn get_strategy_pnl(&self, strategy: &str) -> Option<StrategyPnl> {
+        self.strategy_pnl.get(strategy).map(|pnl| pnl.clone())
+    }
+
+    /// Every strategy with a fill, by name.
+    pub fn get_all_strategy_pnl(&self) -> Vec<(String, StrategyPnl)> {
+        let mut all: Vec<_> = self.strategy_pnl
+            .iter()
+            .map(|entry| (entry.key().clone(), entry.value().clone()))
+            .collect();
+        all.sort_by(|a, b| a.0.cmp(&b.0));
+        all
+    }
+
     pub fn get_all_positions(&self) -> Vec<Position> {
         self.positions
             .iter()
@@ -222,6 +346,39 @@ impl PositionManager {
     }
 }
 
+// Closes `size` of the position at `price`, which must not exceed it, and returns the
+// PnL realized. The remaining units keep their entry price.
+fn close_leg(position: &mut Position, price: Decimal, size: Decimal) -> Decimal {
+    let (pnl_per_unit, remaining) = if position.size.is_sign_positive() {
+        (price - position.entry_price, position.size - size)
+    } else {
+        (position.entry_price - price, position.size + size)
+    };
+    let realized_pnl = pnl_per_unit * size;
+    position.realized_pnl += realized_pnl;
+    position.size = remaining;
+    if remaining == Decimal::ZERO {
+        position.entry_price = Decimal::ZERO;
+    }
+    realized_pnl
+}
+
+// Adds `size` on `side` at `price` to a flat position or one on the same side,
+// averaging the entry price
+fn open_leg(position: &mut Position, side: Side, price: Decimal, size: Decimal) {
+    let signed_size = match side {
+        Side::Buy => size,
+        Side::Sell => -size,
+    };
+    let new_size = position.size + signed_size;
+    position.entry_price = if position.size == Decimal::ZERO {
+        price
+    } else {
+        normalize_computed((position.size * position.entry_price + signed_size * price) / new_size)
+    };
+    position.size = new_size;
+}
+
 impl Default for PositionManager {
     fn default() -> Self {
         Self::new().0
@@ -234,7 +391,123 @@ impl Clone for PositionManager {
             positions: Arc::clone(&self.positions),
             realized_pnl: Arc::clone(&self.realized_pnl),
             total_fees: Arc::clone(&self.total_fees),
+            fill_stats: Arc::clone(&self.fill_stats),
+            strategy_pnl: Arc::clone(&self.strategy_pnl),
+            peak_pnl: Arc::clone(&self.peak_pnl),
+            max_drawdown: Arc::clone(&self.max_drawdown),
             position_events_tx: self.position_events_tx.clone(),
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rust_decimal_macros::dec;
+    use uuid::Uuid;
+
+    fn fill(side: Side, price: Decimal, size: Decimal) -> Fill {
+        Fill {
+            id: Uuid::new_v4(),
+            order_id: Uuid::new_v4(),
+            symbol: "HYPE".to_string(),
+            side,
+            price,
+            size,
+            fee: Decimal::ZERO,
+            timestamp: chrono::Utc::now(),
+            strategy: None,
+        }
+    }
+
+    #[test]
+    fn flipping_long_to_short_and_back_realizes_only_the_closed_size() {
+        let (manager, _rx) = PositionManager::new();
+        manager.process_fill(&fill(Side::Buy, dec!(100), dec!(2)));
+
+        // Long 2 at 100, sell 5 at 110: 2 close for +20, 3 open short at 110
+        manager.process_fill(&fill(Side::Sell, dec!(110), dec!(5)));
+        let position = manager.get_position("HYPE").unwrap();
+        assert_eq!(manager.get_realized_pnl(), dec!(20));
+        assert_eq!(position.size, dec!(-3));
+        assert_eq!(position.entry_price, dec!(110));
+
+        // Short 3 at 110, buy 7 at 105: 3 close for +15, 4 open long at 105
+        manager.process_fill(&fill(Side::Buy, dec!(105), dec!(7)));
+        let position = manager.get_position("HYPE").unwrap();
+        assert_eq!(manager.get_realized_pnl(), dec!(35));
+        assert_eq!(position.realized_pnl, dec!(35));
+        assert_eq!(position.size, dec!(4));
+        assert_eq!(position.entry_price, dec!(105));
+    }
+
+    #[test]
+    fn entry_and_pnl_track_adds_reduces_closes_and_flips() {
+        let (manager, _rx) = PositionManager::new();
+        let position = || manager.get_position("HYPE").unwrap();
+
+        // Repeated adds average the entry
+        manager.process_fill(&fill(Side::Buy, dec!(100), dec!(2)));
+        manager.process_fill(&fill(Side::Buy, dec!(110), dec!(2)));
+        assert_eq!((position().size, position().entry_price), (dec!(4), dec!(105)));
+        assert_eq!(position().unrealized_pnl, dec!(20));
+        assert_eq!(manager.get_realized_pnl(), Decimal::ZERO);
+
+        // A partial reduce keeps the entry of what is left
+        manager.process_fill(&fill(Side::Sell, dec!(120), dec!(1)));
+        assert_eq!((position().size, position().entry_price), (dec!(3), dec!(105)));
+        assert_eq!(position().unrealized_pnl, dec!(45));
+        assert_eq!(manager.get_realized_pnl(), dec!(15));
+
+        // An exact close leaves a flat position with nothing unrealized
+        manager.process_fill(&fill(Side::Sell, dec!(100), dec!(3)));
+        assert_eq!((position().size, position().entry_price), (Decimal::ZERO, Decimal::ZERO));
+        assert_eq!(position().unrealized_pnl, Decimal::ZERO);
+        assert_eq!(manager.get_realized_pnl(), Decimal::ZERO);
+
+        // A flip closes 1 for -10 and opens 3 short at the fill price, paying its fee once
+        manager.process_fill(&fill(Side::Buy, dec!(100), dec!(1)));
+        manager.process_fill(&Fill { fee: dec!(0.5), ..fill(Side::Sell, dec!(90), dec!(4)) });
+        assert_eq!((position().size, position().entry_price), (dec!(-3), dec!(90)));
+        assert_eq!(position().unrealized_pnl, Decimal::ZERO);
+        assert_eq!(manager.get_realized_pnl(), dec!(-10));
+        assert_eq!(manager.get_total_fees(), dec!(0.5));
+
+        manager.update_mark_prices("HYPE", dec!(85));
+        assert_eq!(position().unrealized_pnl, dec!(15));
+        assert_eq!(manager.get_strategy_pnl(MANUAL_STRATEGY).unwrap().net_pnl(), dec!(-10.5));
+    }
+
+    #[test]
+    fn per_strategy_pnl_lines_sum_to_the_global_totals() {
+        let (manager, _rx) = PositionManager::new();
+        let tagged = |strategy: Option<&str>, symbol: &str, side, price, size, fee| Fill {
+            symbol: symbol.to_string(),
+            fee,
+            strategy: strategy.map(str::to_string),
+            ..fill(side, price, size)
+        };
+
+        // Market making round trips HYPE while a TWAP unwinds ETH, with a manual order in between
+        manager.process_fill(&tagged(Some("mm_hype"), "HYPE", Side::Buy, dec!(25), dec!(4), dec!(0.01)));
+        manager.process_fill(&tagged(Some("twap_eth"), "ETH", Side::Sell, dec!(3000), dec!(1), dec!(0.3)));
+        manager.process_fill(&tagged(Some("mm_hype"), "HYPE", Side::Sell, dec!(25.5), dec!(3), dec!(0.01)));
+        manager.process_fill(&tagged(None, "ETH", Side::Buy, dec!(2950), dec!(0.5), dec!(0.15)));
+        manager.process_fill(&tagged(Some("twap_eth"), "ETH", Side::Buy, dec!(2990), dec!(0.5), dec!(0.15)));
+
+        let lines = manager.get_all_strategy_pnl();
+        let names: Vec<&str> = lines.iter().map(|(name, _)| name.as_str()).collect();
+        assert_eq!(names, [MANUAL_STRATEGY, "mm_hype", "twap_eth"]);
+        let line = |name: &str| manager.get_strategy_pnl(name).unwrap();
+        assert_eq!((line("mm_hype").realized_pnl, line("mm_hype").fill_count), (dec!(1.5), 2));
+        assert_eq!((line(MANUAL_STRATEGY).realized_pnl, line(MANUAL_STRATEGY).fill_count), (dec!(25), 1));
+        assert_eq!((line("twap_eth").realized_pnl, line("twap_eth").fill_count), (dec!(5), 2));
+
+        let realized: Decimal = lines.iter().map(|(_, pnl)| pnl.realized_pnl).sum();
+        let fees: Decimal = lines.iter().map(|(_, pnl)| pnl.fees).sum();
+        let fills: u64 = lines.iter().map(|(_, pnl)| pnl.fill_count).sum();
+        assert_eq!(realized, manager.get_realized_pnl());
+        assert_eq!(fees, manager.get_total_fees());
+        assert_eq!(fills, 5);
+    }
+}
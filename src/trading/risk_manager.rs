@@ -1,29 +1,45 @@
+use crate::config::bot_config::RiskConfig;
+use crate::trading::clock::{Clock, SystemClock};
+use crate::trading::performance::PerformanceTracker;
 use crate::trading::types::*;
-use crate::api::types::ApiEvent;
+use crate::trading::volatility::{VolatilityEstimate, VolatilityEstimator};
+use crate::utils::decimal::Bps;
 use anyhow::Result;
 use crossbeam_channel::{Sender, Receiver, unbounded};
 use dashmap::DashMap;
 use parking_lot::RwLock;
 use rust_decimal::Decimal;
+use rust_decimal::prelude::{FromPrimitive, ToPrimitive};
+use std::collections::VecDeque;
+use std::str::FromStr;
 use std::sync::Arc;
-use std::collections::HashMap;
 use std::time::{Duration, Instant};
-use tracing::{error, info, warn, debug};
-use uuid::Uuid;
+use tracing::{info, warn};
 
 pub struct RiskManager {
     pub risk_limits: Arc<DashMap<String, RiskLimits>>,
     pub position_limits: Arc<DashMap<String, PositionLimit>>,
     pub exposure_limits: Arc<DashMap<String, ExposureLimit>>,
     pub volatility_limits: Arc<DashMap<String, VolatilityLimit>>,
+    pub volatility_estimators: Arc<DashMap<String, VolatilityEstimator>>, // Realized vol of the mid, fed by update_volatility
     pub circuit_breakers: Arc<RwLock<Vec<CircuitBreaker>>>,
     pub risk_events_tx: Sender<RiskEvent>,
     pub daily_pnl: Arc<RwLock<Decimal>>,
     pub daily_trades: Arc<RwLock<u32>>,
     pub last_reset: Arc<RwLock<Instant>>,
     pub risk_metrics: Arc<RwLock<RiskMetrics>>,
+    pub halted: Arc<RwLock<Option<String>>>, // Reason trading was halted; latched until resume()
+    pub mark_prices: Arc<DashMap<String, Decimal>>, // Latest mark/mid, for fat-finger checks
+    pub order_submissions: Arc<DashMap<String, VecDeque<Instant>>>, // Submission times within the last second
+    pub clock: Arc<dyn Clock>,
+    pub performance: Arc<RwLock<PerformanceTracker>>, // Equity curve and trade log behind risk_metrics
+    pub strategy_limits: Arc<DashMap<String, RiskLimits>>, // Per strategy name, checked on top of the symbol limits
+    pub strategy_positions: Arc<DashMap<String, Decimal>>, // Net size filled by each strategy's orders
 }
 
+// Window for the MaxOrdersPerSecond breaker
+const ORDER_RATE_WINDOW: Duration = Duration::from_secs(1);
+
 #[derive(Debug, Clone)]
 pub struct PositionLimit {
     pub symbol: String,
@@ -66,7 +82,7 @@ pub struct CircuitBreaker {
     pub cooldown_duration: Duration,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub enum CircuitBreakerType {
     MaxDailyLoss,
     MaxPositionSize,
@@ -76,6 +92,22 @@ pub enum CircuitBreakerType {
     MaxOrdersPerSecond,
 }
 
+impl FromStr for CircuitBreakerType {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "MaxDailyLoss" => Ok(Self::MaxDailyLoss),
+            "MaxPositionSize" => Ok(Self::MaxPositionSize),
+            "MaxExposure" => Ok(Self::MaxExposure),
+            "MaxVolatility" => Ok(Self::MaxVolatility),
+            "MaxTradesPerMinute" => Ok(Self::MaxTradesPerMinute),
+            "MaxOrdersPerSecond" => Ok(Self::MaxOrdersPerSecond),
+            other => Err(format!("Unknown circuit breaker type: {}", other)),
+        }
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct RiskMetrics {
     pub total_exposure: Decimal,
@@ -103,6 +135,10 @@ pub enum RiskEvent {
         threshold: Decimal,
         current_value: Decimal,
     },
+    CircuitBreakerReset {
+        breaker_id: String,
+        symbol: String,
+    },
     RiskWarning {
         message: String,
         symbol: String,
@@ -115,6 +151,10 @@ pub enum RiskEvent {
         pnl: Decimal,
         risk_score: Decimal,
     },
+    TradingHalted {
+        reason: String,
+    },
+    TradingResumed,
 }
 
 #[derive(Debug, Clone, PartialEq)]
@@ -134,6 +174,7 @@ impl RiskManager {
             position_limits: Arc::new(DashMap::new()),
             exposure_limits: Arc::new(DashMap::new()),
             volatility_limits: Arc::new(DashMap::new()),
+            volatility_estimators: Arc::new(DashMap::new()),
             circuit_breakers: Arc::new(RwLock::new(Vec::new())),
             risk_events_tx: tx,
             daily_pnl: Arc::new(RwLock::new(Decimal::ZERO)),
@@ -148,16 +189,126 @@ impl RiskManager {
                 avg_trade_size: Decimal::ZERO,
                 last_updated: Instant::now(),
             })),
+            halted: Arc::new(RwLock::new(None)),
+            mark_prices: Arc::new(DashMap::new()),
+            order_submissions: Arc::new(DashMap::new()),
+            clock: Arc::new(SystemClock),
+            performance: Arc::new(RwLock::new(PerformanceTracker::default())),
+            strategy_limits: Arc::new(DashMap::new()),
+            strategy_positions: Arc::new(DashMap::new()),
         };
         
         (manager, rx)
     }
 
+    pub fn with_clock(mut self, clock: Arc<dyn Clock>) -> Self {
+        self.clock = clock;
+        self
+    }
+
+    /// Number of daily PnL buckets the Sharpe ratio is computed over.
+    pub fn with_sharpe_window(self, days: usize) -> Self {
+        self.performance.write().sharpe_window_days = days;
+        self
+    }
+
     pub fn add_risk_limits(&self, symbol: String, limits: RiskLimits) {
         self.risk_limits.insert(symbol, limits);
         info!("Added risk limits for symbol");
     }
 
+    /// Loads the position, exposure and volatility limits and circuit breakers from
+    /// `config`. Nothing is applied if any breaker has an unknown trigger type.
+    ///
+    /// Safe to re-apply to a running manager: limits keep their tracked values and
+    /// breakers that are still configured keep their trip state. Breakers missing from
+    /// `config` are removed; limits are only ever added or updated.
+    pub fn apply_config(&self, config: &RiskConfig) -> Result<(), String> {
+        let breakers = config.circuit_breakers.iter()
+            .map(|breaker| Ok(CircuitBreaker {
+                id: breaker.id.clone(),
+                symbol: breaker.symbol.clone(),
+                trigger_type: breaker.trigger_type.parse()
+                    .map_err(|e| format!("Circuit breaker {}: {}", breaker.id, e))?,
+                threshold: breaker.threshold,
+                current_value: Decimal::ZERO,
+                is_triggered: false,
+                triggered_at: None,
+                cooldown_duration: Duration::from_secs(breaker.cooldown_seconds),
+            }))
+            .collect::<Result<Vec<_>, String>>()?;
+
+        for (symbol, limit) in &config.position_limits {
+            if let Some(mut current) = self.position_limits.get_mut(symbol) {
+                current.max_long = limit.max_long;
+                current.max_short = limit.max_short;
+                current.max_net = limit.max_net;
+                continue;
+            }
+            self.add_position_limit(symbol.clone(), PositionLimit {
+                symbol: symbol.clone(),
+                max_long: limit.max_long,
+                max_short: limit.max_short,
+                max_net: limit.max_net,
+                current_long: Decimal::ZERO,
+                current_short: Decimal::ZERO,
+                current_net: Decimal::ZERO,
+            });
+        }
+
+        for (symbol, limit) in &config.exposure_limits {
+            if let Some(mut current) = self.exposure_limits.get_mut(symbol) {
+                current.max_notional = limit.max_notional;
+                current.max_leverage = limit.max_leverage;
+                continue;
+            }
+            self.add_exposure_limit(symbol.clone(), ExposureLimit {
+                symbol: symbol.clone(),
+                max_notional: limit.max_notional,
+                current_notional: Decimal::ZERO,
+                max_leverage: limit.max_leverage,
+                current_leverage: Decimal::ZERO,
+            });
+        }
+
+        for (symbol, limit) in &config.volatility_limits {
+            if let Some(mut current) = self.volatility_limits.get_mut(symbol) {
+                current.max_spread_bps = limit.max_spread_bps;
+                current.max_price_change_bps = limit.max_price_change_bps;
+                continue;
+            }
+            self.add_volatility_limit(symbol.clone(), VolatilityLimit {
+                symbol: symbol.clone(),
+                max_spread_bps: limit.max_spread_bps,
+                max_price_change_bps: limit.max_price_change_bps,
+                current_spread_bps: 0,
+                last_price: Decimal::ZERO,
+                price_change_bps: 0,
+            });
+        }
+
+        let mut current_breakers = self.circuit_breakers.write();
+        let breakers = breakers.into_iter()
+            .map(|mut breaker| {
+                if let Some(current) = current_breakers.iter().find(|current| current.id == breaker.id) {
+                    breaker.current_value = current.current_value;
+                    breaker.is_triggered = current.is_triggered;
+                    breaker.triggered_at = current.triggered_at;
+                }
+                breaker
+            })
+            .collect();
+        *current_breakers = breakers;
+        info!("Loaded {} circuit breakers", current_breakers.len());
+
+        Ok(())
+    }
+
+    pub fn add_strategy_limits(&self, strategy: String, limits: RiskLimits) {
+        info!("Added risk limits for strategy {}", strategy);
+        self.strategy_limits.insert(strategy, limits);
+    }
+
     pub fn add_position_limit(&self, symbol: String, limit: PositionLimit) {
         let symbol_clone = symbol.clone();
         self.position_limits.insert(symbol, limit);
@@ -184,6 +335,37 @@ impl RiskManager {
 
     pub fn check_order_risk(&self, order: &NewOrder) -> Result<(), String> {
         let symbol = &order.symbol;
+
+        // Reduce-only orders can only shrink a position, so they are let out while halted.
+        // They still go through the size, price and breaker checks below
+        if let Some(reason) = self.halted.read().as_ref() {
+            if !order.reduce_only {
+                return Err(format!("Trading halted: {}", reason));
+            }
+        }
+
+        // Check per-order size and fat-finger price limits
+        if let Some(risk_limits) = self.risk_limits.get(symbol) {
+            if order.size > risk_limits.max_order_size {
+                return Err(format!(
+                    "Order size exceeds limit: {} > {}",
+                    order.size, risk_limits.max_order_size
+                ));
+            }
+
+            // Market orders take whatever the book offers, so only priced orders are checked
+            if order.order_type != OrderType::Market {
+                if let Some(mark_price) = self.get_mark_price(symbol).filter(|p| *p > Decimal::ZERO) {
+                    let deviation_bps = Bps::from_ratio((order.price - mark_price).abs(), mark_price);
+                    if deviation_bps > Bps::from(risk_limits.max_price_deviation_bps) {
+                        return Err(format!(
+                            "Order price {} is {} bps from mark {} (limit: {} bps)",
+                            order.price, deviation_bps.value().round_dp(1), mark_price, risk_limits.max_price_deviation_bps
+                        ));
+                    }
+                }
+            }
+        }
         
         // Check position limits
         if let Some(position_limit) = self.position_limits.get(symbol) {
@@ -232,7 +414,7 @@ impl RiskManager {
             for breaker in breakers.iter() {
                 if breaker.symbol == *symbol && breaker.is_triggered {
                     if let Some(triggered_at) = breaker.triggered_at {
-                        if triggered_at.elapsed() < breaker.cooldown_duration {
+                        if self.clock.now().duration_since(triggered_at) < breaker.cooldown_duration {
                             return Err(format!(
                                 "Circuit breaker {} is still active",
                                 breaker.id
@@ -246,6 +428,61 @@ impl RiskManager {
         Ok(())
     }
 
+    /// Runs `check_order_risk`, then holds the order to the placing strategy's own
+    /// order size and position limits. The strategy's position only counts fills
+    /// attributed to it, so one strategy can be capped while the symbol has room.
+    pub fn check_strategy_order_risk(&self, strategy: &str, order: &NewOrder) -> Result<(), String> {
+        self.check_order_risk(order)?;
+        if order.reduce_only {
+            return Ok(());
+        }
+
+        let Some(limits) = self.strategy_limits.get(strategy) else {
+            return Ok(());
+        };
+
+        if order.size > limits.max_order_size {
+            return Err(format!(
+                "Order size exceeds limit for strategy {}: {} > {}",
+                strategy, order.size, limits.max_order_size
+            ));
+        }
+
+        let current = self.get_strategy_position(strategy);
+        let new_position = match order.side {
+            Side::Buy => current + order.size,
+            Side::Sell => current - order.size,
+        };
+        if new_position.abs() > limits.max_position_size && new_position.abs() > current.abs() {
+            return Err(format!(
+                "Order would exceed position limit for strategy {}: {} > {}",
+                strategy, new_position.abs(), limits.max_position_size
+            ));
+        }
+
+        Ok(())
+    }
+
+    pub fn record_strategy_fill(&self, strategy: &str, side: Side, size: Decimal) {
+        let signed_size = match side {
+            Side::Buy => size,
+            Side::Sell => -size,
+        };
+        *self.strategy_positions.entry(strategy.to_string()).or_insert(Decimal::ZERO) += signed_size;
+    }
+
+    pub fn get_strategy_position(&self, strategy: &str) -> Decimal {
+        self.strategy_positions.get(strategy).map(|size| *size).unwrap_or(Decimal::ZERO)
+    }
+
+    pub fn update_mark_price(&self, symbol: &str, price: Decimal) {
+        self.mark_prices.insert(symbol.to_string(), price);
+    }
+
+    pub fn get_mark_price(&self, symbol: &str) -> Option<Decimal> {
+        self.mark_prices.get(symbol).map(|entry| *entry.value())
+    }
+
     pub fn update_position(&self, symbol: &str, size: Decimal, price: Decimal) {
         // Update position limits
         if let Some(mut position_limit) = self.position_limits.get_mut(symbol) {
@@ -274,6 +511,22 @@ impl RiskManager {
     }
 
     pub fn update_pnl(&self, pnl: Decimal) {
+        self.apply_pnl(pnl, chrono::Utc::now());
+    }
+
+    /// Feeds an exchange fill into the trade log, books the PnL it realized net of
+    /// fees, and counts it as a trade.
+    pub fn record_fill(&self, fill: &Fill) {
+        let net_pnl = self.performance.write().record_fill(fill);
+        self.apply_pnl(net_pnl, fill.timestamp);
+        self.update_trade_count();
+    }
+
+    pub fn get_equity_curve(&self) -> Vec<(chrono::DateTime<chrono::Utc>, Decimal)> {
+        self.performance.read().equity_curve()
+    }
+
+    fn apply_pnl(&self, pnl: Decimal, at: chrono::DateTime<chrono::Utc>) {
         let mut daily_pnl = self.daily_pnl.write();
         *daily_pnl += pnl;
 
@@ -293,32 +546,118 @@ impl RiskManager {
 
         // Update risk metrics
         {
+            let mut performance = self.performance.write();
+            performance.record_pnl(pnl, at);
+
             let mut metrics = self.risk_metrics.write();
             metrics.total_pnl = *daily_pnl;
+            metrics.max_drawdown = performance.max_drawdown();
+            metrics.sharpe_ratio = performance.sharpe_ratio();
+            metrics.win_rate = performance.win_rate();
+            metrics.avg_trade_size = performance.avg_trade_size();
             metrics.last_updated = Instant::now();
         }
     }
 
     pub fn update_trade_count(&self) {
-        let mut daily_trades = self.daily_trades.write();
-        *daily_trades += 1;
+        let daily_trades = {
+            let mut daily_trades = self.daily_trades.write();
+            *daily_trades += 1;
+            Decimal::from(*daily_trades)
+        };
 
         // Check trades per minute circuit breaker
-        {
-            let breakers = self.circuit_breakers.read();
-            for breaker in breakers.iter() {
-                if matches!(breaker.trigger_type, CircuitBreakerType::MaxTradesPerMinute) {
-                    // This would need more sophisticated tracking in production
-                    if *daily_trades > breaker.threshold.to_string().parse::<u32>().unwrap_or(0) {
-                        self.trigger_circuit_breaker(breaker.id.clone());
-                    }
-                }
+        // This would need more sophisticated tracking in production
+        let tripped: Vec<String> = self.circuit_breakers.read()
+            .iter()
+            .filter(|b| b.trigger_type == CircuitBreakerType::MaxTradesPerMinute && !b.is_triggered)
+            .filter(|b| daily_trades > b.threshold)
+            .map(|b| b.id.clone())
+            .collect();
+        for breaker_id in tripped {
+            self.trigger_circuit_breaker(breaker_id, daily_trades);
+        }
+    }
+
+    /// Records an order sent to the exchange for `symbol` and trips any
+    /// MaxOrdersPerSecond breaker whose threshold the last second's count exceeds.
+    pub fn record_order_submission(&self, symbol: &str) {
+        let now = self.clock.now();
+        self.reset_expired_circuit_breakers();
+
+        let count = {
+            let mut submissions = self.order_submissions.entry(symbol.to_string()).or_default();
+            submissions.push_back(now);
+            while submissions.front().is_some_and(|t| now.duration_since(*t) >= ORDER_RATE_WINDOW) {
+                submissions.pop_front();
+            }
+            Decimal::from(submissions.len())
+        };
+
+        let tripped: Vec<String> = {
+            let mut breakers = self.circuit_breakers.write();
+            breakers.iter_mut()
+                .filter(|b| b.trigger_type == CircuitBreakerType::MaxOrdersPerSecond && b.symbol == symbol)
+                .filter_map(|b| {
+                    b.current_value = count;
+                    (!b.is_triggered && count > b.threshold).then(|| b.id.clone())
+                })
+                .collect()
+        };
+        for breaker_id in tripped {
+            self.trigger_circuit_breaker(breaker_id, count);
+        }
+    }
+
+    pub fn get_order_rate(&self, symbol: &str) -> usize {
+        let now = self.clock.now();
+        self.order_submissions.get(symbol)
+            .map(|s| s.iter().filter(|t| now.duration_since(**t) < ORDER_RATE_WINDOW).count())
+            .unwrap_or(0)
+    }
+
+    /// Clears breakers whose cooldown has elapsed.
+    pub fn reset_expired_circuit_breakers(&self) {
+        let now = self.clock.now();
+        let mut breakers = self.circuit_breakers.write();
+        for breaker in breakers.iter_mut() {
+            let expired = breaker.is_triggered &&
+                breaker.triggered_at.is_some_and(|t| now.duration_since(t) >= breaker.cooldown_duration);
+            if expired {
+                breaker.is_triggered = false;
+                breaker.triggered_at = None;
+                info!("Circuit breaker reset after cooldown: {}", breaker.id);
+                let _ = self.risk_events_tx.send(RiskEvent::CircuitBreakerReset {
+                    breaker_id: breaker.id.clone(),
+                    symbol: breaker.symbol.clone(),
+                });
             }
         }
     }
 
-    pub fn update_volatility(&self, symbol: &str, spread_bps: u32, price_change_bps: u32, current_price: Decimal) {
+    /// Feeds a new mid and the book's spread. The mid updates the symbol's realized
+    /// volatility, which trips MaxVolatility breakers whose threshold (annualized, as
+    /// a fraction) it exceeds; the spread and the move since the previous mid are
+    /// checked against the symbol's volatility limit.
+    pub fn update_volatility(&self, symbol: &str, spread_bps: u32, current_price: Decimal) {
+        let estimate = {
+            let mut estimator = self.volatility_estimators.entry(symbol.to_string()).or_default();
+            estimator.update_at(current_price, self.clock.now());
+            estimator.current_vol()
+        };
+        if let Some(estimate) = estimate {
+            self.check_volatility_breakers(symbol, estimate.annualized);
+        }
+
         if let Some(mut vol_limit) = self.volatility_limits.get_mut(symbol) {
+            let price_change_bps = if vol_limit.last_price > Decimal::ZERO {
+                Bps::from_ratio((current_price - vol_limit.last_price).abs(), vol_limit.last_price)
+                    .value()
+                    .to_u32()
+                    .unwrap_or(u32::MAX)
+            } else {
+                0
+            };
             vol_limit.current_spread_bps = spread_bps;
             vol_limit.price_change_bps = price_change_bps;
             vol_limit.last_price = current_price;
@@ -346,6 +685,31 @@ impl RiskManager {
         }
     }
 
+    pub fn get_volatility(&self, symbol: &str) -> Option<VolatilityEstimate> {
+        self.volatility_estimators.get(symbol).and_then(|estimator| estimator.current_vol())
+    }
+
+    fn check_volatility_breakers(&self, symbol: &str, annualized_vol: f64) {
+        let Some(vol) = Decimal::from_f64(annualized_vol) else {
+            return;
+        };
+        self.reset_expired_circuit_breakers();
+
+        let tripped: Vec<String> = {
+            let mut breakers = self.circuit_breakers.write();
+            breakers.iter_mut()
+                .filter(|b| b.trigger_type == CircuitBreakerType::MaxVolatility && b.symbol == symbol)
+                .filter_map(|b| {
+                    b.current_value = vol;
+                    (!b.is_triggered && vol > b.threshold).then(|| b.id.clone())
+                })
+                .collect()
+        };
+        for breaker_id in tripped {
+            self.trigger_circuit_breaker(breaker_id, vol);
+        }
+    }
+
     fn check_position_limits(&self, symbol: &str) {
         if let Some(position_limit) = self.position_limits.get(symbol) {
             if position_limit.current_net.abs() > position_limit.max_net {
@@ -384,11 +748,12 @@ impl RiskManager {
         }
     }
 
-    fn trigger_circuit_breaker(&self, breaker_id: String) {
+    fn trigger_circuit_breaker(&self, breaker_id: String, current_value: Decimal) {
         let mut breakers = self.circuit_breakers.write();
         if let Some(breaker) = breakers.iter_mut().find(|b| b.id == breaker_id) {
             breaker.is_triggered = true;
-            breaker.triggered_at = Some(Instant::now());
+            breaker.triggered_at = Some(self.clock.now());
+            breaker.current_value = current_value;
 
             let _ = self.risk_events_tx.send(RiskEvent::CircuitBreakerTriggered {
                 breaker_id: breaker.id.clone(),
@@ -433,28 +798,54 @@ impl RiskManager {
 
         // Position size risk
         if let Some(position_limit) = self.position_limits.get(symbol) {
-            let position_ratio = position_limit.current_net.abs() / position_limit.max_net;
+            let position_ratio = limit_usage(position_limit.current_net.abs(), position_limit.max_net);
             score += position_ratio * Decimal::from(40); // 40% weight
         }
 
         // Exposure risk
         if let Some(exposure_limit) = self.exposure_limits.get(symbol) {
-            let exposure_ratio = exposure_limit.current_notional / exposure_limit.max_notional;
+            let exposure_ratio = limit_usage(exposure_limit.current_notional, exposure_limit.max_notional);
             score += exposure_ratio * Decimal::from(30); // 30% weight
         }
 
         // Volatility risk
         if let Some(vol_limit) = self.volatility_limits.get(symbol) {
-            let spread_ratio = Decimal::from(vol_limit.current_spread_bps) / Decimal::from(vol_limit.max_spread_bps);
+            let spread_ratio = limit_usage(Decimal::from(vol_limit.current_spread_bps), Decimal::from(vol_limit.max_spread_bps));
             score += spread_ratio * Decimal::from(20); // 20% weight
 
-            let price_change_ratio = Decimal::from(vol_limit.price_change_bps) / Decimal::from(vol_limit.max_price_change_bps);
+            let price_change_ratio = limit_usage(Decimal::from(vol_limit.price_change_bps), Decimal::from(vol_limit.max_price_change_bps));
             score += price_change_ratio * Decimal::from(10); // 10% weight
         }
 
         score.min(Decimal::from(100)) // Cap at 100
     }
 
+    /// Warns when the predicted funding rate exceeds the symbol's `max_funding_rate`
+    /// and `position_size` is on the paying side (long into positive funding, short
+    /// into negative). Returns whether a warning was raised.
+    pub fn check_funding_risk(&self, funding: &FundingInfo, position_size: Decimal) -> bool {
+        let Some(max_rate) = self.risk_limits.get(&funding.symbol).map(|limits| limits.max_funding_rate) else {
+            return false;
+        };
+
+        let paying = (position_size > Decimal::ZERO && funding.funding_rate > Decimal::ZERO)
+            || (position_size < Decimal::ZERO && funding.funding_rate < Decimal::ZERO);
+        if !paying || funding.funding_rate.abs() <= max_rate {
+            return false;
+        }
+
+        let hourly_cost = position_size.abs() * funding.oracle_px * funding.funding_rate.abs();
+        let _ = self.risk_events_tx.send(RiskEvent::RiskWarning {
+            message: format!(
+                "Predicted funding {} exceeds {} on a {} position (~{} per hour)",
+                funding.funding_rate, max_rate, position_size, hourly_cost.round_dp(2)
+            ),
+            symbol: funding.symbol.clone(),
+            severity: RiskSeverity::Medium,
+        });
+        true
+    }
+
     pub fn get_risk_metrics(&self) -> RiskMetrics {
         self.risk_metrics.read().clone()
     }
@@ -467,11 +858,29 @@ impl RiskManager {
         *self.daily_trades.read()
     }
 
+    /// Latches a halt that rejects every new non-reduce-only order until `resume`.
+    pub fn halt(&self, reason: String) {
+        warn!("Trading halted: {}", reason);
+        *self.halted.write() = Some(reason.clone());
+        let _ = self.risk_events_tx.send(RiskEvent::TradingHalted { reason });
+    }
+
+    pub fn resume(&self) {
+        if self.halted.write().take().is_some() {
+            info!("Trading resumed");
+            let _ = self.risk_events_tx.send(RiskEvent::TradingResumed);
+        }
+    }
+
+    pub fn is_halted(&self) -> bool {
+        self.halted.read().is_some()
+    }
+
     pub fn is_circuit_breaker_active(&self, symbol: &str) -> bool {
         let breakers = self.circuit_breakers.read();
         breakers.iter().any(|breaker| {
             breaker.symbol == symbol && breaker.is_triggered && 
-            breaker.triggered_at.map_or(false, |t| t.elapsed() < breaker.cooldown_duration)
+            breaker.triggered_at.is_some_and(|t| self.clock.now().duration_since(t) < breaker.cooldown_duration)
         })
     }
 
@@ -512,6 +921,18 @@ impl RiskManager {
     }
 }
 
+/// How much of `limit` `current` uses, as a fraction. A zero limit counts as fully
+/// used once there is anything against it.
+pub fn limit_usage(current: Decimal, limit: Decimal) -> Decimal {
+    if limit > Decimal::ZERO {
+        current / limit
+    } else if current > Decimal::ZERO {
+        Decimal::ONE
+    } else {
+        Decimal::ZERO
+    }
+}
+
 impl Default for RiskManager {
     fn default() -> Self {
         Self::new().0
@@ -525,12 +946,275 @@ impl Clone for RiskManager {
             position_limits: Arc::clone(&self.position_limits),
             exposure_limits: Arc::clone(&self.exposure_limits),
             volatility_limits: Arc::clone(&self.volatility_limits),
+            volatility_estimators: Arc::clone(&self.volatility_estimators),
             circuit_breakers: Arc::clone(&self.circuit_breakers),
             risk_events_tx: self.risk_events_tx.clone(),
             daily_pnl: Arc::clone(&self.daily_pnl),
             daily_trades: Arc::clone(&self.daily_trades),
             last_reset: Arc::clone(&self.last_reset),
             risk_metrics: Arc::clone(&self.risk_metrics),
+            halted: Arc::clone(&self.halted),
+            mark_prices: Arc::clone(&self.mark_prices),
+            order_submissions: Arc::clone(&self.order_submissions),
+            clock: Arc::clone(&self.clock),
+            performance: Arc::clone(&self.performance),
+            strategy_limits: Arc::clone(&self.strategy_limits),
+            strategy_positions: Arc::clone(&self.strategy_positions),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::bot_config::{CircuitBreakerConfig, ExposureLimitConfig, PositionLimitConfig, VolatilityLimitConfig};
+    use crate::trading::clock::ManualClock;
+    use rust_decimal_macros::dec;
+
+    fn limits() -> RiskLimits {
+        RiskLimits {
+            max_position_size: dec!(10),
+            max_daily_loss: dec!(1000),
+            max_order_size: dec!(5),
+            max_orders_per_side: 5,
+            max_price_deviation_bps: 500,
+            max_funding_rate: dec!(0.001),
+        }
+    }
+
+    fn order(order_type: OrderType, price: Decimal, size: Decimal) -> NewOrder {
+        NewOrder {
+            symbol: "HYPE".to_string(),
+            side: Side::Buy,
+            order_type,
+            price,
+            size,
+            client_id: None,
+            reduce_only: false,
+            tick_received_at: None,
+        }
+    }
+
+    fn reduce_only_sell(size: Decimal) -> NewOrder {
+        NewOrder {
+            side: Side::Sell,
+            reduce_only: true,
+            ..order(OrderType::Limit, dec!(25), size)
         }
     }
+
+    #[test]
+    fn orders_over_the_max_order_size_are_rejected() {
+        let (manager, _rx) = RiskManager::new();
+        manager.add_risk_limits("HYPE".to_string(), limits());
+
+        let err = manager.check_order_risk(&order(OrderType::Limit, dec!(25), dec!(5.1))).unwrap_err();
+        assert!(err.contains("Order size exceeds limit"), "{}", err);
+        assert!(manager.check_order_risk(&order(OrderType::Limit, dec!(25), dec!(5))).is_ok());
+    }
+
+    #[test]
+    fn a_strategy_hits_its_own_limits_while_the_symbol_has_room() {
+        let (manager, _rx) = RiskManager::new();
+        manager.add_risk_limits("HYPE".to_string(), limits());
+        manager.add_strategy_limits("mm_small".to_string(), RiskLimits {
+            max_position_size: dec!(3),
+            max_order_size: dec!(2),
+            ..limits()
+        });
+        manager.record_strategy_fill("mm_small", Side::Buy, dec!(2.5));
+
+        let buy = order(OrderType::Limit, dec!(25), dec!(1));
+        assert!(manager.check_order_risk(&buy).is_ok());
+        let err = manager.check_strategy_order_risk("mm_small", &buy).unwrap_err();
+        assert!(err.contains("position limit for strategy mm_small"), "{}", err);
+        let err = manager.check_strategy_order_risk("mm_small", &order(OrderType::Limit, dec!(25), dec!(2.5))).unwrap_err();
+        assert!(err.contains("Order size exceeds limit for strategy mm_small"), "{}", err);
+
+        // Selling shrinks its position, and a strategy without limits of its own only meets the global ones
+        let sell = NewOrder { side: Side::Sell, ..order(OrderType::Limit, dec!(25), dec!(2)) };
+        assert!(manager.check_strategy_order_risk("mm_small", &sell).is_ok());
+        assert!(manager.check_strategy_order_risk("mm_other", &order(OrderType::Limit, dec!(25), dec!(4))).is_ok());
+    }
+
+    fn risk_config() -> RiskConfig {
+        let mut config = RiskConfig::default();
+        config.position_limits.insert("HYPE".to_string(), PositionLimitConfig { max_long: dec!(10), max_short: dec!(8), max_net: dec!(9) });
+        config.exposure_limits.insert("HYPE".to_string(), ExposureLimitConfig { max_notional: dec!(5000), max_leverage: dec!(3) });
+        config.volatility_limits.insert("HYPE".to_string(), VolatilityLimitConfig { max_spread_bps: 50, max_price_change_bps: 200 });
+        config.circuit_breakers.push(CircuitBreakerConfig {
+            id: "hype_loss".to_string(),
+            symbol: "HYPE".to_string(),
+            trigger_type: "MaxDailyLoss".to_string(),
+            threshold: dec!(500),
+            cooldown_seconds: 60,
+        });
+        config
+    }
+
+    #[test]
+    fn a_risk_config_lands_in_the_runtime_limits() {
+        let (manager, _rx) = RiskManager::new();
+        manager.apply_config(&risk_config()).unwrap();
+
+        let position = manager.position_limits.get("HYPE").unwrap().clone();
+        assert_eq!((position.max_long, position.max_short, position.max_net), (dec!(10), dec!(8), dec!(9)));
+        let exposure = manager.exposure_limits.get("HYPE").unwrap().clone();
+        assert_eq!((exposure.max_notional, exposure.max_leverage), (dec!(5000), dec!(3)));
+        let volatility = manager.volatility_limits.get("HYPE").unwrap().clone();
+        assert_eq!((volatility.max_spread_bps, volatility.max_price_change_bps), (50, 200));
+        let breakers = manager.circuit_breakers.read().clone();
+        assert_eq!(breakers.len(), 1);
+        assert_eq!(breakers[0].trigger_type, CircuitBreakerType::MaxDailyLoss);
+        assert_eq!(breakers[0].cooldown_duration, Duration::from_secs(60));
+
+        // Re-applying updates the limits but keeps what they have tracked
+        manager.position_limits.get_mut("HYPE").unwrap().current_net = dec!(4);
+        let mut config = risk_config();
+        config.position_limits.get_mut("HYPE").unwrap().max_long = dec!(12);
+        manager.apply_config(&config).unwrap();
+        let position = manager.position_limits.get("HYPE").unwrap().clone();
+        assert_eq!((position.max_long, position.current_net), (dec!(12), dec!(4)));
+    }
+
+    #[test]
+    fn an_unknown_breaker_type_applies_nothing() {
+        let (manager, _rx) = RiskManager::new();
+        let mut config = risk_config();
+        config.circuit_breakers[0].trigger_type = "MaxVibes".to_string();
+
+        let err = manager.apply_config(&config).unwrap_err();
+        assert_eq!(err, "Circuit breaker hype_loss: Unknown circuit breaker type: MaxVibes");
+        assert!(manager.position_limits.is_empty());
+        assert!(manager.circuit_breakers.read().is_empty());
+    }
+
+    #[test]
+    fn limit_orders_far_from_the_mark_are_rejected_but_market_orders_skip_the_check() {
+        let (manager, _rx) = RiskManager::new();
+        manager.add_risk_limits("HYPE".to_string(), limits());
+        manager.update_mark_price("HYPE", dec!(25));
+
+        // 500 bps of 25 is 1.25
+        let err = manager.check_order_risk(&order(OrderType::Limit, dec!(37.5), dec!(1))).unwrap_err();
+        assert!(err.contains("bps from mark"), "{}", err);
+        assert!(manager.check_order_risk(&order(OrderType::PostOnly, dec!(23.7), dec!(1))).is_err());
+        assert!(manager.check_order_risk(&order(OrderType::Market, dec!(37.5), dec!(1))).is_ok());
+    }
+
+    #[test]
+    fn a_limit_order_near_the_touch_passes() {
+        let (manager, _rx) = RiskManager::new();
+        manager.add_risk_limits("HYPE".to_string(), limits());
+        manager.update_mark_price("HYPE", dec!(25));
+
+        assert!(manager.check_order_risk(&order(OrderType::Limit, dec!(24.99), dec!(1))).is_ok());
+        assert!(manager.check_order_risk(&order(OrderType::Limit, dec!(26.25), dec!(1))).is_ok());
+    }
+
+    #[test]
+    fn a_reduce_only_order_passes_while_halted() {
+        let (manager, _rx) = RiskManager::new();
+        manager.add_risk_limits("HYPE".to_string(), limits());
+        manager.halt("test".to_string());
+
+        assert!(manager.check_order_risk(&reduce_only_sell(dec!(2))).is_ok());
+
+        let mut increasing = reduce_only_sell(dec!(2));
+        increasing.reduce_only = false;
+        assert!(manager.check_order_risk(&increasing).is_err());
+    }
+
+    #[test]
+    fn an_oversized_reduce_only_order_is_still_rejected() {
+        let (manager, _rx) = RiskManager::new();
+        manager.add_risk_limits("HYPE".to_string(), limits());
+        manager.halt("test".to_string());
+
+        let err = manager.check_order_risk(&reduce_only_sell(dec!(6))).unwrap_err();
+        assert!(err.contains("Order size exceeds limit"), "{}", err);
+    }
+
+    #[test]
+    fn an_order_burst_trips_the_breaker_until_its_cooldown_runs_out() {
+        let clock = Arc::new(ManualClock::new());
+        let (manager, rx) = RiskManager::new();
+        let manager = manager.with_clock(clock.clone());
+        manager.add_circuit_breaker(CircuitBreaker {
+            id: "hype_ops".to_string(),
+            symbol: "HYPE".to_string(),
+            trigger_type: CircuitBreakerType::MaxOrdersPerSecond,
+            threshold: dec!(3),
+            current_value: Decimal::ZERO,
+            is_triggered: false,
+            triggered_at: None,
+            cooldown_duration: Duration::from_secs(30),
+        });
+        let near_touch = order(OrderType::Limit, dec!(25), dec!(1));
+
+        for _ in 0..3 {
+            manager.record_order_submission("HYPE");
+        }
+        assert!(!manager.is_circuit_breaker_active("HYPE"));
+
+        // Spread out over more than a second, the count never passes the threshold
+        clock.advance(Duration::from_secs(1));
+        manager.record_order_submission("HYPE");
+        assert_eq!(manager.get_order_rate("HYPE"), 1);
+        assert!(!manager.is_circuit_breaker_active("HYPE"));
+
+        for _ in 0..3 {
+            manager.record_order_submission("HYPE");
+        }
+        assert!(manager.is_circuit_breaker_active("HYPE"));
+        assert!(!manager.is_circuit_breaker_active("BTC"));
+        assert!(matches!(rx.try_recv(), Ok(RiskEvent::CircuitBreakerTriggered { current_value, .. }) if current_value == dec!(4)));
+        assert!(manager.check_order_risk(&near_touch).is_err());
+
+        clock.advance(Duration::from_secs(29));
+        assert!(manager.is_circuit_breaker_active("HYPE"));
+
+        clock.advance(Duration::from_secs(1));
+        assert!(!manager.is_circuit_breaker_active("HYPE"));
+        assert!(manager.check_order_risk(&near_touch).is_ok());
+
+        manager.reset_expired_circuit_breakers();
+        assert!(matches!(rx.try_recv(), Ok(RiskEvent::CircuitBreakerReset { breaker_id, .. }) if breaker_id == "hype_ops"));
+        assert!(!manager.circuit_breakers.read()[0].is_triggered);
+    }
+
+    #[test]
+    fn scripted_fills_produce_drawdown_sharpe_win_rate_and_trade_size() {
+        use chrono::TimeZone;
+
+        let (manager, _rx) = RiskManager::new();
+        let fill = |day: u32, side: Side, price: Decimal, size: Decimal| Fill {
+            id: uuid::Uuid::new_v4(),
+            order_id: uuid::Uuid::new_v4(),
+            symbol: "HYPE".to_string(),
+            side,
+            price,
+            size,
+            fee: Decimal::ZERO,
+            timestamp: chrono::Utc.with_ymd_and_hms(2026, 1, day, 12, 0, 0).unwrap(),
+            strategy: None,
+        };
+
+        // A +4 win, a -3 loss and a +2 win on the short side, one round trip a day
+        manager.record_fill(&fill(1, Side::Buy, dec!(10), dec!(2)));
+        manager.record_fill(&fill(1, Side::Sell, dec!(12), dec!(2)));
+        manager.record_fill(&fill(2, Side::Buy, dec!(12), dec!(1)));
+        manager.record_fill(&fill(2, Side::Sell, dec!(9), dec!(1)));
+        manager.record_fill(&fill(3, Side::Sell, dec!(10), dec!(2)));
+        manager.record_fill(&fill(3, Side::Buy, dec!(9), dec!(2)));
+
+        let metrics = manager.get_risk_metrics();
+        assert_eq!(metrics.total_pnl, dec!(3));
+        assert_eq!(metrics.max_drawdown, dec!(3));
+        // Daily PnL of 4, -3, 2: mean 1 over a sample standard deviation of sqrt(13)
+        assert_eq!(metrics.sharpe_ratio, dec!(0.2774));
+        assert_eq!(metrics.win_rate.round_dp(4), dec!(0.6667));
+        assert_eq!(metrics.avg_trade_size.round_dp(4), dec!(1.6667));
+        assert_eq!(manager.get_daily_trades(), 6);
+    }
 }
@@ -0,0 +1,358 @@
+use crate::trading::types::*;
+use crate::utils::decimal::Bps;
+use crossbeam_channel::{Receiver, Sender};
+use dashmap::DashMap;
+use rust_decimal::Decimal;
+use rust_decimal_macros::dec;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::Arc;
+use tracing::warn;
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TriggerLevel {
+    Price(Decimal),     // Absolute trigger price
+    OffsetBps(Decimal), // Distance from the entry price, against the position for stops
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum TriggerOrderStyle {
+    #[default]
+    Market,
+    AggressiveLimit, // Limit order priced through the mid by `slippage_bps`
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TriggerConfig {
+    pub stop_loss: Option<TriggerLevel>,
+    pub take_profit: Option<TriggerLevel>,
+    pub max_loss: Option<Decimal>,     // Flatten once the unrealized loss at the mark exceeds this amount
+    pub max_loss_pct: Option<Decimal>, // ...or this percent of the position's entry notional
+    #[serde(default)]
+    pub order_style: TriggerOrderStyle,
+    #[serde(default = "default_slippage_bps")]
+    pub slippage_bps: Decimal,
+}
+
+fn default_slippage_bps() -> Decimal {
+    dec!(50)
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum TriggerKind {
+    StopLoss,
+    TakeProfit,
+    MaxLoss, // Unrealized loss limit; the strategy for the symbol should stop quoting
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum TriggerEvent {
+    Armed {
+        symbol: String,
+        stop_price: Option<Decimal>,
+        target_price: Option<Decimal>,
+    },
+    Triggered {
+        symbol: String,
+        kind: TriggerKind,
+        trigger_price: Decimal,
+        mark_price: Decimal,
+        order: NewOrder,
+    },
+}
+
+#[derive(Debug, Clone)]
+struct TriggerState {
+    entry_price: Decimal,
+    is_long: bool,
+    stop_price: Option<Decimal>,
+    target_price: Option<Decimal>,
+    fired: bool,
+}
+
+/// Watches open positions against their stop-loss and take-profit levels and their
+/// unrealized loss limits, and produces a reduce-only flattening order when one is
+/// crossed.
+///
+/// Levels are armed against the position's entry price and direction. Each armed
+/// position fires at most once; a new entry price or a flip re-arms it, and a flat
+/// position clears it.
+pub struct TriggerOrderManager {
+    pub configs: Arc<DashMap<String, TriggerConfig>>,
+    states: Arc<DashMap<String, TriggerState>>,
+    pub trigger_events_tx: Sender<TriggerEvent>,
+}
+
+impl TriggerOrderManager {
+    pub fn new() -> (Self, Receiver<TriggerEvent>) {
+        let (tx, rx) = crossbeam_channel::unbounded();
+
+        let manager = Self {
+            configs: Arc::new(DashMap::new()),
+            states: Arc::new(DashMap::new()),
+            trigger_events_tx: tx,
+        };
+
+        (manager, rx)
+    }
+
+    /// Replaces the configs with `configs`. Symbols whose config changed or was
+    /// removed re-arm on their next check; the rest keep their state, so a trigger
+    /// that already fired does not fire again after a reload.
+    pub fn load_configs(&self, configs: &HashMap<String, TriggerConfig>) {
+        self.states.retain(|symbol, _| {
+            self.configs.get(symbol).is_some_and(|current| configs.get(symbol) == Some(current.value()))
+        });
+        self.configs.retain(|symbol, _| configs.contains_key(symbol));
+        for (symbol, config) in configs {
+            self.configs.insert(symbol.clone(), config.clone());
+        }
+    }
+
+    pub fn set_config(&self, symbol: String, config: TriggerConfig) {
+        self.states.remove(&symbol);
+        self.configs.insert(symbol, config);
+    }
+
+    /// Checks `position` against its levels at `mark_price` and returns the order to
+    /// flatten it if a level was crossed for the first time, with what triggered it.
+    pub fn check(&self, position: &Position, mark_price: Decimal) -> Option<(TriggerKind, NewOrder)> {
+        let config = self.configs.get(&position.symbol)?.clone();
+
+        if position.size == Decimal::ZERO {
+            self.states.remove(&position.symbol);
+            return None;
+        }
+
+        let is_long = position.size > Decimal::ZERO;
+        let mut state = self.states.entry(position.symbol.clone()).or_insert_with(|| {
+            Self::arm(&config, position.entry_price, is_long)
+        });
+
+        // Entry or direction changed, i.e. a new position: re-arm at the new levels
+        if state.entry_price != position.entry_price || state.is_long != is_long {
+            *state = Self::arm(&config, position.entry_price, is_long);
+            let _ = self.trigger_events_tx.send(TriggerEvent::Armed {
+                symbol: position.symbol.clone(),
+                stop_price: state.stop_price,
+                target_price: state.target_price,
+            });
+        }
+
+        if state.fired {
+            return None;
+        }
+
+        let (kind, trigger_price) = Self::crossed(&state, mark_price)
+            .or_else(|| Self::loss_exceeded(&config, &state, position.size, mark_price).then_some((TriggerKind::MaxLoss, mark_price)))?;
+        state.fired = true;
+        drop(state);
+
+        let order = Self::flattening_order(&config, position, mark_price);
+        warn!(
+            "{:?} triggered for {} at {} (level {}), flattening {}",
+            kind, position.symbol, mark_price, trigger_price, position.size
+        );
+        let _ = self.trigger_events_tx.send(TriggerEvent::Triggered {
+            symbol: position.symbol.clone(),
+            kind,
+            trigger_price,
+            mark_price,
+            order: order.clone(),
+        });
+
+        Some((kind, order))
+    }
+
+    pub fn is_fired(&self, symbol: &str) -> bool {
+        self.states.get(symbol).is_some_and(|s| s.fired)
+    }
+
+    fn arm(config: &TriggerConfig, entry_price: Decimal, is_long: bool) -> TriggerState {
+        // Stops sit against the position, targets in its favour
+        let direction = if is_long { Decimal::ONE } else { -Decimal::ONE };
+        let resolve = |level: TriggerLevel, sign: Decimal| match level {
+            TriggerLevel::Price(price) => price,
+            TriggerLevel::OffsetBps(bps) => entry_price + sign * Bps(bps).of(entry_price),
+        };
+
+        TriggerState {
+            entry_price,
+            is_long,
+            stop_price: config.stop_loss.map(|level| resolve(level, -direction)),
+            target_price: config.take_profit.map(|level| resolve(level, direction)),
+            fired: false,
+        }
+    }
+
+    fn crossed(state: &TriggerState, mark_price: Decimal) -> Option<(TriggerKind, Decimal)> {
+        if let Some(stop) = state.stop_price {
+            let hit = if state.is_long { mark_price <= stop } else { mark_price >= stop };
+            if hit {
+                return Some((TriggerKind::StopLoss, stop));
+            }
+        }
+
+        if let Some(target) = state.target_price {
+            let hit = if state.is_long { mark_price >= target } else { mark_price <= target };
+            if hit {
+                return Some((TriggerKind::TakeProfit, target));
+            }
+        }
+
+        None
+    }
+
+    // Loss is measured at `mark_price` against the armed entry, so it can't lag the mark
+    fn loss_exceeded(config: &TriggerConfig, state: &TriggerState, size: Decimal, mark_price: Decimal) -> bool {
+        let loss = (state.entry_price - mark_price) * size;
+        if loss <= Decimal::ZERO {
+            return false;
+        }
+
+        let over_amount = config.max_loss.is_some_and(|max_loss| loss >= max_loss);
+        let entry_notional = state.entry_price * size.abs();
+        let over_pct = entry_notional > Decimal::ZERO && config.max_loss_pct
+            .is_some_and(|max_loss_pct| loss / entry_notional * dec!(100) >= max_loss_pct);
+        over_amount || over_pct
+    }
+
+    fn flattening_order(config: &TriggerConfig, position: &Position, mark_price: Decimal) -> NewOrder {
+        let side = if position.size > Decimal::ZERO { Side::Sell } else { Side::Buy };
+
+        let (order_type, price) = match config.order_style {
+            TriggerOrderStyle::Market => (OrderType::Market, mark_price),
+            TriggerOrderStyle::AggressiveLimit => {
+                let slippage = Bps(config.slippage_bps).of(mark_price);
+                let price = match side {
+                    Side::Sell => mark_price - slippage,
+                    Side::Buy => mark_price + slippage,
+                };
+                (OrderType::Limit, price)
+            }
+        };
+
+        NewOrder {
+            symbol: position.symbol.clone(),
+            side,
+            order_type,
+            price,
+            size: position.size.abs(),
+            client_id: None,
+            reduce_only: true,
+            tick_received_at: None,
+        }
+    }
+}
+
+impl Default for TriggerOrderManager {
+    fn default() -> Self {
+        Self::new().0
+    }
+}
+
+impl Clone for TriggerOrderManager {
+    fn clone(&self) -> Self {
+        Self {
+            configs: Arc::clone(&self.configs),
+            states: Arc::clone(&self.states),
+            trigger_events_tx: self.trigger_events_tx.clone(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn stop_config(stop_bps: Decimal) -> TriggerConfig {
+        TriggerConfig {
+            stop_loss: Some(TriggerLevel::OffsetBps(stop_bps)),
+            take_profit: None,
+            max_loss: None,
+            max_loss_pct: None,
+            order_style: TriggerOrderStyle::Market,
+            slippage_bps: default_slippage_bps(),
+        }
+    }
+
+    fn long(symbol: &str) -> Position {
+        Position {
+            symbol: symbol.to_string(),
+            size: dec!(2),
+            entry_price: dec!(100),
+            mark_price: dec!(100),
+            unrealized_pnl: Decimal::ZERO,
+            realized_pnl: Decimal::ZERO,
+            updated_at: chrono::Utc::now(),
+        }
+    }
+
+    #[test]
+    fn a_reload_keeps_fired_triggers_whose_config_is_unchanged() {
+        let (manager, _rx) = TriggerOrderManager::new();
+        let configs = HashMap::from([
+            ("HYPE".to_string(), stop_config(dec!(100))),
+            ("BTC".to_string(), stop_config(dec!(100))),
+        ]);
+        manager.load_configs(&configs);
+
+        // Both stops sit at 99
+        assert!(manager.check(&long("HYPE"), dec!(98)).is_some());
+        assert!(manager.check(&long("BTC"), dec!(98)).is_some());
+
+        let mut reloaded = configs.clone();
+        reloaded.insert("BTC".to_string(), stop_config(dec!(150)));
+        manager.load_configs(&reloaded);
+
+        assert!(manager.is_fired("HYPE"));
+        assert!(manager.check(&long("HYPE"), dec!(97)).is_none());
+        // BTC's stop moved to 98.5, so it re-armed and fires at the new level
+        assert!(!manager.is_fired("BTC"));
+        assert!(manager.check(&long("BTC"), dec!(98.6)).is_none());
+        assert!(manager.check(&long("BTC"), dec!(98.5)).is_some());
+
+        manager.load_configs(&HashMap::from([("BTC".to_string(), stop_config(dec!(150)))]));
+        assert!(!manager.is_fired("HYPE"));
+        assert!(manager.check(&long("HYPE"), dec!(97)).is_none());
+    }
+
+    #[test]
+    fn crossing_the_stop_flattens_once() {
+        let (manager, rx) = TriggerOrderManager::new();
+        manager.set_config("HYPE".to_string(), stop_config(dec!(100)));
+
+        assert!(manager.check(&long("HYPE"), dec!(99.5)).is_none());
+        let (kind, order) = manager.check(&long("HYPE"), dec!(98.9)).unwrap();
+        assert_eq!(kind, TriggerKind::StopLoss);
+        assert_eq!((order.side, order.order_type, order.size), (Side::Sell, OrderType::Market, dec!(2)));
+        assert!(order.reduce_only);
+
+        // Still through the stop on the next tick, with the position not yet closed
+        assert!(manager.check(&long("HYPE"), dec!(98.5)).is_none());
+        let triggered = rx.try_iter().filter(|event| matches!(event, TriggerEvent::Triggered { .. })).count();
+        assert_eq!(triggered, 1);
+    }
+
+    #[test]
+    fn a_short_past_its_loss_limit_is_bought_back_in_full() {
+        let (manager, _rx) = TriggerOrderManager::new();
+        manager.set_config("HYPE".to_string(), TriggerConfig {
+            stop_loss: None,
+            max_loss: Some(dec!(20)),
+            order_style: TriggerOrderStyle::AggressiveLimit,
+            ..stop_config(Decimal::ZERO)
+        });
+        let short = Position { size: dec!(-3), ..long("HYPE") };
+
+        // Short 3 from 100: 6 against is a loss of 18, 7 against is 21
+        assert!(manager.check(&short, dec!(106)).is_none());
+        let (kind, order) = manager.check(&short, dec!(107)).unwrap();
+        assert_eq!(kind, TriggerKind::MaxLoss);
+        assert_eq!((order.side, order.order_type, order.size), (Side::Buy, OrderType::Limit, dec!(3)));
+        // Priced 50bps through the mark so it crosses
+        assert_eq!(order.price, dec!(107.535));
+        assert!(order.reduce_only);
+    }
+}
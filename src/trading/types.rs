@@ -1,7 +1,6 @@
 use chrono::{DateTime, Utc};
 use rust_decimal::Decimal;
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
 use uuid::Uuid;
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
@@ -41,6 +40,8 @@ pub struct Order {
     pub status: OrderStatus,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
+    #[serde(default)]
+    pub exchange_oid: Option<u64>, // Set once the order is known to be resting on the exchange
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -64,6 +65,35 @@ pub struct Fill {
     pub size: Decimal,
     pub fee: Decimal,
     pub timestamp: DateTime<Utc>,
+    #[serde(default)]
+    pub strategy: Option<String>, // Strategy whose order filled, None for manual orders
+}
+
+/// Account fee rates as fractions of notional; negative maker rates are rebates.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FeeRates {
+    pub maker_rate: Decimal,
+    pub taker_rate: Decimal,
+}
+
+impl Default for FeeRates {
+    fn default() -> Self {
+        Self {
+            maker_rate: Decimal::new(15, 5), // 1.5 bps base tier
+            taker_rate: Decimal::new(45, 5), // 4.5 bps base tier
+        }
+    }
+}
+
+/// Perp asset context from `metaAndAssetCtxs`. HyperLiquid settles funding hourly at
+/// `funding_rate` (a fraction of position notional), paid by longs when positive.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FundingInfo {
+    pub symbol: String,
+    pub funding_rate: Decimal,
+    pub next_funding_time: u64, // Unix millis of the next settlement
+    pub oracle_px: Decimal,
+    pub open_interest: Decimal,
 }
 
 #[derive(Debug, Clone)]
@@ -97,6 +127,10 @@ pub struct NewOrder {
     pub price: Decimal,
     pub size: Decimal,
     pub client_id: Option<String>,
+    #[serde(default)]
+    pub reduce_only: bool,
+    #[serde(skip)]
+    pub tick_received_at: Option<std::time::Instant>, // Receipt of the market data that triggered the order, for tick-to-trade latency
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -105,6 +139,18 @@ pub struct RiskLimits {
     pub max_daily_loss: Decimal,
     pub max_order_size: Decimal,
     pub max_orders_per_side: usize,
+    #[serde(default = "default_max_price_deviation_bps")]
+    pub max_price_deviation_bps: u32, // Limit orders further than this from the mark are rejected
+    #[serde(default = "default_max_funding_rate")]
+    pub max_funding_rate: Decimal, // Warn when paying more than this hourly rate on open inventory
+}
+
+fn default_max_price_deviation_bps() -> u32 {
+    500
+}
+
+fn default_max_funding_rate() -> Decimal {
+    Decimal::new(1, 4) // 0.01% per hour
 }
 
 impl Default for RiskLimits {
@@ -114,6 +160,8 @@ impl Default for RiskLimits {
             max_daily_loss: Decimal::from(1000),
             max_order_size: Decimal::from(10),
             max_orders_per_side: 5,
+            max_price_deviation_bps: default_max_price_deviation_bps(),
+            max_funding_rate: default_max_funding_rate(),
         }
     }
 }
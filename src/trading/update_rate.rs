@@ -0,0 +1,46 @@
+use chrono::{DateTime, Duration, Utc};
+use std::collections::VecDeque;
+
+pub const DEFAULT_UPDATE_RATE_WINDOW_MS: u64 = 2000;
+
+/// Updates per second over a sliding window. Times come from the caller so replayed
+/// data is measured on its recorded timestamps.
+#[derive(Debug, Clone)]
+pub struct UpdateRate {
+    pub window: Duration,
+    updates: VecDeque<DateTime<Utc>>,
+}
+
+impl UpdateRate {
+    pub fn new(window_ms: u64) -> Self {
+        Self {
+            window: Duration::milliseconds(window_ms.max(1) as i64),
+            updates: VecDeque::new(),
+        }
+    }
+
+    pub fn record(&mut self, now: DateTime<Utc>) {
+        self.updates.push_back(now);
+        self.expire(now);
+    }
+
+    /// Updates in the window ending at `now`, per second.
+    pub fn per_second(&self, now: DateTime<Utc>) -> f64 {
+        let since = now - self.window;
+        let count = self.updates.iter().rev().take_while(|at| **at > since).count();
+        count as f64 / (self.window.num_milliseconds() as f64 / 1000.0)
+    }
+
+    fn expire(&mut self, now: DateTime<Utc>) {
+        let since = now - self.window;
+        while self.updates.front().is_some_and(|at| *at <= since) {
+            self.updates.pop_front();
+        }
+    }
+}
+
+impl Default for UpdateRate {
+    fn default() -> Self {
+        Self::new(DEFAULT_UPDATE_RATE_WINDOW_MS)
+    }
+}
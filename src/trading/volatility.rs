@@ -0,0 +1,150 @@
+use rust_decimal::Decimal;
+use rust_decimal::prelude::ToPrimitive;
+use std::time::{Duration, Instant};
+
+const SECONDS_PER_YEAR: f64 = 365.0 * 24.0 * 3600.0;
+
+// RiskMetrics' daily decay; per tick it forgets faster, which suits a quoting spread
+pub const DEFAULT_VOLATILITY_DECAY: f64 = 0.94;
+
+// Updates closer together than this are annualized as if they were this far apart
+const MIN_INTERVAL: Duration = Duration::from_millis(1);
+
+/// Realized volatility of the mid price as an EWMA of squared log returns.
+///
+/// Ticks arrive irregularly, so the average time between them is tracked with the
+/// same decay and used to scale the per-tick figure to a year.
+#[derive(Debug, Clone)]
+pub struct VolatilityEstimator {
+    pub decay: f64, // Weight the previous variance keeps on each update
+    variance: f64, // Per tick
+    interval_secs: f64,
+    last_mid: Option<f64>,
+    last_at: Option<Instant>,
+    returns: u64,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct VolatilityEstimate {
+    pub per_tick: f64, // Standard deviation of one tick's log return
+    pub annualized: f64, // As a fraction, so 0.8 is 80%
+    pub returns: u64,
+}
+
+impl VolatilityEstimator {
+    pub fn new(decay: f64) -> Self {
+        Self {
+            decay: decay.clamp(0.0, 1.0),
+            variance: 0.0,
+            interval_secs: 0.0,
+            last_mid: None,
+            last_at: None,
+            returns: 0,
+        }
+    }
+
+    /// Feeds a mid observed `elapsed` after the previous one. Non-positive mids are
+    /// ignored, and the first mid only sets the reference for the next return.
+    pub fn update(&mut self, mid: Decimal, elapsed: Duration) {
+        let Some(mid) = mid.to_f64().filter(|mid| *mid > 0.0) else {
+            return;
+        };
+
+        if let Some(last_mid) = self.last_mid {
+            self.update_return((mid / last_mid).ln(), elapsed);
+        }
+        self.last_mid = Some(mid);
+    }
+
+    /// `update` timed by the caller's clock rather than a measured interval.
+    pub fn update_at(&mut self, mid: Decimal, now: Instant) {
+        let elapsed = self.last_at.map_or(Duration::ZERO, |last_at| now.saturating_duration_since(last_at));
+        self.last_at = Some(now);
+        self.update(mid, elapsed);
+    }
+
+    /// Feeds one log return directly.
+    pub fn update_return(&mut self, log_return: f64, elapsed: Duration) {
+        if !log_return.is_finite() {
+            return;
+        }
+
+        let interval = elapsed.max(MIN_INTERVAL).as_secs_f64();
+        if self.returns == 0 {
+            self.variance = log_return * log_return;
+            self.interval_secs = interval;
+        } else {
+            self.variance = self.decay * self.variance + (1.0 - self.decay) * log_return * log_return;
+            self.interval_secs = self.decay * self.interval_secs + (1.0 - self.decay) * interval;
+        }
+        self.returns += 1;
+    }
+
+    /// `None` until a return has been observed.
+    pub fn current_vol(&self) -> Option<VolatilityEstimate> {
+        if self.returns == 0 {
+            return None;
+        }
+
+        let per_tick = self.variance.sqrt();
+        Some(VolatilityEstimate {
+            per_tick,
+            annualized: per_tick * (SECONDS_PER_YEAR / self.interval_secs).sqrt(),
+            returns: self.returns,
+        })
+    }
+
+    pub fn variance(&self) -> f64 {
+        self.variance
+    }
+}
+
+impl Default for VolatilityEstimator {
+    fn default() -> Self {
+        Self::new(DEFAULT_VOLATILITY_DECAY)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rust_decimal_macros::dec;
+
+    #[test]
+    fn the_ewma_converges_near_the_variance_of_the_returns() {
+        let mut estimator = VolatilityEstimator::default();
+        assert_eq!(estimator.current_vol(), None);
+
+        // Mean square of the cycle is (4 + 1 + 1 + 4) / 4 * 1e-4
+        let cycle = [0.02, -0.01, 0.01, -0.02];
+        for log_return in cycle.iter().cycle().take(400) {
+            estimator.update_return(*log_return, Duration::from_secs(1));
+        }
+
+        let expected = 2.5e-4;
+        assert!((estimator.variance() - expected).abs() < expected * 0.1, "{}", estimator.variance());
+
+        let estimate = estimator.current_vol().unwrap();
+        assert_eq!(estimate.returns, 400);
+        assert!((estimate.per_tick - expected.sqrt()).abs() < 0.001);
+        // One return a second, so a year is SECONDS_PER_YEAR ticks
+        let annualized = expected.sqrt() * SECONDS_PER_YEAR.sqrt();
+        assert!((estimate.annualized - annualized).abs() < annualized * 0.05, "{}", estimate.annualized);
+    }
+
+    #[test]
+    fn mids_become_log_returns_from_the_second_update() {
+        let mut estimator = VolatilityEstimator::default();
+        let start = Instant::now();
+
+        estimator.update_at(dec!(100), start);
+        assert_eq!(estimator.current_vol(), None);
+        estimator.update_at(Decimal::ZERO, start + Duration::from_secs(1));
+        assert_eq!(estimator.current_vol(), None);
+
+        estimator.update_at(dec!(101), start + Duration::from_secs(2));
+        let log_return = (101.0f64 / 100.0).ln();
+        assert!((estimator.variance() - log_return * log_return).abs() < 1e-12);
+        assert_eq!(estimator.current_vol().unwrap().returns, 1);
+    }
+}
@@ -1,17 +1,29 @@
+use crate::api::types::{ApiConfig, HyperLiquidAccountInfo};
+use crate::config::bot_config::{ConfigEvent, StrategyConfig};
 use crate::trading::types::*;
-use crate::trading::order_book::OrderBook;
+use crate::trading::order_book::{OrderBook, SNAPSHOT_LEVELS};
 use crate::trading::order_manager::{OrderManager, OrderEvent};
 use crate::trading::position_manager::{PositionManager, PositionEvent};
+use crate::trading::risk_manager::{self, RiskManager, RiskSeverity};
 use crate::strategies::market_making::{MarketMakingStrategy, MarketMakingConfig};
 use crate::strategies::base_strategy::TradingStrategy;
-use crate::events::event_bus::{EventBus, EventBusConfig, EventPublisher};
+use crate::events::event_bus::{EventBus, EventBusConfig, EventPublisher, Subscription};
 use crate::events::types::*;
+use crate::ui::market_feed::MarketDataFeed;
+use crate::ui::order_router::{OrderCommand, OrderRouter, RoutedOrderEvent};
 use crate::ui::panels::*;
-use egui::{CentralPanel, SidePanel, TopBottomPanel, Context, Ui};
+use crate::ui::panels::chart_panel::{ChartOverlays, PriceHistory, DEFAULT_CHART_POINTS};
+use crate::ui::panels::depth_panel::DEFAULT_DEPTH_WINDOW_BPS;
+use crate::ui::panels::risk_panel::{event_severity, describe_event, RiskEventEntry, RISK_EVENT_HISTORY};
+use crate::ui::panels::strategy_panel::StrategyDraft;
+use crate::ui::components::account_summary::show_account_summary;
+use crate::ui::components::price_display::{format_price, format_size, format_usd, precision_for, PrecisionMap, SymbolPrecision};
+use egui::{CentralPanel, SidePanel, TopBottomPanel, Context};
+use rust_decimal::Decimal;
 use std::collections::VecDeque;
 use crossbeam_channel::Receiver;
-use rust_decimal::Decimal;
 use std::sync::Arc;
+use std::time::Duration;
 use parking_lot::RwLock;
 
 #[derive(Debug, Clone)]
@@ -21,7 +33,7 @@ pub struct LogEntry {
     pub message: String,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum LogLevel {
     Info,
     Warning,
@@ -29,6 +41,18 @@ pub enum LogLevel {
     Debug,
 }
 
+impl LogLevel {
+    // Debug is the least severe, Error the most
+    pub fn severity(self) -> u8 {
+        match self {
+            LogLevel::Debug => 0,
+            LogLevel::Info => 1,
+            LogLevel::Warning => 2,
+            LogLevel::Error => 3,
+        }
+    }
+}
+
 pub struct TradingApp {
     // Core trading components
     pub order_book: Arc<RwLock<OrderBook>>,
@@ -44,18 +68,50 @@ pub struct TradingApp {
     pub order_events_rx: Option<Receiver<OrderEvent>>,
     pub position_events_rx: Option<Receiver<PositionEvent>>,
     pub system_events_rx: Option<Receiver<SystemEvent>>,
+    pub system_events_subscription: Option<Subscription>,
+    pub risk_events_rx: Option<Receiver<risk_manager::RiskEvent>>,
+    pub config_events_rx: Option<Receiver<ConfigEvent>>,
+    
+    // Config key of market_making_strategy, so edits to it in the config retune it
+    pub strategy_name: Option<String>,
+    
+    // Live l2Book feed for selected_symbol, when started with with_live_market_data
+    pub market_data_feed: Option<MarketDataFeed>,
+    
+    // Sends manual orders to the exchange (or paper engine); without it they are dropped
+    pub order_router: Option<OrderRouter>,
+    
+    // Limits and breakers shown on the risk panel, set by with_risk_manager
+    pub risk_manager: Option<RiskManager>,
+    
+    // AccountApi's cached account state, shown in the top panel; None inside until the first fetch
+    pub account_info: Option<Arc<RwLock<Option<HyperLiquidAccountInfo>>>>,
     
     // UI state
     pub connection_status: ConnectionStatus,
     pub logs: Arc<RwLock<VecDeque<LogEntry>>>,
     pub selected_symbol: String,
+    pub symbol_input: String,
     pub manual_order: ManualOrderState,
+    pub symbol_precision: PrecisionMap,
+    pub warm_reconnect: bool,
+    pub halted: Option<String>, // Reason for the latched emergency stop
+    pub price_history: PriceHistory, // Sampled on every book update for the chart
+    pub chart_overlays: ChartOverlays,
+    pub depth_window_bps: u32,
+    pub risk_events: VecDeque<RiskEventEntry>, // Latest RISK_EVENT_HISTORY, oldest first
+    pub strategy_draft: StrategyDraft, // Strategy panel edits waiting for Apply
+    pub min_log_level: LogLevel, // Less severe entries are hidden from the logs panel
     
     // UI panels
     pub show_order_book: bool,
+    pub show_own_orders: bool, // Mark our active orders on the book levels
     pub show_positions: bool,
     pub show_strategy: bool,
     pub show_logs: bool,
+    pub show_chart: bool,
+    pub show_depth: bool,
+    pub show_risk: bool,
 }
 
 #[derive(Debug, Clone)]
@@ -87,6 +143,12 @@ impl Default for ManualOrderState {
     }
 }
 
+impl Default for TradingApp {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl TradingApp {
     pub fn new() -> Self {
         // Create event bus
@@ -99,12 +161,15 @@ impl TradingApp {
         let (position_manager, position_events_rx) = PositionManager::new();
         
         // Create order book and strategy
-        let order_book = Arc::new(RwLock::new(OrderBook::new("HYPE".to_string())));
+        let order_book = Arc::new(RwLock::new(OrderBook::new("HYPE".to_string()).with_integrity_checks(cfg!(debug_assertions))));
         let mm_config = MarketMakingConfig::default();
-        let market_making_strategy = Arc::new(RwLock::new(MarketMakingStrategy::new(mm_config)));
+        // Quoting starts only once it is switched on from the strategy panel
+        let mut strategy = MarketMakingStrategy::new(mm_config);
+        strategy.set_enabled(false);
+        let market_making_strategy = Arc::new(RwLock::new(strategy));
         
         // Subscribe to system events
-        let system_events_rx = event_bus.subscribe("*");
+        let (system_events_subscription, system_events_rx) = event_bus.subscribe_with_handle("*");
         
         // Start event bus processing
         event_bus.start_processing();
@@ -119,15 +184,314 @@ impl TradingApp {
             order_events_rx: Some(order_events_rx),
             position_events_rx: Some(position_events_rx),
             system_events_rx: Some(system_events_rx),
+            system_events_subscription: Some(system_events_subscription),
+            risk_events_rx: None,
+            config_events_rx: None,
+            strategy_name: None,
+            market_data_feed: None,
+            order_router: None,
+            risk_manager: None,
+            account_info: None,
             connection_status: ConnectionStatus::Disconnected,
             logs: Arc::new(RwLock::new(VecDeque::with_capacity(1000))),
             selected_symbol: "HYPE".to_string(),
+            symbol_input: "HYPE".to_string(),
             manual_order: ManualOrderState::default(),
+            symbol_precision: PrecisionMap::new(),
+            warm_reconnect: true,
+            halted: None,
+            price_history: PriceHistory::new("HYPE".to_string(), DEFAULT_CHART_POINTS),
+            chart_overlays: ChartOverlays::default(),
+            depth_window_bps: DEFAULT_DEPTH_WINDOW_BPS,
+            risk_events: VecDeque::new(),
+            strategy_draft: StrategyDraft::default(),
+            min_log_level: LogLevel::Debug,
             show_order_book: true,
+            show_own_orders: true,
             show_positions: true,
             show_strategy: true,
             show_logs: true,
+            show_chart: true,
+            show_depth: true,
+            show_risk: true,
+        }
+    }
+
+    pub fn with_symbol_precision(mut self, symbol_precision: PrecisionMap) -> Self {
+        self.symbol_precision = symbol_precision;
+        self
+    }
+
+    /// Number of book samples the price chart keeps.
+    pub fn with_chart_points(mut self, points: usize) -> Self {
+        self.price_history = PriceHistory::new(self.selected_symbol.clone(), points);
+        self
+    }
+
+    /// Initial price range of the depth chart, in bps either side of the mid.
+    pub fn with_depth_window_bps(mut self, window_bps: u32) -> Self {
+        self.depth_window_bps = window_bps.max(1);
+        self
+    }
+
+    pub fn with_warm_reconnect(mut self, warm_reconnect: bool) -> Self {
+        self.warm_reconnect = warm_reconnect;
+        self
+    }
+
+    /// Streams `selected_symbol` from the exchange onto the event bus, using tasks on
+    /// `runtime`. Changing `selected_symbol` later resubscribes.
+    pub fn with_live_market_data(mut self, runtime: tokio::runtime::Handle, config: &ApiConfig) -> Self {
+        self.market_data_feed = Some(MarketDataFeed::new(runtime, config, self.event_publisher.clone()));
+        self.sync_market_data_symbol();
+        self
+    }
+
+    pub fn with_order_routing(mut self, order_router: OrderRouter) -> Self {
+        if order_router.is_paper() {
+            self.add_log(LogLevel::Warning, "Paper trading: manual orders are simulated against the live book".to_string());
+        }
+        self.order_router = Some(order_router);
+        self
+    }
+
+    /// Shows `risk_manager`'s limits and breakers on the risk panel, along with the
+    /// events from `risk_events_rx`. Pass the router's manager so manual fills count.
+    pub fn with_risk_manager(mut self, risk_manager: RiskManager, risk_events_rx: Receiver<risk_manager::RiskEvent>) -> Self {
+        self.risk_manager = Some(risk_manager);
+        self.risk_events_rx = Some(risk_events_rx);
+        self
+    }
+
+    /// Runs the strategy with `config`, the one named `name` in the config file. It
+    /// still starts disabled.
+    pub fn with_strategy(mut self, name: String, config: MarketMakingConfig) -> Self {
+        let mut strategy = MarketMakingStrategy::new(config);
+        strategy.set_enabled(false);
+        self.market_making_strategy = Arc::new(RwLock::new(strategy));
+        self.strategy_name = Some(name);
+        self
+    }
+
+    /// Shows account value and margin from `account_info`, the cache an `AccountApi`
+    /// keeps current.
+    pub fn with_account_info(mut self, account_info: Arc<RwLock<Option<HyperLiquidAccountInfo>>>) -> Self {
+        self.account_info = Some(account_info);
+        self
+    }
+
+    /// Retunes the strategy when its entry in the config changes. Whether it is
+    /// enabled stays with the strategy panel.
+    pub fn with_config_events(mut self, config_events_rx: Receiver<ConfigEvent>) -> Self {
+        self.config_events_rx = Some(config_events_rx);
+        self
+    }
+
+    /// Hands a manual order action to the order router. Results show up in the logs
+    /// and order list as they come back.
+    pub fn route_order_command(&self, command: OrderCommand) {
+        let Some(router) = &self.order_router else {
+            self.add_log(LogLevel::Error, format!("Order routing is not configured, dropped {:?}", command));
+            return;
+        };
+
+        if let OrderCommand::Place(order) = &command {
+            self.add_log(LogLevel::Info, format!("Submitting {:?} {} {} @ {}", order.side, order.size, order.symbol, order.price));
+        }
+        if let Err(e) = router.send(command) {
+            self.add_log(LogLevel::Error, e);
+        }
+    }
+
+    // Applies what the order router reports back to the order and position managers
+    fn process_routed_orders(&mut self) {
+        let Some(router) = self.order_router.as_mut() else {
+            return;
+        };
+
+        for event in router.poll() {
+            match event {
+                RoutedOrderEvent::Placed(order) => {
+                    let status = order.status;
+                    let order_id = order.id;
+                    self.order_manager.track_order(order);
+                    if status == OrderStatus::Filled {
+                        self.order_manager.update_order(order_id, status, None);
+                    }
+                    self.sync_strategy_order(order_id);
+                }
+                RoutedOrderEvent::Rejected { order, reason } => {
+                    self.add_log(LogLevel::Warning, format!(
+                        "{:?} {} {} @ {} rejected: {}", order.side, order.size, order.symbol, order.price, reason
+                    ));
+                }
+                RoutedOrderEvent::Updated { order_id, status, filled_size } => {
+                    self.order_manager.update_order(order_id, status, Some(filled_size));
+                    self.sync_strategy_order(order_id);
+                }
+                RoutedOrderEvent::Failed { order_id, reason } => {
+                    self.order_manager.update_order(order_id, OrderStatus::Rejected, None);
+                    self.sync_strategy_order(order_id);
+                    self.add_log(LogLevel::Error, format!("Order {} failed: {}", order_id, reason));
+                }
+                RoutedOrderEvent::Fill(mut fill) => {
+                    fill.strategy = self.fill_strategy(fill.order_id);
+                    self.position_manager.process_fill(&fill);
+                    let router = self.order_router.as_ref().expect("order router is set");
+                    router.risk_manager.record_fill(&fill);
+                    if let Some(position) = self.position_manager.get_position(&fill.symbol) {
+                        router.risk_manager.update_position(&fill.symbol, position.size, fill.price);
+                    }
+                }
+                RoutedOrderEvent::Cancelled(order_id) => {
+                    self.order_manager.update_order(order_id, OrderStatus::Cancelled, None);
+                    self.sync_strategy_order(order_id);
+                }
+                RoutedOrderEvent::CancelFailed { order_id, reason } => {
+                    self.add_log(LogLevel::Warning, format!("Failed to cancel {}: {}", order_id, reason));
+                }
+            }
+        }
+    }
+
+    // Tells the strategy about the order's current state if it is one of its quotes
+    fn sync_strategy_order(&self, order_id: uuid::Uuid) {
+        let Some(order) = self.order_manager.get_order(&order_id) else {
+            return;
+        };
+        let mut strategy = self.market_making_strategy.write();
+        if strategy.owns_order(&order) {
+            strategy.track_order(&order);
+        }
+    }
+
+    // The strategy's name if the order is one of its quotes; anything else was manual
+    fn fill_strategy(&self, order_id: uuid::Uuid) -> Option<String> {
+        let order = self.order_manager.get_order(&order_id)?;
+        let strategy = self.market_making_strategy.read();
+        strategy.owns_order(&order).then(|| strategy.get_name().to_string())
+    }
+
+    // Runs the strategy on the current book and carries out what it decides: through
+    // the order router when there is one, otherwise straight into the order manager
+    fn run_strategy(&self) {
+        if self.halted.is_some() {
+            return;
+        }
+        let book = self.order_book.read().snapshot(SNAPSHOT_LEVELS);
+        let actions = {
+            let mut strategy = self.market_making_strategy.write();
+            if strategy.config.base_config.symbol != book.symbol {
+                return;
+            }
+            let actions = strategy.generate_actions_sync(&book);
+            // Restart the (jittered) refresh timer once quotes have been replaced
+            if let (false, Some(mid)) = (actions.is_empty(), book.mid_price()) {
+                strategy.update_last_price(mid);
+            }
+            actions
+        };
+
+        for action in actions {
+            match (action.action_type, action.order, action.order_id) {
+                (OrderActionType::Place, Some(order), _) => {
+                    if self.order_router.is_some() {
+                        self.route_order_command(OrderCommand::Place(order));
+                    } else {
+                        let order_id = self.order_manager.add_order(order);
+                        self.sync_strategy_order(order_id);
+                    }
+                }
+                (OrderActionType::Cancel, _, Some(order_id)) => {
+                    if self.order_router.is_some() {
+                        self.route_order_command(OrderCommand::Cancel(order_id));
+                    } else {
+                        self.order_manager.cancel_order(order_id);
+                        self.order_manager.update_order(order_id, OrderStatus::Cancelled, None);
+                        self.sync_strategy_order(order_id);
+                    }
+                }
+                _ => {}
+            }
+        }
+    }
+
+    // Resubscribes the feed when selected_symbol no longer matches it, starting the
+    // book over for the new symbol
+    fn sync_market_data_symbol(&mut self) {
+        let Some(feed) = self.market_data_feed.as_mut() else {
+            return;
+        };
+        if feed.symbol() == Some(self.selected_symbol.as_str()) {
+            return;
+        }
+
+        feed.subscribe(&self.selected_symbol);
+        *self.order_book.write() = OrderBook::new(self.selected_symbol.clone())
+            .with_integrity_checks(cfg!(debug_assertions));
+        self.manual_order.symbol = self.selected_symbol.clone();
+        self.price_history.reset(self.selected_symbol.clone());
+        self.connection_status = ConnectionStatus::Connecting;
+        self.add_log(LogLevel::Info, format!("Subscribed to {} market data", self.selected_symbol));
+    }
+
+    // Called when the market data connection drops
+    fn on_market_data_lost(&self) {
+        let mut order_book = self.order_book.write();
+        if self.warm_reconnect {
+            order_book.mark_stale();
+        } else {
+            order_book.clear();
+        }
+    }
+
+    /// Disables the strategy, cancels every active order and queues reduce-only
+    /// market orders to flatten all open positions. Manual trading stays blocked
+    /// until `resume_trading` is called.
+    pub fn emergency_stop(&mut self, reason: &str) {
+        self.halted = Some(reason.to_string());
+        self.market_making_strategy.write().set_enabled(false);
+
+        // Cancels are queued ahead of the flattening orders
+        if self.order_router.is_some() {
+            self.route_order_command(OrderCommand::CancelAll(None));
+        } else {
+            self.order_manager.cancel_all_orders(None);
+        }
+
+        for position in self.position_manager.get_all_positions() {
+            if position.size == Decimal::ZERO {
+                continue;
+            }
+
+            let order = NewOrder {
+                symbol: position.symbol.clone(),
+                side: if position.size > Decimal::ZERO { Side::Sell } else { Side::Buy },
+                order_type: OrderType::Market,
+                price: position.mark_price,
+                size: position.size.abs(),
+                client_id: Some("emergency_flatten".to_string()),
+                reduce_only: true,
+                tick_received_at: None,
+            };
+            if self.order_router.is_some() {
+                self.route_order_command(OrderCommand::Place(order));
+            } else {
+                self.order_manager.add_order(order);
+            }
         }
+
+        self.add_log(LogLevel::Error, format!("EMERGENCY STOP: {}", reason));
+    }
+
+    pub fn resume_trading(&mut self) {
+        if self.halted.take().is_some() {
+            self.add_log(LogLevel::Warning, "Trading resumed after emergency stop".to_string());
+        }
+    }
+
+    pub fn precision(&self, symbol: &str) -> SymbolPrecision {
+        precision_for(&self.symbol_precision, symbol)
     }
 
     pub fn add_log(&self, level: LogLevel, message: String) {
@@ -179,11 +543,12 @@ impl TradingApp {
                                      position.symbol, position.size, position.unrealized_pnl));
                     }
                     PositionEvent::FillProcessed(fill) => {
+                        self.price_history.record_fill(&fill);
                         self.add_log(LogLevel::Info, format!("Fill processed: {} {} @ {} (fee: {})", 
                                      fill.symbol, fill.size, fill.price, fill.fee));
                     }
                     PositionEvent::PnlRealized(pnl) => {
-                        self.add_log(LogLevel::Info, format!("PnL realized: ${:.2}", pnl));
+                        self.add_log(LogLevel::Info, format!("PnL realized: {}", format_usd(pnl)));
                     }
                 }
             }
@@ -194,23 +559,31 @@ impl TradingApp {
             while let Ok(event) = rx.try_recv() {
                 match event {
                     SystemEvent::MarketData { symbol, data, .. } => {
+                        // Updates still in flight from a previous symbol's feed
+                        if symbol != self.order_book.read().symbol {
+                            continue;
+                        }
+                        
                         // Update order book
-                        {
-                            let mut order_book = self.order_book.write();
-                            order_book.update_from_tob(&data.data);
+                        let update = self.order_book.write().update_from_tob(&data.data);
+                        if let Err(e) = update {
+                            self.add_log(LogLevel::Warning, e);
+                            continue;
                         }
                         
+                        self.price_history.record_book(&self.order_book.read(), chrono::Utc::now());
+                        
                         // Update position mark prices
                         if let Some(mid_price) = self.order_book.read().mid_price() {
                             self.position_manager.update_mark_prices(&symbol, mid_price);
                         }
                         
-                        // Process strategy
-                        let order_book_read = self.order_book.read();
-                        let mut strategy = self.market_making_strategy.write();
+                        // Resting paper orders fill against the new book
+                        if let Some(router) = &self.order_router {
+                            router.on_book_update(&self.order_book.read());
+                        }
                         
-                        // This would need to be adapted for async in a real implementation
-                        // For now, we'll skip the async strategy processing in the UI thread
+                        self.run_strategy();
                     }
                     SystemEvent::Risk { symbol, event, .. } => {
                         match event {
@@ -235,9 +608,15 @@ impl TradingApp {
                                 self.add_log(LogLevel::Info, format!("Connected: {}", connection_id));
                             }
                             ConnectionEvent::Disconnected => {
+                                self.on_market_data_lost();
                                 self.connection_status = ConnectionStatus::Disconnected;
                                 self.add_log(LogLevel::Warning, format!("Disconnected: {}", connection_id));
                             }
+                            ConnectionEvent::Reconnecting => {
+                                self.on_market_data_lost();
+                                self.connection_status = ConnectionStatus::Connecting;
+                                self.add_log(LogLevel::Warning, format!("Reconnecting: {}", connection_id));
+                            }
                             ConnectionEvent::Error(err) => {
                                 self.connection_status = ConnectionStatus::Error(err.clone());
                                 self.add_log(LogLevel::Error, format!("Connection error {}: {}", connection_id, err));
@@ -245,15 +624,81 @@ impl TradingApp {
                             _ => {}
                         }
                     }
+                    SystemEvent::System { event: SystemLevelEvent::MarketDataLost { connections }, .. } => {
+                        self.on_market_data_lost();
+                        self.connection_status = ConnectionStatus::Connecting;
+                        self.add_log(LogLevel::Warning, format!("All {} market data connections lost", connections));
+                    }
+                    SystemEvent::System { event: SystemLevelEvent::MarketDataRestored { connections }, .. } => {
+                        self.connection_status = ConnectionStatus::Connected;
+                        self.add_log(LogLevel::Info, format!("Market data restored on {} connections", connections));
+                    }
                     _ => {}
                 }
             }
         }
+        
+        self.process_routed_orders();
+        self.process_risk_events();
+        self.process_config_events();
+    }
+
+    fn process_config_events(&mut self) {
+        let Some(rx) = self.config_events_rx.clone() else {
+            return;
+        };
+
+        while let Ok(event) = rx.try_recv() {
+            match event {
+                ConfigEvent::ConfigChanged { section, key, new_value, .. }
+                    if section == "strategies" && self.strategy_name.as_ref() == Some(&key) && !new_value.is_null() =>
+                {
+                    let result = serde_json::from_value::<StrategyConfig>(new_value)
+                        .map_err(|e| format!("Failed to parse strategy {}: {}", key, e))
+                        .and_then(|strategy_config| strategy_config.apply_to(&mut self.market_making_strategy.write()));
+                    match result {
+                        Ok(()) => self.add_log(LogLevel::Info, format!("Strategy {} config applied", key)),
+                        Err(e) => self.add_log(LogLevel::Error, e),
+                    }
+                }
+                ConfigEvent::ConfigError { error, .. } => self.add_log(LogLevel::Error, format!("Config error: {}", error)),
+                _ => {}
+            }
+        }
+    }
+
+    fn process_risk_events(&mut self) {
+        let Some(rx) = self.risk_events_rx.clone() else {
+            return;
+        };
+
+        while let Ok(event) = rx.try_recv() {
+            match event_severity(&event) {
+                RiskSeverity::Critical => self.add_log(LogLevel::Error, describe_event(&event)),
+                RiskSeverity::High => self.add_log(LogLevel::Warning, describe_event(&event)),
+                _ => {}
+            }
+
+            self.risk_events.push_back(RiskEventEntry { received_at: chrono::Utc::now(), event });
+            if self.risk_events.len() > RISK_EVENT_HISTORY {
+                self.risk_events.pop_front();
+            }
+        }
+    }
+
+    // Newest Critical risk event from the last minute, for the banner
+    fn recent_critical_risk_event(&self) -> Option<&RiskEventEntry> {
+        let since = chrono::Utc::now() - chrono::Duration::minutes(1);
+        self.risk_events.iter().rev()
+            .take_while(|entry| entry.received_at >= since)
+            .find(|entry| event_severity(&entry.event) == RiskSeverity::Critical)
     }
 }
 
 impl eframe::App for TradingApp {
     fn update(&mut self, ctx: &Context, _frame: &mut eframe::Frame) {
+        self.sync_market_data_symbol();
+        
         // Process events (non-async version)
         self.process_events();
 
@@ -278,10 +723,49 @@ impl eframe::App for TradingApp {
                 
                 ui.separator();
                 
+                if let Some(account_info) = &self.account_info {
+                    show_account_summary(ui, account_info.read().as_ref());
+                    ui.separator();
+                }
+                
+                // Symbol selection; takes effect on Enter
+                ui.label("Symbol:");
+                let symbol_edit = ui.add(egui::TextEdit::singleline(&mut self.symbol_input).desired_width(60.0));
+                if symbol_edit.lost_focus() && ui.input(|i| i.key_pressed(egui::Key::Enter)) {
+                    let symbol = self.symbol_input.trim().to_uppercase();
+                    if !symbol.is_empty() {
+                        self.selected_symbol = symbol;
+                    }
+                    self.symbol_input = self.selected_symbol.clone();
+                }
+                
+                ui.separator();
+                
                 // Event bus metrics
                 let metrics = self.event_bus.get_metrics();
                 ui.label(format!("Events: {} processed, {} dropped", 
-                               metrics.events_processed, metrics.events_dropped));
+                               metrics.events_processed, metrics.events_dropped))
+                    .on_hover_ui(|ui| {
+                        ui.label(format!("Dropped: H:{} N:{} L:{}",
+                                         metrics.high_priority_dropped,
+                                         metrics.normal_priority_dropped,
+                                         metrics.low_priority_dropped));
+                        ui.separator();
+                        let mut topics: Vec<_> = self.event_bus.get_topic_metrics().into_iter().collect();
+                        topics.sort_by(|a, b| a.0.cmp(&b.0));
+                        egui::Grid::new("event_topics").striped(true).show(ui, |ui| {
+                            ui.strong("Topic");
+                            ui.strong("Subscribers");
+                            ui.strong("Queued");
+                            ui.end_row();
+                            for (topic, topic_metrics) in topics {
+                                ui.label(topic);
+                                ui.label(topic_metrics.subscriber_count.to_string());
+                                ui.label(topic_metrics.queued_events.to_string());
+                                ui.end_row();
+                            }
+                        });
+                    });
                 ui.label(format!("Queues: H:{} N:{} L:{}", 
                                metrics.high_priority_queue_len,
                                metrics.normal_priority_queue_len,
@@ -294,36 +778,97 @@ impl eframe::App for TradingApp {
                 ui.checkbox(&mut self.show_positions, "Positions");
                 ui.checkbox(&mut self.show_strategy, "Strategy");
                 ui.checkbox(&mut self.show_logs, "Logs");
+                ui.checkbox(&mut self.show_chart, "Chart");
+                ui.checkbox(&mut self.show_depth, "Depth");
+                ui.checkbox(&mut self.show_risk, "Risk");
+                
+                ui.separator();
+                
+                let stop_button = egui::Button::new(egui::RichText::new("EMERGENCY STOP").strong().color(egui::Color32::WHITE))
+                    .fill(egui::Color32::from_rgb(200, 30, 30));
+                if ui.add_enabled(self.halted.is_none(), stop_button).clicked() {
+                    self.emergency_stop("Manual emergency stop");
+                }
             });
         });
 
+        // Halted banner
+        if let Some(reason) = self.halted.clone() {
+            TopBottomPanel::top("halted_banner")
+                .frame(egui::Frame::default().fill(egui::Color32::from_rgb(150, 0, 0)).inner_margin(8.0))
+                .show(ctx, |ui| {
+                    ui.horizontal(|ui| {
+                        ui.label(egui::RichText::new(format!("TRADING HALTED: {}", reason))
+                            .heading()
+                            .color(egui::Color32::WHITE));
+                        if ui.button("Resume").clicked() {
+                            self.resume_trading();
+                        }
+                    });
+                });
+        }
+
+        // Critical risk banner
+        if let Some(entry) = self.recent_critical_risk_event() {
+            let message = format!("CRITICAL RISK at {}: {}", entry.received_at.format("%H:%M:%S"), describe_event(&entry.event));
+            TopBottomPanel::top("risk_banner")
+                .frame(egui::Frame::default().fill(egui::Color32::from_rgb(200, 90, 0)).inner_margin(8.0))
+                .show(ctx, |ui| {
+                    ui.label(egui::RichText::new(message).strong().color(egui::Color32::WHITE));
+                });
+        }
+
         // Left panel - Order Book and Trading
+        let mut order_commands = Vec::new();
         if self.show_order_book {
             SidePanel::left("left_panel").resizable(true).show(ctx, |ui| {
-                ui.heading("Order Book");
+                ui.horizontal(|ui| {
+                    ui.heading("Order Book");
+                    ui.checkbox(&mut self.show_own_orders, "My orders");
+                });
                 let order_book = self.order_book.read();
-                order_book_panel::show(ui, &*order_book);
+                let own_orders = if self.show_own_orders {
+                    self.order_manager.get_active_orders(Some(&order_book.symbol))
+                } else {
+                    Vec::new()
+                };
+                order_book_panel::show(ui, &order_book, self.precision(&order_book.symbol), &own_orders);
                 
                 ui.separator();
                 
                 ui.heading("Manual Trading");
-                trading_panel::show(ui, &mut self.manual_order, &self.order_manager);
+                let precision = self.precision(&self.manual_order.symbol);
+                trading_panel::show(ui, &mut self.manual_order, &self.order_manager, precision, self.halted.is_some(),
+                                    &mut |command| order_commands.push(command));
             });
         }
+        for command in order_commands {
+            self.route_order_command(command);
+        }
 
         // Right panel - Strategy and Positions
-        if self.show_strategy || self.show_positions {
+        let show_risk = self.show_risk && self.risk_manager.is_some();
+        if self.show_strategy || self.show_positions || show_risk {
             SidePanel::right("right_panel").resizable(true).show(ctx, |ui| {
                 if self.show_strategy {
                     ui.heading("Market Making Strategy");
                     let mut strategy = self.market_making_strategy.write();
-                    strategy_panel::show(ui, &mut *strategy);
+                    let precision = self.precision(&strategy.config.base_config.symbol);
+                    strategy_panel::show(ui, &mut strategy, &mut self.strategy_draft, precision);
                     ui.separator();
                 }
                 
                 if self.show_positions {
                     ui.heading("Positions & PnL");
-                    positions_panel::show(ui, &self.position_manager);
+                    positions_panel::show(ui, &self.position_manager, &self.symbol_precision);
+                }
+                
+                if let (true, Some(risk_manager)) = (show_risk, &self.risk_manager) {
+                    ui.separator();
+                    ui.heading("Risk");
+                    if risk_panel::show(ui, risk_manager, &self.risk_events) {
+                        self.add_log(LogLevel::Info, "Daily risk metrics reset".to_string());
+                    }
                 }
             });
         }
@@ -332,8 +877,8 @@ impl eframe::App for TradingApp {
         if self.show_logs {
             TopBottomPanel::bottom("bottom_panel").resizable(true).show(ctx, |ui| {
                 ui.heading("Logs");
-                let logs = self.logs.read();
-                logs_panel::show(ui, &*logs);
+                let mut logs = self.logs.write();
+                logs_panel::show(ui, &mut logs, &mut self.min_log_level);
             });
         }
 
@@ -344,11 +889,12 @@ impl eframe::App for TradingApp {
             // Market stats
             ui.horizontal(|ui| {
                 let order_book = self.order_book.read();
+                let precision = self.precision(&order_book.symbol);
                 if let Some(mid_price) = order_book.mid_price() {
-                    ui.label(format!("Mid Price: ${:.4}", mid_price));
+                    ui.label(format!("Mid Price: ${}", format_price(mid_price, precision)));
                 }
                 if let Some(spread) = order_book.spread() {
-                    ui.label(format!("Spread: ${:.4}", spread));
+                    ui.label(format!("Spread: ${}", format_price(spread, precision)));
                 }
                 if let Some(spread_bps) = order_book.spread_bps() {
                     ui.label(format!("Spread: {:.1} bps", spread_bps));
@@ -357,16 +903,40 @@ impl eframe::App for TradingApp {
             
             ui.separator();
             
+            // Price chart
+            if self.show_chart {
+                let strategy = self.market_making_strategy.read();
+                let quotes: Vec<(Side, Decimal)> = strategy.active_orders.values()
+                    .filter(|order| order.symbol == self.price_history.symbol)
+                    .map(|order| (order.side, order.price))
+                    .collect();
+                drop(strategy);
+                let precision = self.precision(&self.price_history.symbol);
+                chart_panel::show(ui, &self.price_history, &quotes, &mut self.chart_overlays, precision);
+                
+                ui.separator();
+            }
+            
+            // Depth chart
+            if self.show_depth {
+                let order_book = self.order_book.read();
+                let precision = self.precision(&order_book.symbol);
+                depth_panel::show(ui, &order_book, &mut self.depth_window_bps, precision);
+                drop(order_book);
+                
+                ui.separator();
+            }
+            
             // PnL Summary
             ui.horizontal(|ui| {
                 let total_pnl = self.position_manager.get_total_pnl();
                 let unrealized_pnl = self.position_manager.get_total_unrealized_pnl();
                 let realized_pnl = self.position_manager.get_realized_pnl();
                 
-                ui.label(format!("Total PnL: ${:.2}", total_pnl));
-                ui.label(format!("Unrealized: ${:.2}", unrealized_pnl));
-                ui.label(format!("Realized: ${:.2}", realized_pnl));
-                ui.label(format!("Fees: ${:.2}", self.position_manager.get_total_fees()));
+                ui.label(format!("Total PnL: {}", format_usd(total_pnl)));
+                ui.label(format!("Unrealized: {}", format_usd(unrealized_pnl)));
+                ui.label(format!("Realized: {}", format_usd(realized_pnl)));
+                ui.label(format!("Fees: {}", format_usd(self.position_manager.get_total_fees())));
             });
             
             ui.separator();
@@ -378,8 +948,8 @@ impl eframe::App for TradingApp {
             
             ui.horizontal(|ui| {
                 ui.label(format!("Active Orders: {}", active_orders.len()));
-                ui.label(format!("Buy: {} (${:.2})", buy_count, buy_exposure));
-                ui.label(format!("Sell: {} (${:.2})", sell_count, sell_exposure));
+                ui.label(format!("Buy: {} ({})", buy_count, format_usd(buy_exposure)));
+                ui.label(format!("Sell: {} ({})", sell_count, format_usd(sell_exposure)));
             });
             
             ui.separator();
@@ -387,6 +957,7 @@ impl eframe::App for TradingApp {
             // Strategy status
             ui.horizontal(|ui| {
                 let strategy = self.market_making_strategy.read();
+                let precision = self.precision(&strategy.config.base_config.symbol);
                 let strategy_status = if strategy.is_enabled() {
                     "Running"
                 } else {
@@ -395,10 +966,64 @@ impl eframe::App for TradingApp {
                 ui.label(format!("Strategy: {}", strategy_status));
                 ui.label(format!("Active MM Orders: {}", strategy.active_orders.len()));
                 if let Some(last_price) = strategy.last_price {
-                    ui.label(format!("Last Price: ${:.4}", last_price));
+                    ui.label(format!("Last Price: ${}", format_price(last_price, precision)));
                 }
-                ui.label(format!("Inventory: {:.4}", strategy.current_inventory));
+                ui.label(format!("Inventory: {}", format_size(strategy.current_inventory, precision)));
             });
         });
     }
+
+    fn on_exit(&mut self, _gl: Option<&eframe::glow::Context>) {
+        if let Some(subscription) = self.system_events_subscription.take() {
+            self.event_bus.unsubscribe(&subscription);
+        }
+        self.system_events_rx = None;
+        if let Some(feed) = self.market_data_feed.as_mut() {
+            feed.stop();
+        }
+        self.event_bus.stop_processing(Duration::from_secs(1));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::hl_msgs::TobMsg;
+    use rust_decimal_macros::dec;
+
+    fn market_data(bid: &str, ask: &str) -> SystemEvent {
+        let data: TobMsg = serde_json::from_value(serde_json::json!({
+            "channel": "l2Book",
+            "data": {
+                "coin": "HYPE",
+                "time": 1,
+                "levels": [[{"px": bid, "sz": "5", "n": 1}], [{"px": ask, "sz": "5", "n": 1}]],
+            },
+        })).unwrap();
+        SystemEvent::new_market_data("HYPE".to_string(), data)
+    }
+
+    #[test]
+    fn market_data_drives_the_enabled_strategy_into_the_order_manager() {
+        let mut app = TradingApp::new();
+        let (system_events_tx, system_events_rx) = crossbeam_channel::unbounded();
+        app.system_events_rx = Some(system_events_rx);
+        app.market_making_strategy.write().set_enabled(true);
+
+        system_events_tx.send(market_data("99.99", "100.01")).unwrap();
+        app.process_events();
+
+        let orders = app.order_manager.get_active_orders(Some("HYPE"));
+        assert_eq!(orders.len(), 6);
+        assert_eq!(orders.iter().filter(|order| order.side == Side::Buy).count(), 3);
+        assert_eq!(app.market_making_strategy.read().active_orders.len(), 6);
+
+        // A 1% move requotes: the old quotes are cancelled and replaced around the new mid
+        system_events_tx.send(market_data("100.99", "101.01")).unwrap();
+        app.process_events();
+
+        let orders = app.order_manager.get_active_orders(Some("HYPE"));
+        assert_eq!(orders.len(), 6);
+        assert!(orders.iter().all(|order| (order.price - dec!(101)).abs() < dec!(1)));
+    }
 }
@@ -0,0 +1,114 @@
+use crate::api::types::{HyperLiquidAccountInfo, HyperLiquidMarginSummary};
+use crate::trading::risk_manager::limit_usage;
+use crate::ui::components::price_display::{format_decimal, format_usd};
+use egui::{Color32, Ui};
+use rust_decimal::Decimal;
+use rust_decimal_macros::dec;
+use std::str::FromStr;
+
+// Margin usage, as a fraction of account value, shown amber and then red
+const MARGIN_WARN_USAGE: Decimal = dec!(0.5);
+const MARGIN_HIGH_USAGE: Decimal = dec!(0.8);
+
+/// A margin summary as the top panel shows it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MarginDisplay {
+    pub account_value: String,
+    pub margin_used: String,
+    pub free_margin: String,
+    pub usage: String,
+    pub usage_fraction: Decimal, // Margin used over account value
+}
+
+/// Free margin is account value less margin used, never below zero.
+pub fn format_margin_summary(summary: &HyperLiquidMarginSummary) -> Result<MarginDisplay, String> {
+    let account_value = Decimal::from_str(&summary.account_value)
+        .map_err(|e| format!("Invalid account value {}: {}", summary.account_value, e))?;
+    let margin_used = Decimal::from_str(&summary.total_margin_used)
+        .map_err(|e| format!("Invalid margin used {}: {}", summary.total_margin_used, e))?;
+    let usage_fraction = limit_usage(margin_used, account_value);
+
+    Ok(MarginDisplay {
+        account_value: format_usd(account_value),
+        margin_used: format_usd(margin_used),
+        free_margin: format_usd((account_value - margin_used).max(Decimal::ZERO)),
+        usage: format!("{}%", format_decimal(usage_fraction * dec!(100), 1)),
+        usage_fraction,
+    })
+}
+
+fn usage_color(usage: Decimal) -> Color32 {
+    if usage >= MARGIN_HIGH_USAGE {
+        Color32::from_rgb(220, 53, 69)
+    } else if usage >= MARGIN_WARN_USAGE {
+        Color32::from_rgb(255, 193, 7)
+    } else {
+        Color32::from_rgb(40, 167, 69)
+    }
+}
+
+/// Account value, margin used and free margin, or a placeholder until the first
+/// account update has arrived.
+pub fn show_account_summary(ui: &mut Ui, account_info: Option<&HyperLiquidAccountInfo>) {
+    let Some(account_info) = account_info else {
+        ui.colored_label(Color32::GRAY, "Account: loading...");
+        return;
+    };
+
+    match format_margin_summary(&account_info.margin_summary) {
+        Ok(display) => {
+            ui.label(format!("Account: {}", display.account_value));
+            ui.colored_label(usage_color(display.usage_fraction), format!("Margin: {} ({})", display.margin_used, display.usage));
+            ui.label(format!("Free: {}", display.free_margin));
+        }
+        Err(e) => {
+            ui.colored_label(Color32::from_rgb(220, 53, 69), "Account: unavailable").on_hover_text(e);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn summary(account_value: &str, total_margin_used: &str) -> HyperLiquidMarginSummary {
+        HyperLiquidMarginSummary {
+            account_value: account_value.to_string(),
+            total_margin_used: total_margin_used.to_string(),
+            total_ntl_pos: "0".to_string(),
+            total_raw_usd: "0".to_string(),
+        }
+    }
+
+    #[test]
+    fn a_margin_summary_formats_to_dollars_and_a_usage_percentage() {
+        let display = format_margin_summary(&summary("10000.5", "2500.125")).unwrap();
+        assert_eq!(display.account_value, "$10000.50");
+        assert_eq!(display.margin_used, "$2500.12");
+        assert_eq!(display.free_margin, "$7500.38");
+        assert_eq!(display.usage, "25.0%");
+        assert_eq!(usage_color(display.usage_fraction), Color32::from_rgb(40, 167, 69));
+
+        assert_eq!(usage_color(format_margin_summary(&summary("1000", "600")).unwrap().usage_fraction), Color32::from_rgb(255, 193, 7));
+    }
+
+    #[test]
+    fn margin_over_the_account_value_leaves_no_free_margin() {
+        let display = format_margin_summary(&summary("1000", "1200")).unwrap();
+        assert_eq!(display.free_margin, "$0.00");
+        assert_eq!(display.usage, "120.0%");
+        assert_eq!(usage_color(display.usage_fraction), Color32::from_rgb(220, 53, 69));
+
+        // With nothing in the account, any margin use is full usage
+        assert_eq!(format_margin_summary(&summary("0", "5")).unwrap().usage, "100.0%");
+        assert_eq!(format_margin_summary(&summary("0", "0")).unwrap().usage, "0.0%");
+    }
+
+    #[test]
+    fn an_unreadable_summary_is_an_error_naming_the_field() {
+        let err = format_margin_summary(&summary("n/a", "0")).unwrap_err();
+        assert!(err.starts_with("Invalid account value n/a"), "{}", err);
+        let err = format_margin_summary(&summary("100", "")).unwrap_err();
+        assert!(err.starts_with("Invalid margin used"), "{}", err);
+    }
+}
@@ -1,3 +1,4 @@
+pub mod account_summary;
 pub mod connection_indicator;
 pub mod order_table;
 pub mod price_display;
\ No newline at end of file
@@ -1,7 +1,9 @@
 use egui::{Ui, Grid, Color32};
 use crate::trading::types::{Order, Side, OrderStatus};
+use crate::ui::components::price_display::{format_price, format_size, SymbolPrecision};
+use uuid::Uuid;
 
-pub fn show_order_table(ui: &mut Ui, orders: &[&Order]) {
+pub fn show_order_table(ui: &mut Ui, orders: &[&Order], precision: SymbolPrecision, on_cancel: &mut dyn FnMut(Uuid)) {
     Grid::new("order_table")
         .num_columns(6)
         .spacing([10.0, 4.0])
@@ -23,14 +25,14 @@ pub fn show_order_table(ui: &mut Ui, orders: &[&Order]) {
                 };
                 
                 ui.colored_label(side_color, format!("{:?}", order.side));
-                ui.label(format!("{:.4}", order.price));
-                ui.label(format!("{:.4}", order.size));
-                ui.label(format!("{:.4}", order.filled_size));
+                ui.label(format_price(order.price, precision));
+                ui.label(format_size(order.size, precision));
+                ui.label(format_size(order.filled_size, precision));
                 ui.label(format!("{:?}", order.status));
                 
                 if matches!(order.status, OrderStatus::Pending | OrderStatus::Submitted | OrderStatus::PartiallyFilled) {
                     if ui.button("Cancel").clicked() {
-                        // Cancel order - would need callback
+                        on_cancel(order.id);
                     }
                 } else {
                     ui.label("");
@@ -39,3 +41,71 @@ pub fn show_order_table(ui: &mut Ui, orders: &[&Order]) {
             }
         });
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::trading::types::OrderType;
+    use egui::{Context, Event, PointerButton, Pos2, RawInput, Rect};
+    use rust_decimal_macros::dec;
+
+    fn order(status: OrderStatus) -> Order {
+        Order {
+            id: Uuid::new_v4(),
+            client_id: None,
+            symbol: "HYPE".to_string(),
+            side: Side::Buy,
+            order_type: OrderType::Limit,
+            price: dec!(25),
+            size: dec!(1),
+            filled_size: dec!(0),
+            remaining_size: dec!(1),
+            status,
+            created_at: chrono::Utc::now(),
+            updated_at: chrono::Utc::now(),
+            exchange_oid: None,
+        }
+    }
+
+    // Runs one frame of the table with `events` as input, returning where it was drawn
+    fn frame(ctx: &Context, events: Vec<Event>, orders: &[&Order], cancelled: &mut Vec<Uuid>) -> Rect {
+        let input = RawInput {
+            screen_rect: Some(Rect::from_min_size(Pos2::ZERO, egui::vec2(800.0, 600.0))),
+            events,
+            ..Default::default()
+        };
+        let mut table = Rect::NOTHING;
+        let _ = ctx.run(input, |ctx| {
+            egui::CentralPanel::default().show(ctx, |ui| {
+                table = ui.scope(|ui| show_order_table(ui, orders, SymbolPrecision::default(), &mut |id| cancelled.push(id))).response.rect;
+            });
+        });
+        table
+    }
+
+    fn button(pos: Pos2, pressed: bool) -> Event {
+        Event::PointerButton { pos, button: PointerButton::Primary, pressed, modifiers: Default::default() }
+    }
+
+    #[test]
+    fn clicking_cancel_hands_the_rows_order_id_to_the_callback() {
+        let filled = order(OrderStatus::Filled);
+        let open = order(OrderStatus::Submitted);
+        let orders = [&filled, &open];
+        let ctx = Context::default();
+        let mut cancelled = Vec::new();
+
+        // The open order's Cancel button is the bottom right cell
+        let table = frame(&ctx, Vec::new(), &orders, &mut cancelled);
+        let cancel = table.right_bottom() - egui::vec2(4.0, 4.0);
+        frame(&ctx, vec![Event::PointerMoved(cancel), button(cancel, true)], &orders, &mut cancelled);
+        frame(&ctx, vec![button(cancel, false)], &orders, &mut cancelled);
+        assert_eq!(cancelled, [open.id]);
+
+        // The filled order's row has no button to click
+        let filled_row = Pos2::new(cancel.x, table.center().y);
+        frame(&ctx, vec![Event::PointerMoved(filled_row), button(filled_row, true)], &orders, &mut cancelled);
+        frame(&ctx, vec![button(filled_row, false)], &orders, &mut cancelled);
+        assert_eq!(cancelled, [open.id]);
+    }
+}
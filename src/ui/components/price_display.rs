@@ -1,11 +1,51 @@
 use egui::{Ui, Color32};
 use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 
-pub fn show_price(ui: &mut Ui, price: Decimal, precision: usize) {
-    ui.label(format!("${:.prec$}", price, prec = precision));
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SymbolPrecision {
+    pub price_decimals: u32, // Decimal places of the symbol's tick size
+    pub size_decimals: u32,  // Decimal places of the symbol's lot size
 }
 
-pub fn show_colored_price(ui: &mut Ui, price: Decimal, reference_price: Option<Decimal>, precision: usize) {
+impl Default for SymbolPrecision {
+    fn default() -> Self {
+        Self {
+            price_decimals: 4,
+            size_decimals: 4,
+        }
+    }
+}
+
+pub type PrecisionMap = HashMap<String, SymbolPrecision>;
+
+pub fn precision_for(precisions: &PrecisionMap, symbol: &str) -> SymbolPrecision {
+    precisions.get(symbol).copied().unwrap_or_default()
+}
+
+/// Rounds to `decimals` places and always renders exactly that many, so columns line up.
+pub fn format_decimal(value: Decimal, decimals: u32) -> String {
+    format!("{:.prec$}", value.round_dp(decimals), prec = decimals as usize)
+}
+
+pub fn format_price(price: Decimal, precision: SymbolPrecision) -> String {
+    format_decimal(price, precision.price_decimals)
+}
+
+pub fn format_size(size: Decimal, precision: SymbolPrecision) -> String {
+    format_decimal(size, precision.size_decimals)
+}
+
+pub fn format_usd(value: Decimal) -> String {
+    format!("${}", format_decimal(value, 2))
+}
+
+pub fn show_price(ui: &mut Ui, price: Decimal, precision: SymbolPrecision) {
+    ui.label(format!("${}", format_price(price, precision)));
+}
+
+pub fn show_colored_price(ui: &mut Ui, price: Decimal, reference_price: Option<Decimal>, precision: SymbolPrecision) {
     let color = if let Some(ref_price) = reference_price {
         if price > ref_price {
             Color32::from_rgb(40, 167, 69)  // Green
@@ -18,5 +58,32 @@ pub fn show_colored_price(ui: &mut Ui, price: Decimal, reference_price: Option<D
         Color32::default()
     };
     
-    ui.colored_label(color, format!("${:.prec$}", price, prec = precision));
+    ui.colored_label(color, format!("${}", format_price(price, precision)));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rust_decimal_macros::dec;
+
+    #[test]
+    fn values_render_with_exactly_the_symbols_decimals() {
+        let btc = SymbolPrecision { price_decimals: 1, size_decimals: 5 };
+        let pepe = SymbolPrecision { price_decimals: 8, size_decimals: 0 };
+
+        assert_eq!(format_price(dec!(64250.25), btc), "64250.2");
+        assert_eq!(format_size(dec!(0.0012), btc), "0.00120");
+        assert_eq!(format_price(dec!(0.0000123), pepe), "0.00001230");
+        assert_eq!(format_size(dec!(1500000.4), pepe), "1500000");
+        assert_eq!(format_decimal(dec!(-2.345), 2), "-2.34");
+        assert_eq!(format_usd(dec!(12.5)), "$12.50");
+    }
+
+    #[test]
+    fn a_symbol_without_known_precision_falls_back_to_four_places() {
+        let precisions = PrecisionMap::from([("HYPE".to_string(), SymbolPrecision { price_decimals: 3, size_decimals: 2 })]);
+
+        assert_eq!(format_price(dec!(25.1), precision_for(&precisions, "HYPE")), "25.100");
+        assert_eq!(format_price(dec!(25.1), precision_for(&precisions, "ETH")), "25.1000");
+    }
 }
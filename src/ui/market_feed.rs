@@ -0,0 +1,116 @@
+use crate::api::types::ApiConfig;
+use crate::clients::ws_manager::WsManager;
+use crate::events::event_bus::EventPublisher;
+use crate::events::types::{ConnectionEvent, SystemEvent};
+use crate::model::hl_msgs::TobMsg;
+use std::time::Duration;
+use tokio::runtime::Handle;
+use tokio::sync::mpsc;
+use tokio::task::JoinHandle;
+use tracing::{error, info, warn};
+
+const RETRY_INITIAL_BACKOFF: Duration = Duration::from_secs(1);
+const RETRY_MAX_BACKOFF: Duration = Duration::from_secs(30);
+
+/// Streams the l2Book feed for one symbol onto the app's event bus from a task on
+/// `runtime`, so the UI thread only ever sees `SystemEvent::MarketData`. Subscribing
+/// to another symbol replaces the task.
+pub struct MarketDataFeed {
+    runtime: Handle,
+    url: String,
+    connections: u64,
+    warmup_updates: u64,
+    max_message_age_ms: u64,
+    publisher: EventPublisher,
+    symbol: Option<String>,
+    task: Option<JoinHandle<()>>,
+}
+
+impl MarketDataFeed {
+    pub fn new(runtime: Handle, config: &ApiConfig, publisher: EventPublisher) -> Self {
+        Self {
+            runtime,
+            url: config.ws_url.clone(),
+            connections: config.market_data_connections,
+            warmup_updates: config.market_data_warmup_updates,
+            max_message_age_ms: config.max_market_data_age_ms,
+            publisher,
+            symbol: None,
+            task: None,
+        }
+    }
+
+    pub fn symbol(&self) -> Option<&str> {
+        self.symbol.as_deref()
+    }
+
+    pub fn subscribe(&mut self, symbol: &str) {
+        self.stop();
+        info!("Streaming market data for {}", symbol);
+
+        let url = self.url.clone();
+        let connections = self.connections;
+        let warmup_updates = self.warmup_updates;
+        let max_message_age_ms = self.max_message_age_ms;
+        let publisher = self.publisher.clone();
+        let feed_symbol = symbol.to_string();
+        self.task = Some(self.runtime.spawn(async move {
+            run_feed(url, feed_symbol, connections, warmup_updates, max_message_age_ms, publisher).await;
+        }));
+        self.symbol = Some(symbol.to_string());
+    }
+
+    /// Aborting the task drops the manager, which closes its connections.
+    pub fn stop(&mut self) {
+        if let Some(task) = self.task.take() {
+            task.abort();
+        }
+        self.symbol = None;
+    }
+}
+
+impl Drop for MarketDataFeed {
+    fn drop(&mut self) {
+        self.stop();
+    }
+}
+
+// The manager recovers dropped connections itself; this loop only covers failing to
+// open them in the first place
+async fn run_feed(url: String, symbol: String, connections: u64, warmup_updates: u64, max_message_age_ms: u64, publisher: EventPublisher) {
+    let connection_id = format!("market_data.{}", symbol);
+    let publish = |event: ConnectionEvent| {
+        if let Err(e) = publisher.publish(SystemEvent::new_connection_event(connection_id.clone(), event)) {
+            error!("Failed to publish market data connection event: {:?}", e);
+        }
+    };
+
+    let mut backoff = RETRY_INITIAL_BACKOFF;
+    loop {
+        publish(ConnectionEvent::Reconnecting);
+
+        let (msg_tx, msg_rx) = mpsc::channel::<TobMsg>(1000);
+        match WsManager::new(connections, &url, std::slice::from_ref(&symbol), msg_tx, msg_rx).await {
+            Ok(manager) => {
+                backoff = RETRY_INITIAL_BACKOFF;
+                publish(ConnectionEvent::Connected);
+
+                let mut manager = manager
+                    .with_warmup_updates(warmup_updates)
+                    .with_max_message_age(max_message_age_ms)
+                    .with_event_publisher(publisher.clone());
+                if let Err(e) = manager.run().await {
+                    error!("Market data for {} stopped: {}", symbol, e);
+                }
+                publish(ConnectionEvent::Disconnected);
+            }
+            Err(e) => {
+                warn!("Failed to connect market data for {}: {}", symbol, e);
+                publish(ConnectionEvent::Error(e.to_string()));
+            }
+        }
+
+        tokio::time::sleep(backoff).await;
+        backoff = (backoff * 2).min(RETRY_MAX_BACKOFF);
+    }
+}
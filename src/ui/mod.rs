@@ -1,3 +1,5 @@
 pub mod app;
 pub mod components;
+pub mod market_feed;
+pub mod order_router;
 pub mod panels;
\ No newline at end of file
@@ -0,0 +1,272 @@
+use crate::api::account_api::{parse_order_status, parse_side};
+use crate::api::trading_api::TradingApi;
+use crate::api::types::ApiEvent;
+use crate::trading::order_book::OrderBook;
+use crate::trading::risk_manager::RiskManager;
+use crate::trading::types::*;
+use chrono::Utc;
+use crossbeam_channel::{Receiver, Sender, unbounded};
+use rust_decimal::Decimal;
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tokio::runtime::Handle;
+use tokio::sync::mpsc;
+use tracing::warn;
+use uuid::Uuid;
+
+// Updates for client ids the router hasn't placed yet are held this long, since they
+// can arrive before the placement result
+const UNMATCHED_UPDATE_TTL: Duration = Duration::from_secs(5);
+
+/// A manual order action from the UI.
+#[derive(Debug, Clone)]
+pub enum OrderCommand {
+    Place(NewOrder),
+    Cancel(Uuid),
+    CancelAll(Option<String>), // Every routed order, or only those for the symbol
+}
+
+/// What the UI should apply to its order and position managers.
+#[derive(Debug, Clone)]
+pub enum RoutedOrderEvent {
+    Placed(Order),
+    Rejected { order: NewOrder, reason: String }, // By the risk manager or the exchange; never placed
+    Updated { order_id: Uuid, status: OrderStatus, filled_size: Decimal },
+    Failed { order_id: Uuid, reason: String }, // Placed, then given up on
+    Fill(Fill),
+    Cancelled(Uuid),
+    CancelFailed { order_id: Uuid, reason: String },
+}
+
+// Sent by the worker once a command has run
+#[derive(Debug)]
+enum CommandResult {
+    Placed { order_id: Uuid, client_order_id: Option<u64>, order: NewOrder },
+    Rejected { order: NewOrder, reason: String },
+    Cancelled(Uuid),
+    CancelFailed { order_id: Uuid, reason: String },
+}
+
+/// Sends manual orders through `RiskManager::check_order_risk` and `TradingApi` from
+/// a worker task on `runtime`, one command at a time, so the UI thread never waits
+/// on the exchange. `poll` turns the results and the API's order events into
+/// `RoutedOrderEvent`s keyed by the ids `TradingApi` returned.
+pub struct OrderRouter {
+    pub trading_api: Arc<TradingApi>,
+    pub risk_manager: RiskManager,
+    commands_tx: mpsc::UnboundedSender<OrderCommand>,
+    results_rx: Receiver<CommandResult>,
+    api_events_rx: Receiver<ApiEvent>,
+    routed: HashMap<u64, Uuid>, // Client order id to internal id, while the order works
+    unmatched: HashMap<u64, (OrderStatus, Decimal, Instant)>,
+}
+
+impl OrderRouter {
+    pub fn new(runtime: &Handle, trading_api: TradingApi, api_events_rx: Receiver<ApiEvent>, risk_manager: RiskManager) -> Self {
+        let trading_api = Arc::new(trading_api);
+        let (commands_tx, commands_rx) = mpsc::unbounded_channel();
+        let (results_tx, results_rx) = unbounded();
+
+        runtime.spawn(run_commands(commands_rx, Arc::clone(&trading_api), risk_manager.clone(), results_tx));
+
+        Self {
+            trading_api,
+            risk_manager,
+            commands_tx,
+            results_rx,
+            api_events_rx,
+            routed: HashMap::new(),
+            unmatched: HashMap::new(),
+        }
+    }
+
+    pub fn is_paper(&self) -> bool {
+        self.trading_api.is_paper()
+    }
+
+    pub fn send(&self, command: OrderCommand) -> Result<(), String> {
+        self.commands_tx.send(command)
+            .map_err(|_| "Order router has stopped".to_string())
+    }
+
+    /// Gives paper orders and the risk manager's price checks the latest book.
+    pub fn on_book_update(&self, book: &OrderBook) {
+        self.trading_api.order_books.insert(book.symbol.clone(), book.clone());
+        self.trading_api.on_book_update(book);
+        if let (Some(mid), false) = (book.mid_price(), book.is_stale()) {
+            self.risk_manager.update_mark_price(&book.symbol, mid);
+        }
+    }
+
+    pub fn poll(&mut self) -> Vec<RoutedOrderEvent> {
+        let mut events = Vec::new();
+
+        // Events a placement caused are sent before its result, so draining them first
+        // means they are usually matched rather than held
+        while let Ok(event) = self.api_events_rx.try_recv() {
+            self.on_api_event(event, &mut events);
+        }
+        while let Ok(result) = self.results_rx.try_recv() {
+            self.on_command_result(result, &mut events);
+        }
+
+        self.unmatched.retain(|_, (_, _, received_at)| received_at.elapsed() < UNMATCHED_UPDATE_TTL);
+        events
+    }
+
+    fn on_api_event(&mut self, event: ApiEvent, events: &mut Vec<RoutedOrderEvent>) {
+        match event {
+            ApiEvent::OrderUpdate { order_id, status, filled_size, .. } => {
+                let filled_size = filled_size.parse::<Decimal>().unwrap_or(Decimal::ZERO);
+                let Some(status) = parse_order_status(&status, filled_size) else {
+                    return;
+                };
+
+                match self.routed.get(&order_id).copied() {
+                    Some(internal_id) => {
+                        if is_terminal(status) {
+                            self.routed.remove(&order_id);
+                        }
+                        events.push(RoutedOrderEvent::Updated { order_id: internal_id, status, filled_size });
+                    }
+                    None => {
+                        self.unmatched.insert(order_id, (status, filled_size, Instant::now()));
+                    }
+                }
+            }
+            ApiEvent::Fill { order_id, coin, side, fill_size, fill_price, fee, timestamp, .. } => {
+                let (Ok(side), Ok(size), Ok(price), Ok(fee)) = (
+                    parse_side(&side),
+                    fill_size.parse::<Decimal>(),
+                    fill_price.parse::<Decimal>(),
+                    fee.parse::<Decimal>(),
+                ) else {
+                    warn!("Unparseable fill for {}, not booked", coin);
+                    return;
+                };
+
+                events.push(RoutedOrderEvent::Fill(Fill {
+                    id: Uuid::new_v4(),
+                    order_id: self.routed.get(&order_id).copied().unwrap_or_else(Uuid::nil),
+                    symbol: coin,
+                    side,
+                    price,
+                    size,
+                    fee,
+                    timestamp: chrono::DateTime::from_timestamp_millis(timestamp as i64).unwrap_or_else(Utc::now),
+                    strategy: None,
+                }));
+            }
+            ApiEvent::OrderRetriesExhausted { order_id, error, .. } => {
+                if let Some(internal_id) = self.routed.remove(&order_id) {
+                    events.push(RoutedOrderEvent::Failed { order_id: internal_id, reason: error });
+                }
+            }
+            _ => {}
+        }
+    }
+
+    fn on_command_result(&mut self, result: CommandResult, events: &mut Vec<RoutedOrderEvent>) {
+        match result {
+            CommandResult::Placed { order_id, client_order_id, order } => {
+                // No client id means the order was done before place_order returned
+                let (status, filled_size) = match client_order_id {
+                    Some(client_order_id) => {
+                        let update = self.unmatched.remove(&client_order_id)
+                            .map(|(status, filled_size, _)| (status, filled_size))
+                            .unwrap_or((OrderStatus::Submitted, Decimal::ZERO));
+                        if !is_terminal(update.0) {
+                            self.routed.insert(client_order_id, order_id);
+                        }
+                        update
+                    }
+                    None => (OrderStatus::Filled, order.size),
+                };
+
+                events.push(RoutedOrderEvent::Placed(Order {
+                    id: order_id,
+                    client_id: order.client_id,
+                    symbol: order.symbol,
+                    side: order.side,
+                    order_type: order.order_type,
+                    price: order.price,
+                    size: order.size,
+                    filled_size,
+                    remaining_size: order.size - filled_size,
+                    status,
+                    created_at: Utc::now(),
+                    updated_at: Utc::now(),
+                    exchange_oid: None,
+                }));
+            }
+            CommandResult::Rejected { order, reason } => {
+                events.push(RoutedOrderEvent::Rejected { order, reason });
+            }
+            CommandResult::Cancelled(order_id) => {
+                self.routed.retain(|_, internal_id| *internal_id != order_id);
+                events.push(RoutedOrderEvent::Cancelled(order_id));
+            }
+            CommandResult::CancelFailed { order_id, reason } => {
+                events.push(RoutedOrderEvent::CancelFailed { order_id, reason });
+            }
+        }
+    }
+}
+
+async fn run_commands(
+    mut commands_rx: mpsc::UnboundedReceiver<OrderCommand>,
+    trading_api: Arc<TradingApi>,
+    risk_manager: RiskManager,
+    results_tx: Sender<CommandResult>,
+) {
+    while let Some(command) = commands_rx.recv().await {
+        match command {
+            OrderCommand::Place(order) => {
+                let _ = results_tx.send(place_order(&trading_api, &risk_manager, order).await);
+            }
+            OrderCommand::Cancel(order_id) => {
+                let _ = results_tx.send(cancel_order(&trading_api, order_id).await);
+            }
+            OrderCommand::CancelAll(symbol) => {
+                let order_ids: Vec<Uuid> = trading_api.pending_orders.iter()
+                    .filter(|entry| symbol.as_deref().is_none_or(|symbol| entry.value().symbol == symbol))
+                    .map(|entry| entry.value().internal_id)
+                    .collect();
+                for order_id in order_ids {
+                    let _ = results_tx.send(cancel_order(&trading_api, order_id).await);
+                }
+            }
+        }
+    }
+}
+
+async fn place_order(trading_api: &TradingApi, risk_manager: &RiskManager, order: NewOrder) -> CommandResult {
+    // Risk is checked against the price and size the exchange will actually see
+    let order = match trading_api.assets.normalize_order(&order) {
+        Ok(normalized) => normalized,
+        Err(e) => return CommandResult::Rejected { order, reason: e.to_string() },
+    };
+    if let Err(reason) = risk_manager.check_order_risk(&order) {
+        return CommandResult::Rejected { order, reason };
+    }
+
+    match trading_api.place_order(order.clone()).await {
+        Ok(order_id) => {
+            risk_manager.record_order_submission(&order.symbol);
+            CommandResult::Placed { order_id, client_order_id: trading_api.client_order_id(order_id), order }
+        }
+        Err(e) => CommandResult::Rejected { order, reason: e.to_string() },
+    }
+}
+
+async fn cancel_order(trading_api: &TradingApi, order_id: Uuid) -> CommandResult {
+    match trading_api.cancel_order(order_id).await {
+        Ok(()) => CommandResult::Cancelled(order_id),
+        Err(e) => CommandResult::CancelFailed { order_id, reason: e.to_string() },
+    }
+}
+
+fn is_terminal(status: OrderStatus) -> bool {
+    matches!(status, OrderStatus::Filled | OrderStatus::Cancelled | OrderStatus::Rejected)
+}
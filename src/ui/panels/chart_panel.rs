@@ -0,0 +1,190 @@
+use crate::trading::order_book::OrderBook;
+use crate::trading::types::{Fill, Side};
+use crate::ui::components::price_display::SymbolPrecision;
+use chrono::{DateTime, Utc};
+use egui::{Color32, Ui};
+use egui_plot::{HLine, Legend, Line, MarkerShape, Plot, PlotPoints, Points};
+use rust_decimal::Decimal;
+use rust_decimal::prelude::ToPrimitive;
+use std::collections::VecDeque;
+
+pub const DEFAULT_CHART_POINTS: usize = 3600;
+
+const BID_COLOR: Color32 = Color32::from_rgb(40, 167, 69);
+const ASK_COLOR: Color32 = Color32::from_rgb(220, 53, 69);
+
+#[derive(Debug, Clone, Copy)]
+pub struct PriceSample {
+    pub time: f64, // Unix seconds
+    pub mid: f64,
+    pub best_bid: f64,
+    pub best_ask: f64,
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct FillMarker {
+    pub time: f64,
+    pub price: f64,
+    pub side: Side,
+}
+
+/// The last `capacity` book samples and fills for one symbol, oldest first.
+#[derive(Debug, Clone)]
+pub struct PriceHistory {
+    pub symbol: String,
+    pub capacity: usize,
+    samples: VecDeque<PriceSample>,
+    fills: VecDeque<FillMarker>,
+}
+
+impl PriceHistory {
+    pub fn new(symbol: String, capacity: usize) -> Self {
+        Self {
+            symbol,
+            capacity: capacity.max(1),
+            samples: VecDeque::new(),
+            fills: VecDeque::new(),
+        }
+    }
+
+    /// Starts over for another symbol.
+    pub fn reset(&mut self, symbol: String) {
+        self.symbol = symbol;
+        self.samples.clear();
+        self.fills.clear();
+    }
+
+    /// Samples the top of `book`. Stale, one-sided or other-symbol books are skipped.
+    pub fn record_book(&mut self, book: &OrderBook, now: DateTime<Utc>) {
+        if book.symbol != self.symbol || book.is_stale() {
+            return;
+        }
+        let (Some((best_bid, _)), Some((best_ask, _)), Some(mid)) = (book.best_bid(), book.best_ask(), book.mid_price()) else {
+            return;
+        };
+
+        push_capped(&mut self.samples, self.capacity, PriceSample {
+            time: unix_seconds(now),
+            mid: to_f64(mid),
+            best_bid: to_f64(best_bid),
+            best_ask: to_f64(best_ask),
+        });
+    }
+
+    pub fn record_fill(&mut self, fill: &Fill) {
+        if fill.symbol != self.symbol {
+            return;
+        }
+
+        push_capped(&mut self.fills, self.capacity, FillMarker {
+            time: unix_seconds(fill.timestamp),
+            price: to_f64(fill.price),
+            side: fill.side,
+        });
+    }
+
+    pub fn samples(&self) -> &VecDeque<PriceSample> {
+        &self.samples
+    }
+
+    pub fn fills(&self) -> &VecDeque<FillMarker> {
+        &self.fills
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.samples.is_empty()
+    }
+}
+
+fn push_capped<T>(buffer: &mut VecDeque<T>, capacity: usize, item: T) {
+    if buffer.len() >= capacity {
+        buffer.pop_front();
+    }
+    buffer.push_back(item);
+}
+
+fn unix_seconds(time: DateTime<Utc>) -> f64 {
+    time.timestamp_millis() as f64 / 1000.0
+}
+
+fn to_f64(value: Decimal) -> f64 {
+    value.to_f64().unwrap_or_default()
+}
+
+/// What the chart draws besides the mid price.
+#[derive(Debug, Clone, Copy)]
+pub struct ChartOverlays {
+    pub show_fills: bool,
+    pub show_quotes: bool,
+}
+
+impl Default for ChartOverlays {
+    fn default() -> Self {
+        Self {
+            show_fills: true,
+            show_quotes: true,
+        }
+    }
+}
+
+/// `quotes` are the strategy's resting orders, drawn as horizontal lines.
+pub fn show(ui: &mut Ui, history: &PriceHistory, quotes: &[(Side, Decimal)], overlays: &mut ChartOverlays, precision: SymbolPrecision) {
+    ui.horizontal(|ui| {
+        ui.checkbox(&mut overlays.show_fills, "Fills");
+        ui.checkbox(&mut overlays.show_quotes, "Quotes");
+        ui.label(format!("{} points", history.samples().len()));
+    });
+
+    if history.is_empty() {
+        ui.group(|ui| {
+            ui.set_min_height(200.0);
+            ui.centered_and_justified(|ui| {
+                ui.label(format!("No price data for {} yet", history.symbol));
+            });
+        });
+        return;
+    }
+
+    let mid: PlotPoints = history.samples().iter().map(|s| [s.time, s.mid]).collect();
+    let bid: PlotPoints = history.samples().iter().map(|s| [s.time, s.best_bid]).collect();
+    let ask: PlotPoints = history.samples().iter().map(|s| [s.time, s.best_ask]).collect();
+    let price_decimals = precision.price_decimals as usize;
+
+    Plot::new("price_chart")
+        .height(250.0)
+        .legend(Legend::default())
+        .x_axis_formatter(|mark, _| {
+            DateTime::from_timestamp_millis((mark.value * 1000.0) as i64)
+                .map(|time| time.format("%H:%M:%S").to_string())
+                .unwrap_or_default()
+        })
+        .y_axis_formatter(move |mark, _| format!("{:.*}", price_decimals, mark.value))
+        .show(ui, |plot_ui| {
+            plot_ui.line(Line::new("Mid", mid).color(Color32::from_rgb(0, 123, 255)));
+            plot_ui.line(Line::new("Best bid", bid).color(BID_COLOR.gamma_multiply(0.4)));
+            plot_ui.line(Line::new("Best ask", ask).color(ASK_COLOR.gamma_multiply(0.4)));
+
+            if overlays.show_fills {
+                for (side, name, color, shape) in [
+                    (Side::Buy, "Buys", BID_COLOR, MarkerShape::Up),
+                    (Side::Sell, "Sells", ASK_COLOR, MarkerShape::Down),
+                ] {
+                    let points: PlotPoints = history.fills().iter()
+                        .filter(|fill| fill.side == side)
+                        .map(|fill| [fill.time, fill.price])
+                        .collect();
+                    plot_ui.points(Points::new(name, points).shape(shape).color(color).filled(true).radius(5.0));
+                }
+            }
+
+            if overlays.show_quotes {
+                for (side, price) in quotes {
+                    let (name, color) = match side {
+                        Side::Buy => ("Quoted bid", BID_COLOR),
+                        Side::Sell => ("Quoted ask", ASK_COLOR),
+                    };
+                    plot_ui.hline(HLine::new(name, to_f64(*price)).color(color));
+                }
+            }
+        });
+}
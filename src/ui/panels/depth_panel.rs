@@ -0,0 +1,98 @@
+use crate::trading::order_book::OrderBook;
+use crate::trading::types::Side;
+use crate::ui::components::price_display::{format_price, SymbolPrecision};
+use crate::utils::decimal::Bps;
+use egui::{Color32, DragValue, Ui};
+use egui_plot::{Legend, Line, Plot, VLine};
+use rust_decimal::Decimal;
+use rust_decimal::prelude::ToPrimitive;
+
+pub const DEFAULT_DEPTH_WINDOW_BPS: u32 = 100;
+
+// Deeper levels almost never fall inside the window and only cost frame time
+const DEPTH_LEVELS: usize = 100;
+
+const BID_COLOR: Color32 = Color32::from_rgb(40, 167, 69);
+const ASK_COLOR: Color32 = Color32::from_rgb(220, 53, 69);
+
+/// Cumulative bid and ask size around the mid, clamped to `window_bps` either side of
+/// it. The window can be changed from the panel.
+pub fn show(ui: &mut Ui, order_book: &OrderBook, window_bps: &mut u32, precision: SymbolPrecision) {
+    ui.horizontal(|ui| {
+        ui.label("Window:");
+        ui.add(DragValue::new(window_bps).range(1..=5000).suffix(" bps"));
+    });
+
+    let bids = order_book.cumulative_depth(Side::Buy, DEPTH_LEVELS);
+    let asks = order_book.cumulative_depth(Side::Sell, DEPTH_LEVELS);
+
+    // A one-sided book is centred on the side it has
+    let center = order_book.mid_price()
+        .or_else(|| bids.first().or(asks.first()).map(|(price, _)| *price));
+    let Some(center) = center.and_then(|price| price.to_f64()) else {
+        ui.group(|ui| {
+            ui.set_min_height(150.0);
+            ui.centered_and_justified(|ui| {
+                ui.label("No order book data");
+            });
+        });
+        return;
+    };
+
+    let half_width = center * Bps::from(*window_bps).to_fraction().to_f64().unwrap_or_default();
+    let (low, high) = (center - half_width, center + half_width);
+    let bid_steps = step_points(&bids, Side::Buy, low, high);
+    let ask_steps = step_points(&asks, Side::Sell, low, high);
+
+    if bid_steps.is_empty() && !bids.is_empty() {
+        ui.colored_label(BID_COLOR, format!("Best bid {} is outside the window", format_price(bids[0].0, precision)));
+    }
+    if ask_steps.is_empty() && !asks.is_empty() {
+        ui.colored_label(ASK_COLOR, format!("Best ask {} is outside the window", format_price(asks[0].0, precision)));
+    }
+
+    let price_decimals = precision.price_decimals as usize;
+    Plot::new("depth_chart")
+        .height(200.0)
+        .legend(Legend::default())
+        .allow_drag(false)
+        .allow_zoom(false)
+        .allow_scroll(false)
+        .include_x(low)
+        .include_x(high)
+        .include_y(0.0)
+        .x_axis_formatter(move |mark, _| format!("{:.*}", price_decimals, mark.value))
+        .show(ui, |plot_ui| {
+            plot_ui.line(Line::new("Bids", bid_steps).color(BID_COLOR).fill(0.0));
+            plot_ui.line(Line::new("Asks", ask_steps).color(ASK_COLOR).fill(0.0));
+            plot_ui.vline(VLine::new("Mid", center).color(Color32::GRAY));
+        });
+}
+
+// Outline of one side's cumulative depth from its best price out to the window edge.
+// Empty when the best price is already outside [low, high].
+fn step_points(depth: &[(Decimal, Decimal)], side: Side, low: f64, high: f64) -> Vec<[f64; 2]> {
+    let mut points = Vec::new();
+    let mut previous_total = 0.0;
+    for (price, total) in depth {
+        let (Some(price), Some(total)) = (price.to_f64(), total.to_f64()) else {
+            continue;
+        };
+        if !(low..=high).contains(&price) {
+            break;
+        }
+        points.push([price, previous_total]);
+        points.push([price, total]);
+        previous_total = total;
+    }
+
+    if !points.is_empty() {
+        // Bids run down to the low edge, asks up to the high one
+        let edge = match side {
+            Side::Buy => low,
+            Side::Sell => high,
+        };
+        points.push([edge, previous_total]);
+    }
+    points
+}
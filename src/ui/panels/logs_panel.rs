@@ -1,20 +1,46 @@
 use crate::ui::app::{LogEntry, LogLevel};
-use egui::{Ui, Color32, ScrollArea};
+use egui::{Ui, Color32, ComboBox, ScrollArea};
 use std::collections::VecDeque;
 
-pub fn show(ui: &mut Ui, logs: &VecDeque<LogEntry>) {
+const LOG_LEVELS: [LogLevel; 4] = [LogLevel::Debug, LogLevel::Info, LogLevel::Warning, LogLevel::Error];
+
+/// Entries at `min_level` or above, oldest first.
+pub fn filter_logs(logs: &VecDeque<LogEntry>, min_level: LogLevel) -> Vec<&LogEntry> {
+    logs.iter().filter(|entry| entry.level.severity() >= min_level.severity()).collect()
+}
+
+fn level_label(level: LogLevel) -> (&'static str, Color32) {
+    match level {
+        LogLevel::Info => ("INFO", Color32::from_rgb(23, 162, 184)),
+        LogLevel::Warning => ("WARN", Color32::from_rgb(255, 193, 7)),
+        LogLevel::Error => ("ERROR", Color32::from_rgb(220, 53, 69)),
+        LogLevel::Debug => ("DEBUG", Color32::from_rgb(108, 117, 125)),
+    }
+}
+
+/// Entries at `min_level` or above, which can be changed from the panel. "Clear"
+/// empties `logs`.
+pub fn show(ui: &mut Ui, logs: &mut VecDeque<LogEntry>, min_level: &mut LogLevel) {
     ui.group(|ui| {
         ui.set_min_height(150.0);
         
+        let visible = filter_logs(logs, *min_level);
+        let mut clear = false;
+        
         // Controls
         ui.horizontal(|ui| {
             ui.label("Logs:");
-            ui.label(format!("({} entries)", logs.len()));
+            ui.label(format!("({} of {} entries)", visible.len(), logs.len()));
+            
+            ComboBox::from_id_salt("log_level_filter")
+                .selected_text(format!("{} and above", level_label(*min_level).0))
+                .show_ui(ui, |ui| {
+                    for level in LOG_LEVELS {
+                        ui.selectable_value(min_level, level, level_label(level).0);
+                    }
+                });
             
-            // Add filter controls here if needed
-            if ui.button("Clear").clicked() {
-                // This would clear logs - need to pass mutable reference
-            }
+            clear = ui.button("Clear").clicked();
         });
         
         ui.separator();
@@ -24,19 +50,14 @@ pub fn show(ui: &mut Ui, logs: &VecDeque<LogEntry>) {
             .auto_shrink([false; 2])
             .stick_to_bottom(true)
             .show(ui, |ui| {
-                for log_entry in logs.iter().rev().take(100) { // Show last 100 logs
+                for log_entry in visible.iter().rev().take(100) { // Show last 100 logs
                     ui.horizontal(|ui| {
                         // Timestamp
                         let timestamp = log_entry.timestamp.format("%H:%M:%S%.3f");
                         ui.label(format!("[{}]", timestamp));
                         
                         // Log level with color
-                        let (level_text, level_color) = match log_entry.level {
-                            LogLevel::Info => ("INFO", Color32::from_rgb(23, 162, 184)),
-                            LogLevel::Warning => ("WARN", Color32::from_rgb(255, 193, 7)),
-                            LogLevel::Error => ("ERROR", Color32::from_rgb(220, 53, 69)),
-                            LogLevel::Debug => ("DEBUG", Color32::from_rgb(108, 117, 125)),
-                        };
+                        let (level_text, level_color) = level_label(log_entry.level);
                         ui.colored_label(level_color, level_text);
                         
                         // Message
@@ -44,11 +65,41 @@ pub fn show(ui: &mut Ui, logs: &VecDeque<LogEntry>) {
                     });
                 }
                 
-                if logs.is_empty() {
+                if visible.is_empty() {
                     ui.centered_and_justified(|ui| {
-                        ui.label("No logs yet");
+                        ui.label(if logs.is_empty() { "No logs yet" } else { "No logs at this level" });
                     });
                 }
             });
+        
+        if clear {
+            logs.clear();
+        }
     });
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(level: LogLevel, message: &str) -> LogEntry {
+        LogEntry { timestamp: chrono::Utc::now(), level, message: message.to_string() }
+    }
+
+    #[test]
+    fn the_filter_hides_entries_below_the_selected_level_and_keeps_their_order() {
+        let logs: VecDeque<LogEntry> = VecDeque::from([
+            entry(LogLevel::Error, "first"),
+            entry(LogLevel::Debug, "second"),
+            entry(LogLevel::Warning, "third"),
+            entry(LogLevel::Info, "fourth"),
+            entry(LogLevel::Error, "fifth"),
+        ]);
+        let messages = |min_level| filter_logs(&logs, min_level).iter().map(|entry| entry.message.as_str()).collect::<Vec<_>>();
+
+        assert_eq!(messages(LogLevel::Debug).len(), 5);
+        assert_eq!(messages(LogLevel::Info), ["first", "third", "fourth", "fifth"]);
+        assert_eq!(messages(LogLevel::Warning), ["first", "third", "fifth"]);
+        assert_eq!(messages(LogLevel::Error), ["first", "fifth"]);
+    }
+}
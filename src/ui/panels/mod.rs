@@ -1,5 +1,8 @@
+pub mod chart_panel;
+pub mod depth_panel;
 pub mod logs_panel;
 pub mod order_book_panel;
 pub mod positions_panel;
+pub mod risk_panel;
 pub mod strategy_panel;
 pub mod trading_panel;
\ No newline at end of file
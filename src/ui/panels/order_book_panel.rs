@@ -1,8 +1,26 @@
 use crate::trading::order_book::OrderBook;
+use crate::trading::types::{Order, Side};
+use crate::ui::components::price_display::{format_price, format_size, SymbolPrecision};
 use egui::{Ui, Grid, Color32};
 use rust_decimal::Decimal;
 
-pub fn show(ui: &mut Ui, order_book: &OrderBook) {
+const OWN_ORDER_COLOR: Color32 = Color32::from_rgb(255, 193, 7);
+
+/// Own remaining size at each of `levels`, matched on exact price. Only orders on
+/// `side` count, so buys are matched against bids and sells against asks.
+pub fn own_size_at_levels(orders: &[Order], side: Side, levels: &[(Decimal, Decimal)]) -> Vec<Decimal> {
+    levels.iter()
+        .map(|(price, _)| {
+            orders.iter()
+                .filter(|order| order.side == side && order.price == *price)
+                .map(|order| order.remaining_size)
+                .sum()
+        })
+        .collect()
+}
+
+/// `own_orders` are marked on the levels they rest at; pass none to hide them.
+pub fn show(ui: &mut Ui, order_book: &OrderBook, precision: SymbolPrecision, own_orders: &[Order]) {
     ui.group(|ui| {
         ui.set_min_height(300.0);
         
@@ -13,10 +31,16 @@ pub fn show(ui: &mut Ui, order_book: &OrderBook) {
             return;
         }
 
+        if order_book.is_stale() {
+            ui.colored_label(Color32::from_rgb(255, 193, 7), "Stale - waiting for fresh snapshot");
+        }
+
         let (bids, asks) = order_book.get_depth(10);
+        let own_bids = own_size_at_levels(own_orders, Side::Buy, &bids);
+        let own_asks = own_size_at_levels(own_orders, Side::Sell, &asks);
         
         Grid::new("order_book_grid")
-            .num_columns(3)
+            .num_columns(4)
             .spacing([10.0, 4.0])
             .striped(true)
             .show(ui, |ui| {
@@ -24,13 +48,15 @@ pub fn show(ui: &mut Ui, order_book: &OrderBook) {
                 ui.label("Size");
                 ui.label("Price");
                 ui.label("Side");
+                ui.label("Mine");
                 ui.end_row();
                 
                 // Show asks in reverse order (highest to lowest)
-                for (price, size) in asks.iter().rev() {
-                    ui.label(format!("{:.4}", size));
-                    ui.colored_label(Color32::from_rgb(220, 53, 69), format!("{:.4}", price));
+                for ((price, size), own) in asks.iter().zip(&own_asks).rev() {
+                    ui.label(format_size(*size, precision));
+                    ui.colored_label(Color32::from_rgb(220, 53, 69), format_price(*price, precision));
                     ui.colored_label(Color32::from_rgb(220, 53, 69), "ASK");
+                    show_own_size(ui, *own, precision);
                     ui.end_row();
                 }
                 
@@ -42,33 +68,92 @@ pub fn show(ui: &mut Ui, order_book: &OrderBook) {
                     ui.label("");
                     ui.colored_label(
                         Color32::from_rgb(108, 117, 125),
-                        format!("Spread: {:.4} ({:.2}%)", spread, spread_pct)
+                        format!("Spread: {} ({:.2}%)", format_price(spread, precision), spread_pct)
                     );
                     ui.label("");
+                    ui.label("");
                     ui.end_row();
                 }
                 
                 // Show bids (highest to lowest)
-                for (price, size) in &bids {
-                    ui.label(format!("{:.4}", size));
-                    ui.colored_label(Color32::from_rgb(40, 167, 69), format!("{:.4}", price));
+                for ((price, size), own) in bids.iter().zip(&own_bids) {
+                    ui.label(format_size(*size, precision));
+                    ui.colored_label(Color32::from_rgb(40, 167, 69), format_price(*price, precision));
                     ui.colored_label(Color32::from_rgb(40, 167, 69), "BID");
+                    show_own_size(ui, *own, precision);
                     ui.end_row();
                 }
             });
         
+        // Own orders away from the displayed levels would otherwise be invisible here
+        let shown = own_bids.iter().chain(&own_asks).sum::<Decimal>();
+        let total = own_orders.iter().map(|order| order.remaining_size).sum::<Decimal>();
+        if total > shown {
+            ui.colored_label(OWN_ORDER_COLOR, format!("Mine off the shown levels: {}", format_size(total - shown, precision)));
+        }
+        
         ui.separator();
         
         // Order book statistics
         ui.horizontal(|ui| {
             ui.label("Stats:");
             if let Some(mid) = order_book.mid_price() {
-                ui.label(format!("Mid: {:.4}", mid));
+                ui.label(format!("Mid: {}", format_price(mid, precision)));
             }
             if let Some(vwap) = order_book.volume_weighted_mid(5) {
-                ui.label(format!("VWAP(5): {:.4}", vwap));
+                ui.label(format!("VWAP(5): {}", format_price(vwap, precision)));
             }
             ui.label(format!("Updates: {}", order_book.sequence));
         });
     });
 }
+
+fn show_own_size(ui: &mut Ui, size: Decimal, precision: SymbolPrecision) {
+    if size > Decimal::ZERO {
+        ui.colored_label(OWN_ORDER_COLOR, format_size(size, precision));
+    } else {
+        ui.label("");
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::trading::types::{OrderStatus, OrderType};
+    use rust_decimal_macros::dec;
+    use uuid::Uuid;
+
+    fn order(side: Side, price: Decimal, remaining_size: Decimal) -> Order {
+        Order {
+            id: Uuid::new_v4(),
+            client_id: None,
+            symbol: "HYPE".to_string(),
+            side,
+            order_type: OrderType::Limit,
+            price,
+            size: dec!(5),
+            filled_size: dec!(5) - remaining_size,
+            remaining_size,
+            status: OrderStatus::Submitted,
+            created_at: chrono::Utc::now(),
+            updated_at: chrono::Utc::now(),
+            exchange_oid: None,
+        }
+    }
+
+    #[test]
+    fn own_orders_are_summed_onto_the_levels_they_rest_at() {
+        let orders = [
+            order(Side::Buy, dec!(25.0), dec!(1)),
+            order(Side::Buy, dec!(25.00), dec!(2)), // Same price at another scale
+            order(Side::Buy, dec!(24.9), dec!(0.5)),
+            order(Side::Buy, dec!(24.85), dec!(4)), // Between levels
+            order(Side::Sell, dec!(24.9), dec!(3)), // Wrong side for the bids
+        ];
+        let bids = [(dec!(25.0), dec!(10)), (dec!(24.9), dec!(10)), (dec!(24.8), dec!(10))];
+
+        assert_eq!(own_size_at_levels(&orders, Side::Buy, &bids), [dec!(3), dec!(0.5), dec!(0)]);
+        assert_eq!(own_size_at_levels(&orders, Side::Sell, &bids), [dec!(0), dec!(3), dec!(0)]);
+        assert!(own_size_at_levels(&[], Side::Buy, &bids).iter().all(|size| size.is_zero()));
+    }
+}
@@ -1,8 +1,9 @@
 use crate::trading::position_manager::PositionManager;
+use crate::ui::components::price_display::{format_price, format_size, format_usd, precision_for, PrecisionMap};
 use egui::{Ui, Grid, Color32};
 use rust_decimal::Decimal;
 
-pub fn show(ui: &mut Ui, position_manager: &PositionManager) {
+pub fn show(ui: &mut Ui, position_manager: &PositionManager, precisions: &PrecisionMap) {
     ui.group(|ui| {
         ui.set_min_height(200.0);
         
@@ -18,9 +19,9 @@ pub fn show(ui: &mut Ui, position_manager: &PositionManager) {
                 Color32::from_rgb(220, 53, 69)
             };
             
-            ui.colored_label(pnl_color, format!("Total PnL: ${:.2}", total_pnl));
-            ui.label(format!("Unrealized: ${:.2}", unrealized_pnl));
-            ui.label(format!("Realized: ${:.2}", *realized_pnl.read()));
+            ui.colored_label(pnl_color, format!("Total PnL: {}", format_usd(total_pnl)));
+            ui.label(format!("Unrealized: {}", format_usd(unrealized_pnl)));
+            ui.label(format!("Realized: {}", format_usd(*realized_pnl.read())));
         });
         
         ui.separator();
@@ -51,6 +52,7 @@ pub fn show(ui: &mut Ui, position_manager: &PositionManager) {
                             continue;
                         }
                         
+                        let precision = precision_for(precisions, &position.symbol);
                         ui.label(&position.symbol);
                         
                         // Size with color based on long/short
@@ -59,10 +61,10 @@ pub fn show(ui: &mut Ui, position_manager: &PositionManager) {
                         } else {
                             Color32::from_rgb(220, 53, 69) // Red for short
                         };
-                        ui.colored_label(size_color, format!("{:.4}", position.size));
+                        ui.colored_label(size_color, format_size(position.size, precision));
                         
-                        ui.label(format!("${:.4}", position.entry_price));
-                        ui.label(format!("${:.4}", position.mark_price));
+                        ui.label(format!("${}", format_price(position.entry_price, precision)));
+                        ui.label(format!("${}", format_price(position.mark_price, precision)));
                         
                         // PnL with color
                         let pnl_color = if position.unrealized_pnl >= Decimal::ZERO {
@@ -70,10 +72,10 @@ pub fn show(ui: &mut Ui, position_manager: &PositionManager) {
                         } else {
                             Color32::from_rgb(220, 53, 69)
                         };
-                        ui.colored_label(pnl_color, format!("${:.2}", position.unrealized_pnl));
+                        ui.colored_label(pnl_color, format_usd(position.unrealized_pnl));
                         
                         let position_value = position.size * position.mark_price;
-                        ui.label(format!("${:.2}", position_value.abs()));
+                        ui.label(format_usd(position_value.abs()));
                         ui.end_row();
                     }
                 });
@@ -81,11 +83,43 @@ pub fn show(ui: &mut Ui, position_manager: &PositionManager) {
         
         ui.separator();
         
+        // Realized PnL by the strategy that traded it
+        let strategy_pnl = position_manager.get_all_strategy_pnl();
+        if !strategy_pnl.is_empty() {
+            Grid::new("strategy_pnl_grid")
+                .num_columns(4)
+                .spacing([10.0, 4.0])
+                .striped(true)
+                .show(ui, |ui| {
+                    ui.label("Strategy");
+                    ui.label("Fills");
+                    ui.label("Realized");
+                    ui.label("Net of fees");
+                    ui.end_row();
+                    
+                    for (strategy, pnl) in &strategy_pnl {
+                        ui.label(strategy);
+                        ui.label(pnl.fill_count.to_string());
+                        ui.label(format_usd(pnl.realized_pnl));
+                        let net_pnl = pnl.net_pnl();
+                        let pnl_color = if net_pnl >= Decimal::ZERO {
+                            Color32::from_rgb(40, 167, 69)
+                        } else {
+                            Color32::from_rgb(220, 53, 69)
+                        };
+                        ui.colored_label(pnl_color, format_usd(net_pnl));
+                        ui.end_row();
+                    }
+                });
+            
+            ui.separator();
+        }
+        
         // Risk metrics
         ui.horizontal(|ui| {
             let net_exposure = position_manager.get_net_exposure();
-            ui.label(format!("Net Exposure: ${:.2}", net_exposure));
-            ui.label(format!("Total Fees: ${:.2}", *position_manager.total_fees.read()));
+            ui.label(format!("Net Exposure: {}", format_usd(net_exposure)));
+            ui.label(format!("Total Fees: {}", format_usd(*position_manager.total_fees.read())));
         });
     });
 }
@@ -0,0 +1,210 @@
+use crate::trading::risk_manager::{limit_usage, RiskEvent, RiskManager, RiskSeverity};
+use crate::ui::components::price_display::format_usd;
+use chrono::{DateTime, Utc};
+use egui::{Color32, Grid, ScrollArea, Ui};
+use rust_decimal::Decimal;
+use std::collections::{BTreeSet, VecDeque};
+
+// Risk events kept for the panel's list
+pub const RISK_EVENT_HISTORY: usize = 200;
+
+const OK_COLOR: Color32 = Color32::from_rgb(40, 167, 69);
+const WARN_COLOR: Color32 = Color32::from_rgb(255, 193, 7);
+const BREACH_COLOR: Color32 = Color32::from_rgb(220, 53, 69);
+
+#[derive(Debug, Clone)]
+pub struct RiskEventEntry {
+    pub received_at: DateTime<Utc>,
+    pub event: RiskEvent,
+}
+
+/// Severity the panel files an event under. Events without one of their own are
+/// Low, except a breaker tripping (High) and a halt (Critical).
+pub fn event_severity(event: &RiskEvent) -> RiskSeverity {
+    match event {
+        RiskEvent::LimitExceeded { severity, .. } | RiskEvent::RiskWarning { severity, .. } => severity.clone(),
+        RiskEvent::CircuitBreakerTriggered { .. } => RiskSeverity::High,
+        RiskEvent::TradingHalted { .. } => RiskSeverity::Critical,
+        _ => RiskSeverity::Low,
+    }
+}
+
+pub fn describe_event(event: &RiskEvent) -> String {
+    match event {
+        RiskEvent::LimitExceeded { limit_type, symbol, current_value, limit_value, .. } => {
+            format!("{} {} limit exceeded: {} > {}", symbol, limit_type, current_value.round_dp(4), limit_value)
+        }
+        RiskEvent::CircuitBreakerTriggered { breaker_id, symbol, trigger_type, threshold, current_value } => {
+            format!("{} breaker {} ({:?}) tripped: {} > {}", symbol, breaker_id, trigger_type, current_value.round_dp(4), threshold)
+        }
+        RiskEvent::CircuitBreakerReset { breaker_id, symbol } => format!("{} breaker {} reset", symbol, breaker_id),
+        RiskEvent::RiskWarning { message, symbol, .. } => format!("{}: {}", symbol, message),
+        RiskEvent::PositionRisk { symbol, position_size, risk_score, .. } => {
+            format!("{} position {} at risk score {}", symbol, position_size, risk_score.round_dp(1))
+        }
+        RiskEvent::TradingHalted { reason } => format!("Trading halted: {}", reason),
+        RiskEvent::TradingResumed => "Trading resumed".to_string(),
+    }
+}
+
+fn severity_color(severity: &RiskSeverity) -> Color32 {
+    match severity {
+        RiskSeverity::Low => Color32::GRAY,
+        RiskSeverity::Medium => WARN_COLOR,
+        RiskSeverity::High => Color32::from_rgb(253, 126, 20),
+        RiskSeverity::Critical => BREACH_COLOR,
+    }
+}
+
+// Green under half the limit, amber up to it, red past it
+fn usage_color(usage: Decimal) -> Color32 {
+    if usage >= Decimal::ONE {
+        BREACH_COLOR
+    } else if usage >= Decimal::new(5, 1) {
+        WARN_COLOR
+    } else {
+        OK_COLOR
+    }
+}
+
+fn score_color(score: Decimal) -> Color32 {
+    if score >= Decimal::from(75) {
+        BREACH_COLOR
+    } else if score >= Decimal::from(40) {
+        WARN_COLOR
+    } else {
+        OK_COLOR
+    }
+}
+
+fn usage_label(ui: &mut Ui, current: Decimal, limit: Decimal) {
+    ui.colored_label(usage_color(limit_usage(current, limit)), format!("{} / {}", current.round_dp(4), limit));
+}
+
+/// Limits, breakers and recent events from `risk_manager`, newest event first.
+/// Returns true when "Reset daily metrics" was clicked; the reset has been applied.
+pub fn show(ui: &mut Ui, risk_manager: &RiskManager, events: &VecDeque<RiskEventEntry>) -> bool {
+    let mut reset = false;
+
+    ui.horizontal(|ui| {
+        match risk_manager.halted.read().as_ref() {
+            Some(reason) => ui.colored_label(BREACH_COLOR, format!("Halted: {}", reason)),
+            None => ui.colored_label(OK_COLOR, "Trading allowed"),
+        };
+        ui.label(format!("Daily PnL: {}", format_usd(risk_manager.get_daily_pnl())));
+        ui.label(format!("Trades: {}", risk_manager.get_daily_trades()));
+        if ui.button("Reset daily metrics").clicked() {
+            risk_manager.reset_daily_metrics();
+            reset = true;
+        }
+    });
+
+    ui.separator();
+
+    let symbols: BTreeSet<String> = risk_manager.position_limits.iter().map(|entry| entry.key().clone())
+        .chain(risk_manager.exposure_limits.iter().map(|entry| entry.key().clone()))
+        .chain(risk_manager.volatility_limits.iter().map(|entry| entry.key().clone()))
+        .collect();
+    if symbols.is_empty() {
+        ui.label("No position, exposure or volatility limits configured");
+    } else {
+        Grid::new("risk_limits_grid")
+            .num_columns(6)
+            .spacing([10.0, 4.0])
+            .striped(true)
+            .show(ui, |ui| {
+                ui.label("Symbol");
+                ui.label("Score");
+                ui.label("Position");
+                ui.label("Exposure");
+                ui.label("Spread bps");
+                ui.label("Move bps");
+                ui.end_row();
+
+                for symbol in &symbols {
+                    ui.label(symbol);
+                    let score = risk_manager.get_risk_score(symbol);
+                    ui.colored_label(score_color(score), score.round_dp(1).to_string());
+
+                    match risk_manager.position_limits.get(symbol) {
+                        Some(limit) => usage_label(ui, limit.current_net.abs(), limit.max_net),
+                        None => { ui.label("-"); }
+                    }
+                    match risk_manager.exposure_limits.get(symbol) {
+                        Some(limit) => usage_label(ui, limit.current_notional, limit.max_notional),
+                        None => { ui.label("-"); }
+                    }
+                    match risk_manager.volatility_limits.get(symbol) {
+                        Some(limit) => {
+                            usage_label(ui, Decimal::from(limit.current_spread_bps), Decimal::from(limit.max_spread_bps));
+                            usage_label(ui, Decimal::from(limit.price_change_bps), Decimal::from(limit.max_price_change_bps));
+                        }
+                        None => {
+                            ui.label("-");
+                            ui.label("-");
+                        }
+                    }
+                    ui.end_row();
+                }
+            });
+    }
+
+    ui.separator();
+
+    let breakers = risk_manager.circuit_breakers.read().clone();
+    if breakers.is_empty() {
+        ui.label("No circuit breakers configured");
+    } else {
+        let now = risk_manager.clock.now();
+        Grid::new("risk_breakers_grid")
+            .num_columns(4)
+            .spacing([10.0, 4.0])
+            .striped(true)
+            .show(ui, |ui| {
+                ui.label("Breaker");
+                ui.label("Type");
+                ui.label("Value");
+                ui.label("State");
+                ui.end_row();
+
+                for breaker in &breakers {
+                    ui.label(format!("{} ({})", breaker.id, breaker.symbol));
+                    ui.label(format!("{:?}", breaker.trigger_type));
+                    ui.label(format!("{} / {}", breaker.current_value.round_dp(4), breaker.threshold));
+                    let remaining = breaker.triggered_at
+                        .filter(|_| breaker.is_triggered)
+                        .map(|triggered_at| breaker.cooldown_duration.saturating_sub(now.duration_since(triggered_at)));
+                    match remaining {
+                        Some(remaining) if !remaining.is_zero() => {
+                            ui.colored_label(BREACH_COLOR, format!("Tripped, {}s left", remaining.as_secs() + 1));
+                        }
+                        Some(_) => { ui.colored_label(WARN_COLOR, "Cooldown over"); }
+                        None => { ui.colored_label(OK_COLOR, "OK"); }
+                    }
+                    ui.end_row();
+                }
+            });
+    }
+
+    ui.separator();
+
+    ui.label(format!("Recent events ({})", events.len()));
+    ScrollArea::vertical()
+        .id_salt("risk_events")
+        .max_height(150.0)
+        .show(ui, |ui| {
+            if events.is_empty() {
+                ui.label("No risk events");
+            }
+            for entry in events.iter().rev() {
+                let severity = event_severity(&entry.event);
+                ui.horizontal(|ui| {
+                    ui.label(entry.received_at.format("%H:%M:%S").to_string());
+                    ui.colored_label(severity_color(&severity), format!("{:?}", severity));
+                    ui.label(describe_event(&entry.event));
+                });
+            }
+        });
+
+    reset
+}
@@ -1,9 +1,20 @@
-use crate::strategies::market_making::MarketMakingStrategy;
+use crate::strategies::market_making::{MarketMakingConfig, MarketMakingStrategy};
 use crate::strategies::base_strategy::TradingStrategy;
-use egui::{Ui, Slider, Button, Color32, DragValue};
+use crate::strategies::quote_ladder::{LadderRung, QuoteLadder};
+use crate::trading::types::{OrderStatus, Side};
+use crate::ui::components::price_display::{format_price, format_size, SymbolPrecision};
+use egui::{Ui, Color32, DragValue, Grid};
 use rust_decimal::Decimal;
 
-pub fn show(ui: &mut Ui, strategy: &mut MarketMakingStrategy) {
+/// Parameter edits not yet applied to the strategy.
+#[derive(Debug, Clone, Default)]
+pub struct StrategyDraft {
+    pub config: MarketMakingConfig,
+    pub dirty: bool, // Edited since the last apply or revert; otherwise the draft follows the strategy
+    pub errors: Vec<String>,
+}
+
+pub fn show(ui: &mut Ui, strategy: &mut MarketMakingStrategy, draft: &mut StrategyDraft, precision: SymbolPrecision) {
     ui.group(|ui| {
         ui.set_min_height(250.0);
         
@@ -23,41 +34,93 @@ pub fn show(ui: &mut Ui, strategy: &mut MarketMakingStrategy) {
         
         ui.separator();
         
-        // Strategy parameters
+        // Parameters are edited on a draft, so the strategy never sees a half-edited config
+        if !draft.dirty {
+            draft.config = strategy.pending_config().unwrap_or(&strategy.config).clone();
+        }
+        let mut changed = false;
         egui::Grid::new("strategy_params")
             .num_columns(2)
             .spacing([10.0, 5.0])
             .show(ui, |ui| {
+                let config = &mut draft.config;
                 ui.label("Spread (bps):");
-                ui.add(DragValue::new(&mut strategy.config.spread_bps).range(1..=1000));
+                changed |= ui.add(DragValue::new(&mut config.spread_bps).range(1..=1000)).changed();
                 ui.end_row();
                 
                 ui.label("Order Size:");
-                let mut order_size_f64 = strategy.config.order_size.to_string().parse::<f64>().unwrap_or(1.0);
+                let mut order_size_f64 = config.order_size.to_string().parse::<f64>().unwrap_or(1.0);
                 if ui.add(DragValue::new(&mut order_size_f64).range(0.001..=100.0).speed(0.1)).changed() {
-                    strategy.config.order_size = Decimal::try_from(order_size_f64).unwrap_or(strategy.config.order_size);
+                    config.order_size = Decimal::try_from(order_size_f64).unwrap_or(config.order_size);
+                    changed = true;
                 }
                 ui.end_row();
                 
                 ui.label("Max Orders/Side:");
-                ui.add(DragValue::new(&mut strategy.config.max_orders_per_side).range(1..=10));
+                changed |= ui.add(DragValue::new(&mut config.max_orders_per_side).range(1..=10)).changed();
                 ui.end_row();
                 
                 ui.label("Min Edge (bps):");
-                ui.add(DragValue::new(&mut strategy.config.min_edge_bps).range(1..=100));
+                changed |= ui.add(DragValue::new(&mut config.min_edge_bps).range(1..=100)).changed();
+                ui.end_row();
+                
+                ui.label("Max Quote Spread (bps):");
+                changed |= ui.add(DragValue::new(&mut config.max_quote_spread_bps).range(10..=5000)).changed();
                 ui.end_row();
                 
                 ui.label("Refresh Interval (ms):");
-                ui.add(DragValue::new(&mut strategy.config.order_refresh_interval_ms).range(100..=10000));
+                changed |= ui.add(DragValue::new(&mut config.order_refresh_interval_ms).range(100..=10000)).changed();
+                ui.end_row();
+                
+                ui.label("Refresh Jitter (ms):");
+                changed |= ui.add(DragValue::new(&mut config.order_refresh_jitter_ms).range(0..=5000)).changed();
                 ui.end_row();
                 
                 ui.label("Inventory Skew:");
-                let mut skew_f64 = strategy.config.inventory_skew_factor.to_string().parse::<f64>().unwrap_or(0.1);
+                let mut skew_f64 = config.inventory_skew_factor.to_string().parse::<f64>().unwrap_or(0.1);
                 if ui.add(DragValue::new(&mut skew_f64).range(0.0..=1.0).speed(0.01)).changed() {
-                    strategy.config.inventory_skew_factor = Decimal::try_from(skew_f64).unwrap_or(strategy.config.inventory_skew_factor);
+                    config.inventory_skew_factor = Decimal::try_from(skew_f64).unwrap_or(config.inventory_skew_factor);
+                    changed = true;
                 }
                 ui.end_row();
             });
+        // The base spread and size are edited above; an override for the quoted symbol wins over them
+        let symbol = &draft.config.base_config.symbol;
+        if draft.config.symbol_overrides.contains_key(symbol) {
+            ui.label(format!(
+                "{} override: quoting {} bps, size {}",
+                symbol,
+                draft.config.spread_bps_for(symbol),
+                format_size(draft.config.order_size_for(symbol), precision)
+            ));
+        }
+        if changed {
+            draft.dirty = true;
+            draft.errors = draft.config.validate().err().unwrap_or_default();
+        }
+
+        for error in &draft.errors {
+            ui.colored_label(Color32::from_rgb(220, 53, 69), error);
+        }
+
+        ui.horizontal(|ui| {
+            let can_apply = draft.dirty && draft.errors.is_empty();
+            if ui.add_enabled(can_apply, egui::Button::new("Apply")).clicked() {
+                match strategy.apply_config(draft.config.clone()) {
+                    Ok(()) => draft.dirty = false,
+                    Err(errors) => draft.errors = errors,
+                }
+            }
+            if ui.add_enabled(draft.dirty, egui::Button::new("Revert")).clicked() {
+                draft.dirty = false;
+                draft.errors.clear();
+            }
+            if draft.dirty {
+                ui.label("Unapplied changes");
+            } else if strategy.pending_config().is_some() {
+                ui.label("Applies on the next quote refresh");
+            }
+        });
         
         ui.separator();
         
@@ -65,12 +128,16 @@ pub fn show(ui: &mut Ui, strategy: &mut MarketMakingStrategy) {
         ui.label("Status:");
         ui.horizontal(|ui| {
             ui.label(format!("Active Orders: {}", strategy.active_orders.len()));
-            ui.label(format!("Current Inventory: {:.4}", strategy.current_inventory));
+            ui.label(format!("Current Inventory: {}", format_size(strategy.current_inventory, precision)));
             if let Some(last_price) = strategy.last_price {
-                ui.label(format!("Last Price: ${:.4}", last_price));
+                ui.label(format!("Last Price: ${}", format_price(last_price, precision)));
             }
         });
         
+        ui.separator();
+
+        show_ladder(ui, strategy.quote_ladder(), precision);
+
         ui.separator();
         
         // Manual controls
@@ -96,3 +163,60 @@ pub fn show(ui: &mut Ui, strategy: &mut MarketMakingStrategy) {
         });
     });
 }
+
+fn rung_state(rung: &LadderRung) -> (&'static str, Color32) {
+    match rung.status {
+        None => ("Intended", Color32::GRAY),
+        Some(OrderStatus::Pending) => ("Pending", Color32::GRAY),
+        Some(OrderStatus::Submitted) => ("Live", Color32::from_rgb(40, 167, 69)),
+        Some(OrderStatus::PartiallyFilled) => ("Partial", Color32::from_rgb(255, 193, 7)),
+        Some(OrderStatus::Filled) => ("Filled", Color32::from_rgb(23, 162, 184)),
+        Some(OrderStatus::Cancelled) => ("Cancelled", Color32::GRAY),
+        Some(OrderStatus::Rejected) => ("Rejected", Color32::from_rgb(220, 53, 69)),
+    }
+}
+
+// Laid out like a book: asks from the furthest in, then bids from the best out
+fn show_ladder(ui: &mut Ui, ladder: &QuoteLadder, precision: SymbolPrecision) {
+    ui.horizontal(|ui| {
+        ui.label(format!("Quote Ladder: {} of {} rungs live", ladder.live_rungs(), ladder.bids.len() + ladder.asks.len()));
+        if let Some(fair_price) = ladder.fair_price {
+            ui.label(format!("Fair: ${}", format_price(fair_price, precision)));
+        }
+        if let Some(quoted_at) = ladder.quoted_at {
+            ui.label(format!("Quoted {}", quoted_at.format("%H:%M:%S")));
+        }
+    });
+    if ladder.is_empty() {
+        ui.label("No quotes this cycle");
+        return;
+    }
+
+    Grid::new("quote_ladder_grid")
+        .num_columns(5)
+        .spacing([10.0, 2.0])
+        .striped(true)
+        .show(ui, |ui| {
+            ui.label("Side");
+            ui.label("Price");
+            ui.label("Size");
+            ui.label("Filled");
+            ui.label("State");
+            ui.end_row();
+
+            let rungs = ladder.asks.iter().rev().map(|rung| (Side::Sell, rung))
+                .chain(ladder.bids.iter().map(|rung| (Side::Buy, rung)));
+            for (side, rung) in rungs {
+                match side {
+                    Side::Buy => ui.colored_label(Color32::from_rgb(40, 167, 69), "Bid"),
+                    Side::Sell => ui.colored_label(Color32::from_rgb(220, 53, 69), "Ask"),
+                };
+                ui.label(format_price(rung.price, precision));
+                ui.label(format_size(rung.size, precision));
+                ui.label(format_size(rung.filled_size, precision));
+                let (state, color) = rung_state(rung);
+                ui.colored_label(color, state);
+                ui.end_row();
+            }
+        });
+}
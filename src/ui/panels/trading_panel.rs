@@ -1,11 +1,16 @@
 use crate::ui::app::ManualOrderState;
 use crate::trading::order_manager::OrderManager;
 use crate::trading::types::*;
+use crate::ui::components::order_table::show_order_table;
+use crate::ui::components::price_display::SymbolPrecision;
+use crate::ui::order_router::OrderCommand;
 use egui::{Ui, ComboBox, Button, Color32};
 use rust_decimal::Decimal;
 use std::str::FromStr;
 
-pub fn show(ui: &mut Ui, manual_order: &mut ManualOrderState, order_manager: &OrderManager) {
+/// Orders and cancels are handed to `on_command` rather than applied to `order_manager`,
+/// which only supplies the active orders shown.
+pub fn show(ui: &mut Ui, manual_order: &mut ManualOrderState, order_manager: &OrderManager, precision: SymbolPrecision, halted: bool, on_command: &mut dyn FnMut(OrderCommand)) {
     ui.group(|ui| {
         ui.set_min_height(200.0);
         
@@ -52,8 +57,8 @@ pub fn show(ui: &mut Ui, manual_order: &mut ManualOrderState, order_manager: &Or
         
         ui.separator();
         
-        // Order buttons
-        ui.horizontal(|ui| {
+        // Order buttons, blocked while trading is halted
+        ui.add_enabled_ui(!halted, |ui| ui.horizontal(|ui| {
             let buy_button = Button::new("Place Buy Order")
                 .fill(Color32::from_rgb(40, 167, 69));
             
@@ -69,8 +74,10 @@ pub fn show(ui: &mut Ui, manual_order: &mut ManualOrderState, order_manager: &Or
                         price,
                         size,
                         client_id: Some("manual_buy".to_string()),
+                        reduce_only: false,
+                        tick_received_at: None,
                     };
-                    order_manager.add_order(new_order);
+                    on_command(OrderCommand::Place(new_order));
                 }
             }
             
@@ -89,11 +96,13 @@ pub fn show(ui: &mut Ui, manual_order: &mut ManualOrderState, order_manager: &Or
                         price,
                         size,
                         client_id: Some("manual_sell".to_string()),
+                        reduce_only: false,
+                        tick_received_at: None,
                     };
-                    order_manager.add_order(new_order);
+                    on_command(OrderCommand::Place(new_order));
                 }
             }
-        });
+        }));
         
         ui.separator();
         
@@ -108,23 +117,8 @@ pub fn show(ui: &mut Ui, manual_order: &mut ManualOrderState, order_manager: &Or
             egui::ScrollArea::vertical()
                 .max_height(150.0)
                 .show(ui, |ui| {
-                    for order in active_orders {
-                        ui.horizontal(|ui| {
-                            let side_color = match order.side {
-                                Side::Buy => Color32::from_rgb(40, 167, 69),
-                                Side::Sell => Color32::from_rgb(220, 53, 69),
-                            };
-                            
-                            ui.colored_label(side_color, format!("{:?}", order.side));
-                            ui.label(format!("{:.4}", order.price));
-                            ui.label(format!("{:.4}", order.remaining_size));
-                            ui.label(format!("{:?}", order.status));
-                            
-                            if ui.button("Cancel").clicked() {
-                                order_manager.cancel_order(order.id);
-                            }
-                        });
-                    }
+                    let orders: Vec<&Order> = active_orders.iter().collect();
+                    show_order_table(ui, &orders, precision, &mut |order_id| on_command(OrderCommand::Cancel(order_id)));
                 });
         }
         
@@ -132,7 +126,7 @@ pub fn show(ui: &mut Ui, manual_order: &mut ManualOrderState, order_manager: &Or
         
         // Cancel all button
         if ui.button("Cancel All Orders").clicked() {
-            order_manager.cancel_all_orders(Some(&manual_order.symbol));
+            on_command(OrderCommand::CancelAll(Some(manual_order.symbol.clone())));
         }
     });
 }
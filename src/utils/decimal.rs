@@ -0,0 +1,131 @@
+// Scale policy for computed decimals.
+//
+// `Decimal` keeps whatever scale an operation produces, so `25.10` and `25.1` are
+// equal but print and serialize differently, and a division can leave 28 digits of
+// fraction behind. Values computed by the crate follow these rules
+// before they are sent, stored or compared as strings:
+//
+// - Prices go to the symbol's tick scale. Bids round down and asks round up, so
+//   rounding never makes a quote more aggressive.
+// - Sizes go to the lot scale, rounding toward zero so an order never grows.
+// - Derived values (averages, PnL, bps) are cut to `MAX_COMPUTED_SCALE` places.
+//
+// All of them are then normalized, dropping trailing zeros.
+
+use crate::trading::types::Side;
+use rust_decimal::{Decimal, RoundingStrategy};
+use rust_decimal_macros::dec;
+use std::fmt;
+
+// Basis points in one; only Bps should need it
+const BPS: Decimal = dec!(10000);
+
+/// Places kept on derived values; anything finer is division noise.
+pub const MAX_COMPUTED_SCALE: u32 = 10;
+
+/// A quantity in basis points. Every conversion between bps and fractions goes
+/// through here, so the factor lives in one place.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Bps(pub Decimal);
+
+impl Bps {
+    /// `value` as basis points of `base`, or zero when `base` is zero.
+    pub fn from_ratio(value: Decimal, base: Decimal) -> Self {
+        if base.is_zero() {
+            return Self(Decimal::ZERO);
+        }
+        Self(normalize_computed(value / base * BPS))
+    }
+
+    pub fn to_fraction(self) -> Decimal {
+        self.0 / BPS
+    }
+
+    /// This many basis points of `value`.
+    pub fn of(self, value: Decimal) -> Decimal {
+        value * self.0 / BPS
+    }
+
+    pub fn value(self) -> Decimal {
+        self.0
+    }
+}
+
+impl From<u32> for Bps {
+    fn from(bps: u32) -> Self {
+        Self(Decimal::from(bps))
+    }
+}
+
+impl fmt::Display for Bps {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} bps", self.0)
+    }
+}
+
+pub fn round_price(price: Decimal, scale: u32, side: Side) -> Decimal {
+    let strategy = match side {
+        Side::Buy => RoundingStrategy::ToNegativeInfinity,
+        Side::Sell => RoundingStrategy::ToPositiveInfinity,
+    };
+    price.round_dp_with_strategy(scale, strategy).normalize()
+}
+
+pub fn round_size(size: Decimal, scale: u32) -> Decimal {
+    size.round_dp_with_strategy(scale, RoundingStrategy::ToZero).normalize()
+}
+
+pub fn normalize_computed(value: Decimal) -> Decimal {
+    value.round_dp_with_strategy(MAX_COMPUTED_SCALE, RoundingStrategy::MidpointNearestEven).normalize()
+}
+
+/// Decimal places needed to write every one of `values`, ignoring trailing zeros.
+/// For the prices on a book this is its tick scale.
+pub fn max_scale<'a>(values: impl IntoIterator<Item = &'a Decimal>) -> Option<u32> {
+    values.into_iter().map(|value| value.normalize().scale()).max()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn prices_round_away_from_the_touch_and_drop_trailing_zeros() {
+        // A bid and ask 10 bps either side of 25.025, as the quoting math leaves them
+        let bid = dec!(25.025) - dec!(25.025) * dec!(10) / BPS;
+        let ask = dec!(25.025) + dec!(25.025) * dec!(10) / BPS;
+        assert!(bid.scale() > 2);
+
+        assert_eq!(round_price(bid, 2, Side::Buy).to_string(), "24.99");
+        assert_eq!(round_price(ask, 2, Side::Sell).to_string(), "25.06");
+        // On the tick already, only the trailing zeros go
+        assert_eq!(round_price(dec!(25.100), 2, Side::Sell).to_string(), "25.1");
+    }
+
+    #[test]
+    fn sizes_round_toward_zero_and_derived_values_lose_division_noise() {
+        assert_eq!(round_size(dec!(1.239), 2).to_string(), "1.23");
+        assert_eq!(round_size(dec!(-1.239), 2).to_string(), "-1.23");
+        assert_eq!((dec!(1) / dec!(3)).scale(), 28);
+        assert_eq!(normalize_computed(dec!(1) / dec!(3)).to_string(), "0.3333333333");
+        assert_eq!(normalize_computed(dec!(2.50000)).to_string(), "2.5");
+    }
+
+    #[test]
+    fn the_tick_scale_ignores_trailing_zeros() {
+        assert_eq!(max_scale(&[dec!(25.10), dec!(25.2), dec!(25)]), Some(1));
+        assert_eq!(max_scale(&[]), None);
+    }
+
+    #[test]
+    fn bps_convert_to_and_from_fractions() {
+        assert_eq!(Bps::from(25).to_fraction(), dec!(0.0025));
+        assert_eq!(Bps::from(20).of(dec!(100)), dec!(0.2));
+        // A 0.02 wide book at 25 is 8 bps
+        assert_eq!(Bps::from_ratio(dec!(0.02), dec!(25)), Bps(dec!(8)));
+        assert_eq!(Bps::from_ratio(dec!(1), dec!(3)).value().to_string(), "3333.3333333333");
+        assert_eq!(Bps::from_ratio(dec!(1), Decimal::ZERO), Bps::default());
+        assert_eq!(Bps::from_ratio(Bps::from(15).of(dec!(40)), dec!(40)), Bps::from(15));
+        assert_eq!(Bps::from(7).to_string(), "7 bps");
+    }
+}
@@ -0,0 +1,108 @@
+//! Server end of a WebSocket connection for tests, speaking just enough of the
+//! protocol to read the client's frames and push text frames back.
+
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::{TcpListener, TcpStream};
+
+/// `WebsocketClient` builds a TLS config even for `ws://` URLs, which needs the crypto
+/// provider the binaries install at startup
+pub fn install_crypto_provider() {
+    let _ = rustls::crypto::ring::default_provider().install_default();
+}
+
+pub struct MockPeer {
+    stream: TcpStream,
+    buf: Vec<u8>,
+}
+
+impl MockPeer {
+    pub async fn accept(listener: &TcpListener) -> Self {
+        Self::accept_unanswered(listener).await.upgrade().await
+    }
+
+    /// Reads the upgrade request but leaves the client waiting for the reply until
+    /// `upgrade` is called
+    pub async fn accept_unanswered(listener: &TcpListener) -> Self {
+        let (mut stream, _) = listener.accept().await.unwrap();
+        let mut buf = Vec::new();
+        let mut chunk = [0u8; 4096];
+        let header_end = loop {
+            let n = stream.read(&mut chunk).await.unwrap();
+            buf.extend_from_slice(&chunk[..n]);
+            if let Some(end) = buf.windows(4).position(|w| w == b"\r\n\r\n") {
+                break end;
+            }
+        };
+        buf.drain(..header_end + 4);
+        Self { stream, buf }
+    }
+
+    pub async fn upgrade(mut self) -> Self {
+        let reply = "HTTP/1.1 101 Switching Protocols\r\nUpgrade: websocket\r\nConnection: Upgrade\r\nSec-WebSocket-Accept: mock\r\n\r\n";
+        self.stream.write_all(reply.as_bytes()).await.unwrap();
+        self
+    }
+
+    // Pops the next complete client text frame off the buffer
+    fn buffered_text(&mut self) -> Option<String> {
+        if self.buf.len() < 2 {
+            return None;
+        }
+        let (len, mut offset) = match self.buf[1] & 0x7f {
+            126 if self.buf.len() >= 4 => (u16::from_be_bytes([self.buf[2], self.buf[3]]) as usize, 4),
+            126 | 127 => return None,
+            len => (len as usize, 2),
+        };
+        let mask: [u8; 4] = self.buf.get(offset..offset + 4)?.try_into().unwrap();
+        offset += 4;
+        if self.buf.len() < offset + len {
+            return None;
+        }
+
+        let frame: Vec<u8> = self.buf.drain(..offset + len).collect();
+        let payload: Vec<u8> = frame[offset..].iter().enumerate().map(|(i, b)| b ^ mask[i % 4]).collect();
+        Some(String::from_utf8(payload).unwrap())
+    }
+
+    pub async fn recv_text(&mut self) -> String {
+        let mut chunk = [0u8; 4096];
+        loop {
+            if let Some(text) = self.buffered_text() {
+                return text;
+            }
+            let n = self.stream.read(&mut chunk).await.unwrap();
+            assert!(n > 0, "client closed the connection");
+            self.buf.extend_from_slice(&chunk[..n]);
+        }
+    }
+
+    /// Channels of the next `count` subscribe frames, in the order they arrive
+    pub async fn recv_subscriptions(&mut self, count: usize) -> Vec<String> {
+        let mut channels = Vec::new();
+        while channels.len() < count {
+            if let Some(channel) = subscribed_channel(&self.recv_text().await) {
+                channels.push(channel);
+            }
+        }
+        channels
+    }
+
+    pub async fn send_text(&mut self, text: &str) {
+        let mut frame = vec![0x81];
+        match text.len() {
+            len @ 0..=125 => frame.push(len as u8),
+            len => {
+                frame.push(126);
+                frame.extend_from_slice(&(len as u16).to_be_bytes());
+            }
+        }
+        frame.extend_from_slice(text.as_bytes());
+        self.stream.write_all(&frame).await.unwrap();
+    }
+}
+
+// Pings and anything else that is not a subscription are skipped
+fn subscribed_channel(text: &str) -> Option<String> {
+    let message: serde_json::Value = serde_json::from_str(text).unwrap();
+    (message["method"] == "subscribe").then(|| message["subscription"]["type"].as_str().unwrap().to_string())
+}
@@ -1 +1,4 @@
+pub mod decimal;
+#[cfg(test)]
+pub mod mock_ws;
 pub mod ws_utils;
\ No newline at end of file
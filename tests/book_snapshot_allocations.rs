@@ -0,0 +1,67 @@
+//! What the event loop pays per tick to hand a book to the strategies: a full
+//! `OrderBook` clone against a `snapshot` of the top levels. Its own test binary,
+//! as the counting allocator sees every allocation in the process.
+
+use hyper_liquid_connector::trading::order_book::{OrderBook, SNAPSHOT_LEVELS};
+use hyper_liquid_connector::trading::types::Side;
+use rust_decimal::Decimal;
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::hint::black_box;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+struct CountingAllocator;
+
+static ALLOCATIONS: AtomicUsize = AtomicUsize::new(0);
+static ALLOCATED_BYTES: AtomicUsize = AtomicUsize::new(0);
+
+unsafe impl GlobalAlloc for CountingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+        ALLOCATED_BYTES.fetch_add(layout.size(), Ordering::Relaxed);
+        System.alloc(layout)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout)
+    }
+}
+
+#[global_allocator]
+static ALLOCATOR: CountingAllocator = CountingAllocator;
+
+// Allocations and bytes allocated while running `f`
+fn allocations<T>(f: impl FnOnce() -> T) -> (usize, usize) {
+    let (count, bytes) = (ALLOCATIONS.load(Ordering::Relaxed), ALLOCATED_BYTES.load(Ordering::Relaxed));
+    black_box(f());
+    (ALLOCATIONS.load(Ordering::Relaxed) - count, ALLOCATED_BYTES.load(Ordering::Relaxed) - bytes)
+}
+
+fn deep_book(levels: i64) -> OrderBook {
+    let mut book = OrderBook::new("HYPE".to_string());
+    for i in 1..=levels {
+        book.update_level(Side::Buy, Decimal::new(25_000 - i, 3), Decimal::ONE);
+        book.update_level(Side::Sell, Decimal::new(25_000 + i, 3), Decimal::ONE);
+    }
+    book
+}
+
+#[test]
+fn a_snapshot_costs_the_same_however_deep_the_book_while_a_clone_grows_with_it() {
+    let shallow = deep_book(SNAPSHOT_LEVELS as i64);
+    let deep = deep_book(5_000);
+
+    let (shallow_snapshots, shallow_snapshot_bytes) = allocations(|| shallow.snapshot(SNAPSHOT_LEVELS));
+    let (deep_snapshots, deep_snapshot_bytes) = allocations(|| deep.snapshot(SNAPSHOT_LEVELS));
+    let (deep_clones, deep_clone_bytes) = allocations(|| deep.clone());
+    println!(
+        "5000 levels a side: clone {} allocations, {} bytes; snapshot {} allocations, {} bytes",
+        deep_clones, deep_clone_bytes, deep_snapshots, deep_snapshot_bytes
+    );
+
+    // A snapshot is the symbol and a vector of SNAPSHOT_LEVELS a side, whatever lies beyond
+    assert_eq!((deep_snapshots, deep_snapshot_bytes), (shallow_snapshots, shallow_snapshot_bytes));
+    assert!(deep_snapshots <= 4, "{} allocations per snapshot", deep_snapshots);
+    // A clone copies every level, one B-tree node at a time
+    assert!(deep_clones > 100 * deep_snapshots, "{} clone allocations against {}", deep_clones, deep_snapshots);
+    assert!(deep_clone_bytes > 10 * deep_snapshot_bytes, "{} clone bytes against {}", deep_clone_bytes, deep_snapshot_bytes);
+}